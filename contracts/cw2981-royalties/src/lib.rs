@@ -2,7 +2,9 @@ pub mod error;
 pub mod msg;
 pub mod query;
 
-pub use query::{check_royalties, query_royalties_info};
+pub use query::{
+    check_royalties, query_all_royalties, query_royalties_info, query_supported_interfaces,
+};
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{to_json_binary, Empty};
@@ -92,6 +94,14 @@ pub mod entry {
                     ..
                 }),
             ..
+        }
+        | ExecuteMsg::MintAndSend {
+            extension:
+                Some(Metadata {
+                    royalty_percentage: Some(royalty_percentage),
+                    ..
+                }),
+            ..
         } = &msg
         {
             // validate royalty_percentage to be between 0 and 100
@@ -100,6 +110,18 @@ pub mod entry {
                 return Err(ContractError::InvalidRoyaltyPercentage);
             }
         }
+        if let ExecuteMsg::ImportTokens { tokens } = &msg {
+            let invalid = tokens.iter().any(|token| {
+                token
+                    .extension
+                    .as_ref()
+                    .and_then(|metadata| metadata.royalty_percentage)
+                    .is_some_and(|royalty_percentage| royalty_percentage > 100)
+            });
+            if invalid {
+                return Err(ContractError::InvalidRoyaltyPercentage);
+            }
+        }
 
         Cw2981Contract::default()
             .execute(deps, env, info, msg)
@@ -114,6 +136,12 @@ pub mod entry {
                 sale_price,
             } => to_json_binary(&query_royalties_info(deps, env, token_id, sale_price)?),
             QueryMsg::CheckRoyalties {} => to_json_binary(&check_royalties(deps)?),
+            QueryMsg::AllRoyalties {
+                start_after,
+                limit,
+                cursor,
+            } => to_json_binary(&query_all_royalties(deps, start_after, limit, cursor)?),
+            QueryMsg::SupportedInterfaces {} => to_json_binary(&query_supported_interfaces()?),
             _ => Cw2981Contract::default().query(deps, env, msg.into()),
         }
     }
@@ -122,9 +150,14 @@ pub mod entry {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::msg::{CheckRoyaltiesResponse, QueryMsg, RoyaltiesInfoResponse};
+    use crate::msg::{
+        AllRoyaltiesResponse, CheckRoyaltiesResponse, QueryMsg, RoyaltiesInfoResponse,
+        RoyaltyConfig, TokenRoyalty,
+    };
 
     use cosmwasm_std::{from_json, Uint128};
+    use cw721_base::msg::SupportedInterfacesResponse;
+    use cw721_base::query::{INTERFACE_BASE, INTERFACE_ROYALTIES};
 
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
 
@@ -141,6 +174,8 @@ mod tests {
             symbol: "SPACE".to_string(),
             minter: None,
             withdraw_address: None,
+            initial_tokens: None,
+            import_mode: None,
         };
         entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
@@ -156,6 +191,8 @@ mod tests {
             owner: "john".to_string(),
             token_uri: token_uri.clone(),
             extension: extension.clone(),
+            approval: None,
+            metadata_hash: None,
         };
         let env = mock_env();
         entry::execute(deps.as_mut(), env.clone(), info, exec_msg).unwrap();
@@ -178,6 +215,8 @@ mod tests {
             symbol: "SPACE".to_string(),
             minter: None,
             withdraw_address: None,
+            initial_tokens: None,
+            import_mode: None,
         };
         entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
@@ -192,6 +231,8 @@ mod tests {
                 royalty_percentage: Some(101),
                 ..Metadata::default()
             }),
+            approval: None,
+            metadata_hash: None,
         };
         // mint will return StdError
         let err = entry::execute(deps.as_mut(), mock_env(), info, exec_msg).unwrap_err();
@@ -209,6 +250,8 @@ mod tests {
             symbol: "SPACE".to_string(),
             minter: None,
             withdraw_address: None,
+            initial_tokens: None,
+            import_mode: None,
         };
         entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
 
@@ -222,6 +265,8 @@ mod tests {
                 name: Some("Starship USS Enterprise".to_string()),
                 ..Metadata::default()
             }),
+            approval: None,
+            metadata_hash: None,
         };
         entry::execute(deps.as_mut(), mock_env(), info, exec_msg).unwrap();
 
@@ -248,6 +293,8 @@ mod tests {
             symbol: "SPACE".to_string(),
             minter: None,
             withdraw_address: None,
+            initial_tokens: None,
+            import_mode: None,
         };
         let env = mock_env();
         entry::instantiate(deps.as_mut(), env.clone(), info.clone(), init_msg).unwrap();
@@ -265,6 +312,8 @@ mod tests {
                 royalty_percentage: Some(10),
                 ..Metadata::default()
             }),
+            approval: None,
+            metadata_hash: None,
         };
         entry::execute(deps.as_mut(), mock_env(), info.clone(), exec_msg).unwrap();
 
@@ -305,6 +354,8 @@ mod tests {
                 royalty_percentage: Some(4),
                 ..Metadata::default()
             }),
+            approval: None,
+            metadata_hash: None,
         };
         entry::execute(deps.as_mut(), mock_env(), info, voyager_exec_msg).unwrap();
 
@@ -324,4 +375,88 @@ mod tests {
         .unwrap();
         assert_eq!(res, voyager_expected);
     }
+
+    #[test]
+    fn all_royalties() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info(CREATOR, &[]);
+        let init_msg = InstantiateMsg {
+            name: "SpaceShips".to_string(),
+            symbol: "SPACE".to_string(),
+            minter: None,
+            withdraw_address: None,
+            initial_tokens: None,
+            import_mode: None,
+        };
+        entry::instantiate(deps.as_mut(), mock_env(), info.clone(), init_msg).unwrap();
+
+        let ships = [
+            ("Defiant", None, None),
+            ("Enterprise", Some("jeanluc"), Some(10)),
+            ("Voyager", None, Some(4)),
+        ];
+        for (token_id, address, percentage) in ships {
+            let exec_msg = ExecuteMsg::Mint {
+                token_id: token_id.to_string(),
+                owner: "starfleet".to_string(),
+                token_uri: None,
+                extension: Some(Metadata {
+                    royalty_payment_address: address.map(String::from),
+                    royalty_percentage: percentage,
+                    ..Metadata::default()
+                }),
+                approval: None,
+                metadata_hash: None,
+            };
+            entry::execute(deps.as_mut(), mock_env(), info.clone(), exec_msg).unwrap();
+        }
+
+        // tokens without royalty percentage are skipped, but still count towards page size
+        let res = query_all_royalties(deps.as_ref(), None, Some(2), None).unwrap();
+        assert_eq!(res.collection_default, None);
+        assert_eq!(
+            res.tokens,
+            vec![TokenRoyalty {
+                token_id: "Enterprise".to_string(),
+                royalty: RoyaltyConfig {
+                    payment_address: Some("jeanluc".to_string()),
+                    percentage: 10,
+                    bps: 1000,
+                },
+            }]
+        );
+        assert!(res.next_cursor.is_some());
+
+        let query_msg = QueryMsg::AllRoyalties {
+            start_after: None,
+            limit: Some(2),
+            cursor: res.next_cursor,
+        };
+        let res: AllRoyaltiesResponse =
+            from_json(entry::query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert_eq!(
+            res.tokens,
+            vec![TokenRoyalty {
+                token_id: "Voyager".to_string(),
+                royalty: RoyaltyConfig {
+                    payment_address: None,
+                    percentage: 4,
+                    bps: 400,
+                },
+            }]
+        );
+        assert_eq!(res.next_cursor, None);
+    }
+
+    #[test]
+    fn supports_royalties_interface() {
+        let deps = mock_dependencies();
+
+        let query_msg = QueryMsg::SupportedInterfaces {};
+        let res: SupportedInterfacesResponse =
+            from_json(entry::query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert!(res.interfaces.contains(&INTERFACE_BASE.to_string()));
+        assert!(res.interfaces.contains(&INTERFACE_ROYALTIES.to_string()));
+    }
 }
@@ -1,11 +1,12 @@
 use crate::Extension;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Binary, Uint128};
 use cw721::msg::Cw721QueryMsg;
 use cw721_base::{
     msg::{
         AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, MinterResponse, NftInfoResponse,
-        NumTokensResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse, TokensResponse,
+        NumTokensResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse,
+        SupportedInterfacesResponse, TokensResponse,
     },
     state::CollectionInfo,
 };
@@ -34,6 +35,15 @@ pub enum QueryMsg {
     /// (i.e. always check on sale)
     #[returns(CheckRoyaltiesResponse)]
     CheckRoyalties {},
+    /// Full royalty configuration, for dashboards rather than sales: the collection default
+    /// and paginated per-token royalties. Tokens minted without a royalty percentage are skipped.
+    #[returns(AllRoyaltiesResponse)]
+    AllRoyalties {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
+    },
 
     // -- below copied from Cw721QueryMsg
     /// Return the owner of the given token, error if token does not exist
@@ -50,11 +60,15 @@ pub enum QueryMsg {
         spender: String,
         include_expired: Option<bool>,
     },
-    /// Return approvals that a token has
+    /// Return approvals that a token has, ordered by spender unless `order_by_expiration` is set
     #[returns(ApprovalsResponse)]
     Approvals {
         token_id: String,
         include_expired: Option<bool>,
+        /// Only approvals of spenders starting with this prefix
+        spender_prefix: Option<String>,
+        /// Approvals expiring soonest first, height based before time based expirations
+        order_by_expiration: Option<bool>,
     },
     /// Return approval of a given operator for all tokens of an owner, error if not set
     #[returns(OperatorResponse)]
@@ -71,6 +85,8 @@ pub enum QueryMsg {
         include_expired: Option<bool>,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
     },
     /// Total number of tokens issued
     #[returns(NumTokensResponse)]
@@ -100,6 +116,8 @@ pub enum QueryMsg {
         token_id: String,
         /// unset or false will filter out expired approvals, you must set to true to see them
         include_expired: Option<bool>,
+        /// set to true to include the collection info
+        include_collection: Option<bool>,
     },
 
     /// With Enumerable extension.
@@ -109,6 +127,8 @@ pub enum QueryMsg {
         owner: String,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
     },
     /// With Enumerable extension.
     /// Requires pagination. Lists all token_ids controlled by the contract.
@@ -116,6 +136,8 @@ pub enum QueryMsg {
     AllTokens {
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
     },
 
     /// Return the minter
@@ -125,6 +147,10 @@ pub enum QueryMsg {
     #[returns(Option<String>)]
     GetWithdrawAddress {},
 
+    /// Returns identifiers of all interfaces implemented by this contract, including royalties
+    #[returns(SupportedInterfacesResponse)]
+    SupportedInterfaces {},
+
     // -- below queries, Extension and GetCollectionInfoExtension, are just dummies, since type annotations are required for
     // -- TMetadataExtension and TCollectionInfoExtension, Error:
     // -- "type annotations needed: cannot infer type for type parameter `TMetadataExtension` declared on the enum `Cw721QueryMsg`"
@@ -148,35 +174,48 @@ impl From<QueryMsg> for Cw721QueryMsg<Extension> {
             QueryMsg::AllNftInfo {
                 token_id,
                 include_expired,
+                include_collection,
             } => Cw721QueryMsg::AllNftInfo {
                 token_id,
                 include_expired,
+                include_collection,
             },
             QueryMsg::Tokens {
                 owner,
                 start_after,
                 limit,
+                cursor,
             } => Cw721QueryMsg::Tokens {
                 owner,
                 start_after,
                 limit,
+                cursor,
+            },
+            QueryMsg::AllTokens {
+                start_after,
+                limit,
+                cursor,
+            } => Cw721QueryMsg::AllTokens {
+                start_after,
+                limit,
+                cursor,
             },
-            QueryMsg::AllTokens { start_after, limit } => {
-                Cw721QueryMsg::AllTokens { start_after, limit }
-            }
             #[allow(deprecated)]
             QueryMsg::Minter {} => Cw721QueryMsg::Minter {},
             QueryMsg::GetWithdrawAddress {} => Cw721QueryMsg::GetWithdrawAddress {},
+            QueryMsg::SupportedInterfaces {} => Cw721QueryMsg::SupportedInterfaces {},
             QueryMsg::AllOperators {
                 owner,
                 include_expired,
                 start_after,
                 limit,
+                cursor,
             } => Cw721QueryMsg::AllOperators {
                 owner,
                 include_expired,
                 start_after,
                 limit,
+                cursor,
             },
             QueryMsg::Approval {
                 token_id,
@@ -190,9 +229,13 @@ impl From<QueryMsg> for Cw721QueryMsg<Extension> {
             QueryMsg::Approvals {
                 token_id,
                 include_expired,
+                spender_prefix,
+                order_by_expiration,
             } => Cw721QueryMsg::Approvals {
                 token_id,
                 include_expired,
+                spender_prefix,
+                order_by_expiration,
             },
             msg => unreachable!("Unsupported query: {:?}", msg),
         }
@@ -213,3 +256,27 @@ pub struct RoyaltiesInfoResponse {
 pub struct CheckRoyaltiesResponse {
     pub royalty_payments: bool,
 }
+
+/// Royalty terms, percentage as set on mint and the same value in basis points
+#[cw_serde]
+pub struct RoyaltyConfig {
+    pub payment_address: Option<String>,
+    pub percentage: u64,
+    pub bps: u64,
+}
+
+#[cw_serde]
+pub struct TokenRoyalty {
+    pub token_id: String,
+    pub royalty: RoyaltyConfig,
+}
+
+#[cw_serde]
+pub struct AllRoyaltiesResponse {
+    /// Applies to tokens without own royalties. Always `None` here, since this contract
+    /// only supports token level royalties, contracts extending it may set one.
+    pub collection_default: Option<RoyaltyConfig>,
+    pub tokens: Vec<TokenRoyalty>,
+    /// Set if more tokens may follow, pass as `cursor` to fetch the next page
+    pub next_cursor: Option<Binary>,
+}
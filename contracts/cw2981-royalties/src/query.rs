@@ -1,7 +1,14 @@
-use crate::msg::{CheckRoyaltiesResponse, RoyaltiesInfoResponse};
+use crate::msg::{
+    AllRoyaltiesResponse, CheckRoyaltiesResponse, RoyaltiesInfoResponse, RoyaltyConfig,
+    TokenRoyalty,
+};
 use crate::Cw2981Contract;
-use cosmwasm_std::{Decimal, Deps, Env, StdResult, Uint128};
-use cw721_base::query::Cw721Query;
+use cosmwasm_std::{Binary, Decimal, Deps, Env, Order, StdResult, Uint128};
+use cw721_base::msg::SupportedInterfacesResponse;
+use cw721_base::query::{
+    cursor_start_after, next_cursor, Cw721Query, DEFAULT_LIMIT, INTERFACE_ROYALTIES, MAX_LIMIT,
+};
+use cw_storage_plus::Bound;
 
 /// NOTE: default behaviour here is to round down
 /// EIP2981 specifies that the rounding behaviour is at the discretion of the implementer
@@ -37,6 +44,53 @@ pub fn query_royalties_info(
     })
 }
 
+/// Lists royalties of all tokens having a royalty percentage. Pages are cut by scanned tokens,
+/// so a page may hold fewer than `limit` entries while `next_cursor` is still set.
+pub fn query_all_royalties(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    cursor: Option<Binary>,
+) -> StdResult<AllRoyaltiesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = cursor_start_after(start_after, cursor)?;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let scanned = Cw2981Contract::default()
+        .config
+        .nft_info
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let next_cursor = next_cursor(
+        scanned.last().map(|(token_id, _)| token_id),
+        scanned.len(),
+        limit,
+    )?;
+
+    let tokens = scanned
+        .into_iter()
+        .filter_map(|(token_id, info)| {
+            let ext = info.extension?;
+            let percentage = ext.royalty_percentage?;
+            Some(TokenRoyalty {
+                token_id,
+                royalty: RoyaltyConfig {
+                    payment_address: ext.royalty_payment_address,
+                    percentage,
+                    bps: percentage * 100,
+                },
+            })
+        })
+        .collect();
+
+    Ok(AllRoyaltiesResponse {
+        collection_default: None,
+        tokens,
+        next_cursor,
+    })
+}
+
 /// As our default implementation here specifies royalties at token level
 /// and not at contract level, it is therefore logically true that
 /// on sale, every token managed by this contract should be checked
@@ -47,3 +101,10 @@ pub fn check_royalties(_deps: Deps) -> StdResult<CheckRoyaltiesResponse> {
         royalty_payments: true,
     })
 }
+
+/// Interfaces of cw721 base, extended by royalties (cw2981)
+pub fn query_supported_interfaces() -> StdResult<SupportedInterfacesResponse> {
+    let mut response = Cw2981Contract::default().query_supported_interfaces()?;
+    response.interfaces.push(INTERFACE_ROYALTIES.to_string());
+    Ok(response)
+}
@@ -0,0 +1,83 @@
+//! cw721 with a chain specific custom message, e.g. for chains like Injective or Osmosis
+//! which dispatch their own `CosmosMsg::Custom` messages. Compiled with `cargo test`, so
+//! entry points and handlers are checked to be generic over the custom message type.
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{from_json, CustomMsg, Empty, Response, Uint128};
+use cw721_base::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, NumTokensResponse, QueryMsg};
+use cw721_base::state::DefaultOptionMetadataExtension;
+
+/// Mock of a chain message wrapper, like `InjectiveMsgWrapper`
+#[cw_serde]
+pub enum ChainMsg {
+    MintTokens { denom: String, amount: Uint128 },
+}
+
+impl CustomMsg for ChainMsg {}
+
+pub const CONTRACT_NAME: &str = "crates.io:cw721-custom-msg-example";
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+cw721_base::cw721_entry_points!(DefaultOptionMetadataExtension, Empty, ChainMsg);
+
+fn main() {
+    let mut deps = mock_dependencies();
+    let info = mock_info("creator", &[]);
+    let res: Response<ChainMsg> = entry::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        info.clone(),
+        InstantiateMsg {
+            name: "Custom".to_string(),
+            symbol: "CSTM".to_string(),
+            minter: None,
+            withdraw_address: None,
+            initial_tokens: None,
+            import_mode: None,
+        },
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+
+    let res: Response<ChainMsg> = entry::execute(
+        deps.as_mut(),
+        mock_env(),
+        info,
+        ExecuteMsg::Mint {
+            token_id: "1".to_string(),
+            owner: "creator".to_string(),
+            token_uri: None,
+            extension: None,
+            approval: None,
+            metadata_hash: None,
+        },
+    )
+    .unwrap();
+    // base handlers don't emit custom messages, but responses can be extended with them
+    let res = res.add_message(ChainMsg::MintTokens {
+        denom: "reward".to_string(),
+        amount: Uint128::new(1),
+    });
+    assert_eq!(res.messages.len(), 1);
+
+    let num_tokens: NumTokensResponse =
+        from_json(entry::query(deps.as_ref(), mock_env(), QueryMsg::NumTokens {}).unwrap())
+            .unwrap();
+    assert_eq!(num_tokens.count, 1);
+
+    let _: Response<ChainMsg> = entry::migrate(
+        deps.as_mut(),
+        mock_env(),
+        MigrateMsg::WithUpdate {
+            minter: None,
+            creator: None,
+            withdraw_address: None,
+            name: None,
+            symbol: None,
+            allow_unknown: None,
+            two_phase: None,
+            import_mode: None,
+        },
+    )
+    .unwrap();
+}
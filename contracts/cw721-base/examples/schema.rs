@@ -1,7 +1,8 @@
 use cosmwasm_schema::{export_schema_with_title, remove_schemas, schema_for};
 use cosmwasm_std::Empty;
-use cw721::state::DefaultOptionMetadataExtension;
-use cw721_base::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use cw721::msg::Cw721ExecuteMsgOf;
+use cw721_base::msg::{InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
+use cw721_base::EntryPointTypes;
 use std::env::current_dir;
 use std::fs::create_dir_all;
 
@@ -14,10 +15,11 @@ fn main() {
     // entry points - generate always with title for avoiding name suffixes like "..._empty_for_..." due to generics
     export_schema_with_title(&schema_for!(InstantiateMsg), &out_dir, "InstantiateMsg");
     export_schema_with_title(
-        &schema_for!(ExecuteMsg::<DefaultOptionMetadataExtension, Empty>),
+        &schema_for!(Cw721ExecuteMsgOf<EntryPointTypes>),
         &out_dir,
         "ExecuteMsg",
     );
     export_schema_with_title(&schema_for!(QueryMsg<Empty>), &out_dir, "QueryMsg");
+    export_schema_with_title(&schema_for!(SudoMsg), &out_dir, "SudoMsg");
     export_schema_with_title(&schema_for!(MigrateMsg), &out_dir, "MigrateMsg");
 }
@@ -0,0 +1,139 @@
+/// Generates a `pub mod entry` with instantiate, execute, query, sudo and migrate entry points,
+/// wired to `Cw721Contract` with the given metadata extension, extension msg and custom
+/// response msg types. Like in this crate, exports are disabled by the `library` feature.
+///
+/// The calling module must define `CONTRACT_NAME` and `CONTRACT_VERSION` (used for cw2),
+/// and the calling crate must depend on `cosmwasm-std`.
+/// An optional 4th argument lists other contract names (as stored in cw2) which may be migrated
+/// to this contract, by default `BASE_COMPATIBLE_CONTRACT_NAMES`.
+/// With the calling crate's `legacy-msg` feature, execute also accepts legacy msg shapes (see `legacy`).
+///
+/// ```ignore
+/// use cosmwasm_std::Empty;
+/// use cw721_base::state::DefaultOptionMetadataExtension;
+///
+/// pub const CONTRACT_NAME: &str = "crates.io:my-nft";
+/// pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+///
+/// cw721_base::cw721_entry_points!(DefaultOptionMetadataExtension, Empty, Empty);
+/// ```
+#[macro_export]
+macro_rules! cw721_entry_points {
+    ($metadata_extension:ty, $metadata_extension_msg:ty, $custom_response_msg:ty) => {
+        $crate::cw721_entry_points!(
+            $metadata_extension,
+            $metadata_extension_msg,
+            $custom_response_msg,
+            $crate::execute::BASE_COMPATIBLE_CONTRACT_NAMES
+        );
+    };
+    (
+        $metadata_extension:ty,
+        $metadata_extension_msg:ty,
+        $custom_response_msg:ty,
+        $compatible_contract_names:expr
+    ) => {
+        pub mod entry {
+            // extension types are resolved from the calling module
+            #[allow(unused_imports)]
+            use super::*;
+
+            #[cfg(not(feature = "library"))]
+            use ::cosmwasm_std::entry_point;
+            use ::cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+            use $crate::{
+                error::Cw721ContractError,
+                execute::Cw721Execute,
+                msg::{
+                    Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721MigrateMsg, Cw721QueryMsg,
+                    Cw721SudoMsg,
+                },
+                query::Cw721Query,
+                Cw721Contract,
+            };
+
+            type EntryPointContract = Cw721Contract<
+                'static,
+                $metadata_extension,
+                $custom_response_msg,
+                $metadata_extension_msg,
+            >;
+
+            #[cfg_attr(not(feature = "library"), entry_point)]
+            pub fn instantiate(
+                deps: DepsMut,
+                env: Env,
+                info: MessageInfo,
+                msg: Cw721InstantiateMsg,
+            ) -> Result<Response<$custom_response_msg>, Cw721ContractError> {
+                EntryPointContract::default().instantiate(
+                    deps,
+                    env,
+                    info,
+                    msg,
+                    super::CONTRACT_NAME,
+                    super::CONTRACT_VERSION,
+                )
+            }
+
+            #[cfg(not(feature = "legacy-msg"))]
+            #[cfg_attr(not(feature = "library"), entry_point)]
+            pub fn execute(
+                deps: DepsMut,
+                env: Env,
+                info: MessageInfo,
+                msg: Cw721ExecuteMsg<$metadata_extension, $metadata_extension_msg>,
+            ) -> Result<Response<$custom_response_msg>, Cw721ContractError> {
+                EntryPointContract::default().execute(deps, env, info, msg)
+            }
+
+            #[cfg(feature = "legacy-msg")]
+            #[cfg_attr(not(feature = "library"), entry_point)]
+            pub fn execute(
+                deps: DepsMut,
+                env: Env,
+                info: MessageInfo,
+                msg: $crate::legacy::Cw721CompatExecuteMsg<
+                    $metadata_extension,
+                    $metadata_extension_msg,
+                >,
+            ) -> Result<Response<$custom_response_msg>, Cw721ContractError> {
+                EntryPointContract::default().execute(deps, env, info, msg.into_current()?)
+            }
+
+            #[cfg_attr(not(feature = "library"), entry_point)]
+            pub fn query(
+                deps: Deps,
+                env: Env,
+                msg: Cw721QueryMsg<$metadata_extension>,
+            ) -> StdResult<Binary> {
+                EntryPointContract::default().query(deps, env, msg)
+            }
+
+            #[cfg_attr(not(feature = "library"), entry_point)]
+            pub fn sudo(
+                deps: DepsMut,
+                env: Env,
+                msg: Cw721SudoMsg,
+            ) -> Result<Response<$custom_response_msg>, Cw721ContractError> {
+                EntryPointContract::default().sudo(deps, env, msg)
+            }
+
+            #[cfg_attr(not(feature = "library"), entry_point)]
+            pub fn migrate(
+                deps: DepsMut,
+                env: Env,
+                msg: Cw721MigrateMsg,
+            ) -> Result<Response<$custom_response_msg>, Cw721ContractError> {
+                EntryPointContract::default().migrate(
+                    deps,
+                    env,
+                    msg,
+                    super::CONTRACT_NAME,
+                    super::CONTRACT_VERSION,
+                    $compatible_contract_names,
+                )
+            }
+        }
+    };
+}
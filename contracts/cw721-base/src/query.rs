@@ -15,3 +15,13 @@ where
     TMetadataExtensionMsg: CustomMsg,
 {
 }
+
+impl<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+    Cw721CustomQuery<TMetadataExtension>
+    for Cw721Contract<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TCustomResponseMessage: CustomMsg,
+    TMetadataExtensionMsg: CustomMsg,
+{
+}
@@ -33,3 +33,17 @@ where
         }
     }
 }
+
+/// Contract for a `Cw721Types` bundle, e.g. `Cw721ContractOf<'a, EmptyCw721>`.
+pub type Cw721ContractOf<'a, T> = Cw721Contract<
+    'a,
+    <T as Cw721Types>::MetadataExtension,
+    <T as Cw721Types>::CustomResponseMessage,
+    <T as Cw721Types>::MetadataExtensionMsg,
+>;
+
+/// Contract with onchain `Metadata` as optional extension, as used by the entry points.
+pub type DefaultCw721Contract<'a> = Cw721ContractOf<'a, DefaultCw721>;
+
+/// Contract without metadata extension.
+pub type EmptyCw721Contract<'a> = Cw721ContractOf<'a, EmptyCw721>;
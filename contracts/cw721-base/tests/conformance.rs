@@ -0,0 +1,18 @@
+use cosmwasm_std::Empty;
+use cw721_conformance::MinterHarness;
+use cw_multi_test::{Contract, ContractWrapper};
+
+fn cw721_base_contract() -> Box<dyn Contract<Empty>> {
+    Box::new(ContractWrapper::new(
+        cw721_base::entry::execute,
+        cw721_base::entry::instantiate,
+        cw721_base::entry::query,
+    ))
+}
+
+#[test]
+fn cw721_base_conformance() {
+    cw721_conformance::run_all(&MinterHarness {
+        contract: cw721_base_contract,
+    });
+}
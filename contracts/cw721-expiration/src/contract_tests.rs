@@ -7,12 +7,13 @@ use cosmwasm_std::{
 };
 
 use cw721::error::Cw721ContractError;
+use cw721::execute::add_cw721_event;
 use cw721::msg::{
     ApprovalResponse, Cw721ExecuteMsg, NftInfoResponse, OperatorResponse, OperatorsResponse,
     OwnerOfResponse, TokensResponse,
 };
 use cw721::receiver::Cw721ReceiveMsg;
-use cw721::state::{CollectionInfo, MINTER};
+use cw721::state::{CollectionInfo, APPROVAL_PERMISSION_ALL, MINTER};
 use cw721::{query::Cw721Query, Approval, Expiration};
 use cw_ownable::{Action, Ownership, OwnershipError};
 
@@ -79,6 +80,8 @@ fn proper_instantiation() {
         CollectionInfo {
             name: CONTRACT_NAME.to_string(),
             symbol: SYMBOL.to_string(),
+            royalty_payout_denoms: None,
+            origin: None,
         }
     );
 
@@ -137,6 +140,8 @@ fn proper_instantiation_with_collection_info() {
         CollectionInfo {
             name: CONTRACT_NAME.to_string(),
             symbol: SYMBOL.to_string(),
+            royalty_payout_denoms: None,
+            origin: None,
         }
     );
 
@@ -174,6 +179,8 @@ fn test_mint() {
         owner: String::from("medusa"),
         token_uri: Some(token_uri.clone()),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     // random cannot mint
@@ -248,6 +255,8 @@ fn test_mint() {
         owner: String::from("hercules"),
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     let allowed = mock_info(MINTER_ADDR, &[]);
@@ -277,6 +286,8 @@ fn test_update_minter() {
         owner: String::from("medusa"),
         token_uri: Some(token_uri.clone()),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     // Minter can mint
@@ -342,6 +353,8 @@ fn test_update_minter() {
         owner: String::from("medusa"),
         token_uri: Some(token_uri),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     // Old owner can not mint.
@@ -372,6 +385,8 @@ fn test_burn() {
         owner: MINTER_ADDR.to_string(),
         token_uri: Some(token_uri),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     let burn_msg = Cw721ExecuteMsg::Burn {
@@ -460,6 +475,8 @@ fn test_transfer_nft() {
         owner: String::from(owner),
         token_uri: Some(token_uri),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     let mut env = mock_env();
@@ -502,11 +519,14 @@ fn test_transfer_nft() {
 
     assert_eq!(
         res,
-        Response::new()
-            .add_attribute("action", "transfer_nft")
-            .add_attribute("sender", owner)
-            .add_attribute("recipient", new_owner)
-            .add_attribute("token_id", token_id.clone())
+        add_cw721_event(
+            Response::new()
+                .add_attribute("action", "transfer_nft")
+                .add_attribute("sender", owner)
+                .add_attribute("recipient", new_owner)
+                .add_attribute("token_id", token_id.clone()),
+            "transfer",
+        ),
     );
 
     // assert invalid nft throws error
@@ -540,6 +560,8 @@ fn test_send_nft() {
         owner: String::from("venus"),
         token_uri: Some(token_uri),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     let mut env = mock_env();
@@ -587,12 +609,15 @@ fn test_send_nft() {
     // and make sure this is the request sent by the contract
     assert_eq!(
         res,
-        Response::new()
-            .add_message(expected)
-            .add_attribute("action", "send_nft")
-            .add_attribute("sender", "venus")
-            .add_attribute("recipient", "another_contract")
-            .add_attribute("token_id", token_id.clone())
+        add_cw721_event(
+            Response::new()
+                .add_message(expected)
+                .add_attribute("action", "send_nft")
+                .add_attribute("sender", "venus")
+                .add_attribute("recipient", "another_contract")
+                .add_attribute("token_id", token_id.clone()),
+            "send",
+        ),
     );
 
     // assert invalid nft throws error
@@ -626,6 +651,8 @@ fn test_approve_revoke() {
         owner: String::from("demeter"),
         token_uri: Some(token_uri),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     let mut env = mock_env();
@@ -650,7 +677,8 @@ fn test_approve_revoke() {
         ApprovalResponse {
             approval: Approval {
                 spender: Addr::unchecked("demeter"),
-                expires: Expiration::Never {}
+                expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_ALL,
             }
         }
     );
@@ -660,6 +688,7 @@ fn test_approve_revoke() {
         spender: String::from("random"),
         token_id: token_id.clone(),
         expires: None,
+        permissions: None,
     };
     let owner = mock_info("demeter", &[]);
     let res = contract
@@ -667,11 +696,14 @@ fn test_approve_revoke() {
         .unwrap();
     assert_eq!(
         res,
-        Response::new()
-            .add_attribute("action", "approve")
-            .add_attribute("sender", "demeter")
-            .add_attribute("spender", "random")
-            .add_attribute("token_id", token_id.clone())
+        add_cw721_event(
+            Response::new()
+                .add_attribute("action", "approve")
+                .add_attribute("sender", "demeter")
+                .add_attribute("spender", "random")
+                .add_attribute("token_id", token_id.clone()),
+            "approve",
+        ),
     );
 
     // test approval query
@@ -690,7 +722,8 @@ fn test_approve_revoke() {
         ApprovalResponse {
             approval: Approval {
                 spender: Addr::unchecked("random"),
-                expires: Expiration::Never {}
+                expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_ALL,
             }
         }
     );
@@ -730,6 +763,7 @@ fn test_approve_revoke() {
         spender: String::from("random"),
         token_id: token_id.clone(),
         expires: None,
+        permissions: None,
     };
     let owner = mock_info("person", &[]);
     contract
@@ -816,6 +850,8 @@ fn test_approve_all_revoke_all() {
         owner: String::from("demeter"),
         token_uri: Some(token_uri1),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     let minter = mock_info(MINTER_ADDR, &[]);
@@ -828,6 +864,8 @@ fn test_approve_all_revoke_all() {
         owner: String::from("demeter"),
         token_uri: Some(token_uri2),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     contract
@@ -856,6 +894,7 @@ fn test_approve_all_revoke_all() {
     let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
         operator: String::from("random"),
         expires: None,
+        filter: None,
     };
     let owner = mock_info("demeter", &[]);
     let res = contract
@@ -863,10 +902,13 @@ fn test_approve_all_revoke_all() {
         .unwrap();
     assert_eq!(
         res,
-        Response::new()
-            .add_attribute("action", "approve_all")
-            .add_attribute("sender", "demeter")
-            .add_attribute("operator", "random")
+        add_cw721_event(
+            Response::new()
+                .add_attribute("action", "approve_all")
+                .add_attribute("sender", "demeter")
+                .add_attribute("operator", "random"),
+            "approve_all",
+        ),
     );
 
     // random can now transfer
@@ -900,6 +942,7 @@ fn test_approve_all_revoke_all() {
     let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
         operator: String::from("operator"),
         expires: None,
+        filter: None,
     };
     // person is now the owner of the tokens
     let owner = mock_info("person", &[]);
@@ -923,7 +966,8 @@ fn test_approve_all_revoke_all() {
         OperatorResponse {
             approval: Approval {
                 spender: Addr::unchecked("operator"),
-                expires: Expiration::Never {}
+                expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_ALL,
             }
         }
     );
@@ -957,8 +1001,10 @@ fn test_approve_all_revoke_all() {
         OperatorsResponse {
             operators: vec![cw721::Approval {
                 spender: Addr::unchecked("operator"),
-                expires: Expiration::Never {}
-            }]
+                expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_ALL,
+            }],
+            next_cursor: None,
         }
     );
 
@@ -967,6 +1013,7 @@ fn test_approve_all_revoke_all() {
     let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
         operator: String::from("buddy"),
         expires: Some(buddy_expires),
+        filter: None,
     };
     let owner = mock_info("person", &[]);
     contract
@@ -991,7 +1038,9 @@ fn test_approve_all_revoke_all() {
             operators: vec![cw721::Approval {
                 spender: Addr::unchecked("buddy"),
                 expires: buddy_expires,
-            }]
+                permissions: APPROVAL_PERMISSION_ALL,
+            }],
+            next_cursor: Some(to_json_binary("buddy").unwrap()),
         }
     );
     let res = contract
@@ -1010,8 +1059,10 @@ fn test_approve_all_revoke_all() {
         OperatorsResponse {
             operators: vec![cw721::Approval {
                 spender: Addr::unchecked("operator"),
-                expires: Expiration::Never {}
-            }]
+                expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_ALL,
+            }],
+            next_cursor: None,
         }
     );
 
@@ -1053,7 +1104,9 @@ fn test_approve_all_revoke_all() {
             operators: vec![cw721::Approval {
                 spender: Addr::unchecked("buddy"),
                 expires: buddy_expires,
-            }]
+                permissions: APPROVAL_PERMISSION_ALL,
+            }],
+            next_cursor: None,
         }
     );
 
@@ -1106,6 +1159,8 @@ fn test_tokens_by_owner() {
         owner: demeter.clone(),
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
     contract
         .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
@@ -1116,6 +1171,8 @@ fn test_tokens_by_owner() {
         owner: ceres.clone(),
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
     contract
         .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
@@ -1126,6 +1183,8 @@ fn test_tokens_by_owner() {
         owner: demeter.clone(),
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
     contract
         .execute(deps.as_mut(), mock_env(), minter, mint_msg)
@@ -1213,6 +1272,8 @@ fn test_nft_info() {
         owner,
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter, mint_msg)
@@ -1255,28 +1316,39 @@ fn test_all_nft_info() {
         owner,
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter, mint_msg)
         .unwrap();
 
     // assert valid nft is returned
-    contract
+    let res = contract
         .query_all_nft_info_include_expired_nft(
             deps.as_ref(),
             env.clone(),
             token_id.clone(),
             false,
             false,
+            true,
         )
         .unwrap();
+    assert_eq!(res.collection.unwrap().name, CONTRACT_NAME);
 
     // assert invalid nft throws error
     let mint_date = env.block.time;
     let expiration = env.block.time.plus_days(1);
     env.block.time = expiration;
     let error = contract
-        .query_all_nft_info_include_expired_nft(deps.as_ref(), env, token_id.clone(), false, false)
+        .query_all_nft_info_include_expired_nft(
+            deps.as_ref(),
+            env,
+            token_id.clone(),
+            false,
+            false,
+            false,
+        )
         .unwrap_err();
     assert_eq!(
         error,
@@ -1303,6 +1375,8 @@ fn test_owner_of() {
         owner,
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter, mint_msg)
@@ -1336,6 +1410,42 @@ fn test_owner_of() {
     );
 }
 
+#[test]
+fn test_token_status() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut(), 1);
+    let minter = mock_info(MINTER_ADDR, &[]);
+
+    let token_id = "grow1".to_string();
+    let mut env = mock_env();
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("ark"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    contract
+        .execute(deps.as_mut(), env.clone(), minter, mint_msg)
+        .unwrap();
+
+    let status = contract
+        .query_token_status(deps.as_ref(), env.clone(), token_id.clone())
+        .unwrap();
+    assert!(status.transferable);
+    assert!(!status.expired);
+
+    // expired token is still reported, but not transferable
+    env.block.time = env.block.time.plus_days(1);
+    let status = contract
+        .query_token_status(deps.as_ref(), env, token_id)
+        .unwrap();
+    assert_eq!(status.owner, "ark");
+    assert!(!status.transferable);
+    assert!(status.expired);
+}
+
 #[test]
 fn test_approval() {
     let mut deps = mock_dependencies();
@@ -1351,6 +1461,8 @@ fn test_approval() {
         owner: owner.clone(),
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter, mint_msg)
@@ -1407,6 +1519,8 @@ fn test_approvals() {
         owner,
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter, mint_msg)
@@ -1455,6 +1569,8 @@ fn test_tokens() {
         owner: owner.clone(),
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter, mint_msg)
@@ -1485,7 +1601,13 @@ fn test_tokens() {
             false,
         )
         .unwrap();
-    assert_eq!(tokens, TokensResponse { tokens: vec![] });
+    assert_eq!(
+        tokens,
+        TokensResponse {
+            tokens: vec![],
+            next_cursor: None,
+        }
+    );
 
     // assert invalid nft is returned
     let tokens = contract
@@ -1494,7 +1616,8 @@ fn test_tokens() {
     assert_eq!(
         tokens,
         TokensResponse {
-            tokens: [token_id].to_vec()
+            tokens: [token_id].to_vec(),
+            next_cursor: None,
         }
     );
 }
@@ -1514,6 +1637,8 @@ fn test_all_tokens() {
         owner: owner.clone(),
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), minter, mint_msg)
@@ -1530,7 +1655,13 @@ fn test_all_tokens() {
     let tokens = contract
         .query_tokens_include_expired_nft(deps.as_ref(), env.clone(), owner, None, None, false)
         .unwrap();
-    assert_eq!(tokens, TokensResponse { tokens: vec![] });
+    assert_eq!(
+        tokens,
+        TokensResponse {
+            tokens: vec![],
+            next_cursor: None,
+        }
+    );
 
     // assert invalid nft is returned
     let tokens = contract
@@ -1539,7 +1670,8 @@ fn test_all_tokens() {
     assert_eq!(
         tokens,
         TokensResponse {
-            tokens: [token_id].to_vec()
+            tokens: [token_id].to_vec(),
+            next_cursor: None,
         }
     );
 }
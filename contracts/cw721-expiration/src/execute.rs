@@ -1,7 +1,8 @@
-use cosmwasm_std::{Binary, CustomMsg, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{Binary, CustomMsg, DepsMut, Env, HexBinary, MessageInfo, Response};
 use cw721::{
-    execute::Cw721Execute,
-    msg::{Cw721ExecuteMsg, Cw721InstantiateMsg},
+    execute::{add_cw721_event, event_name, Cw721Execute},
+    msg::{Cw721ExecuteMsg, Cw721InstantiateMsg, ImportedToken, MintApproval},
+    state::APPROVAL_PERMISSION_BURN,
     Expiration,
 };
 use serde::de::DeserializeOwned;
@@ -47,6 +48,8 @@ where
                 symbol: msg.symbol,
                 minter: msg.minter,
                 withdraw_address: msg.withdraw_address,
+                initial_tokens: None,
+                import_mode: None,
             },
             CONTRACT_NAME,
             CONTRACT_VERSION,
@@ -66,20 +69,80 @@ where
             TCustomResponseMessage,
             TMetadataExtensionMsg,
         >::default();
-        match msg {
+        let event = event_name(&msg);
+        let response = match msg {
             Cw721ExecuteMsg::Mint {
                 token_id,
                 owner,
                 token_uri,
                 extension,
-            } => {
-                contract.mint_with_timestamp(deps, env, info, token_id, owner, token_uri, extension)
+                approval,
+                metadata_hash,
+            } => contract.mint_with_timestamp(
+                deps,
+                env,
+                info,
+                token_id,
+                owner,
+                token_uri,
+                extension,
+                approval,
+                metadata_hash,
+            ),
+            Cw721ExecuteMsg::MintAndSend {
+                token_id,
+                contract: recipient,
+                token_uri,
+                extension,
+                msg,
+                metadata_hash,
+            } => contract.mint_and_send_with_timestamp(
+                deps,
+                env,
+                info,
+                token_id,
+                recipient,
+                token_uri,
+                extension,
+                msg,
+                metadata_hash,
+            ),
+            Cw721ExecuteMsg::ImportTokens { tokens } => {
+                contract.import_tokens_with_timestamp(deps, env, info, tokens)
             }
             Cw721ExecuteMsg::Approve {
                 spender,
                 token_id,
                 expires,
-            } => contract.approve_include_nft_expired(deps, env, info, spender, token_id, expires),
+                permissions,
+            } => contract.approve_include_nft_expired(
+                deps,
+                env,
+                info,
+                spender,
+                token_id,
+                expires,
+                permissions,
+            ),
+            Cw721ExecuteMsg::ApproveBurner {
+                spender,
+                token_id,
+                expires,
+            } => contract.approve_include_nft_expired(
+                deps,
+                env,
+                info,
+                spender,
+                token_id,
+                expires,
+                Some(APPROVAL_PERMISSION_BURN),
+            ),
+            Cw721ExecuteMsg::ApproveBatch {
+                spender,
+                token_ids,
+                expires,
+            } => contract
+                .approve_batch_include_nft_expired(deps, env, info, spender, token_ids, expires),
             Cw721ExecuteMsg::Revoke { spender, token_id } => {
                 contract.revoke_include_nft_expired(deps, env, info, spender, token_id)
             }
@@ -92,14 +155,21 @@ where
                 token_id,
                 msg,
             } => contract.send_nft_include_nft_expired(deps, env, info, recipient, token_id, msg),
+            Cw721ExecuteMsg::ClaimTransfer { token_id } => {
+                contract.claim_transfer_include_nft_expired(deps, env, info, token_id)
+            }
+            Cw721ExecuteMsg::LockGift {
+                token_id,
+                hash,
+                expires,
+            } => contract.lock_gift_include_nft_expired(deps, env, info, token_id, hash, expires),
             Cw721ExecuteMsg::Burn { token_id } => {
                 contract.burn_nft_include_nft_expired(deps, env, info, token_id)
             }
-            _ => {
-                let response = contract.base_contract.execute(deps, env, info, msg)?;
-                Ok(response)
-            }
-        }
+            // base contract emits the typed event itself
+            _ => return Ok(contract.base_contract.execute(deps, env, info, msg)?),
+        }?;
+        Ok(add_cw721_event(response, event))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -112,17 +182,83 @@ where
         owner: String,
         token_uri: Option<String>,
         extension: TMetadataExtension,
+        approval: Option<MintApproval>,
+        metadata_hash: Option<HexBinary>,
+    ) -> Result<Response<TCustomResponseMessage>, ContractError> {
+        let mint_timstamp = env.block.time;
+        self.mint_timestamps
+            .save(deps.storage, &token_id, &mint_timstamp)?;
+        let res = self
+            .base_contract
+            .mint(
+                deps,
+                &env,
+                info,
+                token_id,
+                owner,
+                token_uri,
+                extension,
+                approval,
+                metadata_hash,
+            )?
+            .add_attribute("mint_timestamp", mint_timstamp.to_string());
+        Ok(res)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn mint_and_send_with_timestamp(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        contract: String,
+        token_uri: Option<String>,
+        extension: TMetadataExtension,
+        msg: Binary,
+        metadata_hash: Option<HexBinary>,
     ) -> Result<Response<TCustomResponseMessage>, ContractError> {
         let mint_timstamp = env.block.time;
         self.mint_timestamps
             .save(deps.storage, &token_id, &mint_timstamp)?;
         let res = self
             .base_contract
-            .mint(deps, info, token_id, owner, token_uri, extension)?
+            .mint_and_send(
+                deps,
+                &env,
+                info,
+                token_id,
+                contract,
+                token_uri,
+                extension,
+                msg,
+                metadata_hash,
+            )?
             .add_attribute("mint_timestamp", mint_timstamp.to_string());
         Ok(res)
     }
 
+    /// Imported tokens expire the same as tokens minted at import time
+    pub fn import_tokens_with_timestamp(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        tokens: Vec<ImportedToken<TMetadataExtension>>,
+    ) -> Result<Response<TCustomResponseMessage>, ContractError> {
+        let mint_timstamp = env.block.time;
+        for token in tokens.iter() {
+            self.mint_timestamps
+                .save(deps.storage, &token.token_id, &mint_timstamp)?;
+        }
+        let res = self
+            .base_contract
+            .import_tokens(deps, &env, info, tokens)?
+            .add_attribute("mint_timestamp", mint_timstamp.to_string());
+        Ok(res)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn approve_include_nft_expired(
         &self,
         deps: DepsMut,
@@ -131,11 +267,29 @@ where
         spender: String,
         token_id: String,
         expires: Option<Expiration>,
+        permissions: Option<u8>,
     ) -> Result<Response<TCustomResponseMessage>, ContractError> {
         self.assert_nft_expired(deps.as_ref(), &env, token_id.as_str())?;
         Ok(self
             .base_contract
-            .approve(deps, env, info, spender, token_id, expires)?)
+            .approve(deps, env, info, spender, token_id, expires, permissions)?)
+    }
+
+    pub fn approve_batch_include_nft_expired(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        spender: String,
+        token_ids: Vec<String>,
+        expires: Option<Expiration>,
+    ) -> Result<Response<TCustomResponseMessage>, ContractError> {
+        for token_id in token_ids.iter() {
+            self.assert_nft_expired(deps.as_ref(), &env, token_id)?;
+        }
+        Ok(self
+            .base_contract
+            .approve_batch(deps, env, info, spender, token_ids, expires)?)
     }
 
     pub fn revoke_include_nft_expired(
@@ -181,6 +335,34 @@ where
             .send_nft(deps, env, info, contract, token_id, msg)?)
     }
 
+    pub fn claim_transfer_include_nft_expired(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<TCustomResponseMessage>, ContractError> {
+        self.assert_nft_expired(deps.as_ref(), &env, token_id.as_str())?;
+        Ok(self
+            .base_contract
+            .claim_transfer(deps, env, info, token_id)?)
+    }
+
+    pub fn lock_gift_include_nft_expired(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+        hash: HexBinary,
+        expires: Expiration,
+    ) -> Result<Response<TCustomResponseMessage>, ContractError> {
+        self.assert_nft_expired(deps.as_ref(), &env, token_id.as_str())?;
+        Ok(self
+            .base_contract
+            .lock_gift(deps, env, info, token_id, hash, expires)?)
+    }
+
     pub fn burn_nft_include_nft_expired(
         &self,
         deps: DepsMut,
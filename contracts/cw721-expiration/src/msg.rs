@@ -1,6 +1,6 @@
 use crate::DefaultOptionMetadataExtension;
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Binary};
 use cw721::state::CollectionInfo;
 use cw_ownable::Ownership;
 
@@ -77,8 +77,14 @@ pub enum QueryMsg<TMetadataExtension> {
         include_expired: Option<bool>,
         /// unset or false will filter out expired nfts, you must set to true to see them
         include_expired_nft: Option<bool>,
+        /// set to true to include the collection info
+        include_collection: Option<bool>,
     },
 
+    /// Owner and whether the token can currently be transferred, including expired tokens
+    #[returns(cw721::msg::TokenStatusResponse)]
+    TokenStatus { token_id: String },
+
     /// With Enumerable extension.
     /// Returns all tokens owned by the given address, [] if unset.
     #[returns(cw721::msg::TokensResponse)]
@@ -88,6 +94,8 @@ pub enum QueryMsg<TMetadataExtension> {
         limit: Option<u32>,
         /// unset or false will filter out expired nfts, you must set to true to see them
         include_expired_nft: Option<bool>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
     },
 
     /// With Enumerable extension.
@@ -98,6 +106,8 @@ pub enum QueryMsg<TMetadataExtension> {
         limit: Option<u32>,
         /// unset or false will filter out expired nfts, you must set to true to see them
         include_expired_nft: Option<bool>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
     },
 
     // -------- below is from cw721/src/msg.rs --------
@@ -116,6 +126,8 @@ pub enum QueryMsg<TMetadataExtension> {
         include_expired: Option<bool>,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
     },
     /// Total number of tokens issued, including all expired NFTs
     #[returns(cw721::msg::NumTokensResponse)]
@@ -145,4 +157,8 @@ pub enum QueryMsg<TMetadataExtension> {
 
     #[returns(Option<String>)]
     GetWithdrawAddress {},
+
+    /// Returns identifiers of all interfaces implemented by this contract, including expiration
+    #[returns(SupportedInterfacesResponse)]
+    SupportedInterfaces {},
 }
@@ -1,9 +1,9 @@
 use cosmwasm_std::{to_json_binary, Binary, CustomMsg, Deps, Env, StdResult};
 use cw721::msg::{
     AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, NftInfoResponse, OwnerOfResponse,
-    TokensResponse,
+    SupportedInterfacesResponse, TokenStatusResponse, TokensResponse,
 };
-use cw721::query::Cw721Query;
+use cw721::query::{cursor_start_after, Cw721CustomQuery, Cw721Query, INTERFACE_EXPIRATION};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
@@ -85,6 +85,7 @@ where
                 token_id,
                 include_expired: include_expired_approval,
                 include_expired_nft,
+                include_collection,
             } => Ok(to_json_binary(
                 &contract.query_all_nft_info_include_expired_nft(
                     deps,
@@ -92,19 +93,24 @@ where
                     token_id,
                     include_expired_approval.unwrap_or(false),
                     include_expired_nft.unwrap_or(false),
+                    include_collection.unwrap_or(false),
                 )?,
             )?),
+            QueryMsg::TokenStatus { token_id } => Ok(to_json_binary(
+                &contract.query_token_status(deps, env, token_id)?,
+            )?),
             QueryMsg::Tokens {
                 owner,
                 start_after,
                 limit,
                 include_expired_nft: include_invalid,
+                cursor,
             } => Ok(to_json_binary(
                 &contract.query_tokens_include_expired_nft(
                     deps,
                     env,
                     owner,
-                    start_after,
+                    cursor_start_after(start_after, cursor)?,
                     limit,
                     include_invalid.unwrap_or(false),
                 )?,
@@ -113,11 +119,12 @@ where
                 start_after,
                 limit,
                 include_expired_nft: include_invalid,
+                cursor,
             } => Ok(to_json_binary(
                 &contract.query_all_tokens_include_expired_nft(
                     deps,
                     env,
-                    start_after,
+                    cursor_start_after(start_after, cursor)?,
                     limit,
                     include_invalid.unwrap_or(false),
                 )?,
@@ -139,12 +146,13 @@ where
                 include_expired,
                 start_after,
                 limit,
+                cursor,
             } => Ok(to_json_binary(&contract.base_contract.query_operators(
                 deps,
                 env,
                 owner,
                 include_expired.unwrap_or(false),
-                start_after,
+                cursor_start_after(start_after, cursor)?,
                 limit,
             )?)?),
             QueryMsg::NumTokens {} => Ok(to_json_binary(
@@ -169,15 +177,24 @@ where
             QueryMsg::Minter {} => Ok(to_json_binary(
                 &contract.base_contract.query_minter(deps.storage)?,
             )?),
-            QueryMsg::Extension { msg } => Ok(to_json_binary(
-                &contract.base_contract.query_extension(deps, env, msg)?,
-            )?),
+            QueryMsg::Extension { msg } => {
+                Ok(contract.base_contract.query_custom(deps, env, msg)?)
+            }
             QueryMsg::GetWithdrawAddress {} => Ok(to_json_binary(
                 &contract.base_contract.query_withdraw_address(deps)?,
             )?),
+            QueryMsg::SupportedInterfaces {} => {
+                Ok(to_json_binary(&contract.query_supported_interfaces()?)?)
+            }
         }
     }
 
+    pub fn query_supported_interfaces(&self) -> StdResult<SupportedInterfacesResponse> {
+        let mut response = self.base_contract.query_supported_interfaces()?;
+        response.interfaces.push(INTERFACE_EXPIRATION.to_string());
+        Ok(response)
+    }
+
     pub fn query_nft_info_include_expired_nft(
         &self,
         deps: Deps,
@@ -268,7 +285,10 @@ where
             })
             .map(|token_id| token_id.to_string())
             .collect();
-        Ok(TokensResponse { tokens: filtered })
+        Ok(TokensResponse {
+            tokens: filtered,
+            next_cursor: tokens.next_cursor,
+        })
     }
 
     pub fn query_all_tokens_include_expired_nft(
@@ -293,7 +313,10 @@ where
             })
             .map(|token_id| token_id.to_string())
             .collect();
-        Ok(TokensResponse { tokens: filtered })
+        Ok(TokensResponse {
+            tokens: filtered,
+            next_cursor: tokens.next_cursor,
+        })
     }
 
     pub fn query_all_nft_info_include_expired_nft(
@@ -303,13 +326,36 @@ where
         token_id: String,
         include_expired_approval: bool,
         include_expired_nft: bool,
+        include_collection: bool,
     ) -> Result<AllNftInfoResponse<TMetadataExtension>, ContractError> {
         if !include_expired_nft {
             self.assert_nft_expired(deps, &env, token_id.as_str())?;
         }
-        Ok(self
-            .base_contract
-            .query_all_nft_info(deps, env, token_id, include_expired_approval)?)
+        Ok(self.base_contract.query_all_nft_info(
+            deps,
+            env,
+            token_id,
+            include_expired_approval,
+            include_collection,
+        )?)
+    }
+
+    pub fn query_token_status(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+    ) -> Result<TokenStatusResponse, ContractError> {
+        let mut status =
+            self.base_contract
+                .query_token_status(deps, env.clone(), token_id.clone())?;
+        status.expired = match self.assert_nft_expired(deps, &env, token_id.as_str()) {
+            Ok(()) => false,
+            Err(ContractError::NftExpired { .. }) => true,
+            Err(err) => return Err(err),
+        };
+        status.transferable &= !status.expired;
+        Ok(status)
     }
 
     // --- helpers ---
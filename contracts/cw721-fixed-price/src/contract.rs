@@ -1,19 +1,20 @@
 use std::marker::PhantomData;
 
 use crate::error::ContractError;
-use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, CONFIG};
+use crate::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg};
+use crate::state::{Config, SaleFee, ALLOWLIST, CONFIG};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Reply, ReplyOn, Response,
-    StdResult, SubMsg, Uint128, WasmMsg,
+    from_json, to_json_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env,
+    MessageInfo, Reply, ReplyOn, Response, StdResult, SubMsg, Uint128, WasmMsg,
 };
 use cw2::set_contract_version;
-use cw20::Cw20ReceiveMsg;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 use cw721::helpers::Cw721Contract;
 use cw721::msg::{Cw721ExecuteMsg, Cw721InstantiateMsg};
 use cw721::state::DefaultOptionMetadataExtension;
+use cw721_allowlist::Stage;
 use cw_utils::parse_reply_instantiate_data;
 
 // version info for migration info
@@ -39,6 +40,24 @@ pub fn instantiate(
         return Err(ContractError::InvalidMaxTokens {});
     }
 
+    let withdraw_address = msg
+        .withdraw_address
+        .as_ref()
+        .map(|addr| deps.api.addr_validate(addr))
+        .transpose()?;
+    let sale_fee = match msg.sale_fee {
+        Some(fee) => {
+            if fee.share > Decimal::one() {
+                return Err(ContractError::InvalidSaleFee {});
+            }
+            Some(SaleFee {
+                address: deps.api.addr_validate(fee.address.as_str())?,
+                share: fee.share,
+            })
+        }
+        None => None,
+    };
+
     let config = Config {
         cw721_address: None,
         cw20_address: msg.cw20_address,
@@ -50,6 +69,8 @@ pub fn instantiate(
         token_uri: msg.token_uri.clone(),
         extension: msg.extension.clone(),
         unused_token_id: 0,
+        withdraw_address,
+        sale_fee,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -62,6 +83,8 @@ pub fn instantiate(
                 symbol: msg.symbol,
                 minter: None,
                 withdraw_address: msg.withdraw_address,
+                initial_tokens: None,
+                import_mode: None,
             })?,
             funds: vec![],
             admin: None,
@@ -100,6 +123,12 @@ pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractE
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetConfig {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Stages {} => to_json_binary(&ALLOWLIST.stages(deps.storage)?),
+        QueryMsg::Claimed { stage, address } => {
+            let address = deps.api.addr_validate(&address)?;
+            to_json_binary(&ALLOWLIST.claimed(deps.storage, stage, &address)?)
+        }
+        QueryMsg::Allocations {} => to_json_binary(&ALLOWLIST.allocations(deps.storage)?),
     }
 }
 
@@ -116,13 +145,15 @@ fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         token_uri: config.token_uri,
         extension: config.extension,
         unused_token_id: config.unused_token_id,
+        withdraw_address: config.withdraw_address,
+        sale_fee: config.sale_fee,
     })
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
@@ -131,16 +162,42 @@ pub fn execute(
             sender,
             amount,
             msg,
-        }) => execute_receive(deps, info, sender, amount, msg),
+        }) => execute_receive(deps, env, info, sender, amount, msg),
+        ExecuteMsg::SetStages { stages } => execute_set_stages(deps, info, stages),
     }
 }
 
+pub fn execute_set_stages(
+    deps: DepsMut,
+    info: MessageInfo,
+    stages: Vec<Stage>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let allocated: u64 = stages
+        .iter()
+        .filter_map(|stage| stage.allocation)
+        .map(u64::from)
+        .sum();
+    if allocated > config.max_tokens as u64 {
+        return Err(ContractError::AllocationExceedsMaxTokens {});
+    }
+    let count = stages.len();
+    ALLOWLIST.set_stages(deps.storage, stages)?;
+    Ok(Response::new()
+        .add_attribute("action", "set_stages")
+        .add_attribute("stages", count.to_string()))
+}
+
 pub fn execute_receive(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     sender: String,
     amount: Uint128,
-    _msg: Binary,
+    msg: Binary,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
     if config.cw20_address != info.sender {
@@ -156,14 +213,28 @@ pub fn execute_receive(
     }
 
     if amount != config.unit_price {
-        return Err(ContractError::WrongPaymentAmount {});
+        return Err(ContractError::IncorrectPayment {
+            expected: Coin::new(config.unit_price.u128(), info.sender.as_str()),
+            got: vec![Coin::new(amount.u128(), info.sender.as_str())],
+        });
     }
 
+    let proof = if msg.is_empty() {
+        vec![]
+    } else {
+        let ReceiveMsg::Mint { proof } = from_json(&msg)?;
+        proof
+    };
+    let buyer = deps.api.addr_validate(&sender)?;
+    let stage = ALLOWLIST.claim(deps.storage, &env.block, &buyer, &proof, 1)?;
+
     let mint_msg = Cw721ExecuteMsg::<DefaultOptionMetadataExtension, Empty>::Mint {
         token_id: config.unused_token_id.to_string(),
         owner: sender,
         token_uri: config.token_uri.clone().into(),
         extension: config.extension.clone(),
+        approval: None,
+        metadata_hash: None,
     };
 
     match config.cw721_address.clone() {
@@ -174,21 +245,71 @@ pub fn execute_receive(
                 PhantomData,
             )
             .call(mint_msg)?;
+            let token_id = config.unused_token_id.to_string();
             config.unused_token_id += 1;
             CONFIG.save(deps.storage, &config)?;
 
-            Ok(Response::new().add_message(callback))
+            let (payment_msgs, fee_amount, creator_amount) = split_payment(&config, amount)?;
+
+            Ok(Response::new()
+                .add_message(callback)
+                .add_messages(payment_msgs)
+                .add_attribute("action", "mint")
+                .add_attribute("token_id", token_id)
+                .add_attribute("fee_amount", fee_amount)
+                .add_attribute("creator_amount", creator_amount)
+                .add_attributes(stage.map(|stage| ("stage", stage.name))))
         }
         None => Err(ContractError::Cw721NotLinked {}),
     }
 }
 
+/// Splits payment between fee address and creator's withdraw address.
+/// Creator's share is kept in this contract in case no withdraw address is set.
+fn split_payment(
+    config: &Config,
+    amount: Uint128,
+) -> StdResult<(Vec<CosmosMsg>, Uint128, Uint128)> {
+    let fee_amount = config
+        .sale_fee
+        .as_ref()
+        .map(|fee| amount * fee.share)
+        .unwrap_or_default();
+    let creator_amount = amount - fee_amount;
+    let mut msgs = vec![];
+    if let Some(fee) = &config.sale_fee {
+        if !fee_amount.is_zero() {
+            msgs.push(cw20_transfer(config, &fee.address, fee_amount)?);
+        }
+    }
+    if let Some(withdraw_address) = &config.withdraw_address {
+        if !creator_amount.is_zero() {
+            msgs.push(cw20_transfer(config, withdraw_address, creator_amount)?);
+        }
+    }
+    Ok((msgs, fee_amount, creator_amount))
+}
+
+fn cw20_transfer(config: &Config, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: config.cw20_address.to_string(),
+        msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }
+    .into())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::HexBinary;
     use cosmwasm_std::{from_json, to_json_binary, CosmosMsg, SubMsgResponse, SubMsgResult};
     use cw721::state::DefaultOptionMetadataExtension;
+    use cw721_allowlist::{merkle::leaf, AllowlistError};
     use prost::Message;
 
     const NFT_CONTRACT_ADDR: &str = "nftcontract";
@@ -216,6 +337,7 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            sale_fee: None,
         };
 
         let info = mock_info("owner", &[]);
@@ -233,6 +355,8 @@ mod tests {
                         symbol: msg.symbol.clone(),
                         minter: None,
                         withdraw_address: None,
+                        initial_tokens: None,
+                        import_mode: None,
                     })
                     .unwrap(),
                     funds: vec![],
@@ -280,7 +404,9 @@ mod tests {
                 symbol: msg.symbol,
                 token_uri: msg.token_uri,
                 extension: None,
-                unused_token_id: 0
+                unused_token_id: 0,
+                withdraw_address: None,
+                sale_fee: None,
             }
         );
     }
@@ -299,6 +425,7 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            sale_fee: None,
         };
 
         let info = mock_info("owner", &[]);
@@ -324,6 +451,7 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            sale_fee: None,
         };
 
         let info = mock_info("owner", &[]);
@@ -349,6 +477,7 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            sale_fee: None,
         };
 
         let info = mock_info("owner", &[]);
@@ -386,6 +515,8 @@ mod tests {
             owner: String::from("minter"),
             token_uri: Some(String::from("https://ipfs.io/ipfs/Q")),
             extension: None,
+            approval: None,
+            metadata_hash: None,
         };
 
         assert_eq!(
@@ -417,6 +548,7 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            sale_fee: None,
         };
 
         let info = mock_info("owner", &[]);
@@ -459,6 +591,7 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            sale_fee: None,
         };
 
         let info = mock_info("owner", &[]);
@@ -503,6 +636,7 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            sale_fee: None,
         };
 
         let info = mock_info("owner", &[]);
@@ -558,6 +692,7 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            sale_fee: None,
         };
 
         let info = mock_info("owner", &[]);
@@ -593,6 +728,7 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            sale_fee: None,
         };
 
         let info = mock_info("owner", &[]);
@@ -648,6 +784,7 @@ mod tests {
             token_uri: String::from("https://ipfs.io/ipfs/Q"),
             extension: None,
             withdraw_address: None,
+            sale_fee: None,
         };
 
         let info = mock_info("owner", &[]);
@@ -684,7 +821,222 @@ mod tests {
         let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
 
         match err {
-            ContractError::WrongPaymentAmount {} => {}
+            ContractError::IncorrectPayment { expected, got } => {
+                assert_eq!(expected, Coin::new(1, MOCK_CONTRACT_ADDR));
+                assert_eq!(got, vec![Coin::new(100, MOCK_CONTRACT_ADDR)]);
+            }
+            e => panic!("unexpected error: {e}"),
+        }
+    }
+
+    #[test]
+    fn sale_fee_split() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            owner: Addr::unchecked("owner"),
+            max_tokens: 1,
+            unit_price: Uint128::new(100),
+            name: String::from("SYNTH"),
+            symbol: String::from("SYNTH"),
+            token_code_id: 10u64,
+            cw20_address: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            token_uri: String::from("https://ipfs.io/ipfs/Q"),
+            extension: None,
+            withdraw_address: Some(String::from("creator")),
+            sale_fee: Some(SaleFee {
+                address: Addr::unchecked("platform"),
+                share: Decimal::percent(101),
+            }),
+        };
+
+        // fee share above 100% is rejected
+        let info = mock_info("owner", &[]);
+        let err = instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        match err {
+            ContractError::InvalidSaleFee {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+
+        let msg = InstantiateMsg {
+            sale_fee: Some(SaleFee {
+                address: Addr::unchecked("platform"),
+                share: Decimal::percent(5),
+            }),
+            ..msg
+        };
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let instantiate_reply = MsgInstantiateContractResponse {
+            contract_address: NFT_CONTRACT_ADDR.to_string(),
+            data: vec![2u8; 32769],
+        };
+        let mut encoded_instantiate_reply =
+            Vec::<u8>::with_capacity(instantiate_reply.encoded_len());
+        instantiate_reply
+            .encode(&mut encoded_instantiate_reply)
+            .unwrap();
+
+        let reply_msg = Reply {
+            id: INSTANTIATE_TOKEN_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(encoded_instantiate_reply.into()),
+            }),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("minter"),
+            amount: Uint128::new(100),
+            msg: [].into(),
+        });
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // mint, fee and creator payment
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.messages[1].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("platform"),
+                    amount: Uint128::new(5),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("creator"),
+                    amount: Uint128::new(95),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "fee_amount" && attr.value == "5"));
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "creator_amount" && attr.value == "95"));
+    }
+
+    #[test]
+    fn allowlisted_mint() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            owner: Addr::unchecked("owner"),
+            max_tokens: 3,
+            unit_price: Uint128::new(1),
+            name: String::from("SYNTH"),
+            symbol: String::from("SYNTH"),
+            token_code_id: 10u64,
+            cw20_address: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            token_uri: String::from("https://ipfs.io/ipfs/Q"),
+            extension: None,
+            withdraw_address: None,
+            sale_fee: None,
+        };
+        let info = mock_info("owner", &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+
+        let instantiate_reply = MsgInstantiateContractResponse {
+            contract_address: NFT_CONTRACT_ADDR.to_string(),
+            data: vec![2u8; 32769],
+        };
+        let mut encoded_instantiate_reply =
+            Vec::<u8>::with_capacity(instantiate_reply.encoded_len());
+        instantiate_reply
+            .encode(&mut encoded_instantiate_reply)
+            .unwrap();
+        let reply_msg = Reply {
+            id: INSTANTIATE_TOKEN_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(encoded_instantiate_reply.into()),
+            }),
+        };
+        reply(deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+        // presale only for minter, max 1 mint, tree with a single leaf has the leaf as root
+        let stages = vec![Stage {
+            name: String::from("presale"),
+            start: mock_env().block.time,
+            end: None,
+            merkle_root: Some(HexBinary::from(leaf(&Addr::unchecked("minter")))),
+            per_address_limit: Some(1),
+            allocation: None,
+        }];
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            ExecuteMsg::SetStages {
+                stages: stages.clone(),
+            },
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {e}"),
+        }
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::SetStages {
+                stages: stages.clone(),
+            },
+        )
+        .unwrap();
+        let res: Vec<Stage> =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::Stages {}).unwrap()).unwrap();
+        assert_eq!(res, stages);
+
+        let mint_msg = |sender: &str| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: String::from(sender),
+                amount: Uint128::new(1),
+                msg: to_json_binary(&ReceiveMsg::Mint { proof: vec![] }).unwrap(),
+            })
+        };
+        let info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), mint_msg("random")).unwrap_err();
+        match err {
+            ContractError::Allowlist(AllowlistError::NotAllowlisted { .. }) => {}
+            e => panic!("unexpected error: {e}"),
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), mint_msg("minter")).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "stage" && attr.value == "presale"));
+        let claimed: u32 = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Claimed {
+                    stage: 0,
+                    address: String::from("minter"),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(claimed, 1);
+
+        let err = execute(deps.as_mut(), mock_env(), info, mint_msg("minter")).unwrap_err();
+        match err {
+            ContractError::Allowlist(AllowlistError::ClaimLimitReached { limit: 1, .. }) => {}
             e => panic!("unexpected error: {e}"),
         }
     }
@@ -1,4 +1,5 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, StdError};
+use cw721_allowlist::AllowlistError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,15 +7,24 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Allowlist(#[from] AllowlistError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
     #[error("InvalidUnitPrice")]
     InvalidUnitPrice {},
 
+    #[error("InvalidSaleFee")]
+    InvalidSaleFee {},
+
     #[error("InvalidMaxTokens")]
     InvalidMaxTokens {},
 
+    #[error("AllocationExceedsMaxTokens")]
+    AllocationExceedsMaxTokens {},
+
     #[error("SoldOut")]
     SoldOut {},
 
@@ -24,8 +34,9 @@ pub enum ContractError {
     #[error("Uninitialized")]
     Uninitialized {},
 
-    #[error("WrongPaymentAmount")]
-    WrongPaymentAmount {},
+    /// Cw20 payments use the token contract address as denom
+    #[error("Incorrect payment: expected {expected}")]
+    IncorrectPayment { expected: Coin, got: Vec<Coin> },
 
     #[error("InvalidTokenReplyId")]
     InvalidTokenReplyId {},
@@ -1,7 +1,10 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, HexBinary, Uint128};
 use cw20::Cw20ReceiveMsg;
 use cw721::state::DefaultOptionMetadataExtension;
+use cw721_allowlist::{Stage, StageAllocation};
+
+use crate::state::SaleFee;
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -15,11 +18,23 @@ pub struct InstantiateMsg {
     pub token_uri: String,
     pub extension: DefaultOptionMetadataExtension,
     pub withdraw_address: Option<String>,
+    /// Optional fee split from each payment, remainder goes to withdraw address
+    pub sale_fee: Option<SaleFee>,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
+    /// Replaces allowlist stages, minting is open to everyone if empty. Only owner can call this.
+    SetStages {
+        stages: Vec<Stage>,
+    },
+}
+
+/// Optional msg of `Cw20ReceiveMsg`, required for minting in allowlisted stages
+#[cw_serde]
+pub enum ReceiveMsg {
+    Mint { proof: Vec<HexBinary> },
 }
 
 #[cw_serde]
@@ -27,6 +42,17 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(ConfigResponse)]
     GetConfig {},
+
+    #[returns(Vec<Stage>)]
+    Stages {},
+
+    /// Number of mints of `address` in stage with index `stage`
+    #[returns(u32)]
+    Claimed { stage: u32, address: String },
+
+    /// Allocated, minted and remaining tokens per stage
+    #[returns(Vec<StageAllocation>)]
+    Allocations {},
 }
 
 #[cw_serde]
@@ -41,4 +67,6 @@ pub struct ConfigResponse {
     pub token_uri: String,
     pub extension: DefaultOptionMetadataExtension,
     pub unused_token_id: u32,
+    pub withdraw_address: Option<Addr>,
+    pub sale_fee: Option<SaleFee>,
 }
@@ -1,9 +1,10 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Uint128};
 
 // expose to all others using contract, so others dont need to import cw721
 pub use cw721::state::*;
 
+use cw721_allowlist::Allowlist;
 use cw_storage_plus::Item;
 
 #[cw_serde]
@@ -18,6 +19,18 @@ pub struct Config {
     pub token_uri: String,
     pub extension: DefaultOptionMetadataExtension,
     pub unused_token_id: u32,
+    /// Receives the creator's share of each payment, if unset it stays in this contract
+    pub withdraw_address: Option<Addr>,
+    pub sale_fee: Option<SaleFee>,
+}
+
+/// Share of each payment forwarded to a fee address (e.g. platform or fair-burn).
+#[cw_serde]
+pub struct SaleFee {
+    pub address: Addr,
+    pub share: Decimal,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+pub const ALLOWLIST: Allowlist =
+    Allowlist::new("allowlist_stages", "allowlist_claims", "allowlist_minted");
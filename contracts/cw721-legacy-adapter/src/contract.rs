@@ -0,0 +1,514 @@
+use crate::error::ContractError;
+use crate::msg::{
+    AdapterExecuteMsg, AdapterQueryMsg, ExecuteMsg, InstantiateMsg, LegacyNftInfoResponse, QueryMsg,
+};
+use crate::state::{Cw721AdapterContract, LEGACY_CONTRACT};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, ContractResult, Deps, DepsMut, Empty, Env,
+    MessageInfo, QueryRequest, Response, StdError, StdResult, SystemResult, WasmMsg, WasmQuery,
+};
+use cw721::execute::Cw721Execute;
+use cw721::msg::{Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721QueryMsg};
+use cw721::query::Cw721Query;
+use cw721::receiver::Cw721ReceiveMsg;
+use cw721::state::{DefaultOptionMetadataExtension, Metadata};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:cw721-legacy-adapter";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let legacy_contract = deps.api.addr_validate(&msg.legacy_contract)?;
+    let legacy_info: cw721_016::ContractInfoResponse = deps
+        .querier
+        .query_wasm_smart(&legacy_contract, &cw721_016::Cw721QueryMsg::ContractInfo {})?;
+    LEGACY_CONTRACT.save(deps.storage, &legacy_contract)?;
+    // adapter is minter, so wrapped tokens are only minted against escrowed legacy tokens
+    let response = Cw721AdapterContract::default().instantiate(
+        deps.branch(),
+        env.clone(),
+        info,
+        Cw721InstantiateMsg {
+            name: legacy_info.name,
+            symbol: legacy_info.symbol,
+            minter: Some(env.contract.address.to_string()),
+            withdraw_address: None,
+            initial_tokens: None,
+            import_mode: None,
+        },
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+    )?;
+    Ok(response.add_attribute("legacy_contract", legacy_contract))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Adapter(AdapterExecuteMsg::ReceiveNft(msg)) => {
+            execute_wrap(deps, env, info, msg)
+        }
+        ExecuteMsg::Adapter(AdapterExecuteMsg::Unwrap { token_id })
+        | ExecuteMsg::Cw721(Cw721ExecuteMsg::Burn { token_id }) => {
+            execute_unwrap(deps, env, info, token_id)
+        }
+        ExecuteMsg::Cw721(msg) => {
+            Ok(Cw721AdapterContract::default().execute(deps, env, info, msg)?)
+        }
+    }
+}
+
+pub fn execute_wrap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let legacy_contract = LEGACY_CONTRACT.load(deps.storage)?;
+    if info.sender != legacy_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+    let nft_info = proxy_query(
+        deps.as_ref(),
+        &cw721_016::Cw721QueryMsg::NftInfo {
+            token_id: msg.token_id.clone(),
+        },
+    )?;
+    let (token_uri, extension) = legacy_metadata(&nft_info)?;
+    let minter = MessageInfo {
+        sender: env.contract.address.clone(),
+        funds: vec![],
+    };
+    let response = Cw721AdapterContract::default().mint(
+        deps,
+        &env,
+        minter,
+        msg.token_id,
+        msg.sender,
+        token_uri,
+        extension,
+        None,
+        None,
+    )?;
+    Ok(response.add_attribute("action", "wrap"))
+}
+
+pub fn execute_unwrap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let config = Cw721AdapterContract::default().config;
+    let token = config.nft_info.load(deps.storage, &token_id)?;
+    if token.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.remove_nft(deps.storage, &token_id, &token)?;
+    config.decrement_tokens(deps.storage, env.block.height)?;
+    let msg = WasmMsg::Execute {
+        contract_addr: LEGACY_CONTRACT.load(deps.storage)?.to_string(),
+        msg: to_json_binary(&cw721_016::Cw721ExecuteMsg::TransferNft {
+            recipient: token.owner.to_string(),
+            token_id: token_id.clone(),
+        })?,
+        funds: vec![],
+    };
+    Ok(Response::new()
+        .add_message(msg)
+        .add_attribute("action", "unwrap")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id))
+}
+
+/// Token uri and onchain metadata of a legacy token. Extensions other than `Metadata` are dropped.
+fn legacy_metadata(
+    nft_info: &Binary,
+) -> StdResult<(Option<String>, DefaultOptionMetadataExtension)> {
+    match from_json::<cw721_016::NftInfoResponse<DefaultOptionMetadataExtension>>(nft_info) {
+        Ok(response) => Ok((
+            response.token_uri,
+            response
+                .extension
+                .filter(|metadata| *metadata != Metadata::default()),
+        )),
+        Err(_) => {
+            let response: LegacyNftInfoResponse = from_json(nft_info)?;
+            Ok((response.token_uri, None))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    let contract = Cw721AdapterContract::default();
+    match msg {
+        QueryMsg::Adapter(AdapterQueryMsg::LegacyContract {}) => {
+            to_json_binary(&query_legacy_contract(deps)?)
+        }
+        QueryMsg::Cw721(Cw721QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        }) if !is_wrapped(deps, &token_id) => proxy_query(
+            deps,
+            &cw721_016::Cw721QueryMsg::OwnerOf {
+                token_id,
+                include_expired,
+            },
+        ),
+        QueryMsg::Cw721(Cw721QueryMsg::NftInfo { token_id }) if !is_wrapped(deps, &token_id) => {
+            proxy_query(deps, &cw721_016::Cw721QueryMsg::NftInfo { token_id })
+        }
+        QueryMsg::Cw721(msg) => contract.query(deps, env, msg),
+    }
+}
+
+fn is_wrapped(deps: Deps, token_id: &str) -> bool {
+    Cw721AdapterContract::default()
+        .config
+        .nft_info
+        .has(deps.storage, token_id)
+}
+
+/// Forwards a query to the legacy contract, returning its response as is
+fn proxy_query<T: serde::Serialize>(deps: Deps, msg: &T) -> StdResult<Binary> {
+    let request: QueryRequest<Empty> = WasmQuery::Smart {
+        contract_addr: query_legacy_contract(deps)?.to_string(),
+        msg: to_json_binary(msg)?,
+    }
+    .into();
+    match deps.querier.raw_query(&to_json_binary(&request)?) {
+        SystemResult::Ok(ContractResult::Ok(response)) => Ok(response),
+        SystemResult::Ok(ContractResult::Err(err)) => Err(StdError::generic_err(err)),
+        SystemResult::Err(err) => Err(StdError::generic_err(err.to_string())),
+    }
+}
+
+pub fn query_legacy_contract(deps: Deps) -> StdResult<Addr> {
+    LEGACY_CONTRACT.load(deps.storage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{CosmosMsg, OwnedDeps};
+    use cw721::msg::{NftInfoResponse, NumTokensResponse, OwnerOfResponse};
+    use cw721_conformance::Cw721Harness;
+    use cw_multi_test::{error::AnyResult, App, AppResponse, ContractWrapper, Executor};
+
+    const LEGACY_CONTRACT_ADDR: &str = "legacy";
+    const OWNER: &str = "owner";
+
+    fn legacy_query(msg: &Binary) -> StdResult<Binary> {
+        match from_json(msg)? {
+            cw721_016::Cw721QueryMsg::ContractInfo {} => {
+                to_json_binary(&cw721_016::ContractInfoResponse {
+                    name: String::from("Legacy"),
+                    symbol: String::from("OLD"),
+                })
+            }
+            cw721_016::Cw721QueryMsg::NftInfo { token_id } => match token_id.as_str() {
+                "1" => to_json_binary(&cw721_016::NftInfoResponse {
+                    token_uri: Some(String::from("ipfs://1")),
+                    extension: Some(Metadata {
+                        name: Some(String::from("One")),
+                        ..Metadata::default()
+                    }),
+                }),
+                _ => to_json_binary(&cw721_016::NftInfoResponse {
+                    token_uri: None,
+                    extension: "custom",
+                }),
+            },
+            cw721_016::Cw721QueryMsg::OwnerOf { .. } => {
+                to_json_binary(&cw721_016::OwnerOfResponse {
+                    owner: String::from("legacy_owner"),
+                    approvals: vec![],
+                })
+            }
+            _ => Err(StdError::generic_err("unsupported")),
+        }
+    }
+
+    fn setup_contract() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { msg, .. } => match legacy_query(msg) {
+                Ok(response) => SystemResult::Ok(ContractResult::Ok(response)),
+                Err(err) => SystemResult::Ok(ContractResult::Err(err.to_string())),
+            },
+            _ => SystemResult::Ok(ContractResult::Err(String::from("unsupported"))),
+        });
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            InstantiateMsg {
+                legacy_contract: String::from(LEGACY_CONTRACT_ADDR),
+            },
+        )
+        .unwrap();
+        deps
+    }
+
+    fn wrap(deps: DepsMut, token_id: &str) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            mock_env(),
+            mock_info(LEGACY_CONTRACT_ADDR, &[]),
+            ExecuteMsg::Adapter(AdapterExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: String::from(OWNER),
+                token_id: String::from(token_id),
+                msg: Binary::default(),
+            })),
+        )
+    }
+
+    #[test]
+    fn wrapping() {
+        let mut deps = setup_contract();
+        let collection_info = Cw721AdapterContract::default()
+            .query_collection_info(deps.as_ref(), mock_env())
+            .unwrap();
+        assert_eq!(collection_info.name, "Legacy");
+        assert_eq!(collection_info.symbol, "OLD");
+
+        // only legacy contract can send tokens
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other", &[]),
+            ExecuteMsg::Adapter(AdapterExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: String::from(OWNER),
+                token_id: String::from("1"),
+                msg: Binary::default(),
+            })),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // nobody can mint unbacked tokens
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Cw721(Cw721ExecuteMsg::Mint {
+                token_id: String::from("3"),
+                owner: String::from(OWNER),
+                token_uri: None,
+                extension: None,
+                approval: None,
+                metadata_hash: None,
+            }),
+        );
+        assert!(res.is_err());
+
+        wrap(deps.as_mut(), "1").unwrap();
+        wrap(deps.as_mut(), "2").unwrap();
+
+        let info: NftInfoResponse<DefaultOptionMetadataExtension> = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Cw721(Cw721QueryMsg::NftInfo {
+                    token_id: String::from("1"),
+                }),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(info.token_uri, Some(String::from("ipfs://1")));
+        assert_eq!(info.extension.unwrap().name, Some(String::from("One")));
+
+        // extensions other than metadata are dropped
+        let info: NftInfoResponse<DefaultOptionMetadataExtension> = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Cw721(Cw721QueryMsg::NftInfo {
+                    token_id: String::from("2"),
+                }),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(info.extension, None);
+
+        let owner: OwnerOfResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Cw721(Cw721QueryMsg::OwnerOf {
+                    token_id: String::from("1"),
+                    include_expired: None,
+                }),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(owner.owner, OWNER);
+    }
+
+    #[test]
+    fn unwrapping() {
+        let mut deps = setup_contract();
+        wrap(deps.as_mut(), "1").unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other", &[]),
+            ExecuteMsg::Adapter(AdapterExecuteMsg::Unwrap {
+                token_id: String::from("1"),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // burn unwraps as well
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::Cw721(Cw721ExecuteMsg::Burn {
+                token_id: String::from("1"),
+            }),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from(LEGACY_CONTRACT_ADDR),
+                msg: to_json_binary(&cw721_016::Cw721ExecuteMsg::TransferNft {
+                    recipient: String::from(OWNER),
+                    token_id: String::from("1"),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        let num_tokens: NumTokensResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Cw721(Cw721QueryMsg::NumTokens {}),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(num_tokens.count, 0);
+
+        // tokens not wrapped are looked up in legacy contract
+        let owner: OwnerOfResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Cw721(Cw721QueryMsg::OwnerOf {
+                    token_id: String::from("1"),
+                    include_expired: None,
+                }),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(owner.owner, "legacy_owner");
+    }
+
+    struct AdapterHarness;
+
+    impl Cw721Harness for AdapterHarness {
+        fn instantiate(&self, app: &mut App) -> Addr {
+            use cw721_base_016 as v16;
+            let legacy_code_id = app.store_code(Box::new(ContractWrapper::new(
+                v16::entry::execute,
+                v16::entry::instantiate,
+                v16::entry::query,
+            )));
+            let legacy_contract = app
+                .instantiate_contract(
+                    legacy_code_id,
+                    Addr::unchecked("creator"),
+                    &v16::InstantiateMsg {
+                        name: String::from("Legacy"),
+                        symbol: String::from("OLD"),
+                        minter: String::from("minter"),
+                    },
+                    &[],
+                    "legacy",
+                    None,
+                )
+                .unwrap();
+            let code_id =
+                app.store_code(Box::new(ContractWrapper::new(execute, instantiate, query)));
+            app.instantiate_contract(
+                code_id,
+                Addr::unchecked("creator"),
+                &InstantiateMsg {
+                    legacy_contract: legacy_contract.to_string(),
+                },
+                &[],
+                "adapter",
+                None,
+            )
+            .unwrap()
+        }
+
+        /// Mints a legacy token and wraps it
+        fn mint(
+            &self,
+            app: &mut App,
+            cw721: &Addr,
+            token_id: &str,
+            owner: &Addr,
+        ) -> AnyResult<AppResponse> {
+            let legacy_contract: Addr = app
+                .wrap()
+                .query_wasm_smart(cw721, &AdapterQueryMsg::LegacyContract {})?;
+            app.execute_contract(
+                Addr::unchecked("minter"),
+                legacy_contract.clone(),
+                &cw721_base_016::ExecuteMsg::<Option<Empty>, Empty>::Mint(
+                    cw721_base_016::msg::MintMsg {
+                        token_id: String::from(token_id),
+                        owner: owner.to_string(),
+                        token_uri: None,
+                        extension: None,
+                    },
+                ),
+                &[],
+            )?;
+            app.execute_contract(
+                owner.clone(),
+                legacy_contract,
+                &cw721_016::Cw721ExecuteMsg::SendNft {
+                    contract: cw721.to_string(),
+                    token_id: String::from(token_id),
+                    msg: Binary::default(),
+                },
+                &[],
+            )
+        }
+    }
+
+    #[test]
+    fn conformance() {
+        cw721_conformance::run_all(&AdapterHarness);
+    }
+}
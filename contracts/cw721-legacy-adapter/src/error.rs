@@ -0,0 +1,15 @@
+use cosmwasm_std::StdError;
+use cw721::error::Cw721ContractError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Cw721(#[from] Cw721ContractError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+}
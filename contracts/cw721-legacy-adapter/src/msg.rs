@@ -0,0 +1,55 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Empty};
+use cw721::msg::{Cw721ExecuteMsg, Cw721QueryMsg};
+use cw721::receiver::Cw721ReceiveMsg;
+use cw721::state::DefaultOptionMetadataExtension;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Legacy cw721 contract, name and symbol are taken from its `ContractInfo`
+    pub legacy_contract: String,
+}
+
+/// Adapter messages and the current `Cw721ExecuteMsg`, which acts on wrapped tokens
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[serde(untagged)]
+pub enum ExecuteMsg {
+    Adapter(AdapterExecuteMsg),
+    Cw721(Cw721ExecuteMsg<DefaultOptionMetadataExtension, Empty>),
+}
+
+#[cw_serde]
+pub enum AdapterExecuteMsg {
+    /// Wraps a legacy token, sent via `SendNft` on the legacy contract. A wrapped token with the
+    /// same id and metadata is minted to the sender.
+    ReceiveNft(Cw721ReceiveMsg),
+    /// Burns the wrapped token and returns the legacy token to its owner. `Burn` does the same.
+    Unwrap { token_id: String },
+}
+
+/// Adapter queries and the current `Cw721QueryMsg`. `OwnerOf` and `NftInfo` of tokens not wrapped
+/// are proxied to the legacy contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[serde(untagged)]
+#[query_responses(nested)]
+pub enum QueryMsg {
+    Adapter(AdapterQueryMsg),
+    Cw721(Cw721QueryMsg<DefaultOptionMetadataExtension>),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum AdapterQueryMsg {
+    #[returns(Addr)]
+    LegacyContract {},
+}
+
+/// `NftInfo` response of a legacy contract with an extension other than `Metadata`
+#[derive(Deserialize)]
+pub struct LegacyNftInfoResponse {
+    pub token_uri: Option<String>,
+}
@@ -0,0 +1,9 @@
+use cosmwasm_std::{Addr, Empty};
+use cw721::state::DefaultOptionMetadataExtension;
+use cw721_base::Cw721Contract;
+use cw_storage_plus::Item;
+
+pub type Cw721AdapterContract<'a> = Cw721Contract<'a, DefaultOptionMetadataExtension, Empty, Empty>;
+
+/// Legacy cw721 contract holding the tokens wrapped by this contract
+pub const LEGACY_CONTRACT: Item<Addr> = Item::new("legacy_contract");
@@ -0,0 +1,793 @@
+use std::marker::PhantomData;
+
+use crate::error::ContractError;
+use crate::msg::{
+    Cw20HookMsg, Cw2981QueryMsg, ExecuteMsg, InstantiateMsg, LoansResponse, QueryMsg, ReceiveMsg,
+    RoyaltiesInfoResponse, UncheckedDenom,
+};
+use crate::state::{Denom, Funding, Loan, LOANS};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coin, from_json, to_json_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, Response, StdResult, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw721::helpers::Cw721Contract;
+use cw721::msg::Cw721ExecuteMsg;
+use cw721::receiver::Cw721ReceiveMsg;
+use cw_storage_plus::Bound;
+use cw_utils::one_coin;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:cw721-lending";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+/// Attached native funds or cw20 tokens received via `Send`
+pub struct Payment {
+    pub payer: Addr,
+    pub denom: Denom,
+    pub amount: Uint128,
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ReceiveNft(msg) => execute_receive_nft(deps, info, msg),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, env, info, msg),
+        ExecuteMsg::Lend {
+            nft_contract,
+            token_id,
+        } => execute_lend(deps, env, native_payment(&info)?, nft_contract, token_id),
+        ExecuteMsg::Repay {
+            nft_contract,
+            token_id,
+        } => execute_repay(deps, env, native_payment(&info)?, nft_contract, token_id),
+        ExecuteMsg::Cancel {
+            nft_contract,
+            token_id,
+        } => execute_cancel(deps, info, nft_contract, token_id),
+        ExecuteMsg::ClaimCollateral {
+            nft_contract,
+            token_id,
+        } => execute_claim_collateral(deps, env, info, nft_contract, token_id),
+        ExecuteMsg::Liquidate {
+            nft_contract,
+            token_id,
+        } => execute_liquidate(deps, env, native_payment(&info)?, nft_contract, token_id),
+    }
+}
+
+fn native_payment(info: &MessageInfo) -> Result<Payment, ContractError> {
+    let paid = one_coin(info)?;
+    Ok(Payment {
+        payer: info.sender.clone(),
+        denom: Denom::Native(paid.denom),
+        amount: paid.amount,
+    })
+}
+
+/// Token escrowed via `SendNft`, info.sender is the nft contract
+pub fn execute_receive_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let ReceiveMsg::RequestLoan {
+        denom,
+        principal,
+        interest,
+        duration,
+    } = from_json(&msg.msg)?;
+    if principal.is_zero() {
+        return Err(ContractError::InvalidPrincipal {});
+    }
+    let denom = match denom {
+        UncheckedDenom::Native(denom) => Denom::Native(denom),
+        UncheckedDenom::Cw20(address) => Denom::Cw20(deps.api.addr_validate(&address)?),
+    };
+    let loan = Loan {
+        borrower: deps.api.addr_validate(&msg.sender)?,
+        denom,
+        principal,
+        interest,
+        duration,
+        funding: None,
+    };
+    LOANS.save(deps.storage, (&info.sender, &msg.token_id), &loan)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "request_loan")
+        .add_attribute("nft_contract", info.sender)
+        .add_attribute("token_id", msg.token_id)
+        .add_attribute("borrower", msg.sender))
+}
+
+/// Cw20 tokens sent via `Send`, info.sender is the cw20 contract
+pub fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let payment = Payment {
+        payer: deps.api.addr_validate(&msg.sender)?,
+        denom: Denom::Cw20(info.sender),
+        amount: msg.amount,
+    };
+    match from_json(&msg.msg)? {
+        Cw20HookMsg::Lend {
+            nft_contract,
+            token_id,
+        } => execute_lend(deps, env, payment, nft_contract, token_id),
+        Cw20HookMsg::Repay {
+            nft_contract,
+            token_id,
+        } => execute_repay(deps, env, payment, nft_contract, token_id),
+        Cw20HookMsg::Liquidate {
+            nft_contract,
+            token_id,
+        } => execute_liquidate(deps, env, payment, nft_contract, token_id),
+    }
+}
+
+pub fn execute_lend(
+    deps: DepsMut,
+    env: Env,
+    payment: Payment,
+    nft_contract: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    let mut loan = LOANS.load(deps.storage, (&nft_contract, &token_id))?;
+    if loan.funding.is_some() {
+        return Err(ContractError::Funded {});
+    }
+    check_payment(&loan, &payment, loan.principal)?;
+
+    let expires = loan.duration.after(&env.block);
+    loan.funding = Some(Funding {
+        lender: payment.payer.clone(),
+        expires,
+    });
+    LOANS.save(deps.storage, (&nft_contract, &token_id), &loan)?;
+
+    Ok(Response::new()
+        .add_messages(payout(&loan.denom, &loan.borrower, loan.principal)?)
+        .add_attribute("action", "lend")
+        .add_attribute("nft_contract", nft_contract)
+        .add_attribute("token_id", token_id)
+        .add_attribute("lender", payment.payer)
+        .add_attribute("expires", expires.to_string()))
+}
+
+/// Anyone may repay, token is always returned to the borrower
+pub fn execute_repay(
+    deps: DepsMut,
+    env: Env,
+    payment: Payment,
+    nft_contract: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    let loan = LOANS.load(deps.storage, (&nft_contract, &token_id))?;
+    let funding = loan.funding.as_ref().ok_or(ContractError::NotFunded {})?;
+    if funding.is_defaulted(&env.block) {
+        return Err(ContractError::Defaulted {
+            expires: funding.expires,
+        });
+    }
+    let debt = loan.debt()?;
+    check_payment(&loan, &payment, debt)?;
+    LOANS.remove(deps.storage, (&nft_contract, &token_id));
+
+    Ok(Response::new()
+        .add_messages(payout(&loan.denom, &funding.lender, debt)?)
+        .add_message(transfer_nft(&nft_contract, &loan.borrower, &token_id)?)
+        .add_attribute("action", "repay")
+        .add_attribute("nft_contract", nft_contract)
+        .add_attribute("token_id", token_id)
+        .add_attribute("borrower", loan.borrower))
+}
+
+pub fn execute_cancel(
+    deps: DepsMut,
+    info: MessageInfo,
+    nft_contract: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    let loan = LOANS.load(deps.storage, (&nft_contract, &token_id))?;
+    if loan.borrower != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if loan.funding.is_some() {
+        return Err(ContractError::Funded {});
+    }
+    LOANS.remove(deps.storage, (&nft_contract, &token_id));
+
+    Ok(Response::new()
+        .add_message(transfer_nft(&nft_contract, &loan.borrower, &token_id)?)
+        .add_attribute("action", "cancel")
+        .add_attribute("nft_contract", nft_contract)
+        .add_attribute("token_id", token_id)
+        .add_attribute("borrower", loan.borrower))
+}
+
+pub fn execute_claim_collateral(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    nft_contract: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    let loan = LOANS.load(deps.storage, (&nft_contract, &token_id))?;
+    let funding = loan.funding.ok_or(ContractError::NotFunded {})?;
+    if funding.lender != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !funding.is_defaulted(&env.block) {
+        return Err(ContractError::NotDefaulted {
+            expires: funding.expires,
+        });
+    }
+    LOANS.remove(deps.storage, (&nft_contract, &token_id));
+
+    Ok(Response::new()
+        .add_message(transfer_nft(&nft_contract, &funding.lender, &token_id)?)
+        .add_attribute("action", "claim_collateral")
+        .add_attribute("nft_contract", nft_contract)
+        .add_attribute("token_id", token_id)
+        .add_attribute("lender", funding.lender))
+}
+
+/// Sells token of a defaulted loan for the debt, royalties are deducted from the lender's proceeds
+pub fn execute_liquidate(
+    deps: DepsMut,
+    env: Env,
+    payment: Payment,
+    nft_contract: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    let loan = LOANS.load(deps.storage, (&nft_contract, &token_id))?;
+    let funding = loan.funding.as_ref().ok_or(ContractError::NotFunded {})?;
+    if !funding.is_defaulted(&env.block) {
+        return Err(ContractError::NotDefaulted {
+            expires: funding.expires,
+        });
+    }
+    let debt = loan.debt()?;
+    check_payment(&loan, &payment, debt)?;
+    LOANS.remove(deps.storage, (&nft_contract, &token_id));
+
+    let mut response = Response::new();
+    let mut royalty_amount = Uint128::zero();
+    if let Some(royalty) = query_royalty(deps.as_ref(), &nft_contract, &token_id, debt) {
+        royalty_amount = royalty.royalty_amount.min(debt);
+        let receiver = deps.api.addr_validate(&royalty.address)?;
+        response = response.add_messages(payout(&loan.denom, &receiver, royalty_amount)?);
+    }
+    Ok(response
+        .add_messages(payout(&loan.denom, &funding.lender, debt - royalty_amount)?)
+        .add_message(transfer_nft(&nft_contract, &payment.payer, &token_id)?)
+        .add_attribute("action", "liquidate")
+        .add_attribute("nft_contract", nft_contract)
+        .add_attribute("token_id", token_id)
+        .add_attribute("liquidator", payment.payer)
+        .add_attribute("royalty_amount", royalty_amount))
+}
+
+fn check_payment(loan: &Loan, payment: &Payment, expected: Uint128) -> Result<(), ContractError> {
+    if payment.denom != loan.denom || payment.amount != expected {
+        return Err(ContractError::IncorrectPayment {
+            expected: loan.denom.coin(expected),
+            got: vec![payment.denom.coin(payment.amount)],
+        });
+    }
+    Ok(())
+}
+
+/// Bank or cw20 transfer, `None` for zero amounts
+fn payout(denom: &Denom, recipient: &Addr, amount: Uint128) -> StdResult<Option<CosmosMsg>> {
+    if amount.is_zero() {
+        return Ok(None);
+    }
+    let msg = match denom {
+        Denom::Native(denom) => BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(amount.u128(), denom)],
+        }
+        .into(),
+        Denom::Cw20(address) => WasmMsg::Execute {
+            contract_addr: address.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    };
+    Ok(Some(msg))
+}
+
+fn transfer_nft(nft_contract: &Addr, recipient: &Addr, token_id: &str) -> StdResult<CosmosMsg> {
+    Cw721Contract::<Empty, Empty>(nft_contract.clone(), PhantomData, PhantomData).call(
+        Cw721ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: token_id.to_string(),
+        },
+    )
+}
+
+/// Royalty of cw2981 collections, `None` if the nft contract doesn't support royalties or none is set
+fn query_royalty(
+    deps: Deps,
+    nft_contract: &Addr,
+    token_id: &str,
+    sale_price: Uint128,
+) -> Option<RoyaltiesInfoResponse> {
+    deps.querier
+        .query_wasm_smart(
+            nft_contract,
+            &Cw2981QueryMsg::RoyaltyInfo {
+                token_id: token_id.to_string(),
+                sale_price,
+            },
+        )
+        .ok()
+        .filter(|royalty: &RoyaltiesInfoResponse| {
+            !royalty.royalty_amount.is_zero() && !royalty.address.is_empty()
+        })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Loan {
+            nft_contract,
+            token_id,
+        } => to_json_binary(&query_loan(deps, nft_contract, token_id)?),
+        QueryMsg::Loans {
+            nft_contract,
+            start_after,
+            limit,
+        } => to_json_binary(&query_loans(deps, nft_contract, start_after, limit)?),
+    }
+}
+
+fn query_loan(deps: Deps, nft_contract: String, token_id: String) -> StdResult<Loan> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    LOANS.load(deps.storage, (&nft_contract, &token_id))
+}
+
+fn query_loans(
+    deps: Deps,
+    nft_contract: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<LoansResponse> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+    let loans = LOANS
+        .prefix(&nft_contract)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(LoansResponse { loans })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coins, ContractResult, SubMsg, SystemError, SystemResult, WasmQuery};
+    use cw_utils::{Duration, Expiration, PaymentError};
+
+    const NFT_CONTRACT: &str = "nft";
+    const BORROWER: &str = "borrower";
+    const LENDER: &str = "lender";
+    const DURATION: u64 = 7 * 24 * 60 * 60;
+
+    fn setup_loan(mut deps: DepsMut, denom: UncheckedDenom) {
+        instantiate(
+            deps.branch(),
+            mock_env(),
+            mock_info("creator", &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let receive_msg = Cw721ReceiveMsg {
+            sender: String::from(BORROWER),
+            token_id: String::from("1"),
+            msg: to_json_binary(&ReceiveMsg::RequestLoan {
+                denom,
+                principal: Uint128::new(100),
+                interest: Uint128::new(10),
+                duration: Duration::Time(DURATION),
+            })
+            .unwrap(),
+        };
+        execute(
+            deps,
+            mock_env(),
+            mock_info(NFT_CONTRACT, &[]),
+            ExecuteMsg::ReceiveNft(receive_msg),
+        )
+        .unwrap();
+    }
+
+    fn lend_msg() -> ExecuteMsg {
+        ExecuteMsg::Lend {
+            nft_contract: String::from(NFT_CONTRACT),
+            token_id: String::from("1"),
+        }
+    }
+
+    fn transfer_nft_msg(recipient: &str) -> SubMsg {
+        SubMsg::new(
+            transfer_nft(
+                &Addr::unchecked(NFT_CONTRACT),
+                &Addr::unchecked(recipient),
+                "1",
+            )
+            .unwrap(),
+        )
+    }
+
+    fn bank_send(to: &str, amount: u128) -> SubMsg {
+        SubMsg::new(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: coins(amount, "uatom"),
+        })
+    }
+
+    fn cw20_transfer(recipient: &str, amount: u128) -> SubMsg {
+        SubMsg::new(WasmMsg::Execute {
+            contract_addr: String::from("usdc"),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: Uint128::new(amount),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    }
+
+    #[test]
+    fn lending_and_repaying() {
+        let mut deps = mock_dependencies();
+        setup_loan(deps.as_mut(), UncheckedDenom::Native(String::from("uatom")));
+        let repay_msg = ExecuteMsg::Repay {
+            nft_contract: String::from(NFT_CONTRACT),
+            token_id: String::from("1"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BORROWER, &coins(110, "uatom")),
+            repay_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotFunded {});
+
+        // principal must be paid in loan denom
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(LENDER, &coins(100, "ustars")),
+            lend_msg(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::IncorrectPayment {
+                expected: coin(100, "uatom"),
+                got: coins(100, "ustars"),
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(LENDER, &coins(90, "uatom")),
+            lend_msg(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::IncorrectPayment {
+                expected: coin(100, "uatom"),
+                got: coins(90, "uatom"),
+            }
+        );
+
+        // principal is forwarded to borrower
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(LENDER, &coins(100, "uatom")),
+            lend_msg(),
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![bank_send(BORROWER, 100)]);
+        let expires = Expiration::AtTime(mock_env().block.time.plus_seconds(DURATION));
+        let loan =
+            query_loan(deps.as_ref(), String::from(NFT_CONTRACT), String::from("1")).unwrap();
+        assert_eq!(
+            loan.funding,
+            Some(Funding {
+                lender: Addr::unchecked(LENDER),
+                expires,
+            })
+        );
+
+        // funded loans can neither be funded again nor cancelled nor claimed before default
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("another_lender", &coins(100, "uatom")),
+            lend_msg(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Funded {});
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BORROWER, &[]),
+            ExecuteMsg::Cancel {
+                nft_contract: String::from(NFT_CONTRACT),
+                token_id: String::from("1"),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Funded {});
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(LENDER, &[]),
+            ExecuteMsg::ClaimCollateral {
+                nft_contract: String::from(NFT_CONTRACT),
+                token_id: String::from("1"),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotDefaulted { expires });
+
+        // repayment goes to lender, token back to borrower
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BORROWER, &coins(110, "uatom")),
+            repay_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![bank_send(LENDER, 110), transfer_nft_msg(BORROWER)]
+        );
+        query_loan(deps.as_ref(), String::from(NFT_CONTRACT), String::from("1")).unwrap_err();
+    }
+
+    #[test]
+    fn cancel_and_claim_collateral() {
+        let mut deps = mock_dependencies();
+        setup_loan(deps.as_mut(), UncheckedDenom::Native(String::from("uatom")));
+
+        // only borrower can cancel an unfunded request
+        let cancel_msg = ExecuteMsg::Cancel {
+            nft_contract: String::from(NFT_CONTRACT),
+            token_id: String::from("1"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(LENDER, &[]),
+            cancel_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(BORROWER, &[]),
+            cancel_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![transfer_nft_msg(BORROWER)]);
+
+        // after default, repayment fails and only lender can claim the token
+        setup_loan(deps.as_mut(), UncheckedDenom::Native(String::from("uatom")));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(LENDER, &coins(100, "uatom")),
+            lend_msg(),
+        )
+        .unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(DURATION);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(BORROWER, &coins(110, "uatom")),
+            ExecuteMsg::Repay {
+                nft_contract: String::from(NFT_CONTRACT),
+                token_id: String::from("1"),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::Defaulted {
+                expires: Expiration::AtTime(env.block.time)
+            }
+        );
+        let claim_msg = ExecuteMsg::ClaimCollateral {
+            nft_contract: String::from(NFT_CONTRACT),
+            token_id: String::from("1"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(BORROWER, &[]),
+            claim_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), env, mock_info(LENDER, &[]), claim_msg).unwrap();
+        assert_eq!(res.messages, vec![transfer_nft_msg(LENDER)]);
+    }
+
+    #[test]
+    fn liquidation_with_royalties() {
+        let mut deps = mock_dependencies();
+        setup_loan(deps.as_mut(), UncheckedDenom::Cw20(String::from("usdc")));
+        let cw20_send = |sender: &str, amount: u128, msg: Cw20HookMsg| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: sender.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_json_binary(&msg).unwrap(),
+            })
+        };
+        let lend_hook = Cw20HookMsg::Lend {
+            nft_contract: String::from(NFT_CONTRACT),
+            token_id: String::from("1"),
+        };
+
+        // cw20 loans can't be funded with native or other cw20 tokens
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(LENDER, &coins(100, "uatom")),
+            lend_msg(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::IncorrectPayment {
+                expected: coin(100, "usdc"),
+                got: coins(100, "uatom"),
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other_cw20", &[]),
+            cw20_send(LENDER, 100, lend_hook.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::IncorrectPayment {
+                expected: coin(100, "usdc"),
+                got: coins(100, "other_cw20"),
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(LENDER, &[]),
+            lend_msg(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Payment(PaymentError::NoFunds {}));
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("usdc", &[]),
+            cw20_send(LENDER, 100, lend_hook),
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![cw20_transfer(BORROWER, 100)]);
+
+        // liquidation only after default
+        let liquidate_hook = Cw20HookMsg::Liquidate {
+            nft_contract: String::from(NFT_CONTRACT),
+            token_id: String::from("1"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("usdc", &[]),
+            cw20_send("liquidator", 110, liquidate_hook.clone()),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::NotDefaulted {
+                expires: Expiration::AtTime(mock_env().block.time.plus_seconds(DURATION))
+            }
+        );
+
+        // cw2981 royalty of 10% on the debt is paid to creator, rest to lender
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == NFT_CONTRACT => {
+                let Cw2981QueryMsg::RoyaltyInfo { sale_price, .. } = from_json(msg).unwrap();
+                SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&RoyaltiesInfoResponse {
+                        address: String::from("creator"),
+                        royalty_amount: sale_price.multiply_ratio(1u128, 10u128),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: String::from("wasm"),
+            }),
+        });
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(DURATION);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("usdc", &[]),
+            cw20_send("liquidator", 110, liquidate_hook),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                cw20_transfer("creator", 11),
+                cw20_transfer(LENDER, 99),
+                transfer_nft_msg("liquidator")
+            ]
+        );
+        let res: LoansResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Loans {
+                    nft_contract: String::from(NFT_CONTRACT),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.loans, vec![]);
+    }
+}
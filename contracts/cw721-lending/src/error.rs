@@ -0,0 +1,34 @@
+use cosmwasm_std::{Coin, StdError};
+use cw_utils::{Expiration, PaymentError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("InvalidPrincipal: must be greater than zero")]
+    InvalidPrincipal {},
+
+    /// Cw20 payments use the token contract address as denom
+    #[error("Incorrect payment: expected {expected}")]
+    IncorrectPayment { expected: Coin, got: Vec<Coin> },
+
+    #[error("Loan is already funded")]
+    Funded {},
+
+    #[error("Loan is not funded")]
+    NotFunded {},
+
+    #[error("Loan defaulted at {expires}")]
+    Defaulted { expires: Expiration },
+
+    #[error("Loan can be repaid until {expires}")]
+    NotDefaulted { expires: Expiration },
+}
@@ -0,0 +1,117 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+use cw721::receiver::Cw721ReceiveMsg;
+use cw_utils::Duration;
+
+use crate::state::Loan;
+
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Escrows a token as collateral, sent via `SendNft` with `ReceiveMsg::RequestLoan` as msg
+    ReceiveNft(Cw721ReceiveMsg),
+    /// Cw20 payments sent via `Send` with `Cw20HookMsg` as msg, for loans in a cw20 denom
+    Receive(Cw20ReceiveMsg),
+    /// Funds a loan request, the principal must be attached and is forwarded to the borrower
+    Lend {
+        nft_contract: String,
+        token_id: String,
+    },
+    /// Repays principal plus interest before the loan defaults, payment must be attached and is
+    /// forwarded to the lender. The token is returned to the borrower.
+    Repay {
+        nft_contract: String,
+        token_id: String,
+    },
+    /// Returns token of a loan request not funded yet. Only borrower can call this.
+    Cancel {
+        nft_contract: String,
+        token_id: String,
+    },
+    /// Transfers token of a defaulted loan to the lender. Only lender can call this.
+    ClaimCollateral {
+        nft_contract: String,
+        token_id: String,
+    },
+    /// Buys token of a defaulted loan for principal plus interest. Royalties of cw2981 collections
+    /// are paid to the royalty receiver, the rest is forwarded to the lender.
+    Liquidate {
+        nft_contract: String,
+        token_id: String,
+    },
+}
+
+/// Msg passed in `SendNft` when escrowing a token
+#[cw_serde]
+pub enum ReceiveMsg {
+    RequestLoan {
+        denom: UncheckedDenom,
+        principal: Uint128,
+        interest: Uint128,
+        duration: Duration,
+    },
+}
+
+/// Msg passed in cw20 `Send`, same as the native execute msgs
+#[cw_serde]
+pub enum Cw20HookMsg {
+    Lend {
+        nft_contract: String,
+        token_id: String,
+    },
+    Repay {
+        nft_contract: String,
+        token_id: String,
+    },
+    Liquidate {
+        nft_contract: String,
+        token_id: String,
+    },
+}
+
+#[cw_serde]
+pub enum UncheckedDenom {
+    Native(String),
+    /// Address of the cw20 contract
+    Cw20(String),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(Loan)]
+    Loan {
+        nft_contract: String,
+        token_id: String,
+    },
+    /// Lists loans of given nft contract
+    #[returns(LoansResponse)]
+    Loans {
+        nft_contract: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct LoansResponse {
+    pub loans: Vec<(String, Loan)>,
+}
+
+/// Royalty query of cw2981 collections, see `cw2981-royalties`
+#[cw_serde]
+pub enum Cw2981QueryMsg {
+    RoyaltyInfo {
+        token_id: String,
+        sale_price: Uint128,
+    },
+}
+
+#[cw_serde]
+pub struct RoyaltiesInfoResponse {
+    pub address: String,
+    pub royalty_amount: Uint128,
+}
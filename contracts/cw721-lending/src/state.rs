@@ -0,0 +1,58 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{coin, Addr, BlockInfo, Coin, StdResult, Uint128};
+use cw_storage_plus::Map;
+use cw_utils::{Duration, Expiration};
+
+#[cw_serde]
+pub enum Denom {
+    Native(String),
+    Cw20(Addr),
+}
+
+impl Denom {
+    /// Cw20 tokens use their contract address as denom
+    pub fn coin(&self, amount: Uint128) -> Coin {
+        match self {
+            Denom::Native(denom) => coin(amount.u128(), denom),
+            Denom::Cw20(address) => coin(amount.u128(), address),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct Loan {
+    /// Owner who escrowed the token, receives the principal and gets the token back on repayment
+    pub borrower: Addr,
+    /// Denom of principal and interest
+    pub denom: Denom,
+    pub principal: Uint128,
+    /// Fixed interest due on repayment in addition to the principal
+    pub interest: Uint128,
+    /// Time or blocks the borrower has to repay, starting once the loan is funded
+    pub duration: Duration,
+    /// Set once a lender funded the loan request
+    pub funding: Option<Funding>,
+}
+
+impl Loan {
+    /// Principal plus interest
+    pub fn debt(&self) -> StdResult<Uint128> {
+        Ok(self.principal.checked_add(self.interest)?)
+    }
+}
+
+#[cw_serde]
+pub struct Funding {
+    pub lender: Addr,
+    /// Loan defaults if not repaid before, then the lender can claim or anyone can liquidate the token
+    pub expires: Expiration,
+}
+
+impl Funding {
+    pub fn is_defaulted(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+}
+
+/// Stored as (nft contract, token_id)
+pub const LOANS: Map<(&Addr, &str), Loan> = Map::new("loans");
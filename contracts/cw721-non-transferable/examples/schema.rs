@@ -3,8 +3,6 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema_with_title, remove_schemas, schema_for};
 
-use cosmwasm_std::Empty;
-use cw721::state::DefaultOptionMetadataExtension;
 use cw721_non_transferable::{
     msg::{ExecuteMsg, MigrateMsg},
     InstantiateMsg, QueryMsg,
@@ -18,11 +16,7 @@ fn main() {
 
     // entry points - generate always with title for avoiding name suffixes like "..._empty_for_..." due to generics
     export_schema_with_title(&schema_for!(InstantiateMsg), &out_dir, "InstantiateMsg");
-    export_schema_with_title(
-        &schema_for!(ExecuteMsg::<DefaultOptionMetadataExtension, Empty>),
-        &out_dir,
-        "ExecuteMsg",
-    );
+    export_schema_with_title(&schema_for!(ExecuteMsg), &out_dir, "ExecuteMsg");
     export_schema_with_title(&schema_for!(QueryMsg), &out_dir, "QueryMsg");
     export_schema_with_title(&schema_for!(MigrateMsg), &out_dir, "MigrateMsg");
 }
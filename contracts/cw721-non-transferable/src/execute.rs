@@ -0,0 +1,131 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+use cw721::error::Cw721ContractError;
+use cw721::execute::Cw721Execute;
+use cw721_base::OwnershipError;
+
+use crate::{
+    error::ContractError,
+    state::{BurnedToken, Config, RECOVERY_CONSENTS},
+    Cw721NonTransferableContract,
+};
+
+/// Admin moves a token to a new owner, e.g. in case holder rotated wallets.
+/// In case `require_recovery_consent` is set, holder must have consented to the new owner before.
+pub fn recover(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: &Config,
+    token_id: String,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    if config.admin.as_ref() != Some(&info.sender) {
+        return Err(Cw721ContractError::Ownership(OwnershipError::NotOwner).into());
+    }
+    let new_owner_addr = deps.api.addr_validate(&new_owner)?;
+    if config.require_recovery_consent {
+        let consent = RECOVERY_CONSENTS.may_load(deps.storage, &token_id)?;
+        if consent.as_ref() != Some(&new_owner_addr) {
+            return Err(ContractError::NoRecoveryConsent {
+                token_id,
+                new_owner,
+            });
+        }
+    }
+    RECOVERY_CONSENTS.remove(deps.storage, &token_id);
+
+    let contract = Cw721NonTransferableContract::default();
+    let old_token = contract.config.nft_info.load(deps.storage, &token_id)?;
+    let mut token = old_token.clone();
+    token.owner = new_owner_addr;
+    token.approvals = vec![];
+    token.last_transferred_at = Some((&env.block).into());
+    contract
+        .config
+        .save_nft(deps.storage, &token_id, &token, Some(&old_token))?;
+    contract.config.clear_approvals(deps.storage, &token_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "recover")
+        .add_attribute("sender", info.sender)
+        .add_attribute("owner", old_token.owner)
+        .add_attribute("new_owner", new_owner)
+        .add_attribute("token_id", token_id))
+}
+
+/// Holder consents to a recovery of the token to the given new owner.
+pub fn consent_to_recovery(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    let token = Cw721NonTransferableContract::default()
+        .config
+        .nft_info
+        .load(deps.storage, &token_id)?;
+    if token.owner != info.sender {
+        return Err(Cw721ContractError::Ownership(OwnershipError::NotOwner).into());
+    }
+    let new_owner_addr = deps.api.addr_validate(&new_owner)?;
+    RECOVERY_CONSENTS.save(deps.storage, &token_id, &new_owner_addr)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consent_to_recovery")
+        .add_attribute("sender", info.sender)
+        .add_attribute("new_owner", new_owner)
+        .add_attribute("token_id", token_id))
+}
+
+/// Holder burns own token, e.g. to voluntarily remove a credential.
+pub fn burn(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let contract = Cw721NonTransferableContract::default();
+    let token = contract.config.nft_info.load(deps.storage, &token_id)?;
+    if token.owner != info.sender {
+        return Err(Cw721ContractError::Ownership(OwnershipError::NotOwner).into());
+    }
+    RECOVERY_CONSENTS.remove(deps.storage, &token_id);
+    Ok(contract.burn_nft(deps, env, info, token_id)?)
+}
+
+/// Admin revokes (burns) a token, e.g. an invalidated credential.
+pub fn revoke_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: &Config,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    if config.admin.as_ref() != Some(&info.sender) {
+        return Err(Cw721ContractError::Ownership(OwnershipError::NotOwner).into());
+    }
+    let contract = Cw721NonTransferableContract::default();
+    let token = contract.config.nft_info.load(deps.storage, &token_id)?;
+    contract
+        .config
+        .remove_nft(deps.storage, &token_id, &token)?;
+    contract
+        .config
+        .decrement_tokens(deps.storage, env.block.height)?;
+    contract.config.burned_tokens.save(
+        deps.storage,
+        &token_id,
+        &BurnedToken {
+            owner: token.owner.clone(),
+            burner: info.sender.clone(),
+            height: env.block.height,
+        },
+    )?;
+    RECOVERY_CONSENTS.remove(deps.storage, &token_id);
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_token")
+        .add_attribute("sender", info.sender)
+        .add_attribute("owner", token.owner)
+        .add_attribute("token_id", token_id))
+}
@@ -1,4 +1,5 @@
-pub use crate::msg::{InstantiateMsg, QueryMsg};
+pub use crate::error::ContractError;
+pub use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 use cosmwasm_std::Empty;
 use cw721::state::DefaultOptionMetadataExtension;
 pub use cw721_base::{
@@ -6,6 +7,8 @@ pub use cw721_base::{
     Cw721Contract,
 };
 
+mod error;
+pub mod execute;
 pub mod msg;
 pub mod query;
 pub mod state;
@@ -20,7 +23,8 @@ pub type Cw721NonTransferableContract<'a> =
 #[cfg(not(feature = "library"))]
 pub mod entry {
     use super::*;
-    use crate::query::admin;
+    use crate::execute::{burn, consent_to_recovery, recover, revoke_token};
+    use crate::query::{admin, token_status};
     use crate::state::{Config, CONFIG};
     use cosmwasm_std::{
         entry_point, to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
@@ -36,14 +40,17 @@ pub mod entry {
         env: Env,
         info: MessageInfo,
         msg: InstantiateMsg,
-    ) -> Result<Response, Cw721ContractError> {
+    ) -> Result<Response, ContractError> {
         let admin_addr: Option<Addr> = msg
             .admin
             .as_deref()
             .map(|s| deps.api.addr_validate(s))
             .transpose()?;
 
-        let config = Config { admin: admin_addr };
+        let config = Config {
+            admin: admin_addr,
+            require_recovery_consent: msg.require_recovery_consent.unwrap_or(false),
+        };
 
         CONFIG.save(deps.storage, &config)?;
 
@@ -52,6 +59,8 @@ pub mod entry {
             symbol: msg.symbol,
             minter: msg.minter,
             withdraw_address: msg.withdraw_address,
+            initial_tokens: None,
+            import_mode: None,
         };
 
         Cw721NonTransferableContract::default().instantiate(
@@ -75,17 +84,30 @@ pub mod entry {
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        msg: Cw721ExecuteMsg<DefaultOptionMetadataExtension, Empty>,
-    ) -> Result<Response, Cw721ContractError> {
+        msg: ExecuteMsg,
+    ) -> Result<Response, ContractError> {
         let config = CONFIG.load(deps.storage)?;
+        let msg = match msg {
+            ExecuteMsg::Recover {
+                token_id,
+                new_owner,
+            } => return recover(deps, env, info, &config, token_id, new_owner),
+            ExecuteMsg::ConsentToRecovery {
+                token_id,
+                new_owner,
+            } => return consent_to_recovery(deps, info, token_id, new_owner),
+            ExecuteMsg::RevokeToken { token_id } => {
+                return revoke_token(deps, env, info, &config, token_id)
+            }
+            ExecuteMsg::Burn { token_id } => return burn(deps, env, info, token_id),
+            msg => Cw721ExecuteMsg::from(msg),
+        };
         match config.admin {
             Some(admin) => {
                 if admin == info.sender {
-                    _execute(deps, env, info, msg)
+                    Ok(_execute(deps, env, info, msg)?)
                 } else {
-                    Err(Cw721ContractError::Ownership(
-                        cw721_base::OwnershipError::NotOwner,
-                    ))
+                    Err(Cw721ContractError::Ownership(cw721_base::OwnershipError::NotOwner).into())
                 }
             }
             None => match msg {
@@ -94,11 +116,22 @@ pub mod entry {
                     owner,
                     token_uri,
                     extension,
-                } => Cw721NonTransferableContract::default()
-                    .mint(deps, info, token_id, owner, token_uri, extension),
-                _ => Err(Cw721ContractError::Ownership(
-                    cw721_base::OwnershipError::NotOwner,
-                )),
+                    approval,
+                    metadata_hash,
+                } => Ok(Cw721NonTransferableContract::default().mint(
+                    deps,
+                    &env,
+                    info,
+                    token_id,
+                    owner,
+                    token_uri,
+                    extension,
+                    approval,
+                    metadata_hash,
+                )?),
+                _ => {
+                    Err(Cw721ContractError::Ownership(cw721_base::OwnershipError::NotOwner).into())
+                }
             },
         }
     }
@@ -107,6 +140,9 @@ pub mod entry {
     pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         match msg {
             QueryMsg::Admin {} => to_json_binary(&admin(deps)?),
+            QueryMsg::TokenStatus { token_id } => {
+                to_json_binary(&token_status(deps, env, token_id)?)
+            }
             _ => _query(deps, env, msg.into()),
         }
     }
@@ -1,7 +1,10 @@
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Binary, Coin, Empty};
 // expose to all others using contract, so others dont need to import cw721
-pub use cw721::msg::{Cw721ExecuteMsg as ExecuteMsg, Cw721MigrateMsg as MigrateMsg, *};
-use cw721::state::DefaultOptionMetadataExtension;
+pub use cw721::msg::{Cw721MigrateMsg as MigrateMsg, *};
+use cw721::state::{ApprovalLimits, DefaultOptionMetadataExtension, OperatorFilter};
+use cw721::{Duration, Expiration};
+use cw721_base::Action;
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -10,6 +13,162 @@ pub struct InstantiateMsg {
     pub symbol: String,
     pub minter: Option<String>,
     pub withdraw_address: Option<String>,
+    /// If true, admin can only recover a token to a new owner the holder has consented to
+    pub require_recovery_consent: Option<bool>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Admin moves a token to a new owner, e.g. in case holder rotated wallets.
+    Recover {
+        token_id: String,
+        new_owner: String,
+    },
+    /// Holder consents to a recovery of the token to the given new owner.
+    ConsentToRecovery {
+        token_id: String,
+        new_owner: String,
+    },
+    /// Admin revokes (burns) a token, e.g. an invalidated credential.
+    /// Note: named `RevokeToken` since `Revoke` is used for approvals.
+    RevokeToken {
+        token_id: String,
+    },
+
+    // -- below copied from Cw721ExecuteMsg
+    UpdateOwnership(Action),
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+        permissions: Option<u8>,
+    },
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+        filter: Option<OperatorFilter>,
+    },
+    RevokeAll {
+        operator: String,
+    },
+    Mint {
+        token_id: String,
+        owner: String,
+        token_uri: Option<String>,
+        extension: DefaultOptionMetadataExtension,
+    },
+    /// Holder burns own token
+    Burn {
+        token_id: String,
+    },
+    Extension {
+        msg: Empty,
+    },
+    SetWithdrawAddress {
+        address: String,
+    },
+    RemoveWithdrawAddress {},
+    WithdrawFunds {
+        amount: Coin,
+    },
+    SetTransferCooldown {
+        cooldown: Option<Duration>,
+    },
+    SetApprovalLimits {
+        limits: Option<ApprovalLimits>,
+    },
+}
+
+impl From<ExecuteMsg> for Cw721ExecuteMsg<DefaultOptionMetadataExtension, Empty> {
+    fn from(msg: ExecuteMsg) -> Cw721ExecuteMsg<DefaultOptionMetadataExtension, Empty> {
+        match msg {
+            ExecuteMsg::UpdateOwnership(action) => Cw721ExecuteMsg::UpdateOwnership(action),
+            ExecuteMsg::TransferNft {
+                recipient,
+                token_id,
+            } => Cw721ExecuteMsg::TransferNft {
+                recipient,
+                token_id,
+            },
+            ExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg,
+            } => Cw721ExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg,
+            },
+            ExecuteMsg::Approve {
+                spender,
+                token_id,
+                expires,
+                permissions,
+            } => Cw721ExecuteMsg::Approve {
+                spender,
+                token_id,
+                expires,
+                permissions,
+            },
+            ExecuteMsg::Revoke { spender, token_id } => {
+                Cw721ExecuteMsg::Revoke { spender, token_id }
+            }
+            ExecuteMsg::ApproveAll {
+                operator,
+                expires,
+                filter,
+            } => Cw721ExecuteMsg::ApproveAll {
+                operator,
+                expires,
+                filter,
+            },
+            ExecuteMsg::RevokeAll { operator } => Cw721ExecuteMsg::RevokeAll { operator },
+            ExecuteMsg::Mint {
+                token_id,
+                owner,
+                token_uri,
+                extension,
+            } => Cw721ExecuteMsg::Mint {
+                token_id,
+                owner,
+                token_uri,
+                extension,
+                approval: None,
+                metadata_hash: None,
+            },
+            ExecuteMsg::Burn { token_id } => Cw721ExecuteMsg::Burn { token_id },
+            ExecuteMsg::Extension { msg } => Cw721ExecuteMsg::Extension { msg },
+            ExecuteMsg::SetWithdrawAddress { address } => {
+                Cw721ExecuteMsg::SetWithdrawAddress { address }
+            }
+            ExecuteMsg::RemoveWithdrawAddress {} => Cw721ExecuteMsg::RemoveWithdrawAddress {},
+            ExecuteMsg::WithdrawFunds { amount } => Cw721ExecuteMsg::WithdrawFunds { amount },
+            ExecuteMsg::SetTransferCooldown { cooldown } => {
+                Cw721ExecuteMsg::SetTransferCooldown { cooldown }
+            }
+            ExecuteMsg::SetApprovalLimits { limits } => {
+                Cw721ExecuteMsg::SetApprovalLimits { limits }
+            }
+            ExecuteMsg::Recover { .. } => unreachable!("Recover is not supported!"),
+            ExecuteMsg::ConsentToRecovery { .. } => {
+                unreachable!("ConsentToRecovery is not supported!")
+            }
+            ExecuteMsg::RevokeToken { .. } => unreachable!("RevokeToken is not supported!"),
+        }
+    }
 }
 
 #[cw_serde]
@@ -35,6 +194,7 @@ pub enum QueryMsg {
         include_expired: Option<bool>,
         start_after: Option<String>,
         limit: Option<u32>,
+        cursor: Option<Binary>,
     },
     NumTokens {},
     ContractInfo {},
@@ -45,19 +205,28 @@ pub enum QueryMsg {
     AllNftInfo {
         token_id: String,
         include_expired: Option<bool>,
+        include_collection: Option<bool>,
     },
     Tokens {
         owner: String,
         start_after: Option<String>,
         limit: Option<u32>,
+        cursor: Option<Binary>,
     },
     AllTokens {
         start_after: Option<String>,
         limit: Option<u32>,
+        cursor: Option<Binary>,
     },
     Minter {},
 
     GetWithdrawAddress {},
+
+    SupportedInterfaces {},
+    /// Like `Cw721QueryMsg::TokenStatus`, but reports tokens as soulbound and not transferable
+    TokenStatus {
+        token_id: String,
+    },
 }
 
 impl From<QueryMsg> for Cw721QueryMsg<DefaultOptionMetadataExtension> {
@@ -76,28 +245,40 @@ impl From<QueryMsg> for Cw721QueryMsg<DefaultOptionMetadataExtension> {
             QueryMsg::AllNftInfo {
                 token_id,
                 include_expired,
+                include_collection,
             } => Cw721QueryMsg::AllNftInfo {
                 token_id,
                 include_expired,
+                include_collection,
             },
             QueryMsg::Tokens {
                 owner,
                 start_after,
                 limit,
+                cursor,
             } => Cw721QueryMsg::Tokens {
                 owner,
                 start_after,
                 limit,
+                cursor,
+            },
+            QueryMsg::AllTokens {
+                start_after,
+                limit,
+                cursor,
+            } => Cw721QueryMsg::AllTokens {
+                start_after,
+                limit,
+                cursor,
             },
-            QueryMsg::AllTokens { start_after, limit } => {
-                Cw721QueryMsg::AllTokens { start_after, limit }
-            }
             QueryMsg::Minter {} => Cw721QueryMsg::Minter {},
             QueryMsg::GetWithdrawAddress {} => Cw721QueryMsg::GetWithdrawAddress {},
+            QueryMsg::SupportedInterfaces {} => Cw721QueryMsg::SupportedInterfaces {},
             QueryMsg::AllOperators { .. } => unreachable!("AllOperators is not supported!"),
             QueryMsg::Approval { .. } => unreachable!("Approval is not supported!"),
             QueryMsg::Approvals { .. } => unreachable!("Approvals is not supported!"),
             QueryMsg::Admin { .. } => unreachable!("Approvals is not supported!"),
+            QueryMsg::TokenStatus { .. } => unreachable!("TokenStatus is handled by contract!"),
         }
     }
 }
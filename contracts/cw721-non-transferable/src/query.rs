@@ -1,5 +1,7 @@
-use crate::{msg::AdminResponse, state::CONFIG};
-use cosmwasm_std::{Deps, StdResult};
+use crate::{msg::AdminResponse, state::CONFIG, Cw721NonTransferableContract};
+use cosmwasm_std::{Deps, Env, StdResult};
+use cw721::msg::TokenStatusResponse;
+use cw721::query::Cw721Query;
 
 pub fn admin(deps: Deps) -> StdResult<AdminResponse> {
     let config = CONFIG.load(deps.storage)?;
@@ -7,3 +9,12 @@ pub fn admin(deps: Deps) -> StdResult<AdminResponse> {
         admin: config.admin.map(|admin| admin.to_string()),
     })
 }
+
+/// Holders can never transfer, only admin can move tokens (e.g. via `Recover`)
+pub fn token_status(deps: Deps, env: Env, token_id: String) -> StdResult<TokenStatusResponse> {
+    let mut status =
+        Cw721NonTransferableContract::default().query_token_status(deps, env, token_id)?;
+    status.soulbound = true;
+    status.transferable = false;
+    Ok(status)
+}
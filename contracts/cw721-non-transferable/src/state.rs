@@ -1,6 +1,6 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 
 // expose to all others using contract, so others dont need to import cw721
 pub use cw721::state::*;
@@ -8,6 +8,11 @@ pub use cw721::state::*;
 #[cw_serde]
 pub struct Config {
     pub admin: Option<Addr>,
+    /// If true, admin can only recover a token to a new owner the holder has consented to
+    #[serde(default)]
+    pub require_recovery_consent: bool,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
+/// Stores the new owner a holder has consented to recover the token to
+pub const RECOVERY_CONSENTS: Map<&str, Addr> = Map::new("recovery_consents");
@@ -0,0 +1,320 @@
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, RecipientsResponse};
+use crate::state::{Recipient, RECIPIENTS};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coin, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:cw721-payout-splitter";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.recipients.is_empty() {
+        return Err(ContractError::NoRecipients {});
+    }
+    let mut recipients: Vec<Recipient> = Vec::with_capacity(msg.recipients.len());
+    let mut total = Decimal::zero();
+    for recipient in msg.recipients {
+        let address = deps.api.addr_validate(&recipient.address)?;
+        if recipient.share.is_zero() {
+            return Err(ContractError::InvalidShare {
+                address: recipient.address,
+                share: recipient.share,
+            });
+        }
+        if recipients.iter().any(|r| r.address == address) {
+            return Err(ContractError::DuplicateRecipient {
+                address: recipient.address,
+            });
+        }
+        total = total.checked_add(recipient.share).map_err(StdError::from)?;
+        recipients.push(Recipient {
+            address,
+            share: recipient.share,
+        });
+    }
+    if total != Decimal::one() {
+        return Err(ContractError::InvalidTotalShares { total });
+    }
+    RECIPIENTS.save(deps.storage, &recipients)?;
+
+    Ok(Response::new().add_attribute("recipients", recipients.len().to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Distribute { denoms } => execute_distribute(deps, env, denoms),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, info, msg),
+    }
+}
+
+pub fn execute_distribute(
+    deps: DepsMut,
+    env: Env,
+    denoms: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let recipients = RECIPIENTS.load(deps.storage)?;
+    let balances = match denoms {
+        Some(denoms) => denoms
+            .into_iter()
+            .map(|denom| deps.querier.query_balance(&env.contract.address, denom))
+            .collect::<StdResult<Vec<Coin>>>()?,
+        None => deps.querier.query_all_balances(&env.contract.address)?,
+    };
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for balance in balances {
+        for (address, amount) in split(&recipients, balance.amount) {
+            msgs.push(
+                BankMsg::Send {
+                    to_address: address.to_string(),
+                    amount: vec![coin(amount.u128(), &balance.denom)],
+                }
+                .into(),
+            );
+        }
+    }
+    if msgs.is_empty() {
+        return Err(ContractError::NothingToDistribute {});
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "distribute"))
+}
+
+/// Cw20 tokens sent via `Send`, info.sender is the cw20 contract
+pub fn execute_receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let recipients = RECIPIENTS.load(deps.storage)?;
+    let msgs = split(&recipients, msg.amount)
+        .into_iter()
+        .map(|(address, amount)| {
+            Ok(WasmMsg::Execute {
+                contract_addr: info.sender.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: address.to_string(),
+                    amount,
+                })?,
+                funds: vec![],
+            })
+        })
+        .collect::<StdResult<Vec<WasmMsg>>>()?;
+    if msgs.is_empty() {
+        return Err(ContractError::NothingToDistribute {});
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "distribute")
+        .add_attribute("cw20", info.sender)
+        .add_attribute("amount", msg.amount))
+}
+
+/// Shares are rounded down, last recipient receives the remainder so nothing is left behind.
+/// Zero amounts are skipped.
+fn split(recipients: &[Recipient], amount: Uint128) -> Vec<(&Addr, Uint128)> {
+    let mut remaining = amount;
+    let mut payouts = Vec::with_capacity(recipients.len());
+    for (i, recipient) in recipients.iter().enumerate() {
+        let payout = if i == recipients.len() - 1 {
+            remaining
+        } else {
+            amount * recipient.share
+        };
+        remaining -= payout;
+        if !payout.is_zero() {
+            payouts.push((&recipient.address, payout));
+        }
+    }
+    payouts
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Recipients {} => to_json_binary(&RecipientsResponse {
+            recipients: RECIPIENTS.load(deps.storage)?,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::RecipientMsg;
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
+    };
+    use cosmwasm_std::{coins, from_json, SubMsg};
+
+    fn recipients(shares: &[(&str, u64)]) -> Vec<RecipientMsg> {
+        shares
+            .iter()
+            .map(|(address, percent)| RecipientMsg {
+                address: address.to_string(),
+                share: Decimal::percent(*percent),
+            })
+            .collect()
+    }
+
+    fn bank_send(to: &str, amount: u128, denom: &str) -> SubMsg {
+        SubMsg::new(BankMsg::Send {
+            to_address: to.to_string(),
+            amount: coins(amount, denom),
+        })
+    }
+
+    #[test]
+    fn invalid_recipients() {
+        let mut deps = mock_dependencies();
+        let mut init = |recipients| {
+            instantiate(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("creator", &[]),
+                InstantiateMsg { recipients },
+            )
+            .unwrap_err()
+        };
+
+        assert_eq!(init(vec![]), ContractError::NoRecipients {});
+        assert_eq!(
+            init(recipients(&[("alice", 0), ("bob", 100)])),
+            ContractError::InvalidShare {
+                address: "alice".to_string(),
+                share: Decimal::zero()
+            }
+        );
+        assert_eq!(
+            init(recipients(&[("alice", 50), ("alice", 50)])),
+            ContractError::DuplicateRecipient {
+                address: "alice".to_string()
+            }
+        );
+        assert_eq!(
+            init(recipients(&[("alice", 50), ("bob", 40)])),
+            ContractError::InvalidTotalShares {
+                total: Decimal::percent(90)
+            }
+        );
+    }
+
+    #[test]
+    fn distribute_native() {
+        let mut deps = mock_dependencies_with_balance(&[coin(1001, "uatom"), coin(1, "ustars")]);
+        let msg = InstantiateMsg {
+            recipients: recipients(&[("alice", 70), ("bob", 30)]),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let res: RecipientsResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::Recipients {}).unwrap()).unwrap();
+        assert_eq!(res.recipients[0].address, Addr::unchecked("alice"));
+        assert_eq!(res.recipients[1].share, Decimal::percent(30));
+
+        // shares are rounded down, remainder goes to last recipient, zero payouts are skipped
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Distribute { denoms: None },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![
+                bank_send("alice", 700, "uatom"),
+                bank_send("bob", 301, "uatom"),
+                bank_send("bob", 1, "ustars"),
+            ]
+        );
+
+        // only given denoms
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Distribute {
+                denoms: Some(vec!["ustars".to_string()]),
+            },
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![bank_send("bob", 1, "ustars")]);
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Distribute {
+                denoms: Some(vec!["ujuno".to_string()]),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NothingToDistribute {});
+    }
+
+    #[test]
+    fn distribute_cw20() {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            recipients: recipients(&[("alice", 50), ("bob", 25), ("carol", 25)]),
+        };
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let receive_msg = Cw20ReceiveMsg {
+            sender: "marketplace".to_string(),
+            amount: Uint128::new(100),
+            msg: Binary::default(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("usdc", &[]),
+            ExecuteMsg::Receive(receive_msg),
+        )
+        .unwrap();
+        let transfer = |recipient: &str, amount: u128| {
+            SubMsg::new(WasmMsg::Execute {
+                contract_addr: "usdc".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.to_string(),
+                    amount: Uint128::new(amount),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        };
+        assert_eq!(
+            res.messages,
+            vec![
+                transfer("alice", 50),
+                transfer("bob", 25),
+                transfer("carol", 25)
+            ]
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use cosmwasm_std::{Decimal, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("NoRecipients")]
+    NoRecipients {},
+
+    #[error("DuplicateRecipient: {address}")]
+    DuplicateRecipient { address: String },
+
+    #[error("InvalidShare: {share} of {address} must be greater than 0")]
+    InvalidShare { address: String, share: Decimal },
+
+    #[error("InvalidTotalShares: shares must add up to 1, got {total}")]
+    InvalidTotalShares { total: Decimal },
+
+    #[error("NothingToDistribute")]
+    NothingToDistribute {},
+}
@@ -0,0 +1,38 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Decimal;
+use cw20::Cw20ReceiveMsg;
+
+use crate::state::Recipient;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Shares must be greater than 0 and add up to 1
+    pub recipients: Vec<RecipientMsg>,
+}
+
+#[cw_serde]
+pub struct RecipientMsg {
+    pub address: String,
+    pub share: Decimal,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Splits native balance of this contract between recipients, e.g. royalties paid via `BankMsg`.
+    /// Only given denoms if set, otherwise all. Anyone can call this.
+    Distribute { denoms: Option<Vec<String>> },
+    /// Received cw20 tokens are split right away, msg is ignored
+    Receive(Cw20ReceiveMsg),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(RecipientsResponse)]
+    Recipients {},
+}
+
+#[cw_serde]
+pub struct RecipientsResponse {
+    pub recipients: Vec<Recipient>,
+}
@@ -0,0 +1,13 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal};
+use cw_storage_plus::Item;
+
+/// Receives `share` of each payment, shares of all recipients add up to 1
+#[cw_serde]
+pub struct Recipient {
+    pub address: Addr,
+    pub share: Decimal,
+}
+
+/// Fixed on instantiation, so holders and marketplaces can rely on the split
+pub const RECIPIENTS: Item<Vec<Recipient>> = Item::new("recipients");
@@ -1,10 +1,48 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{from_json, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw721::receiver::{Cw721ReceiveMsg, Cw721Receiver};
 
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InnerMsg, InstantiateMsg, QueryMsg};
 
+/// Reference `Cw721Receiver`, accepting tokens from any cw721 contract
+pub struct ReceiverTester;
+
+impl Cw721Receiver for ReceiverTester {
+    type Msg = InnerMsg;
+    type Error = ContractError;
+
+    fn handle_receive(
+        &self,
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        receive_msg: Cw721ReceiveMsg,
+        msg: InnerMsg,
+    ) -> Result<Response, ContractError> {
+        match msg {
+            InnerMsg::Succeed => Ok(Response::new()
+                .add_attributes([
+                    ("action", "receive_nft"),
+                    ("token_id", receive_msg.token_id.as_str()),
+                    ("sender", receive_msg.sender.as_str()),
+                    ("msg", receive_msg.msg.to_base64().as_str()),
+                ])
+                .set_data(
+                    [
+                        receive_msg.token_id,
+                        receive_msg.sender,
+                        receive_msg.msg.to_base64(),
+                    ]
+                    .concat()
+                    .as_bytes(),
+                )),
+            InnerMsg::Fail => Err(ContractError::Failed {}),
+        }
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     _deps: DepsMut,
@@ -17,33 +55,14 @@ pub fn instantiate(
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    _deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::ReceiveNft(receive_msg) => {
-            let inner: InnerMsg = from_json(&receive_msg.msg)?;
-            match inner {
-                InnerMsg::Succeed => Ok(Response::new()
-                    .add_attributes([
-                        ("action", "receive_nft"),
-                        ("token_id", receive_msg.token_id.as_str()),
-                        ("sender", receive_msg.sender.as_str()),
-                        ("msg", receive_msg.msg.to_base64().as_str()),
-                    ])
-                    .set_data(
-                        [
-                            receive_msg.token_id,
-                            receive_msg.sender,
-                            receive_msg.msg.to_base64(),
-                        ]
-                        .concat()
-                        .as_bytes(),
-                    )),
-                InnerMsg::Fail => Err(ContractError::Failed {}),
-            }
+            ReceiverTester.receive_nft(deps, env, info, receive_msg)
         }
     }
 }
@@ -1,4 +1,5 @@
 use cosmwasm_std::StdError;
+use cw721::error::Cw721ReceiveError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,6 +7,9 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Receive(#[from] Cw721ReceiveError),
+
     #[error("I failed because you asked me to do so")]
     Failed {},
     // Add any other custom errors you like here.
@@ -1,4 +1,4 @@
-use cosmwasm_std::{to_json_binary, Addr, Attribute, Binary};
+use cosmwasm_std::{to_json_binary, Addr, Attribute, Binary, StdResult};
 use cw_multi_test::{App, ContractWrapper, Executor};
 
 #[test]
@@ -98,6 +98,70 @@ fn test_cw721_base_receive_fail() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_cw721_base_mint_and_send() {
+    use cw721_base::msg::*;
+    use cw721_receiver_tester::msg::InnerMsg;
+
+    let mut app = App::default();
+    let admin = app.api().addr_make("admin");
+
+    let Contracts {
+        nft_contract,
+        receiver_contract,
+    } = setup_contracts(&mut app, admin.clone());
+
+    let response = app
+        .execute_contract(
+            admin.clone(),
+            nft_contract.clone(),
+            &ExecuteMsg::<(), ()>::MintAndSend {
+                token_id: "minted".to_string(),
+                contract: receiver_contract.to_string(),
+                token_uri: None,
+                extension: (),
+                msg: to_json_binary(&InnerMsg::Succeed).unwrap(),
+                metadata_hash: None,
+            },
+            &[],
+        )
+        .unwrap();
+    let receive_event = response
+        .events
+        .iter()
+        .filter(|e| e.ty == "wasm")
+        .find(|e| get_attribute(&e.attributes, "action") == Some("receive_nft"))
+        .unwrap();
+    assert_eq!(
+        get_attribute(&receive_event.attributes, "token_id"),
+        Some("minted")
+    );
+
+    // receiver failing reverts the mint
+    app.execute_contract(
+        admin,
+        nft_contract.clone(),
+        &ExecuteMsg::<(), ()>::MintAndSend {
+            token_id: "reverted".to_string(),
+            contract: receiver_contract.to_string(),
+            token_uri: None,
+            extension: (),
+            msg: to_json_binary(&InnerMsg::Fail).unwrap(),
+            metadata_hash: None,
+        },
+        &[],
+    )
+    .unwrap_err();
+    let owner: StdResult<OwnerOfResponse> = app.wrap().query_wasm_smart(
+        nft_contract,
+        &QueryMsg::<()>::OwnerOf {
+            token_id: "reverted".to_string(),
+            include_expired: None,
+        },
+    );
+    assert!(owner.is_err());
+}
+
 struct Contracts {
     nft_contract: Addr,
     receiver_contract: Addr,
@@ -127,6 +191,8 @@ fn setup_contracts(app: &mut App, admin: Addr) -> Contracts {
                 symbol: "NFT".to_string(),
                 minter: Some(admin.to_string()),
                 withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
             },
             &[],
             "nft".to_string(),
@@ -154,6 +220,8 @@ fn setup_contracts(app: &mut App, admin: Addr) -> Contracts {
             owner: admin.to_string(),
             token_uri: Some("https://example.com".to_string()),
             extension: (),
+            approval: None,
+            metadata_hash: None,
         },
         &[],
     )
@@ -0,0 +1,508 @@
+use std::marker::PhantomData;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, ListingsResponse, QueryMsg, ReceiveMsg, UserOfResponse,
+};
+use crate::state::{Listing, Rental, LISTINGS};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, BankMsg, Binary, Coin, Deps, DepsMut, Empty, Env, MessageInfo,
+    Order, Response, StdError, StdResult, Uint128,
+};
+use cw2::set_contract_version;
+use cw721::helpers::Cw721Contract;
+use cw721::msg::Cw721ExecuteMsg;
+use cw721::receiver::Cw721ReceiveMsg;
+use cw_storage_plus::Bound;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:cw721-rental";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ReceiveNft(msg) => execute_receive_nft(deps, info, msg),
+        ExecuteMsg::UpdateListing {
+            nft_contract,
+            token_id,
+            daily_price,
+            max_days,
+        } => execute_update_listing(deps, info, nft_contract, token_id, daily_price, max_days),
+        ExecuteMsg::Rent {
+            nft_contract,
+            token_id,
+            days,
+        } => execute_rent(deps, env, info, nft_contract, token_id, days),
+        ExecuteMsg::Withdraw {
+            nft_contract,
+            token_id,
+        } => execute_withdraw(deps, env, info, nft_contract, token_id),
+    }
+}
+
+/// Token deposited via `SendNft`, info.sender is the nft contract
+pub fn execute_receive_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let ReceiveMsg::List {
+        daily_price,
+        max_days,
+    } = from_json(&msg.msg)?;
+    if max_days == 0 {
+        return Err(ContractError::InvalidMaxDays {});
+    }
+    if daily_price.amount.is_zero() {
+        return Err(ContractError::InvalidDailyPrice {});
+    }
+    let owner = deps.api.addr_validate(&msg.sender)?;
+    let listing = Listing {
+        owner,
+        daily_price,
+        max_days,
+        rental: None,
+    };
+    LISTINGS.save(deps.storage, (&info.sender, &msg.token_id), &listing)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "list")
+        .add_attribute("nft_contract", info.sender)
+        .add_attribute("token_id", msg.token_id)
+        .add_attribute("owner", msg.sender))
+}
+
+pub fn execute_update_listing(
+    deps: DepsMut,
+    info: MessageInfo,
+    nft_contract: String,
+    token_id: String,
+    daily_price: Coin,
+    max_days: u32,
+) -> Result<Response, ContractError> {
+    if max_days == 0 {
+        return Err(ContractError::InvalidMaxDays {});
+    }
+    if daily_price.amount.is_zero() {
+        return Err(ContractError::InvalidDailyPrice {});
+    }
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    let mut listing = LISTINGS.load(deps.storage, (&nft_contract, &token_id))?;
+    if listing.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    listing.daily_price = daily_price;
+    listing.max_days = max_days;
+    LISTINGS.save(deps.storage, (&nft_contract, &token_id), &listing)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_listing")
+        .add_attribute("nft_contract", nft_contract)
+        .add_attribute("token_id", token_id))
+}
+
+pub fn execute_rent(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    nft_contract: String,
+    token_id: String,
+    days: u32,
+) -> Result<Response, ContractError> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    let mut listing = LISTINGS.load(deps.storage, (&nft_contract, &token_id))?;
+    if let Some(rental) = listing.active_rental(&env.block) {
+        return Err(ContractError::Rented {
+            expires: rental.expires,
+        });
+    }
+    if days == 0 || days > listing.max_days {
+        return Err(ContractError::InvalidDays {
+            max_days: listing.max_days,
+        });
+    }
+    let expected = Coin {
+        denom: listing.daily_price.denom.clone(),
+        amount: listing
+            .daily_price
+            .amount
+            .checked_mul(Uint128::from(days))
+            .map_err(StdError::from)?,
+    };
+    if info.funds != [expected.clone()] {
+        return Err(ContractError::IncorrectPayment {
+            expected,
+            got: info.funds,
+        });
+    }
+
+    let expires = env.block.time.plus_seconds(days as u64 * SECONDS_PER_DAY);
+    listing.rental = Some(Rental {
+        user: info.sender.clone(),
+        expires,
+    });
+    LISTINGS.save(deps.storage, (&nft_contract, &token_id), &listing)?;
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: listing.owner.to_string(),
+            amount: vec![expected],
+        })
+        .add_attribute("action", "rent")
+        .add_attribute("nft_contract", nft_contract)
+        .add_attribute("token_id", token_id)
+        .add_attribute("user", info.sender)
+        .add_attribute("expires", expires.to_string()))
+}
+
+pub fn execute_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    nft_contract: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    let listing = LISTINGS.load(deps.storage, (&nft_contract, &token_id))?;
+    if listing.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(rental) = listing.active_rental(&env.block) {
+        return Err(ContractError::Rented {
+            expires: rental.expires,
+        });
+    }
+    LISTINGS.remove(deps.storage, (&nft_contract, &token_id));
+
+    let transfer_msg =
+        Cw721Contract::<Empty, Empty>(nft_contract.clone(), PhantomData, PhantomData).call(
+            Cw721ExecuteMsg::TransferNft {
+                recipient: listing.owner.to_string(),
+                token_id: token_id.clone(),
+            },
+        )?;
+    Ok(Response::new()
+        .add_message(transfer_msg)
+        .add_attribute("action", "withdraw")
+        .add_attribute("nft_contract", nft_contract)
+        .add_attribute("token_id", token_id)
+        .add_attribute("owner", listing.owner))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Listing {
+            nft_contract,
+            token_id,
+        } => to_json_binary(&query_listing(deps, nft_contract, token_id)?),
+        QueryMsg::Listings {
+            nft_contract,
+            start_after,
+            limit,
+        } => to_json_binary(&query_listings(deps, nft_contract, start_after, limit)?),
+        QueryMsg::UserOf {
+            nft_contract,
+            token_id,
+        } => to_json_binary(&query_user_of(deps, env, nft_contract, token_id)?),
+    }
+}
+
+fn query_listing(deps: Deps, nft_contract: String, token_id: String) -> StdResult<Listing> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    LISTINGS.load(deps.storage, (&nft_contract, &token_id))
+}
+
+fn query_listings(
+    deps: Deps,
+    nft_contract: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+    let listings = LISTINGS
+        .prefix(&nft_contract)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ListingsResponse { listings })
+}
+
+fn query_user_of(
+    deps: Deps,
+    env: Env,
+    nft_contract: String,
+    token_id: String,
+) -> StdResult<UserOfResponse> {
+    let nft_contract = deps.api.addr_validate(&nft_contract)?;
+    let listing = LISTINGS.may_load(deps.storage, (&nft_contract, &token_id))?;
+    let rental = listing
+        .as_ref()
+        .and_then(|listing| listing.active_rental(&env.block));
+    Ok(UserOfResponse {
+        user: rental.map(|rental| rental.user.clone()),
+        expires: rental.map(|rental| rental.expires),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coin, coins, Addr, CosmosMsg, WasmMsg};
+
+    const NFT_CONTRACT: &str = "nft";
+    const OWNER: &str = "owner";
+    const RENTER: &str = "renter";
+
+    fn setup_listing(mut deps: DepsMut) {
+        instantiate(
+            deps.branch(),
+            mock_env(),
+            mock_info("creator", &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let receive_msg = Cw721ReceiveMsg {
+            sender: String::from(OWNER),
+            token_id: String::from("1"),
+            msg: to_json_binary(&ReceiveMsg::List {
+                daily_price: coin(10, "uatom"),
+                max_days: 7,
+            })
+            .unwrap(),
+        };
+        execute(
+            deps,
+            mock_env(),
+            mock_info(NFT_CONTRACT, &[]),
+            ExecuteMsg::ReceiveNft(receive_msg),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn renting() {
+        let mut deps = mock_dependencies();
+        setup_listing(deps.as_mut());
+
+        let rent_msg = ExecuteMsg::Rent {
+            nft_contract: String::from(NFT_CONTRACT),
+            token_id: String::from("1"),
+            days: 3,
+        };
+        // too many days
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(RENTER, &coins(80, "uatom")),
+            ExecuteMsg::Rent {
+                nft_contract: String::from(NFT_CONTRACT),
+                token_id: String::from("1"),
+                days: 8,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidDays { max_days: 7 });
+        // wrong payment
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(RENTER, &coins(20, "uatom")),
+            rent_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::IncorrectPayment {
+                expected: coin(30, "uatom"),
+                got: coins(20, "uatom"),
+            }
+        );
+
+        // rent is paid to owner
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(RENTER, &coins(30, "uatom")),
+            rent_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from(OWNER),
+                amount: coins(30, "uatom"),
+            })
+        );
+        let expires = mock_env().block.time.plus_seconds(3 * SECONDS_PER_DAY);
+        let user_of = query_user_of(
+            deps.as_ref(),
+            mock_env(),
+            String::from(NFT_CONTRACT),
+            String::from("1"),
+        )
+        .unwrap();
+        assert_eq!(
+            user_of,
+            UserOfResponse {
+                user: Some(Addr::unchecked(RENTER)),
+                expires: Some(expires),
+            }
+        );
+
+        // cannot be rented again or withdrawn while rented
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("another_renter", &coins(30, "uatom")),
+            rent_msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Rented { expires });
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            nft_contract: String::from(NFT_CONTRACT),
+            token_id: String::from("1"),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            withdraw_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Rented { expires });
+
+        // user role ends automatically after expiry, then owner can withdraw
+        let mut env = mock_env();
+        env.block.time = expires;
+        let user_of = query_user_of(
+            deps.as_ref(),
+            env.clone(),
+            String::from(NFT_CONTRACT),
+            String::from("1"),
+        )
+        .unwrap();
+        assert_eq!(
+            user_of,
+            UserOfResponse {
+                user: None,
+                expires: None,
+            }
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(RENTER, &[]),
+            withdraw_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), env, mock_info(OWNER, &[]), withdraw_msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: String::from(NFT_CONTRACT),
+                msg: to_json_binary(&Cw721ExecuteMsg::<Empty, Empty>::TransferNft {
+                    recipient: String::from(OWNER),
+                    token_id: String::from("1"),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        query_listing(deps.as_ref(), String::from(NFT_CONTRACT), String::from("1")).unwrap_err();
+    }
+
+    #[test]
+    fn zero_daily_price() {
+        let mut deps = mock_dependencies();
+        setup_listing(deps.as_mut());
+
+        let receive_msg = Cw721ReceiveMsg {
+            sender: String::from(OWNER),
+            token_id: String::from("2"),
+            msg: to_json_binary(&ReceiveMsg::List {
+                daily_price: coin(0, "uatom"),
+                max_days: 7,
+            })
+            .unwrap(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(NFT_CONTRACT, &[]),
+            ExecuteMsg::ReceiveNft(receive_msg),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidDailyPrice {});
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::UpdateListing {
+                nft_contract: String::from(NFT_CONTRACT),
+                token_id: String::from("1"),
+                daily_price: coin(0, "uatom"),
+                max_days: 7,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidDailyPrice {});
+    }
+
+    #[test]
+    fn rent_overflow() {
+        let mut deps = mock_dependencies();
+        setup_listing(deps.as_mut());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::UpdateListing {
+                nft_contract: String::from(NFT_CONTRACT),
+                token_id: String::from("1"),
+                daily_price: coin(u128::MAX, "uatom"),
+                max_days: 7,
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(RENTER, &coins(1, "uatom")),
+            ExecuteMsg::Rent {
+                nft_contract: String::from(NFT_CONTRACT),
+                token_id: String::from("1"),
+                days: 2,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::Overflow { .. })));
+    }
+}
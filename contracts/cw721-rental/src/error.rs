@@ -0,0 +1,30 @@
+use cosmwasm_std::{Coin, StdError, Timestamp};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("InvalidMaxDays")]
+    InvalidMaxDays {},
+
+    #[error("InvalidDailyPrice: must be greater than zero")]
+    InvalidDailyPrice {},
+
+    #[error("InvalidDays: must be between 1 and {max_days}")]
+    InvalidDays { max_days: u32 },
+
+    #[error("Incorrect payment: expected {expected}")]
+    IncorrectPayment { expected: Coin, got: Vec<Coin> },
+
+    #[error("Rented until {expires}")]
+    Rented { expires: Timestamp },
+}
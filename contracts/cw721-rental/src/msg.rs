@@ -0,0 +1,75 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Coin, Timestamp};
+use cw721::receiver::Cw721ReceiveMsg;
+
+use crate::state::Listing;
+
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Deposits a token, sent via `SendNft` with `ReceiveMsg::List` as msg
+    ReceiveNft(Cw721ReceiveMsg),
+    /// Updates rent terms, only affects future rentals. Only listing owner can call this.
+    UpdateListing {
+        nft_contract: String,
+        token_id: String,
+        daily_price: Coin,
+        max_days: u32,
+    },
+    /// Rents token for given days, payment of daily price times days must be attached
+    /// and is forwarded to listing owner.
+    Rent {
+        nft_contract: String,
+        token_id: String,
+        days: u32,
+    },
+    /// Returns token to listing owner, only possible if not rented.
+    /// Only listing owner can call this.
+    Withdraw {
+        nft_contract: String,
+        token_id: String,
+    },
+}
+
+/// Msg passed in `SendNft` when depositing a token
+#[cw_serde]
+pub enum ReceiveMsg {
+    List { daily_price: Coin, max_days: u32 },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(Listing)]
+    Listing {
+        nft_contract: String,
+        token_id: String,
+    },
+    /// Lists tokens of given nft contract
+    #[returns(ListingsResponse)]
+    Listings {
+        nft_contract: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Current user of the token, similar to ERC-4907 `userOf` and `userExpires`
+    #[returns(UserOfResponse)]
+    UserOf {
+        nft_contract: String,
+        token_id: String,
+    },
+}
+
+#[cw_serde]
+pub struct ListingsResponse {
+    pub listings: Vec<(String, Listing)>,
+}
+
+#[cw_serde]
+pub struct UserOfResponse {
+    /// `None` if not rented or rental has expired
+    pub user: Option<Addr>,
+    pub expires: Option<Timestamp>,
+}
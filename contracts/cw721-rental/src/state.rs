@@ -0,0 +1,34 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, BlockInfo, Coin, Timestamp};
+use cw_storage_plus::Map;
+
+#[cw_serde]
+pub struct Listing {
+    /// Owner who deposited the token, receives rent payments and can withdraw the token
+    pub owner: Addr,
+    /// Rent per day
+    pub daily_price: Coin,
+    /// Max number of days a token can be rented at once
+    pub max_days: u32,
+    /// Current or last rental, renter's user role ends automatically once expired
+    pub rental: Option<Rental>,
+}
+
+impl Listing {
+    /// returns rental, unless it is expired
+    pub fn active_rental(&self, block: &BlockInfo) -> Option<&Rental> {
+        self.rental
+            .as_ref()
+            .filter(|rental| rental.expires > block.time)
+    }
+}
+
+/// ERC-4907 like user role, user can use but not transfer the token
+#[cw_serde]
+pub struct Rental {
+    pub user: Addr,
+    pub expires: Timestamp,
+}
+
+/// Stored as (nft contract, token_id)
+pub const LISTINGS: Map<(&Addr, &str), Listing> = Map::new("listings");
@@ -0,0 +1,703 @@
+use crate::error::ContractError;
+use crate::msg::{DelegationResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::state::{Cw721RolesContract, MetadataExt, DELEGATIONS, HOOKS, MEMBERS, TOTAL, WEIGHTS};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdResult,
+    Storage, SubMsg, Uint64,
+};
+use cw4::{
+    AdminResponse, Member, MemberChangedHookMsg, MemberDiff, MemberListResponse, MemberResponse,
+    TotalWeightResponse,
+};
+use cw721::execute::Cw721Execute;
+use cw721::msg::Cw721ExecuteMsg;
+use cw721::query::Cw721Query;
+use cw721::state::MINTER;
+use cw_storage_plus::Bound;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:cw721-roles";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let response = Cw721RolesContract::default().instantiate(
+        deps.branch(),
+        env.clone(),
+        info,
+        msg,
+        CONTRACT_NAME,
+        CONTRACT_VERSION,
+    )?;
+    TOTAL.save(deps.storage, &0, env.block.height)?;
+    Ok(response)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Mint {
+            token_id,
+            owner,
+            token_uri,
+            extension,
+        } => execute_mint(deps, env, info, token_id, owner, token_uri, extension),
+        ExecuteMsg::Burn { token_id } => execute_burn(deps, env, info, token_id),
+        ExecuteMsg::UpdateTokenWeight { token_id, weight } => {
+            execute_update_token_weight(deps, env, info, token_id, weight)
+        }
+        ExecuteMsg::Delegate { to } => execute_delegate(deps, env, info, to),
+        ExecuteMsg::Undelegate {} => execute_undelegate(deps, env, info),
+        ExecuteMsg::AddHook { addr } => {
+            MINTER.assert_owner(deps.storage, &info.sender)?;
+            HOOKS.add_hook(deps.storage, deps.api.addr_validate(&addr)?)?;
+            Ok(Response::new()
+                .add_attribute("action", "add_hook")
+                .add_attribute("hook", addr))
+        }
+        ExecuteMsg::RemoveHook { addr } => {
+            MINTER.assert_owner(deps.storage, &info.sender)?;
+            HOOKS.remove_hook(deps.storage, deps.api.addr_validate(&addr)?)?;
+            Ok(Response::new()
+                .add_attribute("action", "remove_hook")
+                .add_attribute("hook", addr))
+        }
+        ExecuteMsg::UpdateOwnership(action) => Ok(Cw721RolesContract::default().execute(
+            deps,
+            env,
+            info,
+            Cw721ExecuteMsg::UpdateOwnership(action),
+        )?),
+    }
+}
+
+pub fn execute_mint(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    owner: String,
+    token_uri: Option<String>,
+    extension: MetadataExt,
+) -> Result<Response, ContractError> {
+    let weight = extension.weight;
+    let response = Cw721RolesContract::default().mint(
+        deps.branch(),
+        &env,
+        info,
+        token_id,
+        owner.clone(),
+        token_uri,
+        extension,
+        None,
+        None,
+    )?;
+    let owner = deps.api.addr_validate(&owner)?;
+    let diffs = update_weight(deps.storage, env.block.height, &owner, weight, 0)?;
+    Ok(response.add_submessages(member_changed_hooks(deps.storage, diffs)?))
+}
+
+pub fn execute_burn(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    MINTER.assert_owner(deps.storage, &info.sender)?;
+    let config = Cw721RolesContract::default().config;
+    let token = config.nft_info.load(deps.storage, &token_id)?;
+    config.remove_nft(deps.storage, &token_id, &token)?;
+    config.decrement_tokens(deps.storage, env.block.height)?;
+    let diffs = update_weight(
+        deps.storage,
+        env.block.height,
+        &token.owner,
+        0,
+        token.extension.weight,
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "burn")
+        .add_attribute("sender", info.sender)
+        .add_attribute("token_id", token_id)
+        .add_submessages(member_changed_hooks(deps.storage, diffs)?))
+}
+
+pub fn execute_update_token_weight(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    weight: u64,
+) -> Result<Response, ContractError> {
+    MINTER.assert_owner(deps.storage, &info.sender)?;
+    let config = Cw721RolesContract::default().config;
+    let old_token = config.nft_info.load(deps.storage, &token_id)?;
+    let mut token = old_token.clone();
+    token.extension.weight = weight;
+    config.save_nft(deps.storage, &token_id, &token, Some(&old_token))?;
+    let diffs = update_weight(
+        deps.storage,
+        env.block.height,
+        &token.owner,
+        weight,
+        old_token.extension.weight,
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "update_token_weight")
+        .add_attribute("token_id", token_id)
+        .add_attribute("weight", weight.to_string())
+        .add_submessages(member_changed_hooks(deps.storage, diffs)?))
+}
+
+pub fn execute_delegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to: String,
+) -> Result<Response, ContractError> {
+    let to = deps.api.addr_validate(&to)?;
+    if to == info.sender {
+        return Err(ContractError::SelfDelegation {});
+    }
+    let weight = WEIGHTS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let current = DELEGATIONS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_else(|| info.sender.clone());
+    DELEGATIONS.save(deps.storage, &info.sender, &to)?;
+    let diffs = vec![
+        update_voting_power(deps.storage, env.block.height, &current, 0, weight)?,
+        update_voting_power(deps.storage, env.block.height, &to, weight, 0)?,
+    ];
+    Ok(Response::new()
+        .add_attribute("action", "delegate")
+        .add_attribute("sender", info.sender)
+        .add_attribute("delegate", to)
+        .add_attribute("weight", weight.to_string())
+        .add_submessages(member_changed_hooks(deps.storage, diffs)?))
+}
+
+pub fn execute_undelegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let delegate = DELEGATIONS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::NotDelegated {})?;
+    DELEGATIONS.remove(deps.storage, &info.sender);
+    let weight = WEIGHTS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let diffs = vec![
+        update_voting_power(deps.storage, env.block.height, &delegate, 0, weight)?,
+        update_voting_power(deps.storage, env.block.height, &info.sender, weight, 0)?,
+    ];
+    Ok(Response::new()
+        .add_attribute("action", "undelegate")
+        .add_attribute("sender", info.sender)
+        .add_attribute("delegate", delegate)
+        .add_attribute("weight", weight.to_string())
+        .add_submessages(member_changed_hooks(deps.storage, diffs)?))
+}
+
+/// Adds and subtracts token weight held by `addr` and total weight.
+/// Voting power goes to its delegate, if any.
+fn update_weight(
+    storage: &mut dyn Storage,
+    height: u64,
+    addr: &Addr,
+    add: u64,
+    sub: u64,
+) -> StdResult<Vec<MemberDiff>> {
+    let weight = apply_weight(
+        WEIGHTS.may_load(storage, addr)?.unwrap_or_default(),
+        add,
+        sub,
+    )?;
+    if weight == 0 {
+        WEIGHTS.remove(storage, addr);
+    } else {
+        WEIGHTS.save(storage, addr, &weight)?;
+    }
+    let total = apply_weight(TOTAL.may_load(storage)?.unwrap_or_default(), add, sub)?;
+    TOTAL.save(storage, &total, height)?;
+    let delegate = DELEGATIONS
+        .may_load(storage, addr)?
+        .unwrap_or_else(|| addr.clone());
+    Ok(vec![update_voting_power(
+        storage, height, &delegate, add, sub,
+    )?])
+}
+
+/// Adds and subtracts voting power of a member. Members without voting power are removed.
+fn update_voting_power(
+    storage: &mut dyn Storage,
+    height: u64,
+    addr: &Addr,
+    add: u64,
+    sub: u64,
+) -> StdResult<MemberDiff> {
+    let old = MEMBERS.may_load(storage, addr)?;
+    let weight = apply_weight(old.unwrap_or_default(), add, sub)?;
+    let new = if weight == 0 {
+        MEMBERS.remove(storage, addr, height)?;
+        None
+    } else {
+        MEMBERS.save(storage, addr, &weight, height)?;
+        Some(weight)
+    };
+    Ok(MemberDiff::new(addr, old, new))
+}
+
+/// `weight - sub + add`, erroring on overflow, e.g. of summed weights set by the minter
+fn apply_weight(weight: u64, add: u64, sub: u64) -> StdResult<u64> {
+    Ok(Uint64::new(weight)
+        .checked_sub(Uint64::new(sub))?
+        .checked_add(Uint64::new(add))?
+        .u64())
+}
+
+fn member_changed_hooks(storage: &dyn Storage, diffs: Vec<MemberDiff>) -> StdResult<Vec<SubMsg>> {
+    let diffs: Vec<_> = diffs
+        .into_iter()
+        .filter(|diff| diff.old != diff.new)
+        .collect();
+    if diffs.is_empty() {
+        return Ok(vec![]);
+    }
+    let msg = MemberChangedHookMsg::new(diffs);
+    HOOKS.prepare_hooks(storage, |hook| {
+        msg.clone().into_cosmos_msg(hook).map(SubMsg::new)
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Admin {} => to_json_binary(&query_admin(deps)?),
+        QueryMsg::TotalWeight { at_height } => {
+            to_json_binary(&query_total_weight(deps, at_height)?)
+        }
+        QueryMsg::ListMembers { start_after, limit } => {
+            to_json_binary(&query_list_members(deps, start_after, limit)?)
+        }
+        QueryMsg::Member { addr, at_height } => {
+            to_json_binary(&query_member(deps, addr, at_height)?)
+        }
+        QueryMsg::Hooks {} => to_json_binary(&HOOKS.query_hooks(deps)?),
+        QueryMsg::Delegation { addr } => to_json_binary(&query_delegation(deps, addr)?),
+        _ => Cw721RolesContract::default().query(deps, env, msg.into()),
+    }
+}
+
+pub fn query_admin(deps: Deps) -> StdResult<AdminResponse> {
+    let admin = MINTER.get_ownership(deps.storage)?.owner;
+    Ok(AdminResponse {
+        admin: admin.map(String::from),
+    })
+}
+
+pub fn query_total_weight(deps: Deps, at_height: Option<u64>) -> StdResult<TotalWeightResponse> {
+    let weight = match at_height {
+        Some(height) => TOTAL.may_load_at_height(deps.storage, height)?,
+        None => TOTAL.may_load(deps.storage)?,
+    };
+    Ok(TotalWeightResponse {
+        weight: weight.unwrap_or_default(),
+    })
+}
+
+pub fn query_member(deps: Deps, addr: String, at_height: Option<u64>) -> StdResult<MemberResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let weight = match at_height {
+        Some(height) => MEMBERS.may_load_at_height(deps.storage, &addr, height)?,
+        None => MEMBERS.may_load(deps.storage, &addr)?,
+    };
+    Ok(MemberResponse { weight })
+}
+
+pub fn query_delegation(deps: Deps, addr: String) -> StdResult<DelegationResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    Ok(DelegationResponse {
+        delegate: DELEGATIONS.may_load(deps.storage, &addr)?,
+        weight: WEIGHTS.may_load(deps.storage, &addr)?.unwrap_or_default(),
+    })
+}
+
+pub fn query_list_members(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<MemberListResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let members = MEMBERS
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (addr, weight) = item?;
+            Ok(Member {
+                addr: addr.into(),
+                weight,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(MemberListResponse { members })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::from_json;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cw721::msg::TokensResponse;
+    use cw_ownable::OwnershipError;
+
+    const ADMIN: &str = "admin";
+    const HOOK: &str = "dao";
+
+    fn setup_contract(deps: DepsMut) {
+        instantiate(
+            deps,
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {
+                name: "Council".to_string(),
+                symbol: "ROLE".to_string(),
+                minter: None,
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
+            },
+        )
+        .unwrap();
+    }
+
+    fn mint_msg(token_id: &str, owner: &str, weight: u64) -> ExecuteMsg {
+        ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            token_uri: None,
+            extension: MetadataExt {
+                role: Some("council".to_string()),
+                weight,
+            },
+        }
+    }
+
+    #[test]
+    fn membership_follows_role_tokens() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        setup_contract(deps.as_mut());
+        assert_eq!(
+            query_admin(deps.as_ref()).unwrap().admin,
+            Some(ADMIN.to_string())
+        );
+
+        // only admin can add hooks and mint
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::AddHook {
+                addr: HOOK.to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Ownership(OwnershipError::NotOwner));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            ExecuteMsg::AddHook {
+                addr: HOOK.to_string(),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            mint_msg("1", "alice", 1),
+        )
+        .unwrap_err();
+
+        // weights of all tokens held by a member add up, hooks are notified
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            mint_msg("1", "alice", 2),
+        )
+        .unwrap();
+        let expected = MemberChangedHookMsg::one(MemberDiff::new("alice", None, Some(2)));
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(expected.into_cosmos_msg(HOOK).unwrap())]
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            mint_msg("2", "alice", 3),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            mint_msg("3", "bob", 1),
+        )
+        .unwrap();
+        let mint_height = env.block.height;
+        assert_eq!(
+            query_member(deps.as_ref(), "alice".to_string(), None)
+                .unwrap()
+                .weight,
+            Some(5)
+        );
+        assert_eq!(query_total_weight(deps.as_ref(), None).unwrap().weight, 6);
+        assert_eq!(
+            query_list_members(deps.as_ref(), None, None)
+                .unwrap()
+                .members,
+            vec![
+                Member {
+                    addr: "alice".to_string(),
+                    weight: 5
+                },
+                Member {
+                    addr: "bob".to_string(),
+                    weight: 1
+                },
+            ]
+        );
+
+        // role tokens are queryable as cw721 tokens
+        let tokens: TokensResponse = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Tokens {
+                    owner: "alice".to_string(),
+                    start_after: None,
+                    limit: None,
+                    cursor: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(tokens.tokens, vec!["1".to_string(), "2".to_string()]);
+
+        // weight update and burn change membership
+        env.block.height += 1;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            ExecuteMsg::UpdateTokenWeight {
+                token_id: "1".to_string(),
+                weight: 10,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            ExecuteMsg::Burn {
+                token_id: "3".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            query_member(deps.as_ref(), "alice".to_string(), None)
+                .unwrap()
+                .weight,
+            Some(13)
+        );
+        assert_eq!(
+            query_member(deps.as_ref(), "bob".to_string(), None)
+                .unwrap()
+                .weight,
+            None
+        );
+        assert_eq!(query_total_weight(deps.as_ref(), None).unwrap().weight, 13);
+
+        // snapshots at previous height are kept for voting
+        assert_eq!(
+            query_member(deps.as_ref(), "bob".to_string(), Some(mint_height + 1))
+                .unwrap()
+                .weight,
+            Some(1)
+        );
+        assert_eq!(
+            query_total_weight(deps.as_ref(), Some(mint_height + 1))
+                .unwrap()
+                .weight,
+            6
+        );
+    }
+
+    #[test]
+    fn delegation() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        setup_contract(deps.as_mut());
+        let admin = mock_info(ADMIN, &[]);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin.clone(),
+            mint_msg("1", "alice", 2),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin.clone(),
+            mint_msg("2", "bob", 1),
+        )
+        .unwrap();
+        let voting_power = |deps: Deps, addr: &str, at_height: Option<u64>| {
+            query_member(deps, addr.to_string(), at_height)
+                .unwrap()
+                .weight
+        };
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::Undelegate {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NotDelegated {});
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::Delegate {
+                to: "alice".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::SelfDelegation {});
+
+        // alice delegates to bob, total weight is unchanged
+        env.block.height += 1;
+        let delegate_height = env.block.height;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            ExecuteMsg::Delegate {
+                to: "bob".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(voting_power(deps.as_ref(), "alice", None), None);
+        assert_eq!(voting_power(deps.as_ref(), "bob", None), Some(3));
+        assert_eq!(query_total_weight(deps.as_ref(), None).unwrap().weight, 3);
+        assert_eq!(
+            query_delegation(deps.as_ref(), "alice".to_string()).unwrap(),
+            DelegationResponse {
+                delegate: Some(Addr::unchecked("bob")),
+                weight: 2
+            }
+        );
+
+        // weight of tokens received later follows the delegation
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            admin.clone(),
+            mint_msg("3", "alice", 4),
+        )
+        .unwrap();
+        assert_eq!(voting_power(deps.as_ref(), "bob", None), Some(7));
+
+        // snapshots keep voting power before delegation
+        assert_eq!(
+            voting_power(deps.as_ref(), "alice", Some(delegate_height)),
+            Some(2)
+        );
+        assert_eq!(
+            voting_power(deps.as_ref(), "bob", Some(delegate_height)),
+            Some(1)
+        );
+
+        env.block.height += 1;
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("alice", &[]),
+            ExecuteMsg::Undelegate {},
+        )
+        .unwrap();
+        assert_eq!(voting_power(deps.as_ref(), "alice", None), Some(6));
+        assert_eq!(voting_power(deps.as_ref(), "bob", None), Some(1));
+        assert_eq!(
+            query_delegation(deps.as_ref(), "alice".to_string())
+                .unwrap()
+                .delegate,
+            None
+        );
+    }
+
+    #[test]
+    fn weight_overflow() {
+        let mut deps = mock_dependencies();
+        setup_contract(deps.as_mut());
+        for (token_id, owner) in [("1", "alice"), ("2", "bob")] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ADMIN, &[]),
+                mint_msg(token_id, owner, 1),
+            )
+            .unwrap();
+        }
+        // total weight would overflow
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            ExecuteMsg::UpdateTokenWeight {
+                token_id: "1".to_string(),
+                weight: u64::MAX,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Std(cosmwasm_std::StdError::Overflow { .. })
+        ));
+    }
+}
@@ -0,0 +1,26 @@
+use cosmwasm_std::StdError;
+use cw721::error::Cw721ContractError;
+use cw_controllers::HookError;
+use cw_ownable::OwnershipError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Base(#[from] Cw721ContractError),
+
+    #[error("{0}")]
+    Ownership(#[from] OwnershipError),
+
+    #[error("{0}")]
+    Hook(#[from] HookError),
+
+    #[error("Can't delegate to self, use Undelegate")]
+    SelfDelegation {},
+
+    #[error("Voting power is not delegated")]
+    NotDelegated {},
+}
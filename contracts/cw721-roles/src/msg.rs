@@ -0,0 +1,173 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Binary};
+use cw4::{AdminResponse, MemberListResponse, MemberResponse, TotalWeightResponse};
+use cw721::msg::{
+    AllNftInfoResponse, Cw721InstantiateMsg, Cw721QueryMsg, NftInfoResponse, NumTokensResponse,
+    OwnerOfResponse, TokensResponse,
+};
+use cw721::state::CollectionInfo;
+use cw_controllers::HooksResponse;
+use cw_ownable::{Action, Ownership};
+
+use crate::state::MetadataExt;
+
+/// Minter is the admin of the group
+pub type InstantiateMsg = Cw721InstantiateMsg;
+
+/// Role tokens are soulbound, so there are no transfer and approval messages.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Mints a role token, adding its weight to the owner's membership. Only minter can call this.
+    Mint {
+        token_id: String,
+        owner: String,
+        token_uri: Option<String>,
+        extension: MetadataExt,
+    },
+    /// Burns a role token, removing its weight from the holder's membership.
+    /// Only minter can call this.
+    Burn {
+        token_id: String,
+    },
+    /// Changes weight of a role token and its holder's membership. Only minter can call this.
+    UpdateTokenWeight {
+        token_id: String,
+        weight: u64,
+    },
+    /// Delegates sender's weight as voting power to `to`, without transferring role tokens.
+    /// Replaces any previous delegation, weight of tokens received later follows the delegation.
+    Delegate {
+        to: String,
+    },
+    /// Takes back sender's delegated voting power
+    Undelegate {},
+    /// Adds a contract notified with cw4's `MemberChangedHookMsg`. Only minter can call this.
+    AddHook {
+        addr: String,
+    },
+    /// Only minter can call this.
+    RemoveHook {
+        addr: String,
+    },
+    UpdateOwnership(Action),
+}
+
+/// cw4 group queries, plus cw721 queries for role tokens
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Minter, as admin of the group
+    #[returns(AdminResponse)]
+    Admin {},
+    #[returns(TotalWeightResponse)]
+    TotalWeight { at_height: Option<u64> },
+    #[returns(MemberListResponse)]
+    ListMembers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Voting power of a member including delegations, `None` if it has none
+    #[returns(MemberResponse)]
+    Member {
+        addr: String,
+        at_height: Option<u64>,
+    },
+    #[returns(HooksResponse)]
+    Hooks {},
+    /// Delegate and own weight of an address
+    #[returns(DelegationResponse)]
+    Delegation { addr: String },
+
+    // -- below copied from Cw721QueryMsg
+    #[returns(OwnerOfResponse)]
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    #[returns(NumTokensResponse)]
+    NumTokens {},
+    #[returns(CollectionInfo)]
+    ContractInfo {},
+    #[returns(Ownership<Addr>)]
+    Ownership {},
+    #[returns(NftInfoResponse<MetadataExt>)]
+    NftInfo { token_id: String },
+    #[returns(AllNftInfoResponse<MetadataExt>)]
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+        include_collection: Option<bool>,
+    },
+    /// Role tokens held by `owner`
+    #[returns(TokensResponse)]
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
+    },
+    #[returns(TokensResponse)]
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
+    },
+}
+
+impl From<QueryMsg> for Cw721QueryMsg<MetadataExt> {
+    fn from(msg: QueryMsg) -> Cw721QueryMsg<MetadataExt> {
+        match msg {
+            QueryMsg::OwnerOf {
+                token_id,
+                include_expired,
+            } => Cw721QueryMsg::OwnerOf {
+                token_id,
+                include_expired,
+            },
+            QueryMsg::NumTokens {} => Cw721QueryMsg::NumTokens {},
+            QueryMsg::ContractInfo {} => Cw721QueryMsg::ContractInfo {},
+            QueryMsg::Ownership {} => Cw721QueryMsg::Ownership {},
+            QueryMsg::NftInfo { token_id } => Cw721QueryMsg::NftInfo { token_id },
+            QueryMsg::AllNftInfo {
+                token_id,
+                include_expired,
+                include_collection,
+            } => Cw721QueryMsg::AllNftInfo {
+                token_id,
+                include_expired,
+                include_collection,
+            },
+            QueryMsg::Tokens {
+                owner,
+                start_after,
+                limit,
+                cursor,
+            } => Cw721QueryMsg::Tokens {
+                owner,
+                start_after,
+                limit,
+                cursor,
+            },
+            QueryMsg::AllTokens {
+                start_after,
+                limit,
+                cursor,
+            } => Cw721QueryMsg::AllTokens {
+                start_after,
+                limit,
+                cursor,
+            },
+            msg => unreachable!("Unsupported query: {:?}", msg),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct DelegationResponse {
+    /// Address receiving this address' voting power, if delegated
+    pub delegate: Option<Addr>,
+    /// Sum of role token weights held by this address
+    pub weight: u64,
+}
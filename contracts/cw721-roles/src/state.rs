@@ -0,0 +1,45 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Empty};
+use cw4::{
+    MEMBERS_CHANGELOG, MEMBERS_CHECKPOINTS, MEMBERS_KEY, TOTAL_KEY, TOTAL_KEY_CHANGELOG,
+    TOTAL_KEY_CHECKPOINTS,
+};
+use cw721_base::Cw721Contract;
+use cw_controllers::Hooks;
+use cw_storage_plus::{Map, SnapshotItem, SnapshotMap, Strategy};
+
+#[cw_serde]
+#[derive(Default)]
+pub struct MetadataExt {
+    /// Optional role name, e.g. "council"
+    pub role: Option<String>,
+    /// Voting weight, a member's weight is the sum over all role tokens it holds
+    pub weight: u64,
+}
+
+pub type Cw721RolesContract<'a> = Cw721Contract<'a, MetadataExt, Empty, Empty>;
+
+/// Member voting power, i.e. own weight unless delegated plus weight delegated to it.
+/// Same keys as cw4-group so `Cw4Contract` raw queries work
+pub const MEMBERS: SnapshotMap<&Addr, u64> = SnapshotMap::new(
+    MEMBERS_KEY,
+    MEMBERS_CHECKPOINTS,
+    MEMBERS_CHANGELOG,
+    Strategy::EveryBlock,
+);
+
+pub const TOTAL: SnapshotItem<u64> = SnapshotItem::new(
+    TOTAL_KEY,
+    TOTAL_KEY_CHECKPOINTS,
+    TOTAL_KEY_CHANGELOG,
+    Strategy::EveryBlock,
+);
+
+/// Sum of role token weights held by an address, regardless of delegation
+pub const WEIGHTS: Map<&Addr, u64> = Map::new("weights");
+
+/// Stored as delegator -> delegate, receiving the delegator's weight as voting power
+pub const DELEGATIONS: Map<&Addr, Addr> = Map::new("delegations");
+
+/// Contracts notified via `MemberChangedHookMsg` on every membership change
+pub const HOOKS: Hooks = Hooks::new("cw4-hooks");
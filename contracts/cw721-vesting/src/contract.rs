@@ -0,0 +1,461 @@
+use std::marker::PhantomData;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, TokensResponse, VestingResponse};
+use crate::state::{Config, Schedule, Vesting, CONFIG, TOKENS, VESTING};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, Order,
+    Response, StdResult, Storage,
+};
+use cw2::set_contract_version;
+use cw721::helpers::Cw721Contract;
+use cw721::msg::Cw721ExecuteMsg;
+use cw721::receiver::Cw721ReceiveMsg;
+use cw_storage_plus::Bound;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:cw721-vesting";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    if msg.tranches == 0 || (msg.tranches > 1 && msg.interval == 0) {
+        return Err(ContractError::InvalidSchedule {});
+    }
+    let config = Config {
+        grantor: info.sender,
+        beneficiary: deps.api.addr_validate(&msg.beneficiary)?,
+        nft_contract: deps.api.addr_validate(&msg.nft_contract)?,
+        schedule: Schedule {
+            start: msg.start.unwrap_or(env.block.time),
+            cliff: msg.cliff,
+            tranches: msg.tranches,
+            interval: msg.interval,
+        },
+    };
+    CONFIG.save(deps.storage, &config)?;
+    VESTING.save(
+        deps.storage,
+        &Vesting {
+            total: 0,
+            released: 0,
+            revoked: false,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("grantor", config.grantor)
+        .add_attribute("beneficiary", config.beneficiary))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ReceiveNft(msg) => execute_receive_nft(deps, info, msg),
+        ExecuteMsg::Release { limit } => execute_release(deps, env, limit),
+        ExecuteMsg::Revoke {} => execute_revoke(deps, env, info),
+    }
+}
+
+/// Token deposited via `SendNft`, info.sender is the nft contract
+pub fn execute_receive_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.nft_contract || msg.sender != config.grantor {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut vesting = VESTING.load(deps.storage)?;
+    if vesting.revoked {
+        return Err(ContractError::Revoked {});
+    }
+    TOKENS.save(deps.storage, &msg.token_id, &Empty {})?;
+    vesting.total += 1;
+    VESTING.save(deps.storage, &vesting)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "deposit")
+        .add_attribute("token_id", msg.token_id)
+        .add_attribute("total", vesting.total.to_string()))
+}
+
+pub fn execute_release(
+    deps: DepsMut,
+    env: Env,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut vesting = VESTING.load(deps.storage)?;
+    let releasable = vesting.vested(&config.schedule, env.block.time) - vesting.released;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as u64;
+    let token_ids = take_tokens(deps.storage, Order::Ascending, releasable.min(limit))?;
+    if token_ids.is_empty() {
+        return Err(ContractError::NothingToRelease {});
+    }
+    vesting.released += token_ids.len() as u64;
+    VESTING.save(deps.storage, &vesting)?;
+
+    Ok(Response::new()
+        .add_messages(transfer_nfts(
+            &config.nft_contract,
+            &config.beneficiary,
+            &token_ids,
+        )?)
+        .add_attribute("action", "release")
+        .add_attribute("beneficiary", config.beneficiary)
+        .add_attribute("token_ids", token_ids.join(","))
+        .add_attribute("released", vesting.released.to_string()))
+}
+
+pub fn execute_revoke(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.grantor {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut vesting = VESTING.load(deps.storage)?;
+    if vesting.revoked {
+        return Err(ContractError::Revoked {});
+    }
+    let vested = vesting.vested(&config.schedule, env.block.time);
+    // highest token ids are returned, lowest are released first
+    let token_ids = take_tokens(deps.storage, Order::Descending, vesting.total - vested)?;
+    vesting.total = vested;
+    vesting.revoked = true;
+    VESTING.save(deps.storage, &vesting)?;
+
+    Ok(Response::new()
+        .add_messages(transfer_nfts(
+            &config.nft_contract,
+            &config.grantor,
+            &token_ids,
+        )?)
+        .add_attribute("action", "revoke")
+        .add_attribute("grantor", config.grantor)
+        .add_attribute("token_ids", token_ids.join(","))
+        .add_attribute("vested", vested.to_string()))
+}
+
+/// Removes up to `count` tokens in given order
+fn take_tokens(storage: &mut dyn Storage, order: Order, count: u64) -> StdResult<Vec<String>> {
+    let token_ids = TOKENS
+        .keys(storage, None, None, order)
+        .take(count as usize)
+        .collect::<StdResult<Vec<_>>>()?;
+    for token_id in &token_ids {
+        TOKENS.remove(storage, token_id);
+    }
+    Ok(token_ids)
+}
+
+fn transfer_nfts(
+    nft_contract: &Addr,
+    recipient: &Addr,
+    token_ids: &[String],
+) -> StdResult<Vec<CosmosMsg>> {
+    let nft_contract =
+        Cw721Contract::<Empty, Empty>(nft_contract.clone(), PhantomData, PhantomData);
+    token_ids
+        .iter()
+        .map(|token_id| {
+            nft_contract.call(Cw721ExecuteMsg::TransferNft {
+                recipient: recipient.to_string(),
+                token_id: token_id.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Vesting {} => to_json_binary(&query_vesting(deps, env)?),
+        QueryMsg::Tokens { start_after, limit } => {
+            to_json_binary(&query_tokens(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_vesting(deps: Deps, env: Env) -> StdResult<VestingResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let vesting = VESTING.load(deps.storage)?;
+    Ok(VestingResponse {
+        total: vesting.total,
+        vested: vesting.vested(&config.schedule, env.block.time),
+        released: vesting.released,
+        revoked: vesting.revoked,
+    })
+}
+
+fn query_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let tokens = TOKENS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(TokensResponse { tokens })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{from_json, SubMsg, Timestamp};
+
+    const NFT_CONTRACT: &str = "nft";
+    const GRANTOR: &str = "grantor";
+    const BENEFICIARY: &str = "beneficiary";
+    const DAY: u64 = 24 * 60 * 60;
+
+    /// 4 tranches every 30 days after a 90 day cliff, 10 tokens deposited
+    fn setup_vesting(mut deps: DepsMut) {
+        instantiate(
+            deps.branch(),
+            mock_env(),
+            mock_info(GRANTOR, &[]),
+            InstantiateMsg {
+                beneficiary: String::from(BENEFICIARY),
+                nft_contract: String::from(NFT_CONTRACT),
+                start: None,
+                cliff: 90 * DAY,
+                tranches: 4,
+                interval: 30 * DAY,
+            },
+        )
+        .unwrap();
+        for token_id in 0..10 {
+            execute(
+                deps.branch(),
+                mock_env(),
+                mock_info(NFT_CONTRACT, &[]),
+                ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                    sender: String::from(GRANTOR),
+                    token_id: token_id.to_string(),
+                    msg: Binary::default(),
+                }),
+            )
+            .unwrap();
+        }
+    }
+
+    fn env_at(days: u64) -> Env {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(days * DAY);
+        env
+    }
+
+    fn transfers(recipient: &str, token_ids: &[&str]) -> Vec<SubMsg> {
+        let token_ids: Vec<String> = token_ids.iter().map(|id| id.to_string()).collect();
+        transfer_nfts(
+            &Addr::unchecked(NFT_CONTRACT),
+            &Addr::unchecked(recipient),
+            &token_ids,
+        )
+        .unwrap()
+        .into_iter()
+        .map(SubMsg::new)
+        .collect()
+    }
+
+    #[test]
+    fn schedule() {
+        let schedule = Schedule {
+            start: Timestamp::from_seconds(0),
+            cliff: 90,
+            tranches: 4,
+            interval: 30,
+        };
+        let vested = |seconds| schedule.vested(10, Timestamp::from_seconds(seconds));
+        assert_eq!(vested(89), 0);
+        assert_eq!(vested(90), 2);
+        assert_eq!(vested(119), 2);
+        assert_eq!(vested(120), 5);
+        assert_eq!(vested(150), 7);
+        assert_eq!(vested(180), 10);
+        assert_eq!(vested(1000), 10);
+
+        let single_tranche = Schedule {
+            tranches: 1,
+            interval: 0,
+            ..schedule
+        };
+        assert_eq!(single_tranche.vested(10, Timestamp::from_seconds(89)), 0);
+        assert_eq!(single_tranche.vested(10, Timestamp::from_seconds(90)), 10);
+    }
+
+    #[test]
+    fn deposit_and_release() {
+        let mut deps = mock_dependencies();
+        setup_vesting(deps.as_mut());
+
+        // only grantor can deposit tokens of the vested contract
+        let deposit = |nft_contract: &str, sender: &str| {
+            (
+                mock_info(nft_contract, &[]),
+                ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                    sender: sender.to_string(),
+                    token_id: String::from("x"),
+                    msg: Binary::default(),
+                }),
+            )
+        };
+        let (info, msg) = deposit("other_nft", GRANTOR);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let (info, msg) = deposit(NFT_CONTRACT, "random");
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // nothing vested before cliff
+        let release_msg = ExecuteMsg::Release { limit: None };
+        let err = execute(
+            deps.as_mut(),
+            env_at(89),
+            mock_info("anyone", &[]),
+            release_msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::NothingToRelease {});
+
+        // first tranche at cliff, limited releases
+        let res = execute(
+            deps.as_mut(),
+            env_at(90),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Release { limit: Some(1) },
+        )
+        .unwrap();
+        assert_eq!(res.messages, transfers(BENEFICIARY, &["0"]));
+        let res = execute(
+            deps.as_mut(),
+            env_at(90),
+            mock_info("anyone", &[]),
+            release_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(res.messages, transfers(BENEFICIARY, &["1"]));
+
+        // second tranche
+        let res = execute(
+            deps.as_mut(),
+            env_at(120),
+            mock_info("anyone", &[]),
+            release_msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages, transfers(BENEFICIARY, &["2", "3", "4"]));
+        let res: VestingResponse =
+            from_json(query(deps.as_ref(), env_at(180), QueryMsg::Vesting {}).unwrap()).unwrap();
+        assert_eq!(
+            res,
+            VestingResponse {
+                total: 10,
+                vested: 10,
+                released: 5,
+                revoked: false,
+            }
+        );
+    }
+
+    #[test]
+    fn revoke() {
+        let mut deps = mock_dependencies();
+        setup_vesting(deps.as_mut());
+        execute(
+            deps.as_mut(),
+            env_at(90),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Release { limit: Some(1) },
+        )
+        .unwrap();
+
+        // only grantor can revoke, unvested tokens are returned
+        let err = execute(
+            deps.as_mut(),
+            env_at(120),
+            mock_info(BENEFICIARY, &[]),
+            ExecuteMsg::Revoke {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let res = execute(
+            deps.as_mut(),
+            env_at(120),
+            mock_info(GRANTOR, &[]),
+            ExecuteMsg::Revoke {},
+        )
+        .unwrap();
+        assert_eq!(res.messages, transfers(GRANTOR, &["9", "8", "7", "6", "5"]));
+        let err = execute(
+            deps.as_mut(),
+            env_at(120),
+            mock_info(GRANTOR, &[]),
+            ExecuteMsg::Revoke {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Revoked {});
+
+        // tokens vested at revoke stay releasable, nothing more vests
+        let res: TokensResponse = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Tokens {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.tokens, vec!["1", "2", "3", "4"]);
+        let res = execute(
+            deps.as_mut(),
+            env_at(1000),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Release { limit: None },
+        )
+        .unwrap();
+        assert_eq!(res.messages, transfers(BENEFICIARY, &["1", "2", "3", "4"]));
+        let err = execute(
+            deps.as_mut(),
+            env_at(1000),
+            mock_info(NFT_CONTRACT, &[]),
+            ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: String::from(GRANTOR),
+                token_id: String::from("10"),
+                msg: Binary::default(),
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Revoked {});
+    }
+}
@@ -0,0 +1,20 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("InvalidSchedule: tranches must be at least 1, interval must be set for more than 1 tranche")]
+    InvalidSchedule {},
+
+    #[error("Vesting is revoked")]
+    Revoked {},
+
+    #[error("No vested tokens to release")]
+    NothingToRelease {},
+}
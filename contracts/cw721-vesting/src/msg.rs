@@ -0,0 +1,58 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Timestamp;
+use cw721::receiver::Cw721ReceiveMsg;
+
+use crate::state::Config;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub beneficiary: String,
+    pub nft_contract: String,
+    /// Start of the schedule, block time of instantiation if unset
+    pub start: Option<Timestamp>,
+    /// Seconds after start until the first tranche vests
+    pub cliff: u64,
+    pub tranches: u32,
+    /// Seconds between tranches, may be 0 for a single tranche
+    pub interval: u64,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Deposits a token, sent via `SendNft` by the grantor. Deposited tokens vest on the same schedule.
+    ReceiveNft(Cw721ReceiveMsg),
+    /// Transfers vested tokens to the beneficiary, anyone can call this
+    Release { limit: Option<u32> },
+    /// Returns unvested tokens to the grantor, tokens vested until now stay releasable.
+    /// Only grantor can call this.
+    Revoke {},
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(Config)]
+    Config {},
+    /// Token counts at current block time
+    #[returns(VestingResponse)]
+    Vesting {},
+    /// Tokens held by this contract, not released yet
+    #[returns(TokensResponse)]
+    Tokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct VestingResponse {
+    pub total: u64,
+    pub vested: u64,
+    pub released: u64,
+    pub revoked: bool,
+}
+
+#[cw_serde]
+pub struct TokensResponse {
+    pub tokens: Vec<String>,
+}
@@ -0,0 +1,66 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Empty, Timestamp};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    /// Deposits tokens and can revoke unvested ones
+    pub grantor: Addr,
+    pub beneficiary: Addr,
+    /// Only tokens of this contract can be deposited
+    pub nft_contract: Addr,
+    pub schedule: Schedule,
+}
+
+/// Nothing vests before `start + cliff`, then `tranches` equal tranches vest every `interval` seconds,
+/// the first one at the cliff.
+#[cw_serde]
+pub struct Schedule {
+    pub start: Timestamp,
+    /// Seconds after start until the first tranche vests
+    pub cliff: u64,
+    pub tranches: u32,
+    /// Seconds between tranches
+    pub interval: u64,
+}
+
+impl Schedule {
+    /// Number of `total` tokens vested at `time`, rounded down, so all tokens vest with the last tranche
+    pub fn vested(&self, total: u64, time: Timestamp) -> u64 {
+        let cliff = self.start.plus_seconds(self.cliff);
+        if time < cliff {
+            return 0;
+        }
+        let elapsed = time.seconds() - cliff.seconds();
+        let vested_tranches = match elapsed.checked_div(self.interval) {
+            Some(intervals) => (intervals + 1).min(self.tranches.into()),
+            // single tranche, validated on instantiation
+            None => self.tranches.into(),
+        };
+        (total as u128 * vested_tranches as u128 / self.tranches as u128) as u64
+    }
+}
+
+#[cw_serde]
+pub struct Vesting {
+    /// Number of tokens deposited, reduced to vested tokens on revoke
+    pub total: u64,
+    /// Number of tokens transferred to beneficiary
+    pub released: u64,
+    /// Once revoked, all remaining tokens are vested and no more tokens can be deposited
+    pub revoked: bool,
+}
+
+impl Vesting {
+    pub fn vested(&self, schedule: &Schedule, time: Timestamp) -> u64 {
+        if self.revoked {
+            return self.total;
+        }
+        schedule.vested(self.total, time)
+    }
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const VESTING: Item<Vesting> = Item::new("vesting");
+/// Deposited tokens not released yet, released and revoked in ascending token id order
+pub const TOKENS: Map<&str, Empty> = Map::new("tokens");
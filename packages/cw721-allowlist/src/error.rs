@@ -0,0 +1,27 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum AllowlistError {
+    #[error(transparent)]
+    Std(#[from] StdError),
+
+    #[error("Invalid stages: {reason}")]
+    InvalidStages { reason: String },
+
+    #[error("No active mint stage")]
+    NoActiveStage {},
+
+    #[error("{address} is not allowlisted in stage {stage}")]
+    NotAllowlisted { stage: String, address: String },
+
+    #[error("Stage {stage} has {remaining} tokens left")]
+    AllocationExhausted { stage: String, remaining: u32 },
+
+    #[error("{address} reached limit of {limit} mints in stage {stage}")]
+    ClaimLimitReached {
+        stage: String,
+        address: String,
+        limit: u32,
+    },
+}
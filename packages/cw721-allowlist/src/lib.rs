@@ -0,0 +1,6 @@
+pub mod error;
+pub mod merkle;
+pub mod state;
+
+pub use crate::error::AllowlistError;
+pub use crate::state::{Allowlist, Stage, StageAllocation};
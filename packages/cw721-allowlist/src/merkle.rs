@@ -0,0 +1,59 @@
+use cosmwasm_std::{Addr, HexBinary};
+use sha2::{Digest, Sha256};
+
+/// Leaf of an allowlist tree: sha256 hash of the address
+pub fn leaf(address: &Addr) -> [u8; 32] {
+    Sha256::digest(address.as_bytes()).into()
+}
+
+/// Verifies `leaf` is part of the tree with `root`. Pairs are hashed in sorted order
+/// (same as OpenZeppelin's `MerkleProof`), so proofs don't need to carry the position.
+pub fn verify_proof(root: &HexBinary, leaf: [u8; 32], proof: &[HexBinary]) -> bool {
+    let computed = proof.iter().fold(leaf, |hash, sibling| {
+        let sibling = sibling.as_slice();
+        let mut hasher = Sha256::new();
+        if hash.as_slice() <= sibling {
+            hasher.update(hash);
+            hasher.update(sibling);
+        } else {
+            hasher.update(sibling);
+            hasher.update(hash);
+        }
+        hasher.finalize().into()
+    });
+    computed.as_slice() == root.as_slice()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash_pair(a: &[u8], b: &[u8]) -> [u8; 32] {
+        let (first, second) = if a <= b { (a, b) } else { (b, a) };
+        let mut hasher = Sha256::new();
+        hasher.update(first);
+        hasher.update(second);
+        hasher.finalize().into()
+    }
+
+    #[test]
+    fn proofs() {
+        let leaves: Vec<[u8; 32]> = ["alice", "bob", "carol", "dave"]
+            .iter()
+            .map(|addr| leaf(&Addr::unchecked(*addr)))
+            .collect();
+        let left = hash_pair(&leaves[0], &leaves[1]);
+        let right = hash_pair(&leaves[2], &leaves[3]);
+        let root = HexBinary::from(hash_pair(&left, &right));
+
+        let proof = vec![HexBinary::from(leaves[3]), HexBinary::from(left)];
+        assert!(verify_proof(&root, leaves[2], &proof));
+        // wrong leaf
+        assert!(!verify_proof(&root, leaves[1], &proof));
+        // incomplete proof
+        assert!(!verify_proof(&root, leaves[2], &proof[..1]));
+
+        // single leaf tree, root is the leaf
+        assert!(verify_proof(&HexBinary::from(leaves[0]), leaves[0], &[]));
+    }
+}
@@ -0,0 +1,390 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, BlockInfo, HexBinary, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Item, Map};
+
+use crate::error::AllowlistError;
+use crate::merkle::{leaf, verify_proof};
+
+/// Mint window, optionally restricted to addresses in a merkle tree.
+#[cw_serde]
+pub struct Stage {
+    pub name: String,
+    pub start: Timestamp,
+    /// Stage is open until next stage starts, or forever if last, if not set
+    pub end: Option<Timestamp>,
+    /// Root of a tree with sha256 hashed addresses as leaves, open to everyone if not set
+    pub merkle_root: Option<HexBinary>,
+    /// Max mints per address within this stage, unlimited if not set
+    pub per_address_limit: Option<u32>,
+    /// Number of tokens allocated to this stage, unsold allocation of earlier stages rolls over.
+    /// Unlimited if not set.
+    pub allocation: Option<u32>,
+}
+
+#[cw_serde]
+pub struct StageAllocation {
+    pub stage: String,
+    pub allocation: Option<u32>,
+    pub minted: u32,
+    /// Allocation including rollover minus minted, unlimited if not set
+    pub remaining: Option<u32>,
+}
+
+impl Stage {
+    fn is_active(&self, block: &BlockInfo, next: Option<&Stage>) -> bool {
+        let end = self.end.or(next.map(|stage| stage.start));
+        block.time >= self.start && end.map_or(true, |end| block.time < end)
+    }
+}
+
+/// Allowlist stages and claims per (stage, address), for contracts minting cw721 tokens.
+pub struct Allowlist<'a> {
+    pub stages: Item<'a, Vec<Stage>>,
+    /// Stored as (stage index, address) -> number of mints
+    pub claims: Map<'a, (u32, &'a Addr), u32>,
+    /// Stored as stage index -> number of mints
+    pub minted: Map<'a, u32, u32>,
+}
+
+impl<'a> Default for Allowlist<'a> {
+    fn default() -> Self {
+        Self::new("allowlist_stages", "allowlist_claims", "allowlist_minted")
+    }
+}
+
+impl<'a> Allowlist<'a> {
+    pub const fn new(stages_key: &'a str, claims_key: &'a str, minted_key: &'a str) -> Self {
+        Self {
+            stages: Item::new(stages_key),
+            claims: Map::new(claims_key),
+            minted: Map::new(minted_key),
+        }
+    }
+
+    /// Replaces all stages. Stages must be ordered by start and must not overlap.
+    /// Claims are kept, so changing limits of an ongoing stage doesn't reset them.
+    pub fn set_stages(
+        &self,
+        storage: &mut dyn Storage,
+        stages: Vec<Stage>,
+    ) -> Result<(), AllowlistError> {
+        validate_stages(&stages)?;
+        if stages.is_empty() {
+            self.stages.remove(storage);
+        } else {
+            self.stages.save(storage, &stages)?;
+        }
+        Ok(())
+    }
+
+    pub fn stages(&self, storage: &dyn Storage) -> StdResult<Vec<Stage>> {
+        Ok(self.stages.may_load(storage)?.unwrap_or_default())
+    }
+
+    /// Returns index and stage active at current block, if any
+    pub fn active_stage(
+        &self,
+        storage: &dyn Storage,
+        block: &BlockInfo,
+    ) -> StdResult<Option<(u32, Stage)>> {
+        let stages = self.stages(storage)?;
+        Ok(stages
+            .iter()
+            .enumerate()
+            .find(|(index, stage)| stage.is_active(block, stages.get(index + 1)))
+            .map(|(index, stage)| (index as u32, stage.clone())))
+    }
+
+    /// Allocation, minted and remaining tokens per stage. Unsold allocation of a stage rolls over
+    /// to later stages with allocation, mints in stages without allocation don't count.
+    pub fn allocations(&self, storage: &dyn Storage) -> StdResult<Vec<StageAllocation>> {
+        let mut rollover = 0u32;
+        self.stages(storage)?
+            .into_iter()
+            .enumerate()
+            .map(|(index, stage)| -> StdResult<StageAllocation> {
+                let minted = self
+                    .minted
+                    .may_load(storage, index as u32)?
+                    .unwrap_or_default();
+                let remaining = stage.allocation.map(|allocation| {
+                    let remaining = (allocation + rollover).saturating_sub(minted);
+                    rollover = remaining;
+                    remaining
+                });
+                Ok(StageAllocation {
+                    stage: stage.name,
+                    allocation: stage.allocation,
+                    minted,
+                    remaining,
+                })
+            })
+            .collect()
+    }
+
+    pub fn claimed(&self, storage: &dyn Storage, stage: u32, address: &Addr) -> StdResult<u32> {
+        Ok(self
+            .claims
+            .may_load(storage, (stage, address))?
+            .unwrap_or_default())
+    }
+
+    /// Checks `address` may mint `count` tokens in the active stage and records the claim.
+    /// Returns the active stage, or `None` in case no stages are set and minting is open.
+    pub fn claim(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        address: &Addr,
+        proof: &[HexBinary],
+        count: u32,
+    ) -> Result<Option<Stage>, AllowlistError> {
+        if !self.stages.exists(storage) {
+            return Ok(None);
+        }
+        let (index, stage) = self
+            .active_stage(storage, block)?
+            .ok_or(AllowlistError::NoActiveStage {})?;
+        if let Some(root) = &stage.merkle_root {
+            if !verify_proof(root, leaf(address), proof) {
+                return Err(AllowlistError::NotAllowlisted {
+                    stage: stage.name,
+                    address: address.to_string(),
+                });
+            }
+        }
+        let claimed = self.claimed(storage, index, address)? + count;
+        if let Some(limit) = stage.per_address_limit {
+            if claimed > limit {
+                return Err(AllowlistError::ClaimLimitReached {
+                    stage: stage.name,
+                    address: address.to_string(),
+                    limit,
+                });
+            }
+        }
+        if stage.allocation.is_some() {
+            let remaining = self.allocations(storage)?[index as usize]
+                .remaining
+                .unwrap_or_default();
+            if count > remaining {
+                return Err(AllowlistError::AllocationExhausted {
+                    stage: stage.name,
+                    remaining,
+                });
+            }
+        }
+        self.claims.save(storage, (index, address), &claimed)?;
+        self.minted
+            .update(storage, index, |minted| -> StdResult<_> {
+                Ok(minted.unwrap_or_default() + count)
+            })?;
+        Ok(Some(stage))
+    }
+}
+
+fn validate_stages(stages: &[Stage]) -> Result<(), AllowlistError> {
+    let invalid = |reason: String| Err(AllowlistError::InvalidStages { reason });
+    for (index, stage) in stages.iter().enumerate() {
+        if let Some(end) = stage.end {
+            if end <= stage.start {
+                return invalid(format!("stage {} ends before it starts", stage.name));
+            }
+        }
+        if let Some(root) = &stage.merkle_root {
+            if root.len() != 32 {
+                return invalid(format!(
+                    "merkle root of stage {} is not 32 bytes",
+                    stage.name
+                ));
+            }
+        }
+        if let Some(next) = stages.get(index + 1) {
+            if next.start < stage.end.unwrap_or(stage.start) || next.start <= stage.start {
+                return invalid(format!("stage {} overlaps stage {}", stage.name, next.name));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+    fn stage(name: &str, start: u64, end: Option<u64>) -> Stage {
+        Stage {
+            name: name.to_string(),
+            start: Timestamp::from_seconds(start),
+            end: end.map(Timestamp::from_seconds),
+            merkle_root: None,
+            per_address_limit: None,
+            allocation: None,
+        }
+    }
+
+    #[test]
+    fn stages_and_claims() {
+        let mut deps = mock_dependencies();
+        let allowlist = Allowlist::default();
+        let mut env = mock_env();
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+
+        // open minting without stages
+        let res = allowlist
+            .claim(&mut deps.storage, &env.block, &alice, &[], 1)
+            .unwrap();
+        assert_eq!(res, None);
+
+        // overlapping stages are rejected
+        let err = allowlist
+            .set_stages(
+                &mut deps.storage,
+                vec![stage("presale", 100, Some(200)), stage("public", 150, None)],
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            AllowlistError::InvalidStages {
+                reason: "stage presale overlaps stage public".to_string()
+            }
+        );
+
+        // presale is restricted to alice, max 2 mints
+        let presale = Stage {
+            merkle_root: Some(HexBinary::from(leaf(&alice))),
+            per_address_limit: Some(2),
+            ..stage("presale", 100, Some(200))
+        };
+        allowlist
+            .set_stages(
+                &mut deps.storage,
+                vec![presale.clone(), stage("public", 300, None)],
+            )
+            .unwrap();
+
+        env.block.time = Timestamp::from_seconds(50);
+        let err = allowlist
+            .claim(&mut deps.storage, &env.block, &alice, &[], 1)
+            .unwrap_err();
+        assert_eq!(err, AllowlistError::NoActiveStage {});
+
+        env.block.time = Timestamp::from_seconds(100);
+        let err = allowlist
+            .claim(&mut deps.storage, &env.block, &bob, &[], 1)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            AllowlistError::NotAllowlisted {
+                stage: "presale".to_string(),
+                address: "bob".to_string()
+            }
+        );
+        let res = allowlist
+            .claim(&mut deps.storage, &env.block, &alice, &[], 2)
+            .unwrap();
+        assert_eq!(res, Some(presale));
+        let err = allowlist
+            .claim(&mut deps.storage, &env.block, &alice, &[], 1)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            AllowlistError::ClaimLimitReached {
+                stage: "presale".to_string(),
+                address: "alice".to_string(),
+                limit: 2
+            }
+        );
+        assert_eq!(allowlist.claimed(&deps.storage, 0, &alice).unwrap(), 2);
+
+        // gap between stages
+        env.block.time = Timestamp::from_seconds(200);
+        assert_eq!(
+            allowlist.active_stage(&deps.storage, &env.block).unwrap(),
+            None
+        );
+
+        // public stage is open to everyone without limit
+        env.block.time = Timestamp::from_seconds(300);
+        allowlist
+            .claim(&mut deps.storage, &env.block, &bob, &[], 10)
+            .unwrap();
+        assert_eq!(allowlist.claimed(&deps.storage, 1, &bob).unwrap(), 10);
+    }
+
+    #[test]
+    fn allocation_rollover() {
+        let mut deps = mock_dependencies();
+        let allowlist = Allowlist::default();
+        let mut env = mock_env();
+        let alice = Addr::unchecked("alice");
+        allowlist
+            .set_stages(
+                &mut deps.storage,
+                vec![
+                    Stage {
+                        allocation: Some(5),
+                        ..stage("presale", 100, Some(200))
+                    },
+                    stage("raffle", 200, Some(300)),
+                    Stage {
+                        allocation: Some(10),
+                        ..stage("public", 300, None)
+                    },
+                ],
+            )
+            .unwrap();
+
+        env.block.time = Timestamp::from_seconds(100);
+        allowlist
+            .claim(&mut deps.storage, &env.block, &alice, &[], 3)
+            .unwrap();
+        let err = allowlist
+            .claim(&mut deps.storage, &env.block, &alice, &[], 3)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            AllowlistError::AllocationExhausted {
+                stage: "presale".to_string(),
+                remaining: 2
+            }
+        );
+
+        // stage without allocation is unlimited and doesn't affect rollover
+        env.block.time = Timestamp::from_seconds(200);
+        allowlist
+            .claim(&mut deps.storage, &env.block, &alice, &[], 50)
+            .unwrap();
+
+        // 2 unsold presale tokens roll over to public stage
+        env.block.time = Timestamp::from_seconds(300);
+        allowlist
+            .claim(&mut deps.storage, &env.block, &alice, &[], 11)
+            .unwrap();
+        assert_eq!(
+            allowlist.allocations(&deps.storage).unwrap(),
+            vec![
+                StageAllocation {
+                    stage: "presale".to_string(),
+                    allocation: Some(5),
+                    minted: 3,
+                    remaining: Some(2),
+                },
+                StageAllocation {
+                    stage: "raffle".to_string(),
+                    allocation: None,
+                    minted: 50,
+                    remaining: None,
+                },
+                StageAllocation {
+                    stage: "public".to_string(),
+                    allocation: Some(10),
+                    minted: 11,
+                    remaining: Some(1),
+                },
+            ]
+        );
+    }
+}
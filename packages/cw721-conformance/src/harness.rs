@@ -0,0 +1,72 @@
+use cosmwasm_std::{Addr, Empty};
+use cw721::msg::{Cw721ExecuteMsg, Cw721InstantiateMsg};
+use cw_multi_test::{error::AnyResult, App, AppResponse, Contract, Executor};
+
+pub const CREATOR: &str = "creator";
+pub const MINTER: &str = "minter";
+
+/// Sets up the collection under test. Implement this for contracts with their own instantiate msg
+/// or way of minting, e.g. wrappers or launchpads.
+pub trait Cw721Harness {
+    /// Stores and instantiates the collection, returning its address
+    fn instantiate(&self, app: &mut App) -> Addr;
+
+    /// Creates `token_id` owned by `owner`, without approvals
+    fn mint(
+        &self,
+        app: &mut App,
+        cw721: &Addr,
+        token_id: &str,
+        owner: &Addr,
+    ) -> AnyResult<AppResponse>;
+}
+
+/// Harness for collections taking `Cw721InstantiateMsg` and minting via `Cw721ExecuteMsg::Mint`,
+/// like cw721-base and most of its forks.
+pub struct MinterHarness {
+    pub contract: fn() -> Box<dyn Contract<Empty>>,
+}
+
+impl Cw721Harness for MinterHarness {
+    fn instantiate(&self, app: &mut App) -> Addr {
+        let code_id = app.store_code((self.contract)());
+        app.instantiate_contract(
+            code_id,
+            Addr::unchecked(CREATOR),
+            &Cw721InstantiateMsg {
+                name: String::from("Conformance"),
+                symbol: String::from("CONF"),
+                minter: Some(String::from(MINTER)),
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
+            },
+            &[],
+            "cw721",
+            None,
+        )
+        .unwrap()
+    }
+
+    fn mint(
+        &self,
+        app: &mut App,
+        cw721: &Addr,
+        token_id: &str,
+        owner: &Addr,
+    ) -> AnyResult<AppResponse> {
+        app.execute_contract(
+            Addr::unchecked(MINTER),
+            cw721.clone(),
+            &Cw721ExecuteMsg::<Option<Empty>, Empty>::Mint {
+                token_id: String::from(token_id),
+                owner: owner.to_string(),
+                token_uri: None,
+                extension: None,
+                approval: None,
+                metadata_hash: None,
+            },
+            &[],
+        )
+    }
+}
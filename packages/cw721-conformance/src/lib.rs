@@ -0,0 +1,6 @@
+pub mod harness;
+pub mod msg;
+pub mod suite;
+
+pub use crate::harness::{Cw721Harness, MinterHarness};
+pub use crate::suite::run_all;
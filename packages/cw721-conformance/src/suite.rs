@@ -0,0 +1,403 @@
+//! Checks of the cw721 spec, each on a fresh `App` and collection. Failures panic, so they can be
+//! called from `#[test]` functions of the contract under test.
+use cosmwasm_std::{Addr, StdResult};
+use cw721::msg::{
+    ApprovalResponse, ApprovalsResponse, NftInfoResponse, NumTokensResponse, OperatorsResponse,
+    OwnerOfResponse, TokensResponse,
+};
+use cw_multi_test::{error::AnyResult, App, AppResponse, Executor};
+use cw_utils::Expiration;
+use serde::de::{DeserializeOwned, IgnoredAny};
+
+use crate::harness::Cw721Harness;
+use crate::msg::{StandardExecuteMsg, StandardQueryMsg};
+
+pub const OWNER: &str = "owner";
+pub const SPENDER: &str = "spender";
+pub const OPERATOR: &str = "operator";
+pub const OTHER: &str = "other";
+
+/// Runs all checks against the collection set up by `harness`
+pub fn run_all(harness: &impl Cw721Harness) {
+    mint(harness);
+    transfer(harness);
+    approve(harness);
+    operator(harness);
+    enumeration(harness);
+    expiration(harness);
+}
+
+fn setup(harness: &impl Cw721Harness) -> (App, Addr) {
+    let mut app = App::default();
+    let cw721 = harness.instantiate(&mut app);
+    (app, cw721)
+}
+
+fn mint_to(harness: &impl Cw721Harness, app: &mut App, cw721: &Addr, token_id: &str, owner: &str) {
+    harness
+        .mint(app, cw721, token_id, &Addr::unchecked(owner))
+        .unwrap();
+}
+
+fn execute(
+    app: &mut App,
+    cw721: &Addr,
+    sender: &str,
+    msg: StandardExecuteMsg,
+) -> AnyResult<AppResponse> {
+    app.execute_contract(Addr::unchecked(sender), cw721.clone(), &msg, &[])
+}
+
+fn transfer_nft(
+    app: &mut App,
+    cw721: &Addr,
+    sender: &str,
+    token_id: &str,
+    recipient: &str,
+) -> AnyResult<AppResponse> {
+    execute(
+        app,
+        cw721,
+        sender,
+        StandardExecuteMsg::TransferNft {
+            recipient: String::from(recipient),
+            token_id: String::from(token_id),
+        },
+    )
+}
+
+fn query<T: DeserializeOwned>(app: &App, cw721: &Addr, msg: StandardQueryMsg) -> StdResult<T> {
+    app.wrap().query_wasm_smart(cw721, &msg)
+}
+
+fn owner_of(app: &App, cw721: &Addr, token_id: &str, include_expired: bool) -> OwnerOfResponse {
+    query(
+        app,
+        cw721,
+        StandardQueryMsg::OwnerOf {
+            token_id: String::from(token_id),
+            include_expired: Some(include_expired),
+        },
+    )
+    .unwrap()
+}
+
+fn num_tokens(app: &App, cw721: &Addr) -> u64 {
+    query::<NumTokensResponse>(app, cw721, StandardQueryMsg::NumTokens {})
+        .unwrap()
+        .count
+}
+
+fn tokens(
+    app: &App,
+    cw721: &Addr,
+    owner: Option<&str>,
+    start_after: Option<&str>,
+    limit: Option<u32>,
+) -> Vec<String> {
+    let start_after = start_after.map(String::from);
+    let msg = match owner {
+        Some(owner) => StandardQueryMsg::Tokens {
+            owner: String::from(owner),
+            start_after,
+            limit,
+        },
+        None => StandardQueryMsg::AllTokens { start_after, limit },
+    };
+    query::<TokensResponse>(app, cw721, msg).unwrap().tokens
+}
+
+fn operators(app: &App, cw721: &Addr, owner: &str, include_expired: bool) -> Vec<String> {
+    query::<OperatorsResponse>(
+        app,
+        cw721,
+        StandardQueryMsg::AllOperators {
+            owner: String::from(owner),
+            include_expired: Some(include_expired),
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap()
+    .operators
+    .into_iter()
+    .map(|approval| approval.spender.to_string())
+    .collect()
+}
+
+/// Minted tokens are owned and counted, token ids are unique
+pub fn mint(harness: &impl Cw721Harness) {
+    let (mut app, cw721) = setup(harness);
+    assert_eq!(num_tokens(&app, &cw721), 0);
+    query::<OwnerOfResponse>(
+        &app,
+        &cw721,
+        StandardQueryMsg::OwnerOf {
+            token_id: String::from("1"),
+            include_expired: None,
+        },
+    )
+    .unwrap_err();
+
+    mint_to(harness, &mut app, &cw721, "1", OWNER);
+    let owner = owner_of(&app, &cw721, "1", false);
+    assert_eq!(owner.owner, OWNER);
+    assert!(owner.approvals.is_empty());
+    assert_eq!(num_tokens(&app, &cw721), 1);
+    query::<NftInfoResponse<IgnoredAny>>(
+        &app,
+        &cw721,
+        StandardQueryMsg::NftInfo {
+            token_id: String::from("1"),
+        },
+    )
+    .unwrap();
+    query::<IgnoredAny>(&app, &cw721, StandardQueryMsg::ContractInfo {}).unwrap();
+
+    // token ids can't be minted twice
+    harness
+        .mint(&mut app, &cw721, "1", &Addr::unchecked(OTHER))
+        .unwrap_err();
+    assert_eq!(owner_of(&app, &cw721, "1", false).owner, OWNER);
+    assert_eq!(num_tokens(&app, &cw721), 1);
+}
+
+/// Only the owner transfers, transfers change ownership
+pub fn transfer(harness: &impl Cw721Harness) {
+    let (mut app, cw721) = setup(harness);
+    mint_to(harness, &mut app, &cw721, "1", OWNER);
+
+    transfer_nft(&mut app, &cw721, OTHER, "1", OTHER).unwrap_err();
+    transfer_nft(&mut app, &cw721, OWNER, "2", OTHER).unwrap_err();
+
+    transfer_nft(&mut app, &cw721, OWNER, "1", OTHER).unwrap();
+    assert_eq!(owner_of(&app, &cw721, "1", false).owner, OTHER);
+    assert_eq!(num_tokens(&app, &cw721), 1);
+
+    // previous owner lost control
+    transfer_nft(&mut app, &cw721, OWNER, "1", OWNER).unwrap_err();
+    transfer_nft(&mut app, &cw721, OTHER, "1", OWNER).unwrap();
+    assert_eq!(owner_of(&app, &cw721, "1", false).owner, OWNER);
+}
+
+/// Approved spenders can transfer a token, approvals are revocable and cleared on transfer
+pub fn approve(harness: &impl Cw721Harness) {
+    let (mut app, cw721) = setup(harness);
+    mint_to(harness, &mut app, &cw721, "1", OWNER);
+    let approve_msg = StandardExecuteMsg::Approve {
+        spender: String::from(SPENDER),
+        token_id: String::from("1"),
+        expires: None,
+    };
+    let approval_msg = StandardQueryMsg::Approval {
+        token_id: String::from("1"),
+        spender: String::from(SPENDER),
+        include_expired: None,
+    };
+
+    execute(&mut app, &cw721, OTHER, approve_msg.clone()).unwrap_err();
+    transfer_nft(&mut app, &cw721, SPENDER, "1", SPENDER).unwrap_err();
+
+    execute(&mut app, &cw721, OWNER, approve_msg.clone()).unwrap();
+    let approval: ApprovalResponse = query(&app, &cw721, approval_msg.clone()).unwrap();
+    assert_eq!(approval.approval.spender, SPENDER);
+    assert_eq!(approval.approval.expires, Expiration::Never {});
+    let approvals: ApprovalsResponse = query(
+        &app,
+        &cw721,
+        StandardQueryMsg::Approvals {
+            token_id: String::from("1"),
+            include_expired: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(approvals.approvals.len(), 1);
+    assert_eq!(owner_of(&app, &cw721, "1", false).approvals.len(), 1);
+
+    // revoked approvals don't allow transfers
+    execute(
+        &mut app,
+        &cw721,
+        OWNER,
+        StandardExecuteMsg::Revoke {
+            spender: String::from(SPENDER),
+            token_id: String::from("1"),
+        },
+    )
+    .unwrap();
+    query::<ApprovalResponse>(&app, &cw721, approval_msg).unwrap_err();
+    transfer_nft(&mut app, &cw721, SPENDER, "1", SPENDER).unwrap_err();
+
+    // approvals are cleared on transfer
+    execute(&mut app, &cw721, OWNER, approve_msg).unwrap();
+    transfer_nft(&mut app, &cw721, SPENDER, "1", OTHER).unwrap();
+    let owner = owner_of(&app, &cw721, "1", true);
+    assert_eq!(owner.owner, OTHER);
+    assert!(owner.approvals.is_empty());
+    transfer_nft(&mut app, &cw721, SPENDER, "1", SPENDER).unwrap_err();
+}
+
+/// Operators can transfer and approve all tokens of the owner, until revoked
+pub fn operator(harness: &impl Cw721Harness) {
+    let (mut app, cw721) = setup(harness);
+    for token_id in ["1", "2", "3"] {
+        mint_to(harness, &mut app, &cw721, token_id, OWNER);
+    }
+    mint_to(harness, &mut app, &cw721, "4", OTHER);
+
+    execute(
+        &mut app,
+        &cw721,
+        OWNER,
+        StandardExecuteMsg::ApproveAll {
+            operator: String::from(OPERATOR),
+            expires: None,
+        },
+    )
+    .unwrap();
+    assert_eq!(operators(&app, &cw721, OWNER, false), vec![OPERATOR]);
+    assert!(operators(&app, &cw721, OTHER, false).is_empty());
+
+    transfer_nft(&mut app, &cw721, OPERATOR, "1", OTHER).unwrap();
+    assert_eq!(owner_of(&app, &cw721, "1", false).owner, OTHER);
+    execute(
+        &mut app,
+        &cw721,
+        OPERATOR,
+        StandardExecuteMsg::Approve {
+            spender: String::from(SPENDER),
+            token_id: String::from("2"),
+            expires: None,
+        },
+    )
+    .unwrap();
+    transfer_nft(&mut app, &cw721, SPENDER, "2", OTHER).unwrap();
+
+    // no control over tokens of other owners, including tokens transferred away
+    transfer_nft(&mut app, &cw721, OPERATOR, "4", OPERATOR).unwrap_err();
+    transfer_nft(&mut app, &cw721, OPERATOR, "1", OPERATOR).unwrap_err();
+
+    execute(
+        &mut app,
+        &cw721,
+        OWNER,
+        StandardExecuteMsg::RevokeAll {
+            operator: String::from(OPERATOR),
+        },
+    )
+    .unwrap();
+    assert!(operators(&app, &cw721, OWNER, true).is_empty());
+    transfer_nft(&mut app, &cw721, OPERATOR, "3", OPERATOR).unwrap_err();
+    assert_eq!(owner_of(&app, &cw721, "3", false).owner, OWNER);
+}
+
+/// Token ids are listed in ascending order, per owner and overall, and paginate with `start_after`
+pub fn enumeration(harness: &impl Cw721Harness) {
+    let (mut app, cw721) = setup(harness);
+    for token_id in ["1", "2", "3"] {
+        mint_to(harness, &mut app, &cw721, token_id, OWNER);
+    }
+    mint_to(harness, &mut app, &cw721, "4", OTHER);
+    assert_eq!(num_tokens(&app, &cw721), 4);
+
+    assert_eq!(
+        tokens(&app, &cw721, Some(OWNER), None, None),
+        ["1", "2", "3"]
+    );
+    assert_eq!(tokens(&app, &cw721, Some(OWNER), None, Some(2)), ["1", "2"]);
+    assert_eq!(tokens(&app, &cw721, Some(OWNER), Some("2"), None), ["3"]);
+    assert!(tokens(&app, &cw721, Some(SPENDER), None, None).is_empty());
+
+    assert_eq!(tokens(&app, &cw721, None, None, None), ["1", "2", "3", "4"]);
+    assert_eq!(tokens(&app, &cw721, None, Some("1"), Some(2)), ["2", "3"]);
+    assert!(tokens(&app, &cw721, None, Some("4"), None).is_empty());
+
+    // owner lists follow transfers
+    transfer_nft(&mut app, &cw721, OWNER, "1", OTHER).unwrap();
+    assert_eq!(tokens(&app, &cw721, Some(OWNER), None, None), ["2", "3"]);
+    assert_eq!(tokens(&app, &cw721, Some(OTHER), None, None), ["1", "4"]);
+    assert_eq!(num_tokens(&app, &cw721), 4);
+}
+
+/// Expired approvals and operators don't allow transfers and are hidden unless `include_expired`.
+/// Setting an already expired approval fails.
+pub fn expiration(harness: &impl Cw721Harness) {
+    let (mut app, cw721) = setup(harness);
+    mint_to(harness, &mut app, &cw721, "1", OWNER);
+    let block = app.block_info();
+
+    execute(
+        &mut app,
+        &cw721,
+        OWNER,
+        StandardExecuteMsg::Approve {
+            spender: String::from(SPENDER),
+            token_id: String::from("1"),
+            expires: Some(Expiration::AtHeight(block.height)),
+        },
+    )
+    .unwrap_err();
+    execute(
+        &mut app,
+        &cw721,
+        OWNER,
+        StandardExecuteMsg::ApproveAll {
+            operator: String::from(OPERATOR),
+            expires: Some(Expiration::AtTime(block.time)),
+        },
+    )
+    .unwrap_err();
+
+    execute(
+        &mut app,
+        &cw721,
+        OWNER,
+        StandardExecuteMsg::Approve {
+            spender: String::from(SPENDER),
+            token_id: String::from("1"),
+            expires: Some(Expiration::AtHeight(block.height + 1)),
+        },
+    )
+    .unwrap();
+    execute(
+        &mut app,
+        &cw721,
+        OWNER,
+        StandardExecuteMsg::ApproveAll {
+            operator: String::from(OPERATOR),
+            expires: Some(Expiration::AtTime(block.time.plus_seconds(10))),
+        },
+    )
+    .unwrap();
+
+    // approval expires at next height, operator is still valid
+    next_block(&mut app);
+    assert!(owner_of(&app, &cw721, "1", false).approvals.is_empty());
+    assert_eq!(owner_of(&app, &cw721, "1", true).approvals.len(), 1);
+    query::<ApprovalResponse>(
+        &app,
+        &cw721,
+        StandardQueryMsg::Approval {
+            token_id: String::from("1"),
+            spender: String::from(SPENDER),
+            include_expired: None,
+        },
+    )
+    .unwrap_err();
+    transfer_nft(&mut app, &cw721, SPENDER, "1", SPENDER).unwrap_err();
+    assert_eq!(operators(&app, &cw721, OWNER, false), vec![OPERATOR]);
+
+    // operator expires at its time
+    next_block(&mut app);
+    assert!(operators(&app, &cw721, OWNER, false).is_empty());
+    assert_eq!(operators(&app, &cw721, OWNER, true), vec![OPERATOR]);
+    transfer_nft(&mut app, &cw721, OPERATOR, "1", OPERATOR).unwrap_err();
+    assert_eq!(owner_of(&app, &cw721, "1", false).owner, OWNER);
+}
+
+fn next_block(app: &mut App) {
+    app.update_block(|block| {
+        block.height += 1;
+        block.time = block.time.plus_seconds(5);
+    });
+}
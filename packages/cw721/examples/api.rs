@@ -0,0 +1,9 @@
+use cosmwasm_std::Empty;
+use cw721::state::DefaultOptionMetadataExtension;
+use cw721::write_cw721_api;
+
+fn main() {
+    // one schema per commonly used extension, for generating clients (e.g. via ts-codegen)
+    write_cw721_api!("cw721-empty", Empty, Empty);
+    write_cw721_api!("cw721-metadata", DefaultOptionMetadataExtension, Empty);
+}
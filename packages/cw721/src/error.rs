@@ -1,5 +1,6 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, Decimal, StdError, Timestamp};
 use cw_ownable::OwnershipError;
+use cw_utils::{Duration, Expiration, PaymentError};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -13,15 +14,241 @@ pub enum Cw721ContractError {
     #[error(transparent)]
     Version(#[from] cw2::VersionError),
 
+    #[error(transparent)]
+    Payment(#[from] PaymentError),
+
     #[error("token_id already claimed")]
     Claimed {},
 
     #[error("Cannot set approval that is already expired")]
     Expired {},
 
+    #[error("Invalid approval permissions: {permissions}")]
+    InvalidApprovalPermissions { permissions: u8 },
+
+    #[error("Token {token_id} exceeds max approvals: {max}")]
+    TooManyApprovals { token_id: String, max: u32 },
+
+    #[error("Granter exceeds max operators: {max}")]
+    TooManyOperators { max: u32 },
+
+    #[error("Operator filter must not be empty and list at most {max} token ids")]
+    InvalidOperatorFilter { max: usize },
+
+    #[error("Operator {operator} is not allowed by operator registry")]
+    OperatorNotAllowed { operator: String },
+
+    /// Attached funds don't match the required payment, e.g. a flat transfer fee
+    #[error("Incorrect payment: expected {expected}")]
+    IncorrectPayment { expected: Coin, got: Vec<Coin> },
+
+    #[error("Invalid transfer fee share {share}, must not exceed 1")]
+    InvalidTransferFeeShare { share: Decimal },
+
+    #[error("Invalid media asset {uri}: {reason}")]
+    InvalidMediaAsset { uri: String, reason: String },
+
+    #[error("Too many media assets, max: {max}")]
+    TooManyMediaAssets { max: usize },
+
+    #[error("{field} is {size} bytes, max: {max} bytes")]
+    MetadataTooLarge {
+        field: String,
+        size: usize,
+        max: u32,
+    },
+
+    #[error("Too many initial tokens, max: {max}")]
+    TooManyInitialTokens { max: usize },
+
+    #[error("Genesis import mode is not open")]
+    ImportNotOpen {},
+
+    #[error("Genesis import mode has been closed for good")]
+    ImportClosed {},
+
+    #[error("Invalid origin: {reason}")]
+    InvalidOrigin { reason: String },
+
+    #[error("Collection origin is already set")]
+    OriginAlreadySet {},
+
+    #[error("Origin token id of {token_id} is already set")]
+    OriginTokenIdAlreadySet { token_id: String },
+
+    #[error("Token {token_id} was burned and can't be minted again")]
+    TokenRetired { token_id: String },
+
+    #[error("Collection is frozen by governance: {reason}")]
+    Frozen { reason: String },
+
+    #[error("Collection is not frozen")]
+    NotFrozen {},
+
+    #[error("Queued action {id} not found")]
+    QueuedActionNotFound { id: u64 },
+
+    #[error("Queued action is timelocked until {executable_at}")]
+    Timelocked { executable_at: Expiration },
+
+    #[error("Collection is paused")]
+    Paused {},
+
+    #[error("Collection is not paused")]
+    NotPaused {},
+
+    #[error("Invalid protocol fee {bps} bps, must not exceed {max}")]
+    InvalidProtocolFee { bps: u16, max: u16 },
+
+    #[error("Contract URI must not be empty")]
+    InvalidContractUri {},
+
+    #[error("Invalid royalty payout denom {denom}: {reason}")]
+    InvalidPayoutDenom { denom: String, reason: String },
+
+    #[error("No reveal placeholder set")]
+    NoRevealPlaceholder {},
+
+    #[error("Tokens are already revealed")]
+    AlreadyRevealed {},
+
+    #[error("Provenance does not match committed hash {provenance_hash}")]
+    InvalidProvenance { provenance_hash: String },
+
+    #[error("Provenance hash can't be changed after first mint")]
+    ProvenanceHashLocked {},
+
+    #[error("No randomness source set")]
+    NoRandomnessSource {},
+
+    #[error("Randomness is only accepted from randomness source, not {sender}")]
+    UnauthorizedRandomnessSource { sender: String },
+
+    #[error("Randomness already received")]
+    RandomnessAlreadyReceived {},
+
+    #[error("Randomness source can't be changed after first mint")]
+    RandomnessSourceLocked {},
+
+    #[error("Persistent operators can't be added after first mint")]
+    PersistentOperatorLocked {},
+
+    #[error("Randomness has not been received yet")]
+    RandomnessNotReceived {},
+
+    #[error("Mint window must end after it starts")]
+    InvalidMintWindow {},
+
+    #[error("Minting starts at {start}")]
+    MintNotStarted { start: Timestamp },
+
+    #[error("Minting ended at {end}")]
+    MintEnded { end: Timestamp },
+
+    #[error("Mint rate limit needs a non-zero max mints and window")]
+    InvalidMintRateLimit {},
+
+    #[error("Mint rate limit of {max_mints} per {window} reached")]
+    MintRateLimited { max_mints: u32, window: Duration },
+
+    #[error("Tokens of this collection can't be burned")]
+    BurnDisabled {},
+
+    #[error("Burnable flag is locked")]
+    BurnableLocked {},
+
+    #[error("Burn windows must end after they start, max: {max}")]
+    InvalidBurnWindows { max: usize },
+
+    #[error(
+        "Burning is only allowed within burn windows{}",
+        .next_start.as_ref().map(|start| format!(", next one starts at {start}")).unwrap_or_default()
+    )]
+    BurnWindowClosed { next_start: Option<Timestamp> },
+
+    #[error("Tokens can only be burned by creator after holder's burn offer")]
+    BurnCreatorGated {},
+
+    #[error("Burning is not creator-gated")]
+    BurnNotCreatorGated {},
+
+    #[error("No burn offer by current holder of token {token_id}")]
+    NoBurnOffer { token_id: String },
+
+    #[error("No pending transfer offer of token {token_id} to sender")]
+    NoTransferOffer { token_id: String },
+
+    #[error("Token {token_id} is not locked as a gift")]
+    NoGift { token_id: String },
+
+    #[error("Gift lock must expire, so the owner can reclaim an unclaimed gift")]
+    GiftMustExpire {},
+
+    #[error("Invalid secret for gift {token_id}")]
+    InvalidGiftSecret { token_id: String },
+
+    #[error("Claim of gift {token_id} must be committed in an earlier block")]
+    GiftClaimNotCommitted { token_id: String },
+
+    #[error("Gift {token_id} expired")]
+    GiftExpired { token_id: String },
+
+    #[error("Gift {token_id} can be reclaimed once expired")]
+    GiftNotExpired { token_id: String },
+
+    #[error("Unique metadata requires the uri-index feature")]
+    UniqueMetadataUnsupported {},
+
+    #[error("Token uri {token_uri} already exists")]
+    DuplicateTokenUri { token_uri: String },
+
+    #[error("Extension already exists")]
+    DuplicateExtension {},
+
     #[error("Approval not found for: {spender}")]
     ApprovalNotFound { spender: String },
 
+    #[error("Cannot migrate contract {stored} to {contract_name}, set `allow_unknown` to force migration")]
+    IncompatibleContract {
+        stored: String,
+        contract_name: String,
+    },
+
+    #[error("Invalid token range {start_id}..={end_id}")]
+    InvalidTokenRange { start_id: u64, end_id: u64 },
+
+    #[error("Contract is locked until pending migration is committed")]
+    MigrationPending {},
+
+    #[error("No pending migration")]
+    NoPendingMigration {},
+
+    #[error("Invalid migration: {errors}")]
+    InvalidMigration { errors: String },
+
     #[error("No withdraw address set")]
     NoWithdrawAddress {},
+
+    #[error("Token {token_id} cannot be transferred before {ends}")]
+    TransferCooldown { token_id: String, ends: Expiration },
+}
+
+/// Returned by `helpers::parse_receive` for contracts implementing `ReceiveNft`
+#[derive(Error, Debug, PartialEq)]
+pub enum Cw721ReceiveError {
+    #[error("Empty msg received with token {token_id} from {sender}")]
+    EmptyMsg { token_id: String, sender: String },
+
+    #[error("Invalid msg received with token {token_id} from {sender}: {reason}")]
+    InvalidMsg {
+        token_id: String,
+        sender: String,
+        reason: String,
+    },
+}
+
+impl From<Cw721ReceiveError> for StdError {
+    fn from(err: Cw721ReceiveError) -> Self {
+        StdError::generic_err(err.to_string())
+    }
 }
@@ -1,63 +1,159 @@
+use std::collections::BTreeMap;
+
 use cosmwasm_std::{
-    Addr, Api, BankMsg, Binary, Coin, CustomMsg, Deps, DepsMut, Empty, Env, MessageInfo, Response,
-    StdResult, Storage,
+    from_json, to_json_binary, to_json_vec, Addr, Api, BankMsg, Binary, CanonicalAddr, Coin,
+    CustomMsg, Decimal, Deps, DepsMut, Empty, Env, Event, HexBinary, MessageInfo, Order, Response,
+    StdError, StdResult, Storage, WasmMsg,
 };
 use cw_ownable::{none_or, Action, Ownership, OwnershipError, OwnershipStore};
-use cw_storage_plus::Item;
-use cw_utils::Expiration;
+use cw_storage_plus::{Bound, Item, Map};
+use cw_utils::{must_pay, Duration, Expiration};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 
+#[cfg(feature = "uri-index")]
+use crate::state::{extension_hash, uri_hash};
 use crate::{
     error::Cw721ContractError,
-    msg::{Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721MigrateMsg},
+    msg::{
+        Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721MigrateMsg, Cw721SudoMsg, ImportedToken,
+        MintApproval, NoisCallback, RandomnessSourceMsg,
+    },
+    query::validate_migration,
     receiver::Cw721ReceiveMsg,
-    state::{CollectionInfo, Cw721Config, DefaultOptionMetadataExtension, NftInfo, MINTER},
+    registry::is_operator_allowed,
+    state::{
+        as_metadata, ApprovalLimits, BurnOffer, BurnWindow, Burnable, BurnedToken, CollectionInfo,
+        Cw721Config, DefaultOptionMetadataExtension, FreezeInfo, GiftLock, LegacyTokenInfo,
+        Metadata, MetadataLimits, MintRateLimit, MintRateUsage, MintWindow, NftInfo,
+        OperatorFilter, OriginInfo, PauseInfo, PayoutDenom, PendingMigration, ProtocolFee,
+        RangeApproval, RevealInfo, TokenShuffle, TransferFee, TransferOffer, UniqueMetadata,
+        APPROVAL_PERMISSION_ALL, APPROVAL_PERMISSION_BURN, APPROVAL_PERMISSION_SEND,
+        APPROVAL_PERMISSION_TRANSFER, CREATOR, MAX_BURN_WINDOWS, MAX_INITIAL_TOKENS,
+        MAX_MEDIA_ASSETS, MAX_OPERATOR_FILTER_TOKEN_IDS, MAX_PROTOCOL_FEE_BPS, MINTER,
+        RANDOMNESS_JOB_ID, ROYALTY_ADMIN,
+    },
     Approval,
 };
 
-pub trait Cw721Execute<
+/// Hook for contracts building on cw721 as a library: `Cw721Execute::execute` routes
+/// `Cw721ExecuteMsg::Extension { msg }` here, so custom behaviour composes with base handlers.
+pub trait Cw721CustomExecute<
     // Metadata defined in NftInfo (used for mint).
     TMetadataExtension,
     // Defines for `CosmosMsg::Custom<T>` in response. Barely used, so `Empty` can be used.
     TCustomResponseMessage,
-    // Message passed for updating metadata.
+    // Message passed via `Cw721ExecuteMsg::Extension`.
     TMetadataExtensionMsg,
 > where
     TMetadataExtension: Serialize + DeserializeOwned + Clone,
     TCustomResponseMessage: CustomMsg,
     TMetadataExtensionMsg: CustomMsg,
 {
-    fn instantiate(
+    /// Allows creator to update onchain metadata. For now this is a no-op.
+    fn execute_custom(
         &self,
         deps: DepsMut,
         _env: Env,
         info: MessageInfo,
+        _config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+        _msg: TMetadataExtensionMsg,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(deps.storage, &info.sender)?;
+        Ok(Response::new().add_attribute("action", "update_metadata_extension"))
+    }
+}
+
+pub trait Cw721Execute<
+    // Metadata defined in NftInfo (used for mint).
+    TMetadataExtension,
+    // Defines for `CosmosMsg::Custom<T>` in response. Barely used, so `Empty` can be used.
+    TCustomResponseMessage,
+    // Message passed for updating metadata.
+    TMetadataExtensionMsg,
+>: Cw721CustomExecute<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg> where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TCustomResponseMessage: CustomMsg,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    /// Storage layout used by all default methods. Override to use keys set via
+    /// `Cw721Config::builder()`, keep in sync with `Cw721Query::config`.
+    fn config(
+        &self,
+    ) -> Cw721Config<'static, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+    {
+        Cw721Config::default()
+    }
+
+    fn instantiate(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
         msg: Cw721InstantiateMsg,
         contract_name: &str,
         contract_version: &str,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
         cw2::set_contract_version(deps.storage, contract_name, contract_version)?;
-        let config = Cw721Config::<Empty, Empty, Empty>::default();
+        let config = self.config();
         let collection_info = CollectionInfo {
             name: msg.name,
             symbol: msg.symbol,
+            royalty_payout_denoms: None,
+            origin: None,
         };
         config
             .collection_info
             .save(deps.storage, &collection_info)?;
+        config.supply.save(deps.storage, &0, env.block.height)?;
 
         let minter = match msg.minter {
             Some(owner) => deps.api.addr_validate(&owner)?,
             None => info.sender,
         };
         self.initialize_minter(deps.storage, deps.api, Some(minter.as_ref()))?;
+        // creator starts out as minter, both roles can be transferred separately afterwards
+        CREATOR.initialize_owner(deps.storage, deps.api, Some(minter.as_str()))?;
+        if msg.import_mode == Some(true) {
+            config.import_mode.save(deps.storage, &true)?;
+        }
 
         if let Some(withdraw_address) = msg.withdraw_address {
-            self.set_withdraw_address(deps, &minter, withdraw_address)?;
+            self.set_withdraw_address(deps.branch(), &minter, withdraw_address)?;
         }
 
-        Ok(Response::default().add_attribute("minter", minter))
+        let initial_tokens = msg.initial_tokens.unwrap_or_default();
+        if initial_tokens.len() > MAX_INITIAL_TOKENS {
+            return Err(Cw721ContractError::TooManyInitialTokens {
+                max: MAX_INITIAL_TOKENS,
+            });
+        }
+        let mut response = Response::default().add_attribute("minter", &minter);
+        // minted by the minter, funds of instantiation aren't a mint payment
+        let mint_info = MessageInfo {
+            sender: minter,
+            funds: vec![],
+        };
+        for token in initial_tokens {
+            let extension = match token.extension {
+                Some(extension) => from_json(extension)?,
+                None => from_json(b"null")?,
+            };
+            self.mint(
+                deps.branch(),
+                &env,
+                mint_info.clone(),
+                token.token_id.clone(),
+                token.owner,
+                token.token_uri,
+                extension,
+                None,
+                None,
+            )?;
+            response = response.add_attribute("initial_token", token.token_id);
+        }
+        Ok(response)
     }
 
     fn execute(
@@ -66,6 +162,49 @@ pub trait Cw721Execute<
         env: Env,
         info: MessageInfo,
         msg: Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        // contract is locked until a prepared migration is committed
+        if !matches!(
+            msg,
+            Cw721ExecuteMsg::CommitMigration {} | Cw721ExecuteMsg::ContinueMigration {}
+        ) && config.pending_migration.exists(deps.storage)
+        {
+            return Err(Cw721ContractError::MigrationPending {});
+        }
+        if is_transfer_or_mint(&msg) {
+            if let Some(frozen) = config.frozen.may_load(deps.storage)? {
+                return Err(Cw721ContractError::Frozen {
+                    reason: frozen.reason,
+                });
+            }
+            if config.paused.exists(deps.storage) {
+                return Err(Cw721ContractError::Paused {});
+            }
+        }
+        if let Some(action) = timelocked_action(&msg) {
+            if let Some(delay) = config.timelock.may_load(deps.storage)? {
+                return self
+                    .queue_action(deps.storage, &env, &info.sender, delay, action, &msg)
+                    .map(|response| add_cw721_event(response, "queue_action"));
+            }
+        }
+        // recorded upfront, since failed actions are reverted anyway
+        if let Some(action) = audit_action(&msg) {
+            config.record_audit(deps.storage, &env.block, &info.sender, action)?;
+        }
+        let event = event_name(&msg);
+        self.dispatch(deps, env, info, msg)
+            .map(|response| add_cw721_event(response, event))
+    }
+
+    /// Handles `msg` without the checks of `execute`, e.g. for queued actions whose timelock expired
+    fn dispatch(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
         match msg {
             Cw721ExecuteMsg::Mint {
@@ -73,19 +212,80 @@ pub trait Cw721Execute<
                 owner,
                 token_uri,
                 extension,
-            } => self.mint(deps, info, token_id, owner, token_uri, extension),
+                approval,
+                metadata_hash,
+            } => self.mint(
+                deps,
+                &env,
+                info,
+                token_id,
+                owner,
+                token_uri,
+                extension,
+                approval,
+                metadata_hash,
+            ),
+            Cw721ExecuteMsg::MintAndSend {
+                token_id,
+                contract,
+                token_uri,
+                extension,
+                msg,
+                metadata_hash,
+            } => self.mint_and_send(
+                deps,
+                &env,
+                info,
+                token_id,
+                contract,
+                token_uri,
+                extension,
+                msg,
+                metadata_hash,
+            ),
+            Cw721ExecuteMsg::ImportTokens { tokens } => {
+                self.import_tokens(deps, &env, info, tokens)
+            }
+            Cw721ExecuteMsg::CloseImport {} => self.close_import(deps, info),
             Cw721ExecuteMsg::Approve {
                 spender,
                 token_id,
                 expires,
-            } => self.approve(deps, env, info, spender, token_id, expires),
+                permissions,
+            } => self.approve(deps, env, info, spender, token_id, expires, permissions),
+            Cw721ExecuteMsg::ApproveBurner {
+                spender,
+                token_id,
+                expires,
+            } => self.approve_burner(deps, env, info, spender, token_id, expires),
+            Cw721ExecuteMsg::ApproveBatch {
+                spender,
+                token_ids,
+                expires,
+            } => self.approve_batch(deps, env, info, spender, token_ids, expires),
             Cw721ExecuteMsg::Revoke { spender, token_id } => {
                 self.revoke(deps, env, info, spender, token_id)
             }
-            Cw721ExecuteMsg::ApproveAll { operator, expires } => {
-                self.approve_all(deps, env, info, operator, expires)
-            }
+            Cw721ExecuteMsg::ApproveAll {
+                operator,
+                expires,
+                filter,
+            } => self.approve_all(deps, env, info, operator, expires, filter),
             Cw721ExecuteMsg::RevokeAll { operator } => self.revoke_all(deps, env, info, operator),
+            Cw721ExecuteMsg::RevokeAllOperators {} => self.revoke_all_operators(deps, info),
+            Cw721ExecuteMsg::OptOutPersistentOperator { operator } => {
+                self.opt_out_persistent_operator(deps, info, operator, true)
+            }
+            Cw721ExecuteMsg::OptInPersistentOperator { operator } => {
+                self.opt_out_persistent_operator(deps, info, operator, false)
+            }
+            Cw721ExecuteMsg::ApproveRange {
+                spender,
+                start_id,
+                end_id,
+                expires,
+            } => self.approve_range(deps, env, info, spender, start_id, end_id, expires),
+            Cw721ExecuteMsg::RevokeRange { spender } => self.revoke_range(deps, info, spender),
             Cw721ExecuteMsg::TransferNft {
                 recipient,
                 token_id,
@@ -95,12 +295,48 @@ pub trait Cw721Execute<
                 token_id,
                 msg,
             } => self.send_nft(deps, env, info, contract, token_id, msg),
+            Cw721ExecuteMsg::OfferTransfer {
+                recipient,
+                token_id,
+                expires,
+            } => self.offer_transfer(deps, env, info, recipient, token_id, expires),
+            Cw721ExecuteMsg::ClaimTransfer { token_id } => {
+                self.claim_transfer(deps, env, info, token_id)
+            }
+            Cw721ExecuteMsg::CancelTransferOffer { token_id } => {
+                self.cancel_transfer_offer(deps, env, info, token_id)
+            }
+            Cw721ExecuteMsg::LockGift {
+                token_id,
+                hash,
+                expires,
+            } => self.lock_gift(deps, env, info, token_id, hash, expires),
+            Cw721ExecuteMsg::CommitGiftClaim {
+                token_id,
+                commitment,
+            } => self.commit_gift_claim(deps, env, token_id, commitment),
+            Cw721ExecuteMsg::ClaimGift {
+                token_id,
+                secret,
+                recipient,
+            } => self.claim_gift(deps, env, info, token_id, secret, recipient),
+            Cw721ExecuteMsg::ReclaimGift { token_id } => {
+                self.reclaim_gift(deps, env, info, token_id)
+            }
             Cw721ExecuteMsg::Burn { token_id } => self.burn_nft(deps, env, info, token_id),
-            Cw721ExecuteMsg::UpdateOwnership(action) => {
+            Cw721ExecuteMsg::UpdateOwnership(action)
+            | Cw721ExecuteMsg::UpdateMinterOwnership(action) => {
                 self.update_minter_ownership(deps, env, info, action)
             }
+            Cw721ExecuteMsg::UpdateCreatorOwnership(action) => {
+                self.update_creator_ownership(deps, env, info, action)
+            }
+            Cw721ExecuteMsg::UpdateRoyaltyAdminOwnership(action) => {
+                self.update_royalty_admin_ownership(deps, env, info, action)
+            }
             Cw721ExecuteMsg::Extension { msg } => {
-                self.update_metadata_extension(deps, env, info, msg)
+                let config = self.config();
+                self.execute_custom(deps, env, info, &config, msg)
             }
             Cw721ExecuteMsg::SetWithdrawAddress { address } => {
                 self.set_withdraw_address(deps, &info.sender, address)
@@ -109,6 +345,125 @@ pub trait Cw721Execute<
                 self.remove_withdraw_address(deps.storage, &info.sender)
             }
             Cw721ExecuteMsg::WithdrawFunds { amount } => self.withdraw_funds(deps.storage, &amount),
+            Cw721ExecuteMsg::SweepFunds { denoms } => self.sweep_funds(deps.as_ref(), &env, denoms),
+            Cw721ExecuteMsg::SetTransferCooldown { cooldown } => {
+                self.set_transfer_cooldown(deps.storage, &info.sender, cooldown)
+            }
+            Cw721ExecuteMsg::SetApprovalLimits { limits } => {
+                self.set_approval_limits(deps.storage, &info.sender, limits)
+            }
+            Cw721ExecuteMsg::SetMetadataLimits { limits } => {
+                self.set_metadata_limits(deps.storage, &info.sender, limits)
+            }
+            Cw721ExecuteMsg::SetOperatorRegistry { registry } => {
+                self.set_operator_registry(deps, &info.sender, registry)
+            }
+            Cw721ExecuteMsg::SetTransferFee { fee } => {
+                self.set_transfer_fee(deps.storage, &info.sender, fee)
+            }
+            Cw721ExecuteMsg::SetRetireBurnedTokens { retire } => {
+                self.set_retire_burned_tokens(deps.storage, &info.sender, retire)
+            }
+            Cw721ExecuteMsg::SetContractUri { contract_uri } => {
+                self.set_contract_uri(deps.storage, &info.sender, contract_uri)
+            }
+            Cw721ExecuteMsg::SetRoyaltyPayoutDenoms { denoms } => {
+                self.set_royalty_payout_denoms(deps, &info.sender, denoms)
+            }
+            Cw721ExecuteMsg::SetOrigin { origin } => self.set_origin(deps, &info.sender, origin),
+            Cw721ExecuteMsg::SetOriginTokenIds { origin_token_ids } => {
+                self.set_origin_token_ids(deps, &info.sender, origin_token_ids)
+            }
+            Cw721ExecuteMsg::SetProvenanceHash { provenance_hash } => {
+                self.set_provenance_hash(deps.storage, &info.sender, provenance_hash)
+            }
+            Cw721ExecuteMsg::SetRevealPlaceholder { placeholder_uri } => {
+                self.set_reveal_placeholder(deps.storage, &info.sender, placeholder_uri)
+            }
+            Cw721ExecuteMsg::Reveal {
+                base_uri,
+                token_uris,
+                provenance,
+            } => self.reveal(
+                deps.storage,
+                &env,
+                &info.sender,
+                base_uri,
+                token_uris,
+                provenance,
+            ),
+            Cw721ExecuteMsg::SetRandomnessSource { source } => {
+                self.set_randomness_source(deps, &info.sender, source)
+            }
+            Cw721ExecuteMsg::RequestRandomness {} => self.request_randomness(deps.storage, info),
+            Cw721ExecuteMsg::NoisReceive { callback } => {
+                self.receive_randomness(deps.storage, &info.sender, callback)
+            }
+            Cw721ExecuteMsg::SetMintWindow { window } => {
+                self.set_mint_window(deps.storage, &info.sender, window)
+            }
+            Cw721ExecuteMsg::SetMintRateLimit { limit } => {
+                self.set_mint_rate_limit(deps.storage, &info.sender, limit)
+            }
+            Cw721ExecuteMsg::SetBurnable {
+                burnable,
+                creator_gated,
+                lock,
+            } => self.set_burnable(deps.storage, &info.sender, burnable, creator_gated, lock),
+            Cw721ExecuteMsg::SetBurnWindows { windows } => {
+                self.set_burn_windows(deps.storage, &info.sender, windows)
+            }
+            Cw721ExecuteMsg::OfferBurn { token_id } => self.offer_burn(deps, env, info, token_id),
+            Cw721ExecuteMsg::ConfirmBurn { token_id } => {
+                self.confirm_burn(deps, env, info, token_id)
+            }
+            Cw721ExecuteMsg::SetUniqueMetadata { unique } => {
+                self.set_unique_metadata(deps.storage, &info.sender, unique)
+            }
+            Cw721ExecuteMsg::CommitMigration {} => {
+                self.commit_migration(deps.storage, &info.sender)
+            }
+            Cw721ExecuteMsg::ContinueMigration {} => {
+                self.continue_migration(deps.storage, deps.api, &info.sender)
+            }
+            Cw721ExecuteMsg::AddPersistentOperator { operator } => {
+                self.set_persistent_operator(deps, &info.sender, operator, true)
+            }
+            Cw721ExecuteMsg::RemovePersistentOperator { operator } => {
+                self.set_persistent_operator(deps, &info.sender, operator, false)
+            }
+            Cw721ExecuteMsg::SetGuardian { guardian } => {
+                self.set_guardian(deps, &info.sender, guardian)
+            }
+            Cw721ExecuteMsg::SetRoyaltyAdmin { admin } => {
+                self.set_royalty_admin(deps, &info.sender, admin)
+            }
+            Cw721ExecuteMsg::Pause {} => self.pause(deps.storage, &env, &info.sender),
+            Cw721ExecuteMsg::Unpause {} => self.unpause(deps.storage, &info.sender),
+            Cw721ExecuteMsg::SetTimelock { delay } => {
+                self.set_timelock(deps.storage, &info.sender, delay)
+            }
+            Cw721ExecuteMsg::ExecuteQueued { id } => self.execute_queued(deps, env, info, id),
+            Cw721ExecuteMsg::CancelQueued { id } => {
+                self.cancel_queued(deps.storage, &info.sender, id)
+            }
+        }
+    }
+
+    /// Chain governance only, e.g. to set protocol fee policy without forking
+    fn sudo(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        msg: Cw721SudoMsg,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        match msg {
+            Cw721SudoMsg::SetProtocolFee { bps, recipient } => {
+                self.set_protocol_fee(deps, bps, recipient)
+            }
+            Cw721SudoMsg::RemoveProtocolFee {} => self.remove_protocol_fee(deps.storage),
+            Cw721SudoMsg::Freeze { reason } => self.freeze(deps.storage, &env, reason),
+            Cw721SudoMsg::Unfreeze {} => self.unfreeze(deps.storage),
         }
     }
 
@@ -119,16 +474,51 @@ pub trait Cw721Execute<
         msg: Cw721MigrateMsg,
         contract_name: &str,
         contract_version: &str,
-    ) -> Result<Response, Cw721ContractError> {
-        let response = Response::<Empty>::default();
-        // first migrate legacy data ...
+        compatible_contract_names: &[&str],
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        assert_migration_compatible(deps.storage, &msg, contract_name, compatible_contract_names)?;
+        let response =
+            Response::<TCustomResponseMessage>::default().add_attribute("action", "migrate");
+        // first migrate legacy data (older versions always used default keys) ...
         let response =
             migrate_legacy_minter_and_creator(deps.storage, deps.api, &env, &msg, response)?;
         let response = migrate_legacy_collection_info(deps.storage, &env, &msg, response)?;
-        // ... then migrate
-        let response = migrate_version(deps.storage, contract_name, contract_version, response)?;
+        let response = migrate_legacy_tokens(deps.storage, deps.api, &env, &msg, response)?;
+        // ... then migrate, or only prepare it in case of two-phase migration,
+        // or in case legacy tokens are left for `ContinueMigration`
+        let config = self.config();
+        let two_phase = matches!(
+            msg,
+            Cw721MigrateMsg::WithUpdate {
+                two_phase: Some(true),
+                ..
+            }
+        ) || config.legacy_token_cursor.exists(deps.storage);
+        let response = if two_phase {
+            prepare_migration(
+                deps.storage,
+                &config,
+                &env,
+                contract_name,
+                contract_version,
+                response,
+            )?
+        } else {
+            migrate_version(
+                deps.storage,
+                &config,
+                contract_name,
+                contract_version,
+                response,
+            )?
+        };
         // ... and update creator and minter AFTER legacy migration
+        let response = migrate_creator(deps.storage, deps.api, &env, &msg, response)?;
         let response = migrate_minter(deps.storage, deps.api, &env, &msg, response)?;
+        let response =
+            migrate_withdraw_address(deps.storage, &config, deps.api, &env, &msg, response)?;
+        let response = migrate_collection_info(deps.storage, &config, &env, &msg, response)?;
+        let response = migrate_import_mode(deps.storage, &config, &env, &msg, response)?;
         Ok(response)
     }
 
@@ -141,9 +531,20 @@ pub trait Cw721Execute<
         recipient: String,
         token_id: String,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        _transfer_nft::<TMetadataExtension>(deps, &env, &info, &recipient, &token_id)?;
+        let config = self.config();
+        let fee_msgs = transfer_fee_msgs(deps.as_ref(), &config, &info, &token_id)?;
+        _transfer_nft(
+            deps,
+            &config,
+            &env,
+            &info,
+            &recipient,
+            &token_id,
+            APPROVAL_PERMISSION_TRANSFER,
+        )?;
 
         Ok(Response::new()
+            .add_messages(fee_msgs)
             .add_attribute("action", "transfer_nft")
             .add_attribute("sender", info.sender)
             .add_attribute("recipient", recipient)
@@ -159,8 +560,20 @@ pub trait Cw721Execute<
         token_id: String,
         msg: Binary,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        let contract_addr = deps.api.addr_validate(&contract)?;
+        check_operator_allowed(deps.as_ref(), &config, &contract_addr)?;
+        let fee_msgs = transfer_fee_msgs(deps.as_ref(), &config, &info, &token_id)?;
         // Transfer token
-        _transfer_nft::<TMetadataExtension>(deps, &env, &info, &contract, &token_id)?;
+        _transfer_nft(
+            deps,
+            &config,
+            &env,
+            &info,
+            &contract,
+            &token_id,
+            APPROVAL_PERMISSION_SEND,
+        )?;
 
         let send = Cw721ReceiveMsg {
             sender: info.sender.to_string(),
@@ -170,6 +583,7 @@ pub trait Cw721Execute<
 
         // Send message
         Ok(Response::new()
+            .add_messages(fee_msgs)
             .add_message(send.into_cosmos_msg(contract.clone())?)
             .add_attribute("action", "send_nft")
             .add_attribute("sender", info.sender)
@@ -177,425 +591,3030 @@ pub trait Cw721Execute<
             .add_attribute("token_id", token_id))
     }
 
-    fn approve(
+    fn offer_transfer(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        spender: String,
+        recipient: String,
         token_id: String,
         expires: Option<Expiration>,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        _update_approvals::<TMetadataExtension>(
-            deps, &env, &info, &spender, &token_id, true, expires,
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(Cw721ContractError::Expired {});
+        }
+        let recipient_addr = deps.api.addr_validate(&recipient)?;
+        let config = self.config();
+        let token = config.nft_info.load(deps.storage, &token_id)?;
+        check_can_send(
+            deps.as_ref(),
+            &config,
+            &env,
+            &info,
+            &token_id,
+            &token,
+            APPROVAL_PERMISSION_TRANSFER,
+        )?;
+        config.transfer_offers.save(
+            deps.storage,
+            &token_id,
+            &TransferOffer {
+                owner: token.owner,
+                recipient: recipient_addr,
+                expires,
+            },
         )?;
 
         Ok(Response::new()
-            .add_attribute("action", "approve")
+            .add_attribute("action", "offer_transfer")
             .add_attribute("sender", info.sender)
-            .add_attribute("spender", spender)
+            .add_attribute("recipient", recipient)
             .add_attribute("token_id", token_id))
     }
 
-    fn revoke(
+    fn claim_transfer(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        let token = config.nft_info.load(deps.storage, &token_id)?;
+        // offer is void once token changed hands
+        match config.transfer_offers.may_load(deps.storage, &token_id)? {
+            Some(offer)
+                if offer.owner == token.owner
+                    && offer.recipient == info.sender
+                    && !offer.is_expired(&env.block) => {}
+            _ => return Err(Cw721ContractError::NoTransferOffer { token_id }),
+        }
+        let fee_msgs = transfer_fee_msgs(deps.as_ref(), &config, &info, &token_id)?;
+        let owner = token.owner.clone();
+        _move_nft(deps, &config, &env, &token_id, token, info.sender.clone())?;
+
+        Ok(Response::new()
+            .add_messages(fee_msgs)
+            .add_attribute("action", "claim_transfer")
+            .add_attribute("sender", info.sender)
+            .add_attribute("owner", owner)
+            .add_attribute("token_id", token_id))
+    }
+
+    fn cancel_transfer_offer(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        spender: String,
         token_id: String,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        _update_approvals::<TMetadataExtension>(
-            deps, &env, &info, &spender, &token_id, false, None,
+        let config = self.config();
+        let token = config.nft_info.load(deps.storage, &token_id)?;
+        check_can_send(
+            deps.as_ref(),
+            &config,
+            &env,
+            &info,
+            &token_id,
+            &token,
+            APPROVAL_PERMISSION_TRANSFER,
         )?;
+        config.transfer_offers.remove(deps.storage, &token_id);
 
         Ok(Response::new()
-            .add_attribute("action", "revoke")
+            .add_attribute("action", "cancel_transfer_offer")
             .add_attribute("sender", info.sender)
-            .add_attribute("spender", spender)
             .add_attribute("token_id", token_id))
     }
 
-    fn approve_all(
+    fn lock_gift(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        operator: String,
-        expires: Option<Expiration>,
+        token_id: String,
+        hash: HexBinary,
+        expires: Expiration,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        // reject expired data as invalid
-        let expires = expires.unwrap_or_default();
+        if hash.len() != 32 {
+            return Err(StdError::generic_err("Gift hash must be a sha256 hash").into());
+        }
+        if matches!(expires, Expiration::Never {}) {
+            return Err(Cw721ContractError::GiftMustExpire {});
+        }
         if expires.is_expired(&env.block) {
             return Err(Cw721ContractError::Expired {});
         }
-
-        // set the operator for us
-        let operator_addr = deps.api.addr_validate(&operator)?;
-        let config = Cw721Config::<
-            TMetadataExtension,
-            TCustomResponseMessage,
-            TMetadataExtensionMsg,
-        >::default();
-        config
-            .operators
-            // stores info.sender as key (=granter, NFT owner) and operator as value (operator only(!) has control over NFTs of granter)
-            // check is done in `check_can_send()`
-            .save(deps.storage, (&info.sender, &operator_addr), &expires)?;
+        // transfer fee is paid on lock, claim and reclaim are free
+        let config = self.config();
+        let fee_msgs = transfer_fee_msgs(deps.as_ref(), &config, &info, &token_id)?;
+        let owner = config.nft_info.load(deps.storage, &token_id)?.owner;
+        config.gift_locks.save(
+            deps.storage,
+            &token_id,
+            &GiftLock {
+                owner,
+                hash,
+                expires,
+            },
+        )?;
+        // escrow token in contract
+        _transfer_nft(
+            deps,
+            &config,
+            &env,
+            &info,
+            env.contract.address.as_str(),
+            &token_id,
+            APPROVAL_PERMISSION_TRANSFER,
+        )?;
 
         Ok(Response::new()
-            .add_attribute("action", "approve_all")
+            .add_messages(fee_msgs)
+            .add_attribute("action", "lock_gift")
             .add_attribute("sender", info.sender)
-            .add_attribute("operator", operator))
+            .add_attribute("token_id", token_id)
+            .add_attribute("expires", expires.to_string()))
     }
 
-    fn revoke_all(
+    fn commit_gift_claim(
         &self,
         deps: DepsMut,
-        _env: Env,
-        info: MessageInfo,
-        operator: String,
+        env: Env,
+        token_id: String,
+        commitment: HexBinary,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        let operator_addr = deps.api.addr_validate(&operator)?;
-        let config = Cw721Config::<
-            TMetadataExtension,
-            TCustomResponseMessage,
-            TMetadataExtensionMsg,
-        >::default();
-        config
-            .operators
-            .remove(deps.storage, (&info.sender, &operator_addr));
+        if commitment.len() != 32 {
+            return Err(
+                StdError::generic_err("Gift claim commitment must be a sha256 hash").into(),
+            );
+        }
+        let config = self.config();
+        let Some(gift) = config.gift_locks.may_load(deps.storage, &token_id)? else {
+            return Err(Cw721ContractError::NoGift { token_id });
+        };
+        if gift.is_expired(&env.block) {
+            return Err(Cw721ContractError::GiftExpired { token_id });
+        }
+        // keep height of first commitment, resubmitting it doesn't delay the claim
+        let key = (token_id.as_str(), commitment.as_slice());
+        if !config.gift_claim_commitments.has(deps.storage, key) {
+            config
+                .gift_claim_commitments
+                .save(deps.storage, key, &env.block.height)?;
+        }
 
         Ok(Response::new()
-            .add_attribute("action", "revoke_all")
-            .add_attribute("sender", info.sender)
-            .add_attribute("operator", operator))
+            .add_attribute("action", "commit_gift_claim")
+            .add_attribute("token_id", token_id)
+            .add_attribute("commitment", commitment.to_string()))
     }
 
-    fn burn_nft(
+    fn claim_gift(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
         token_id: String,
+        secret: Binary,
+        recipient: Option<String>,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        let config = Cw721Config::<
-            TMetadataExtension,
-            TCustomResponseMessage,
-            TMetadataExtensionMsg,
-        >::default();
+        let config = self.config();
+        let Some(gift) = config.gift_locks.may_load(deps.storage, &token_id)? else {
+            return Err(Cw721ContractError::NoGift { token_id });
+        };
+        if gift.is_expired(&env.block) {
+            return Err(Cw721ContractError::GiftExpired { token_id });
+        }
+        if gift.hash.as_slice() != Sha256::digest(secret.as_slice()).as_slice() {
+            return Err(Cw721ContractError::InvalidGiftSecret { token_id });
+        }
+        let recipient = match recipient {
+            Some(recipient) => deps.api.addr_validate(&recipient)?,
+            None => info.sender.clone(),
+        };
         let token = config.nft_info.load(deps.storage, &token_id)?;
-        check_can_send(deps.as_ref(), &env, &info, &token)?;
-
-        config.nft_info.remove(deps.storage, &token_id)?;
-        config.decrement_tokens(deps.storage)?;
+        // lock is stale once token left escrow
+        if token.owner != env.contract.address {
+            return Err(Cw721ContractError::NoGift { token_id });
+        }
+        // recipient must have been committed along with the secret before it was revealed
+        let commitment = gift_claim_commitment(secret.as_slice(), &recipient);
+        let key = (token_id.as_str(), commitment.as_slice());
+        let committed_at = config.gift_claim_commitments.may_load(deps.storage, key)?;
+        if !matches!(committed_at, Some(height) if height < env.block.height) {
+            return Err(Cw721ContractError::GiftClaimNotCommitted { token_id });
+        }
+        config.gift_claim_commitments.remove(deps.storage, key);
+        config.gift_locks.remove(deps.storage, &token_id);
+        _move_nft(deps, &config, &env, &token_id, token, recipient.clone())?;
 
         Ok(Response::new()
-            .add_attribute("action", "burn")
+            .add_attribute("action", "claim_gift")
             .add_attribute("sender", info.sender)
+            .add_attribute("owner", gift.owner)
+            .add_attribute("recipient", recipient)
             .add_attribute("token_id", token_id))
     }
 
-    // ------- opionated cw721 functions -------
-    fn initialize_minter(
-        &self,
-        storage: &mut dyn Storage,
-        api: &dyn Api,
-        minter: Option<&str>,
-    ) -> StdResult<Ownership<Addr>> {
-        MINTER.initialize_owner(storage, api, minter)
-    }
-
-    fn mint(
+    fn reclaim_gift(
         &self,
         deps: DepsMut,
+        env: Env,
         info: MessageInfo,
         token_id: String,
-        owner: String,
-        token_uri: Option<String>,
-        extension: TMetadataExtension,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        MINTER.assert_owner(deps.storage, &info.sender)?;
-
-        // create the token
-        let token = NftInfo {
-            owner: deps.api.addr_validate(&owner)?,
-            approvals: vec![],
-            token_uri,
-            extension,
+        let config = self.config();
+        let Some(gift) = config.gift_locks.may_load(deps.storage, &token_id)? else {
+            return Err(Cw721ContractError::NoGift { token_id });
         };
-        let config = Cw721Config::<TMetadataExtension, Empty, Empty>::default();
-        config
-            .nft_info
-            .update(deps.storage, &token_id, |old| match old {
-                Some(_) => Err(Cw721ContractError::Claimed {}),
-                None => Ok(token),
-            })?;
-
-        config.increment_tokens(deps.storage)?;
+        if gift.owner != info.sender {
+            return Err(Cw721ContractError::Ownership(OwnershipError::NotOwner));
+        }
+        if !gift.is_expired(&env.block) {
+            return Err(Cw721ContractError::GiftNotExpired { token_id });
+        }
+        let token = config.nft_info.load(deps.storage, &token_id)?;
+        if token.owner != env.contract.address {
+            return Err(Cw721ContractError::NoGift { token_id });
+        }
+        config.gift_locks.remove(deps.storage, &token_id);
+        _move_nft(deps, &config, &env, &token_id, token, gift.owner)?;
 
         Ok(Response::new()
-            .add_attribute("action", "mint")
-            .add_attribute("minter", info.sender)
-            .add_attribute("owner", owner)
+            .add_attribute("action", "reclaim_gift")
+            .add_attribute("sender", info.sender)
             .add_attribute("token_id", token_id))
     }
 
-    fn update_minter_ownership(
+    #[allow(clippy::too_many_arguments)]
+    fn approve(
         &self,
         deps: DepsMut,
         env: Env,
         info: MessageInfo,
-        action: Action,
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+        permissions: Option<u8>,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        let ownership =
-            MINTER.update_ownership(deps.api, deps.storage, &env.block, &info.sender, action)?;
-        Ok(Response::new()
+        _update_approvals(
+            deps,
+            &self.config(),
+            &env,
+            &info,
+            &spender,
+            &token_id,
+            true,
+            expires,
+            permissions,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "approve")
+            .add_attribute("sender", info.sender)
+            .add_attribute("spender", spender)
+            .add_attribute("token_id", token_id))
+    }
+
+    fn approve_burner(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        _update_approvals(
+            deps,
+            &self.config(),
+            &env,
+            &info,
+            &spender,
+            &token_id,
+            true,
+            expires,
+            Some(APPROVAL_PERMISSION_BURN),
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "approve_burner")
+            .add_attribute("sender", info.sender)
+            .add_attribute("spender", spender)
+            .add_attribute("token_id", token_id))
+    }
+
+    fn approve_batch(
+        &self,
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        spender: String,
+        token_ids: Vec<String>,
+        expires: Option<Expiration>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        for token_id in token_ids.iter() {
+            _update_approvals(
+                deps.branch(),
+                &config,
+                &env,
+                &info,
+                &spender,
+                token_id,
+                true,
+                expires,
+                None,
+            )?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "approve_batch")
+            .add_attribute("sender", info.sender)
+            .add_attribute("spender", spender)
+            .add_attribute("token_ids", token_ids.join(",")))
+    }
+
+    fn revoke(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        spender: String,
+        token_id: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        _update_approvals(
+            deps,
+            &self.config(),
+            &env,
+            &info,
+            &spender,
+            &token_id,
+            false,
+            None,
+            None,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke")
+            .add_attribute("sender", info.sender)
+            .add_attribute("spender", spender)
+            .add_attribute("token_id", token_id))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn approve_range(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        spender: String,
+        start_id: u64,
+        end_id: u64,
+        expires: Option<Expiration>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        if start_id > end_id {
+            return Err(Cw721ContractError::InvalidTokenRange { start_id, end_id });
+        }
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(Cw721ContractError::Expired {});
+        }
+        let spender_addr = deps.api.addr_validate(&spender)?;
+        let config = self.config();
+        check_operator_allowed(deps.as_ref(), &config, &spender_addr)?;
+        config.range_approvals.save(
+            deps.storage,
+            (&info.sender, &spender_addr),
+            &RangeApproval {
+                start_id,
+                end_id,
+                expires,
+            },
+        )?;
+        Ok(Response::new()
+            .add_attribute("action", "approve_range")
+            .add_attribute("sender", info.sender)
+            .add_attribute("spender", spender)
+            .add_attribute("start_id", start_id.to_string())
+            .add_attribute("end_id", end_id.to_string()))
+    }
+
+    fn revoke_range(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        spender: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let spender_addr = deps.api.addr_validate(&spender)?;
+        self.config()
+            .range_approvals
+            .remove(deps.storage, (&info.sender, &spender_addr));
+        Ok(Response::new()
+            .add_attribute("action", "revoke_range")
+            .add_attribute("sender", info.sender)
+            .add_attribute("spender", spender))
+    }
+
+    fn approve_all(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+        expires: Option<Expiration>,
+        filter: Option<OperatorFilter>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(Cw721ContractError::Expired {});
+        }
+        let empty_filter = match &filter {
+            Some(OperatorFilter::TokenIdPrefix(prefix)) => prefix.is_empty(),
+            Some(OperatorFilter::TokenIds(token_ids)) => {
+                token_ids.is_empty() || token_ids.len() > MAX_OPERATOR_FILTER_TOKEN_IDS
+            }
+            None => false,
+        };
+        if empty_filter {
+            return Err(Cw721ContractError::InvalidOperatorFilter {
+                max: MAX_OPERATOR_FILTER_TOKEN_IDS,
+            });
+        }
+
+        // set the operator for us
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        let config = self.config();
+        check_operator_allowed(deps.as_ref(), &config, &operator_addr)?;
+        // enforce operator cap, unless operator is only renewed
+        if !config
+            .operators
+            .has(deps.storage, (&info.sender, &operator_addr))
+        {
+            let max = config.approval_limits(deps.storage)?.max_operators;
+            let count = config
+                .operators
+                .prefix(&info.sender)
+                .keys_raw(deps.storage, None, None, Order::Ascending)
+                .count();
+            if count >= max as usize {
+                return Err(Cw721ContractError::TooManyOperators { max });
+            }
+        }
+        // stores info.sender as key (=granter, NFT owner) and operator as value (operator only(!) has control over NFTs of granter)
+        // check is done in `check_can_send()`
+        config.save_operator(
+            deps.storage,
+            &info.sender,
+            &operator_addr,
+            &expires,
+            filter.as_ref(),
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "approve_all")
+            .add_attribute("sender", info.sender)
+            .add_attribute("operator", operator))
+    }
+
+    fn revoke_all(
+        &self,
+        deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        operator: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let operator_addr = deps.api.addr_validate(&operator)?;
+        let config = self.config();
+        config.remove_operator(deps.storage, &info.sender, &operator_addr)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke_all")
+            .add_attribute("sender", info.sender)
+            .add_attribute("operator", operator))
+    }
+
+    fn revoke_all_operators(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        // bounded by `max_operators`
+        let operators = config
+            .operators
+            .prefix(&info.sender)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for operator in &operators {
+            config.remove_operator(deps.storage, &info.sender, operator)?;
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "revoke_all_operators")
+            .add_attribute("sender", info.sender)
+            .add_attribute("operators_revoked", operators.len().to_string()))
+    }
+
+    fn opt_out_persistent_operator(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+        operator: String,
+        opt_out: bool,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        let operator = deps.api.addr_validate(&operator)?;
+        let key = (&info.sender, &operator);
+        let action = if opt_out {
+            config
+                .persistent_operator_opt_outs
+                .save(deps.storage, key, &Empty {})?;
+            config.persistent_operator_opt_ins.remove(deps.storage, key);
+            "opt_out_persistent_operator"
+        } else {
+            config
+                .persistent_operator_opt_outs
+                .remove(deps.storage, key);
+            config
+                .persistent_operator_opt_ins
+                .save(deps.storage, key, &Empty {})?;
+            "opt_in_persistent_operator"
+        };
+        Ok(Response::new()
+            .add_attribute("action", action)
+            .add_attribute("sender", info.sender)
+            .add_attribute("operator", operator))
+    }
+
+    fn burn_nft(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        let burnable = config.burnable(deps.storage)?;
+        if !burnable.burnable {
+            return Err(Cw721ContractError::BurnDisabled {});
+        }
+        if burnable.creator_gated {
+            return Err(Cw721ContractError::BurnCreatorGated {});
+        }
+        check_burn_window(deps.storage, &config, &env)?;
+        let token = config.nft_info.load(deps.storage, &token_id)?;
+        check_can_send(
+            deps.as_ref(),
+            &config,
+            &env,
+            &info,
+            &token_id,
+            &token,
+            APPROVAL_PERMISSION_BURN,
+        )?;
+        _burn_nft(deps.storage, &config, &env, &token_id, token, &info.sender)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "burn")
+            .add_attribute("sender", info.sender)
+            .add_attribute("token_id", token_id))
+    }
+
+    fn offer_burn(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        let burnable = config.burnable(deps.storage)?;
+        if !burnable.burnable {
+            return Err(Cw721ContractError::BurnDisabled {});
+        }
+        if !burnable.creator_gated {
+            return Err(Cw721ContractError::BurnNotCreatorGated {});
+        }
+        let token = config.nft_info.load(deps.storage, &token_id)?;
+        check_can_send(
+            deps.as_ref(),
+            &config,
+            &env,
+            &info,
+            &token_id,
+            &token,
+            APPROVAL_PERMISSION_BURN,
+        )?;
+        config.burn_offers.save(
+            deps.storage,
+            &token_id,
+            &BurnOffer {
+                owner: token.owner.clone(),
+                height: env.block.height,
+            },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "offer_burn")
+            .add_attribute("sender", info.sender)
+            .add_attribute("owner", token.owner)
+            .add_attribute("token_id", token_id))
+    }
+
+    fn confirm_burn(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        token_id: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(deps.storage, &info.sender)?;
+        let config = self.config();
+        let burnable = config.burnable(deps.storage)?;
+        if !burnable.burnable {
+            return Err(Cw721ContractError::BurnDisabled {});
+        }
+        if !burnable.creator_gated {
+            return Err(Cw721ContractError::BurnNotCreatorGated {});
+        }
+        check_burn_window(deps.storage, &config, &env)?;
+        let token = config.nft_info.load(deps.storage, &token_id)?;
+        // offer is void once token changed hands
+        match config.burn_offers.may_load(deps.storage, &token_id)? {
+            Some(offer) if offer.owner == token.owner => {}
+            _ => return Err(Cw721ContractError::NoBurnOffer { token_id }),
+        }
+        let owner = token.owner.clone();
+        _burn_nft(deps.storage, &config, &env, &token_id, token, &info.sender)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "confirm_burn")
+            .add_attribute("sender", info.sender)
+            .add_attribute("owner", owner)
+            .add_attribute("token_id", token_id))
+    }
+
+    // ------- opionated cw721 functions -------
+    fn initialize_minter(
+        &self,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        minter: Option<&str>,
+    ) -> StdResult<Ownership<Addr>> {
+        MINTER.initialize_owner(storage, api, minter)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn mint(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        info: MessageInfo,
+        token_id: String,
+        owner: String,
+        token_uri: Option<String>,
+        extension: TMetadataExtension,
+        approval: Option<MintApproval>,
+        metadata_hash: Option<HexBinary>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        MINTER.assert_owner(deps.storage, &info.sender)?;
+        validate_media(&extension)?;
+        if metadata_hash.as_ref().is_some_and(|hash| hash.len() != 32) {
+            return Err(StdError::generic_err("Metadata hash must be a sha256 hash").into());
+        }
+        let config = self.config();
+        check_metadata_size(
+            &config.metadata_limits(deps.storage)?,
+            token_uri.as_deref(),
+            &extension,
+        )?;
+        if let Some(window) = config.mint_window.may_load(deps.storage)? {
+            if let Some(start) = window.start {
+                if env.block.time < start {
+                    return Err(Cw721ContractError::MintNotStarted { start });
+                }
+            }
+            if let Some(end) = window.end {
+                if env.block.time >= end {
+                    return Err(Cw721ContractError::MintEnded { end });
+                }
+            }
+        }
+        check_mint_rate_limit(deps.storage, &config, env)?;
+
+        // create the token
+        let token = NftInfo {
+            owner: deps.api.addr_validate(&owner)?,
+            approvals: vec![],
+            token_uri,
+            extension,
+            last_transferred_at: None,
+            metadata_hash,
+        };
+        if config.nft_info.may_load(deps.storage, &token_id)?.is_some() {
+            return Err(Cw721ContractError::Claimed {});
+        }
+        if config.burned_tokens.has(deps.storage, &token_id) {
+            if config.retire_burned_tokens(deps.storage)? {
+                return Err(Cw721ContractError::TokenRetired { token_id });
+            }
+            config.burned_tokens.remove(deps.storage, &token_id);
+        }
+        #[cfg(feature = "uri-index")]
+        check_unique_metadata(deps.storage, &config, &token)?;
+        config.save_nft(deps.storage, &token_id, &token, None)?;
+
+        config.increment_tokens(deps.storage, env.block.height)?;
+
+        let mut response = Response::new()
+            .add_messages(protocol_fee_msgs(deps.storage, &config, &info)?)
+            .add_attribute("action", "mint")
+            .add_attribute("minter", info.sender)
+            .add_attribute("owner", owner)
+            .add_attribute("token_id", &token_id);
+        // resolved like in `NftInfo` query, so pinning services don't need to query it
+        if let Some(metadata_uri) = config.token_uri(deps.storage, &token_id, token.token_uri)? {
+            response = response.add_attribute("metadata_uri", metadata_uri);
+        }
+        if let Some(approval) = approval {
+            let spender = _mint_approval(deps, env, &config, &token_id, approval)?;
+            response = response.add_attribute("spender", spender);
+        }
+        Ok(response)
+    }
+
+    /// Mints token owned by `contract` and dispatches `Cw721ReceiveMsg` to it, so e.g. launchpads
+    /// can mint directly into staking contracts.
+    #[allow(clippy::too_many_arguments)]
+    fn mint_and_send(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        info: MessageInfo,
+        token_id: String,
+        contract: String,
+        token_uri: Option<String>,
+        extension: TMetadataExtension,
+        msg: Binary,
+        metadata_hash: Option<HexBinary>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let contract_addr = deps.api.addr_validate(&contract)?;
+        check_operator_allowed(deps.as_ref(), &self.config(), &contract_addr)?;
+        let mint_response = self.mint(
+            deps,
+            env,
+            info.clone(),
+            token_id.clone(),
+            contract.clone(),
+            token_uri,
+            extension,
+            None,
+            metadata_hash,
+        )?;
+
+        let send = Cw721ReceiveMsg {
+            sender: info.sender.to_string(),
+            token_id: token_id.clone(),
+            msg,
+        };
+
+        // keeps protocol fee messages of mint
+        Ok(Response::new()
+            .add_submessages(mint_response.messages)
+            .add_message(send.into_cosmos_msg(contract.clone())?)
+            .add_attribute("action", "mint_and_send")
+            .add_attribute("minter", info.sender)
+            .add_attribute("recipient", contract)
+            .add_attribute("token_id", token_id)
+            .add_attributes(
+                mint_response
+                    .attributes
+                    .into_iter()
+                    .filter(|attr| attr.key == "metadata_uri"),
+            ))
+    }
+
+    /// Bulk mint of genesis import mode, with a single event and supply update per batch
+    fn import_tokens(
+        &self,
+        deps: DepsMut,
+        env: &Env,
+        info: MessageInfo,
+        tokens: Vec<ImportedToken<TMetadataExtension>>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        MINTER.assert_owner(deps.storage, &info.sender)?;
+        let config = self.config();
+        match config.import_mode.may_load(deps.storage)? {
+            Some(true) => {}
+            Some(false) => return Err(Cw721ContractError::ImportClosed {}),
+            None => return Err(Cw721ContractError::ImportNotOpen {}),
+        }
+
+        let count = tokens.len() as u64;
+        let limits = config.metadata_limits(deps.storage)?;
+        for imported in tokens {
+            validate_media(&imported.extension)?;
+            check_metadata_size(&limits, imported.token_uri.as_deref(), &imported.extension)?;
+            let token = NftInfo {
+                owner: deps.api.addr_validate(&imported.owner)?,
+                approvals: vec![],
+                token_uri: imported.token_uri,
+                extension: imported.extension,
+                last_transferred_at: None,
+                metadata_hash: None,
+            };
+            if config
+                .nft_info
+                .may_load(deps.storage, &imported.token_id)?
+                .is_some()
+            {
+                return Err(Cw721ContractError::Claimed {});
+            }
+            if config.burned_tokens.has(deps.storage, &imported.token_id) {
+                if config.retire_burned_tokens(deps.storage)? {
+                    return Err(Cw721ContractError::TokenRetired {
+                        token_id: imported.token_id,
+                    });
+                }
+                config
+                    .burned_tokens
+                    .remove(deps.storage, &imported.token_id);
+            }
+            #[cfg(feature = "uri-index")]
+            check_unique_metadata(deps.storage, &config, &token)?;
+            config.save_nft(deps.storage, &imported.token_id, &token, None)?;
+            if let Some(origin_token_id) = &imported.origin_token_id {
+                save_origin_token_id(deps.storage, &config, &imported.token_id, origin_token_id)?;
+            }
+        }
+        let token_count = config.token_count(deps.storage)? + count;
+        config.token_count.save(deps.storage, &token_count)?;
+        config
+            .supply
+            .save(deps.storage, &token_count, env.block.height)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "import_tokens")
+            .add_attribute("minter", info.sender)
+            .add_attribute("count", count.to_string()))
+    }
+
+    fn close_import(
+        &self,
+        deps: DepsMut,
+        info: MessageInfo,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        MINTER.assert_owner(deps.storage, &info.sender)?;
+        let config = self.config();
+        if config.import_mode.may_load(deps.storage)? != Some(true) {
+            return Err(Cw721ContractError::ImportNotOpen {});
+        }
+        config.import_mode.save(deps.storage, &false)?;
+        Ok(Response::new()
+            .add_attribute("action", "close_import")
+            .add_attribute("sender", info.sender))
+    }
+
+    fn update_minter_ownership(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        action: Action,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let ownership =
+            MINTER.update_ownership(deps.api, deps.storage, &env.block, &info.sender, action)?;
+        Ok(Response::new()
             .add_attribute("update_minter_ownership", info.sender)
             .add_attributes(ownership.into_attributes()))
     }
 
-    /// Allows creator to update onchain metadata. For now this is a no-op.
-    fn update_metadata_extension(
+    fn update_creator_ownership(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        action: Action,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let ownership =
+            CREATOR.update_ownership(deps.api, deps.storage, &env.block, &info.sender, action)?;
+        Ok(Response::new()
+            .add_attribute("update_creator_ownership", info.sender)
+            .add_attributes(ownership.into_attributes()))
+    }
+
+    fn update_royalty_admin_ownership(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        action: Action,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let ownership = ROYALTY_ADMIN.update_ownership(
+            deps.api,
+            deps.storage,
+            &env.block,
+            &info.sender,
+            action,
+        )?;
+        Ok(Response::new()
+            .add_attribute("update_royalty_admin_ownership", info.sender)
+            .add_attributes(ownership.into_attributes()))
+    }
+
+    fn set_withdraw_address(
+        &self,
+        deps: DepsMut,
+        sender: &Addr,
+        address: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(deps.storage, sender)?;
+        deps.api.addr_validate(&address)?;
+        let config = self.config();
+        config.withdraw_address.save(deps.storage, &address)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_withdraw_address")
+            .add_attribute("address", address))
+    }
+
+    fn remove_withdraw_address(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        let address = config.withdraw_address.may_load(storage)?;
+        match address {
+            Some(address) => {
+                config.withdraw_address.remove(storage);
+                Ok(Response::new()
+                    .add_attribute("action", "remove_withdraw_address")
+                    .add_attribute("address", address))
+            }
+            None => Err(Cw721ContractError::NoWithdrawAddress {}),
+        }
+    }
+
+    fn withdraw_funds(
+        &self,
+        storage: &mut dyn Storage,
+        amount: &Coin,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let withdraw_address = self.config().withdraw_address.may_load(storage)?;
+        match withdraw_address {
+            Some(address) => {
+                let msg = BankMsg::Send {
+                    to_address: address,
+                    amount: vec![amount.clone()],
+                };
+                Ok(Response::new()
+                    .add_message(msg)
+                    .add_attribute("action", "withdraw_funds")
+                    .add_attribute("amount", amount.amount.to_string())
+                    .add_attribute("denom", amount.denom.to_string()))
+            }
+            None => Err(Cw721ContractError::NoWithdrawAddress {}),
+        }
+    }
+
+    /// Zero balances are skipped, sweeping nothing is not an error
+    fn sweep_funds(
+        &self,
+        deps: Deps,
+        env: &Env,
+        denoms: Option<Vec<String>>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let withdraw_address = self
+            .config()
+            .withdraw_address
+            .may_load(deps.storage)?
+            .ok_or(Cw721ContractError::NoWithdrawAddress {})?;
+        let balances = match denoms {
+            Some(denoms) => denoms
+                .into_iter()
+                .map(|denom| deps.querier.query_balance(&env.contract.address, denom))
+                .collect::<StdResult<Vec<Coin>>>()?,
+            None => deps.querier.query_all_balances(&env.contract.address)?,
+        };
+        let amount: Vec<Coin> = balances
+            .into_iter()
+            .filter(|coin| !coin.amount.is_zero())
+            .collect();
+        let swept = amount
+            .iter()
+            .map(Coin::to_string)
+            .collect::<Vec<String>>()
+            .join(",");
+        let mut response = Response::new()
+            .add_attribute("action", "sweep_funds")
+            .add_attribute("recipient", withdraw_address.clone())
+            .add_attribute(
+                "amount",
+                if swept.is_empty() {
+                    "none"
+                } else {
+                    swept.as_str()
+                },
+            );
+        if !amount.is_empty() {
+            response = response.add_message(BankMsg::Send {
+                to_address: withdraw_address,
+                amount,
+            });
+        }
+        Ok(response)
+    }
+
+    fn set_transfer_cooldown(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        cooldown: Option<Duration>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        let response = Response::new().add_attribute("action", "set_transfer_cooldown");
+        match cooldown {
+            Some(cooldown) => {
+                config.transfer_cooldown.save(storage, &cooldown)?;
+                Ok(response.add_attribute("cooldown", cooldown.to_string()))
+            }
+            None => {
+                config.transfer_cooldown.remove(storage);
+                Ok(response.add_attribute("cooldown", "none"))
+            }
+        }
+    }
+
+    fn set_approval_limits(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        limits: Option<ApprovalLimits>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        match limits {
+            Some(limits) => config.approval_limits.save(storage, &limits)?,
+            None => config.approval_limits.remove(storage),
+        }
+        let limits = config.approval_limits(storage)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_approval_limits")
+            .add_attribute(
+                "max_token_approvals",
+                limits.max_token_approvals.to_string(),
+            )
+            .add_attribute("max_operators", limits.max_operators.to_string()))
+    }
+
+    fn set_metadata_limits(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        limits: Option<MetadataLimits>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        match limits {
+            Some(limits) => config.metadata_limits.save(storage, &limits)?,
+            None => config.metadata_limits.remove(storage),
+        }
+        let limits = config.metadata_limits(storage)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_metadata_limits")
+            .add_attribute(
+                "max_token_uri_bytes",
+                limits.max_token_uri_bytes.to_string(),
+            )
+            .add_attribute(
+                "max_extension_bytes",
+                limits.max_extension_bytes.to_string(),
+            ))
+    }
+
+    fn set_operator_registry(
+        &self,
+        deps: DepsMut,
+        sender: &Addr,
+        registry: Option<String>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(deps.storage, sender)?;
+        let config = self.config();
+        let response = Response::new().add_attribute("action", "set_operator_registry");
+        match registry {
+            Some(registry) => {
+                let registry = deps.api.addr_validate(&registry)?;
+                config.operator_registry.save(deps.storage, &registry)?;
+                Ok(response.add_attribute("registry", registry))
+            }
+            None => {
+                config.operator_registry.remove(deps.storage);
+                Ok(response.add_attribute("registry", "none"))
+            }
+        }
+    }
+
+    fn set_protocol_fee(
+        &self,
+        deps: DepsMut,
+        bps: u16,
+        recipient: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        if bps > MAX_PROTOCOL_FEE_BPS {
+            return Err(Cw721ContractError::InvalidProtocolFee {
+                bps,
+                max: MAX_PROTOCOL_FEE_BPS,
+            });
+        }
+        let fee = ProtocolFee {
+            bps,
+            recipient: deps.api.addr_validate(&recipient)?,
+        };
+        let config = self.config();
+        config.protocol_fee.save(deps.storage, &fee)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_protocol_fee")
+            .add_attribute("bps", bps.to_string())
+            .add_attribute("recipient", recipient))
+    }
+
+    fn remove_protocol_fee(
+        &self,
+        storage: &mut dyn Storage,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        config.protocol_fee.remove(storage);
+        Ok(Response::new().add_attribute("action", "remove_protocol_fee"))
+    }
+
+    /// Overwrites reason of an existing freeze
+    fn freeze(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        reason: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        let frozen = FreezeInfo {
+            reason: reason.clone(),
+            height: env.block.height,
+        };
+        config.frozen.save(storage, &frozen)?;
+        Ok(Response::new()
+            .add_attribute("action", "freeze")
+            .add_attribute("reason", reason))
+    }
+
+    fn unfreeze(
+        &self,
+        storage: &mut dyn Storage,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        if !config.frozen.exists(storage) {
+            return Err(Cw721ContractError::NotFrozen {});
+        }
+        config.frozen.remove(storage);
+        Ok(Response::new().add_attribute("action", "unfreeze"))
+    }
+
+    fn set_retire_burned_tokens(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        retire: bool,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        config.retire_burned_tokens.save(storage, &retire)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_retire_burned_tokens")
+            .add_attribute("retire", retire.to_string()))
+    }
+
+    fn set_contract_uri(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        contract_uri: Option<String>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        let response = Response::new().add_attribute("action", "set_contract_uri");
+        match contract_uri {
+            Some(contract_uri) => {
+                if contract_uri.trim().is_empty() {
+                    return Err(Cw721ContractError::InvalidContractUri {});
+                }
+                config.contract_uri.save(storage, &contract_uri)?;
+                Ok(response.add_attribute("contract_uri", contract_uri))
+            }
+            None => {
+                config.contract_uri.remove(storage);
+                Ok(response.add_attribute("contract_uri", "none"))
+            }
+        }
+    }
+
+    /// Native denoms must not be empty and cw20 addresses must be valid, duplicates are rejected
+    fn set_royalty_payout_denoms(
+        &self,
+        deps: DepsMut,
+        sender: &Addr,
+        denoms: Option<Vec<PayoutDenom>>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        assert_owner_or_royalty_admin(deps.storage, sender, "set_royalty_payout_denoms")?;
+        if let Some(denoms) = &denoms {
+            if denoms.is_empty() {
+                return Err(Cw721ContractError::InvalidPayoutDenom {
+                    denom: "none".to_string(),
+                    reason: "at least one denom required, unset to remove".to_string(),
+                });
+            }
+            for (i, denom) in denoms.iter().enumerate() {
+                match denom {
+                    PayoutDenom::Native { denom } if denom.trim().is_empty() => {
+                        return Err(Cw721ContractError::InvalidPayoutDenom {
+                            denom: denom.clone(),
+                            reason: "native denom must not be empty".to_string(),
+                        });
+                    }
+                    PayoutDenom::Native { .. } => {}
+                    PayoutDenom::Cw20 { address } => {
+                        deps.api.addr_validate(address)?;
+                    }
+                }
+                if denoms[..i].contains(denom) {
+                    return Err(Cw721ContractError::InvalidPayoutDenom {
+                        denom: denom.as_str().to_string(),
+                        reason: "duplicate".to_string(),
+                    });
+                }
+            }
+        }
+        let config = self.config();
+        let mut collection_info = config.collection_info.load(deps.storage)?;
+        let labels = match &denoms {
+            Some(denoms) => denoms
+                .iter()
+                .map(PayoutDenom::as_str)
+                .collect::<Vec<&str>>()
+                .join(","),
+            None => "none".to_string(),
+        };
+        collection_info.royalty_payout_denoms = denoms;
+        config
+            .collection_info
+            .save(deps.storage, &collection_info)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_royalty_payout_denoms")
+            .add_attribute("denoms", labels))
+    }
+
+    fn set_origin(
+        &self,
+        deps: DepsMut,
+        sender: &Addr,
+        origin: OriginInfo,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(deps.storage, sender)?;
+        if origin.chain_id.trim().is_empty() || origin.contract.trim().is_empty() {
+            return Err(Cw721ContractError::InvalidOrigin {
+                reason: "chain id and contract must not be empty".to_string(),
+            });
+        }
+        if origin
+            .bridge_tx
+            .as_ref()
+            .is_some_and(|tx| tx.trim().is_empty())
+        {
+            return Err(Cw721ContractError::InvalidOrigin {
+                reason: "bridge tx must not be empty, unset if unknown".to_string(),
+            });
+        }
+        let config = self.config();
+        let mut collection_info = config.collection_info.load(deps.storage)?;
+        if collection_info.origin.is_some() {
+            return Err(Cw721ContractError::OriginAlreadySet {});
+        }
+        let response = Response::new()
+            .add_attribute("action", "set_origin")
+            .add_attribute("chain_id", &origin.chain_id)
+            .add_attribute("contract", &origin.contract);
+        collection_info.origin = Some(origin);
+        config
+            .collection_info
+            .save(deps.storage, &collection_info)?;
+        Ok(response)
+    }
+
+    fn set_origin_token_ids(
+        &self,
+        deps: DepsMut,
+        sender: &Addr,
+        origin_token_ids: BTreeMap<String, String>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        MINTER.assert_owner(deps.storage, sender)?;
+        let config = self.config();
+        for (token_id, origin_token_id) in &origin_token_ids {
+            // token must exist
+            config.nft_info.load(deps.storage, token_id)?;
+            save_origin_token_id(deps.storage, &config, token_id, origin_token_id)?;
+        }
+        Ok(Response::new()
+            .add_attribute("action", "set_origin_token_ids")
+            .add_attribute("count", origin_token_ids.len().to_string()))
+    }
+
+    fn set_provenance_hash(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        provenance_hash: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        if minting_started(storage, &config)? {
+            return Err(Cw721ContractError::ProvenanceHashLocked {});
+        }
+        // normalize to lower case hex, so it can be compared on reveal
+        let provenance_hash = HexBinary::from_hex(&provenance_hash)?;
+        if provenance_hash.len() != 32 {
+            return Err(
+                StdError::generic_err("Provenance hash must be a hex encoded sha256 hash").into(),
+            );
+        }
+        let provenance_hash = provenance_hash.to_hex();
+        config.provenance_hash.save(storage, &provenance_hash)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_provenance_hash")
+            .add_attribute("provenance_hash", provenance_hash))
+    }
+
+    fn set_reveal_placeholder(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        placeholder_uri: String,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        if let Some(reveal) = config.reveal.may_load(storage)? {
+            if reveal.revealed {
+                return Err(Cw721ContractError::AlreadyRevealed {});
+            }
+        }
+        let response = Response::new()
+            .add_attribute("action", "set_reveal_placeholder")
+            .add_attribute("placeholder_uri", &placeholder_uri);
+        config.reveal.save(
+            storage,
+            &RevealInfo {
+                placeholder_uri,
+                revealed: false,
+                base_uri: None,
+                shuffle: None,
+            },
+        )?;
+        Ok(response)
+    }
+
+    fn reveal(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        sender: &Addr,
+        base_uri: Option<String>,
+        token_uris: Option<BTreeMap<String, String>>,
+        provenance: Option<String>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        let mut reveal = config
+            .reveal
+            .may_load(storage)?
+            .ok_or(Cw721ContractError::NoRevealPlaceholder {})?;
+        if reveal.revealed {
+            return Err(Cw721ContractError::AlreadyRevealed {});
+        }
+        if let Some(provenance_hash) = config.provenance_hash.may_load(storage)? {
+            let matches = provenance.is_some_and(|provenance| {
+                HexBinary::from(Sha256::digest(provenance.as_bytes()).as_slice()).to_hex()
+                    == provenance_hash
+            });
+            if !matches {
+                return Err(Cw721ContractError::InvalidProvenance { provenance_hash });
+            }
+        }
+
+        // randomness is received after mint, so token ids can't be sniped for rare metadata
+        if config.randomness_source.may_load(storage)?.is_some() {
+            let randomness = config
+                .randomness
+                .may_load(storage)?
+                .ok_or(Cw721ContractError::RandomnessNotReceived {})?;
+            reveal.shuffle = Some(TokenShuffle::new(randomness, config.token_count(storage)?));
+        }
+
+        let token_uris = token_uris.unwrap_or_default();
+        let limits = config.metadata_limits(storage)?;
+        for (token_id, token_uri) in &token_uris {
+            let old_token = config.nft_info.load(storage, token_id)?;
+            check_metadata_size(&limits, Some(token_uri), &old_token.extension)?;
+            let mut token = NftInfo {
+                token_uri: Some(token_uri.clone()),
+                ..old_token.clone()
+            };
+            config.prune_expired_approvals(storage, &env.block, token_id, &mut token)?;
+            config.save_nft(storage, token_id, &token, Some(&old_token))?;
+        }
+
+        let mut response = Response::new()
+            .add_attribute("action", "reveal")
+            .add_attribute("updated_tokens", token_uris.len().to_string());
+        if let Some(base_uri) = &base_uri {
+            response = response.add_attribute("base_uri", base_uri);
+        }
+        if let Some(shuffle) = &reveal.shuffle {
+            response = response.add_attribute("shuffle_offset", shuffle.offset.to_string());
+        }
+        reveal.revealed = true;
+        reveal.base_uri = base_uri;
+        // resolved uris of updated tokens, in case of a base uri all tokens resolve to `{base_uri}{token_id}`
+        for (token_id, token_uri) in token_uris {
+            if let Some(metadata_uri) = reveal.token_uri(&token_id, Some(token_uri)) {
+                response = response
+                    .add_attribute("token_id", token_id)
+                    .add_attribute("metadata_uri", metadata_uri);
+            }
+        }
+        config.reveal.save(storage, &reveal)?;
+        Ok(response)
+    }
+
+    fn set_mint_window(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        window: Option<MintWindow>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        let response = Response::new().add_attribute("action", "set_mint_window");
+        match window {
+            Some(window) => {
+                if let (Some(start), Some(end)) = (window.start, window.end) {
+                    if end <= start {
+                        return Err(Cw721ContractError::InvalidMintWindow {});
+                    }
+                }
+                let response = response
+                    .add_attribute("start", none_or(window.start.as_ref()))
+                    .add_attribute("end", none_or(window.end.as_ref()));
+                config.mint_window.save(storage, &window)?;
+                Ok(response)
+            }
+            None => {
+                config.mint_window.remove(storage);
+                Ok(response.add_attribute("window", "none"))
+            }
+        }
+    }
+
+    fn set_mint_rate_limit(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        limit: Option<MintRateLimit>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        let response = Response::new().add_attribute("action", "set_mint_rate_limit");
+        // usage is reset, so a changed window length doesn't count mints of an old window
+        config.mint_rate_usage.remove(storage);
+        match limit {
+            Some(limit) => {
+                if limit.max_mints == 0
+                    || matches!(limit.window, Duration::Height(0) | Duration::Time(0))
+                {
+                    return Err(Cw721ContractError::InvalidMintRateLimit {});
+                }
+                config.mint_rate_limit.save(storage, &limit)?;
+                Ok(response
+                    .add_attribute("max_mints", limit.max_mints.to_string())
+                    .add_attribute("window", limit.window.to_string()))
+            }
+            None => {
+                config.mint_rate_limit.remove(storage);
+                Ok(response.add_attribute("limit", "none"))
+            }
+        }
+    }
+
+    fn set_burnable(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        burnable: bool,
+        creator_gated: Option<bool>,
+        lock: Option<bool>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        if config.burnable(storage)?.locked {
+            return Err(Cw721ContractError::BurnableLocked {});
+        }
+        let creator_gated = creator_gated.unwrap_or(false);
+        let locked = lock.unwrap_or(false);
+        config.burnable.save(
+            storage,
+            &Burnable {
+                burnable,
+                creator_gated,
+                locked,
+            },
+        )?;
+        Ok(Response::new()
+            .add_attribute("action", "set_burnable")
+            .add_attribute("burnable", burnable.to_string())
+            .add_attribute("creator_gated", creator_gated.to_string())
+            .add_attribute("locked", locked.to_string()))
+    }
+
+    fn set_burn_windows(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        windows: Vec<BurnWindow>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        // locked flags guarantee burnability, so windows can't restrict it anymore
+        if config.burnable(storage)?.locked {
+            return Err(Cw721ContractError::BurnableLocked {});
+        }
+        if windows.len() > MAX_BURN_WINDOWS || windows.iter().any(|w| w.end <= w.start) {
+            return Err(Cw721ContractError::InvalidBurnWindows {
+                max: MAX_BURN_WINDOWS,
+            });
+        }
+        if windows.is_empty() {
+            config.burn_windows.remove(storage);
+        } else {
+            config.burn_windows.save(storage, &windows)?;
+        }
+        Ok(Response::new()
+            .add_attribute("action", "set_burn_windows")
+            .add_attribute("windows", windows.len().to_string()))
+    }
+
+    fn set_unique_metadata(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        unique: UniqueMetadata,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        if cfg!(not(feature = "uri-index")) && (unique.token_uri || unique.extension) {
+            return Err(Cw721ContractError::UniqueMetadataUnsupported {});
+        }
+        let config = self.config();
+        config.unique_metadata.save(storage, &unique)?;
+        Ok(Response::new()
+            .add_attribute("action", "set_unique_metadata")
+            .add_attribute("token_uri", unique.token_uri.to_string())
+            .add_attribute("extension", unique.extension.to_string()))
+    }
+
+    fn set_persistent_operator(
+        &self,
+        deps: DepsMut,
+        sender: &Addr,
+        operator: String,
+        add: bool,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(deps.storage, sender)?;
+        let config = self.config();
+        let operator = deps.api.addr_validate(&operator)?;
+        let action = if add {
+            // holders must know about persistent operators before acquiring tokens
+            if minting_started(deps.storage, &config)? {
+                return Err(Cw721ContractError::PersistentOperatorLocked {});
+            }
+            config
+                .persistent_operators
+                .save(deps.storage, &operator, &Empty {})?;
+            "add_persistent_operator"
+        } else {
+            config.persistent_operators.remove(deps.storage, &operator);
+            "remove_persistent_operator"
+        };
+        Ok(Response::new()
+            .add_attribute("action", action)
+            .add_attribute("operator", operator))
+    }
+
+    fn set_guardian(
+        &self,
+        deps: DepsMut,
+        sender: &Addr,
+        guardian: Option<String>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(deps.storage, sender)?;
+        let config = self.config();
+        let response = Response::new().add_attribute("action", "set_guardian");
+        match guardian {
+            Some(guardian) => {
+                let guardian = deps.api.addr_validate(&guardian)?;
+                config.guardian.save(deps.storage, &guardian)?;
+                Ok(response.add_attribute("guardian", guardian))
+            }
+            None => {
+                config.guardian.remove(deps.storage);
+                Ok(response.add_attribute("guardian", "none"))
+            }
+        }
+    }
+
+    /// Replaces the royalty admin, clearing any pending transfer of the role
+    fn set_royalty_admin(
+        &self,
+        deps: DepsMut,
+        sender: &Addr,
+        admin: Option<String>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(deps.storage, sender)?;
+        let ownership = ROYALTY_ADMIN.initialize_owner(deps.storage, deps.api, admin.as_deref())?;
+        Ok(Response::new()
+            .add_attribute("action", "set_royalty_admin")
+            .add_attribute(
+                "royalty_admin",
+                ownership
+                    .owner
+                    .map_or_else(|| "none".to_string(), |admin| admin.to_string()),
+            ))
+    }
+
+    /// Guardian can pause, but never unpause, so a compromised guardian key can't do more
+    /// than halting transfers and mints until the owner steps in.
+    fn pause(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        sender: &Addr,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        let guardian = config.guardian.may_load(storage)?;
+        if guardian.as_ref() != Some(sender) {
+            CREATOR.assert_owner(storage, sender)?;
+        }
+        if config.paused.exists(storage) {
+            return Err(Cw721ContractError::Paused {});
+        }
+        let paused = PauseInfo {
+            paused_by: sender.clone(),
+            height: env.block.height,
+        };
+        config.paused.save(storage, &paused)?;
+        Ok(Response::new()
+            .add_attribute("action", "pause")
+            .add_attribute("paused_by", sender))
+    }
+
+    fn unpause(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        if !config.paused.exists(storage) {
+            return Err(Cw721ContractError::NotPaused {});
+        }
+        config.paused.remove(storage);
+        Ok(Response::new().add_attribute("action", "unpause"))
+    }
+
+    fn set_timelock(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        delay: Option<Duration>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        let response = Response::new().add_attribute("action", "set_timelock");
+        match delay {
+            Some(delay) => {
+                config.timelock.save(storage, &delay)?;
+                Ok(response.add_attribute("delay", delay.to_string()))
+            }
+            None => {
+                config.timelock.remove(storage);
+                Ok(response.add_attribute("delay", "none"))
+            }
+        }
+    }
+
+    /// Queues a config change instead of applying it, so holders get advance notice
+    fn queue_action(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        sender: &Addr,
+        delay: Duration,
+        action: &str,
+        msg: &Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        assert_owner_or_royalty_admin(storage, sender, action)?;
+        let config = self.config();
+        let id = config.queued_action_seq.may_load(storage)?.unwrap_or(0);
+        config.queued_action_seq.save(storage, &(id + 1))?;
+        let executable_at = delay.after(&env.block);
+        config.queued_actions.save(
+            storage,
+            id,
+            &QueuedAction {
+                action: action.to_string(),
+                msg: to_json_binary(msg)?,
+                proposer: sender.clone(),
+                executable_at,
+            },
+        )?;
+        config.record_audit(storage, &env.block, sender, "queue_action")?;
+        Ok(Response::new()
+            .add_attribute("action", "queue_action")
+            .add_attribute("queued_action", action)
+            .add_attribute("id", id.to_string())
+            .add_attribute("executable_at", executable_at.to_string()))
+    }
+
+    fn execute_queued(
         &self,
         deps: DepsMut,
-        _env: Env,
+        env: Env,
         info: MessageInfo,
-        _msg: TMetadataExtensionMsg,
+        id: u64,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        cw_ownable::assert_owner(deps.storage, &info.sender)?;
-        Ok(Response::new().add_attribute("action", "update_metadata_extension"))
+        let config = self.config();
+        let queued = config
+            .queued_actions
+            .may_load(deps.storage, id)?
+            .ok_or(Cw721ContractError::QueuedActionNotFound { id })?;
+        assert_owner_or_royalty_admin(deps.storage, &info.sender, &queued.action)?;
+        if !queued.executable_at.is_expired(&env.block) {
+            return Err(Cw721ContractError::Timelocked {
+                executable_at: queued.executable_at,
+            });
+        }
+        config.queued_actions.remove(deps.storage, id);
+        // the change is recorded once actually applied
+        config.record_audit(deps.storage, &env.block, &info.sender, &queued.action)?;
+        let msg: Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg> =
+            from_json(&queued.msg)?;
+        let response = self.dispatch(deps, env, info, msg)?;
+        Ok(response.add_attribute("queued_action_id", id.to_string()))
     }
 
-    fn set_withdraw_address(
+    fn cancel_queued(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        id: u64,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        let config = self.config();
+        let queued = config
+            .queued_actions
+            .may_load(storage, id)?
+            .ok_or(Cw721ContractError::QueuedActionNotFound { id })?;
+        assert_owner_or_royalty_admin(storage, sender, &queued.action)?;
+        config.queued_actions.remove(storage, id);
+        Ok(Response::new()
+            .add_attribute("action", "cancel_queued")
+            .add_attribute("queued_action", queued.action)
+            .add_attribute("id", id.to_string()))
+    }
+
+    /// Completes a two-phase migration: checks prepared state, drops legacy storage
+    /// and switches cw2 version to the migrated contract.
+    fn commit_migration(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        let pending = config
+            .pending_migration
+            .may_load(storage)?
+            .ok_or(Cw721ContractError::NoPendingMigration {})?;
+        let validation = validate_migration(storage, &config)?;
+        if !validation.errors.is_empty() {
+            return Err(Cw721ContractError::InvalidMigration {
+                errors: validation.errors.join("; "),
+            });
+        }
+        remove_legacy_storage(storage);
+        let from_version = cw2::get_contract_version(storage)?.version;
+        cw2::set_contract_version(storage, &pending.contract_name, &pending.contract_version)?;
+        config.pending_migration.remove(storage);
+        Ok(Response::new()
+            .add_attribute("action", "commit_migration")
+            .add_attribute("from_version", from_version)
+            .add_attribute("to_version", pending.contract_version))
+    }
+
+    /// Rewrites the next chunk of legacy tokens of a prepared migration,
+    /// until `ValidateMigration` no longer reports pending legacy tokens.
+    fn continue_migration(
+        &self,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        sender: &Addr,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        if !config.pending_migration.exists(storage) {
+            return Err(Cw721ContractError::NoPendingMigration {});
+        }
+        let rewritten = match config.legacy_token_cursor.may_load(storage)? {
+            Some(cursor) => migrate_legacy_token_layout(storage, api, Some(cursor))?,
+            None => 0,
+        };
+        Ok(Response::new()
+            .add_attribute("action", "continue_migration")
+            .add_attribute("legacy_tokens_rewritten", rewritten.to_string())
+            .add_attribute(
+                "legacy_tokens_pending",
+                config.legacy_token_cursor.exists(storage).to_string(),
+            ))
+    }
+
+    fn set_randomness_source(
         &self,
         deps: DepsMut,
         sender: &Addr,
-        address: String,
+        source: Option<String>,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        cw_ownable::assert_owner(deps.storage, sender)?;
-        deps.api.addr_validate(&address)?;
-        let config = Cw721Config::<
-            TMetadataExtension,
-            TCustomResponseMessage,
-            TMetadataExtensionMsg,
-        >::default();
-        config.withdraw_address.save(deps.storage, &address)?;
+        CREATOR.assert_owner(deps.storage, sender)?;
+        let config = self.config();
+        if let Some(reveal) = config.reveal.may_load(deps.storage)? {
+            if reveal.revealed {
+                return Err(Cw721ContractError::AlreadyRevealed {});
+            }
+        }
+        // otherwise owner could re-roll randomness by switching source
+        if config.randomness.exists(deps.storage) {
+            return Err(Cw721ContractError::RandomnessAlreadyReceived {});
+        }
+        // otherwise owner could switch to a source of its own and deliver chosen randomness
+        // once token ids are known
+        if minting_started(deps.storage, &config)? {
+            return Err(Cw721ContractError::RandomnessSourceLocked {});
+        }
+        let response = Response::new().add_attribute("action", "set_randomness_source");
+        match source {
+            Some(source) => {
+                let source = deps.api.addr_validate(&source)?;
+                config.randomness_source.save(deps.storage, &source)?;
+                Ok(response.add_attribute("source", source))
+            }
+            None => {
+                config.randomness_source.remove(deps.storage);
+                Ok(response.add_attribute("source", "none"))
+            }
+        }
+    }
+
+    fn request_randomness(
+        &self,
+        storage: &mut dyn Storage,
+        info: MessageInfo,
+    ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
+        CREATOR.assert_owner(storage, &info.sender)?;
+        let config = self.config();
+        let source = config
+            .randomness_source
+            .may_load(storage)?
+            .ok_or(Cw721ContractError::NoRandomnessSource {})?;
+        if config.randomness.exists(storage) {
+            return Err(Cw721ContractError::RandomnessAlreadyReceived {});
+        }
+        let msg = WasmMsg::Execute {
+            contract_addr: source.to_string(),
+            msg: to_json_binary(&RandomnessSourceMsg::GetNextRandomness {
+                job_id: RANDOMNESS_JOB_ID.to_string(),
+            })?,
+            funds: info.funds,
+        };
         Ok(Response::new()
-            .add_attribute("action", "set_withdraw_address")
-            .add_attribute("address", address))
+            .add_message(msg)
+            .add_attribute("action", "request_randomness")
+            .add_attribute("source", source))
     }
 
-    fn remove_withdraw_address(
+    fn receive_randomness(
         &self,
         storage: &mut dyn Storage,
         sender: &Addr,
+        callback: NoisCallback,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        cw_ownable::assert_owner(storage, sender)?;
-        let config = Cw721Config::<
-            TMetadataExtension,
-            TCustomResponseMessage,
-            TMetadataExtensionMsg,
-        >::default();
-        let address = config.withdraw_address.may_load(storage)?;
-        match address {
-            Some(address) => {
-                config.withdraw_address.remove(storage);
-                Ok(Response::new()
-                    .add_attribute("action", "remove_withdraw_address")
-                    .add_attribute("address", address))
+        let config = self.config();
+        let source = config
+            .randomness_source
+            .may_load(storage)?
+            .ok_or(Cw721ContractError::NoRandomnessSource {})?;
+        if *sender != source {
+            return Err(Cw721ContractError::UnauthorizedRandomnessSource {
+                sender: sender.to_string(),
+            });
+        }
+        if let Some(reveal) = config.reveal.may_load(storage)? {
+            if reveal.revealed {
+                return Err(Cw721ContractError::AlreadyRevealed {});
             }
-            None => Err(Cw721ContractError::NoWithdrawAddress {}),
         }
+        if config.randomness.exists(storage) {
+            return Err(Cw721ContractError::RandomnessAlreadyReceived {});
+        }
+        config.randomness.save(storage, &callback.randomness)?;
+        Ok(Response::new()
+            .add_attribute("action", "receive_randomness")
+            .add_attribute("job_id", callback.job_id)
+            .add_attribute("randomness", callback.randomness.to_hex()))
     }
 
-    fn withdraw_funds(
+    fn set_transfer_fee(
         &self,
         storage: &mut dyn Storage,
-        amount: &Coin,
+        sender: &Addr,
+        fee: Option<TransferFee>,
     ) -> Result<Response<TCustomResponseMessage>, Cw721ContractError> {
-        let withdraw_address = Cw721Config::<
-            TMetadataExtension,
-            TCustomResponseMessage,
-            TMetadataExtensionMsg,
-        >::default()
-        .withdraw_address
-        .may_load(storage)?;
-        match withdraw_address {
-            Some(address) => {
-                let msg = BankMsg::Send {
-                    to_address: address,
-                    amount: vec![amount.clone()],
-                };
-                Ok(Response::new()
-                    .add_message(msg)
-                    .add_attribute("action", "withdraw_funds")
-                    .add_attribute("amount", amount.amount.to_string())
-                    .add_attribute("denom", amount.denom.to_string()))
+        CREATOR.assert_owner(storage, sender)?;
+        let config = self.config();
+        let response = Response::new().add_attribute("action", "set_transfer_fee");
+        match fee {
+            Some(TransferFee::Flat(coin)) => {
+                let response = response.add_attribute("fee", coin.to_string());
+                config
+                    .transfer_fee
+                    .save(storage, &TransferFee::Flat(coin))?;
+                Ok(response)
             }
-            None => Err(Cw721ContractError::NoWithdrawAddress {}),
+            Some(TransferFee::Percentage { denom, share }) => {
+                if share > Decimal::one() {
+                    return Err(Cw721ContractError::InvalidTransferFeeShare { share });
+                }
+                let response = response.add_attribute("fee", format!("{share}{denom}"));
+                config
+                    .transfer_fee
+                    .save(storage, &TransferFee::Percentage { denom, share })?;
+                Ok(response)
+            }
+            None => {
+                config.transfer_fee.remove(storage);
+                Ok(response.add_attribute("fee", "none"))
+            }
+        }
+    }
+}
+
+// ------- helper cw721 functions -------
+fn _transfer_nft<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    deps: DepsMut,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    env: &Env,
+    info: &MessageInfo,
+    recipient: &str,
+    token_id: &str,
+    // approval permission required in case sender is an approved spender
+    permission: u8,
+) -> Result<NftInfo<TMetadataExtension>, Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    let old_token = config.nft_info.load(deps.storage, token_id)?;
+    // ensure we have permissions
+    check_can_send(
+        deps.as_ref(),
+        config,
+        env,
+        info,
+        token_id,
+        &old_token,
+        permission,
+    )?;
+    let recipient = deps.api.addr_validate(recipient)?;
+    _move_nft(deps, config, env, token_id, old_token, recipient)
+}
+
+/// Moves token to recipient without checking sender's permissions, e.g. for claimed transfer offers
+fn _move_nft<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    deps: DepsMut,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    env: &Env,
+    token_id: &str,
+    old_token: NftInfo<TMetadataExtension>,
+    recipient: Addr,
+) -> Result<NftInfo<TMetadataExtension>, Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    check_transfer_cooldown(deps.storage, config, env, token_id, &old_token)?;
+    // set owner and remove existing approvals and transfer offer
+    let mut token = old_token.clone();
+    token.owner = recipient;
+    token.approvals = vec![];
+    token.last_transferred_at = Some((&env.block).into());
+    config.save_nft(deps.storage, token_id, &token, Some(&old_token))?;
+    config.clear_approvals(deps.storage, token_id)?;
+    config.transfer_offers.remove(deps.storage, token_id);
+    Ok(token)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn _update_approvals<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    deps: DepsMut,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    env: &Env,
+    info: &MessageInfo,
+    spender: &str,
+    token_id: &str,
+    // if add == false, remove. if add == true, remove then set with this expiration
+    add: bool,
+    expires: Option<Expiration>,
+    permissions: Option<u8>,
+) -> Result<(), Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    let mut token = config.nft_info.load(deps.storage, token_id)?;
+    // ensure we have permissions
+    check_can_approve(deps.as_ref(), config, env, info, token_id, &token)?;
+
+    // drop expired approvals, so they neither count towards the cap nor bloat storage
+    let legacy_approvals = token.approvals.len();
+    config.prune_expired_approvals(deps.storage, &env.block, token_id, &mut token)?;
+
+    // remove any approval for the same spender before adding
+    let spender_addr = deps.api.addr_validate(spender)?;
+    config
+        .token_approvals
+        .remove(deps.storage, (token_id, &spender_addr));
+    token.approvals.retain(|apr| apr.spender != spender_addr);
+    // legacy approvals are stored in token, rewrite it only in case any was removed
+    if token.approvals.len() != legacy_approvals {
+        // owner is unchanged, so updated token can be passed as old token for owner lookup
+        config.save_nft(deps.storage, token_id, &token, Some(&token))?;
+    }
+
+    // only difference between approve and revoke
+    if add {
+        // reject expired data as invalid
+        let expires = expires.unwrap_or_default();
+        if expires.is_expired(&env.block) {
+            return Err(Cw721ContractError::Expired {});
+        }
+        let permissions = permissions.unwrap_or(APPROVAL_PERMISSION_ALL);
+        if permissions == 0 || permissions & !APPROVAL_PERMISSION_ALL != 0 {
+            return Err(Cw721ContractError::InvalidApprovalPermissions { permissions });
+        }
+        // enforce approval cap, any approval of same spender has been removed above
+        let max = config.approval_limits(deps.storage)?.max_token_approvals;
+        let count = token.approvals.len()
+            + config
+                .token_approvals
+                .prefix(token_id)
+                .keys_raw(deps.storage, None, None, Order::Ascending)
+                .count();
+        if count >= max as usize {
+            return Err(Cw721ContractError::TooManyApprovals {
+                token_id: token_id.to_string(),
+                max,
+            });
+        }
+        let approval = Approval {
+            spender: spender_addr.clone(),
+            expires,
+            permissions,
+        };
+        config
+            .token_approvals
+            .save(deps.storage, (token_id, &spender_addr), &approval)?;
+    }
+
+    Ok(())
+}
+
+/// Removes token and records it as burned, without checking sender's permissions
+fn _burn_nft<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &mut dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    env: &Env,
+    token_id: &str,
+    token: NftInfo<TMetadataExtension>,
+    burner: &Addr,
+) -> Result<(), Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    config.remove_nft(storage, token_id, &token)?;
+    config.decrement_tokens(storage, env.block.height)?;
+    config.burned_tokens.save(
+        storage,
+        token_id,
+        &BurnedToken {
+            owner: token.owner,
+            burner: burner.clone(),
+            height: env.block.height,
+        },
+    )?;
+    Ok(())
+}
+
+/// Saves the approval requested on mint, returns the validated spender.
+fn _mint_approval<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    deps: DepsMut,
+    env: &Env,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    token_id: &str,
+    approval: MintApproval,
+) -> Result<Addr, Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    let spender = deps.api.addr_validate(&approval.spender)?;
+    // reject expired data as invalid
+    let expires = approval.expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(Cw721ContractError::Expired {});
+    }
+    let permissions = approval.permissions.unwrap_or(APPROVAL_PERMISSION_ALL);
+    if permissions == 0 || permissions & !APPROVAL_PERMISSION_ALL != 0 {
+        return Err(Cw721ContractError::InvalidApprovalPermissions { permissions });
+    }
+    let max = config.approval_limits(deps.storage)?.max_token_approvals;
+    if max == 0 {
+        return Err(Cw721ContractError::TooManyApprovals {
+            token_id: token_id.to_string(),
+            max,
+        });
+    }
+    let approval = Approval {
+        spender: spender.clone(),
+        expires,
+        permissions,
+    };
+    config
+        .token_approvals
+        .save(deps.storage, (token_id, &spender), &approval)?;
+    Ok(spender)
+}
+
+/// Commitment of a gift claim for `CommitGiftClaim`: sha256 of the secret followed by the recipient address.
+pub fn gift_claim_commitment(secret: &[u8], recipient: &Addr) -> HexBinary {
+    let mut hasher = Sha256::new();
+    hasher.update(secret);
+    hasher.update(recipient.as_bytes());
+    HexBinary::from(hasher.finalize().as_slice())
+}
+
+/// returns true if the sender can execute approve or reject on the contract
+pub fn check_can_approve<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    deps: Deps,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    token: &NftInfo<TMetadataExtension>,
+) -> Result<(), Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    // owner can approve
+    if token.owner == info.sender {
+        return Ok(());
+    }
+    // operator can approve
+    if is_operator_of(deps, config, env, &token.owner, &info.sender, token_id)? {
+        Ok(())
+    } else {
+        Err(Cw721ContractError::Ownership(OwnershipError::NotOwner))
+    }
+}
+
+/// returns true iff operator has a non-expired grant of owner, with a filter (if any) matching the token
+fn is_operator_of<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    deps: Deps,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    env: &Env,
+    owner: &Addr,
+    operator: &Addr,
+    token_id: &str,
+) -> StdResult<bool>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    match config.operators.may_load(deps.storage, (owner, operator))? {
+        Some(expires) if !expires.is_expired(&env.block) => Ok(config
+            .operator_filters
+            .may_load(deps.storage, (owner, operator))?
+            .map_or(true, |filter| filter.matches(token_id))),
+        _ => Ok(false),
+    }
+}
+
+/// returns true iff the sender can transfer ownership of the token.
+/// In case sender is an approved spender, the approval must grant given `APPROVAL_PERMISSION_*`.
+pub fn check_can_send<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    deps: Deps,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: &str,
+    token: &NftInfo<TMetadataExtension>,
+    permission: u8,
+) -> Result<(), Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    // owner can send
+    if token.owner == info.sender {
+        return Ok(());
+    }
+
+    // any non-expired token approval with required permission can send
+    let approval = match config
+        .token_approvals
+        .may_load(deps.storage, (token_id, &info.sender))?
+    {
+        Some(approval) => Some(approval),
+        // fall back to legacy approvals stored in token
+        None => token
+            .approvals
+            .iter()
+            .find(|apr| apr.spender == info.sender)
+            .cloned(),
+    };
+    if let Some(approval) = approval {
+        if !approval.is_expired(&env.block) && approval.has_permission(permission) {
+            return Ok(());
+        }
+    }
+
+    // range approval of current owner covering the token id can transfer and send,
+    // as long as spender is still allowed by operator registry
+    if let Some(range) = config
+        .range_approvals
+        .may_load(deps.storage, (&token.owner, &info.sender))?
+    {
+        let range_permissions = APPROVAL_PERMISSION_TRANSFER | APPROVAL_PERMISSION_SEND;
+        if !range.is_expired(&env.block)
+            && range.contains(token_id)
+            && range_permissions & permission == permission
+        {
+            check_operator_allowed(deps, config, &info.sender)?;
+            return Ok(());
+        }
+    }
+
+    // persistent operators are set by creator and apply to all holders who didn't opt out,
+    // only for sending into escrow flows (e.g. staking), never for plain transfers
+    if permission == APPROVAL_PERMISSION_SEND
+        && config.is_persistent_operator(deps, &env.contract.address, &token.owner, &info.sender)?
+    {
+        return Ok(());
+    }
+
+    // operator can send, i.e. token owner gave grant to sender for control over owner's NFTs
+    if is_operator_of(deps, config, env, &token.owner, &info.sender, token_id)? {
+        Ok(())
+    } else {
+        Err(Cw721ContractError::Ownership(OwnershipError::NotOwner))
+    }
+}
+
+/// returns bank messages paying the transfer fee, if set, from funds attached by sender
+pub fn transfer_fee_msgs<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    deps: Deps,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<Vec<BankMsg>, Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    let fee = match config.transfer_fee.may_load(deps.storage)? {
+        Some(fee) => fee,
+        None => return Ok(vec![]),
+    };
+    let withdraw_address = config.withdraw_address.may_load(deps.storage)?;
+    let mut msgs = vec![];
+    let (denom, fee_amount) = match fee {
+        TransferFee::Flat(coin) => {
+            // exactly the fee must be attached
+            if info.funds != [coin.clone()] {
+                return Err(Cw721ContractError::IncorrectPayment {
+                    expected: coin,
+                    got: info.funds.clone(),
+                });
+            }
+            (coin.denom, coin.amount)
+        }
+        TransferFee::Percentage { denom, share } => {
+            let paid = must_pay(info, &denom)?;
+            let fee_amount = paid * share;
+            // remaining funds go to previous owner
+            let owner = config.nft_info.load(deps.storage, token_id)?.owner;
+            let remaining = paid - fee_amount;
+            if !remaining.is_zero() {
+                msgs.push(BankMsg::Send {
+                    to_address: owner.to_string(),
+                    amount: vec![Coin::new(remaining.u128(), &denom)],
+                });
+            }
+            (denom, fee_amount)
+        }
+    };
+    // without withdraw address fee is kept in contract
+    if let Some(withdraw_address) = withdraw_address {
+        if !fee_amount.is_zero() {
+            msgs.push(BankMsg::Send {
+                to_address: withdraw_address,
+                amount: vec![Coin::new(fee_amount.u128(), denom)],
+            });
+        }
+    }
+    Ok(msgs)
+}
+
+/// returns bank messages paying the protocol fee, if set, from funds attached to a mint.
+/// Remaining funds stay in this contract.
+pub fn protocol_fee_msgs<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    info: &MessageInfo,
+) -> Result<Vec<BankMsg>, Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    let msgs = match config.protocol_fee.may_load(storage)? {
+        Some(fee) => fee
+            .fees(&info.funds)
+            .into_iter()
+            .map(|coin| BankMsg::Send {
+                to_address: fee.recipient.to_string(),
+                amount: vec![coin],
+            })
+            .collect(),
+        None => vec![],
+    };
+    Ok(msgs)
+}
+
+/// returns an error in case royalties are enforced and operator is not allowed by operator registry
+pub fn check_operator_allowed<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    deps: Deps,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    operator: &Addr,
+) -> Result<(), Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    if let Some(registry) = config.operator_registry.may_load(deps.storage)? {
+        if !is_operator_allowed(&deps.querier, &registry, operator)? {
+            return Err(Cw721ContractError::OperatorNotAllowed {
+                operator: operator.to_string(),
+            });
         }
     }
+    Ok(())
 }
 
-// ------- helper cw721 functions -------
-fn _transfer_nft<TMetadataExtension>(
-    deps: DepsMut,
+/// returns an error in case the token has been transferred within the transfer cooldown
+pub fn check_transfer_cooldown<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
     env: &Env,
-    info: &MessageInfo,
-    recipient: &str,
     token_id: &str,
-) -> Result<NftInfo<TMetadataExtension>, Cw721ContractError>
+    token: &NftInfo<TMetadataExtension>,
+) -> Result<(), Cw721ContractError>
 where
     TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
 {
-    let config = Cw721Config::<TMetadataExtension, Empty, Empty>::default();
-    let mut token = config.nft_info.load(deps.storage, token_id)?;
-    // ensure we have permissions
-    check_can_send(deps.as_ref(), env, info, &token)?;
-    // set owner and remove existing approvals
-    token.owner = deps.api.addr_validate(recipient)?;
-    token.approvals = vec![];
-    config.nft_info.save(deps.storage, token_id, &token)?;
-    Ok(token)
+    let cooldown = config.transfer_cooldown.may_load(storage)?;
+    if let (Some(cooldown), Some(last_transferred_at)) = (cooldown, &token.last_transferred_at) {
+        let ends = last_transferred_at.cooldown_ends(cooldown)?;
+        if !ends.is_expired(&env.block) {
+            return Err(Cw721ContractError::TransferCooldown {
+                token_id: token_id.to_string(),
+                ends,
+            });
+        }
+    }
+    Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
-fn _update_approvals<TMetadataExtension>(
-    deps: DepsMut,
+/// returns an error in case burn windows are set and none of them is open
+pub fn check_burn_window<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
     env: &Env,
-    info: &MessageInfo,
-    spender: &str,
-    token_id: &str,
-    // if add == false, remove. if add == true, remove then set with this expiration
-    add: bool,
-    expires: Option<Expiration>,
-) -> Result<NftInfo<TMetadataExtension>, Cw721ContractError>
+) -> Result<(), Cw721ContractError>
 where
     TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
 {
-    let config = Cw721Config::<TMetadataExtension, Empty, Empty>::default();
-    let mut token = config.nft_info.load(deps.storage, token_id)?;
-    // ensure we have permissions
-    check_can_approve(deps.as_ref(), env, info, &token)?;
-
-    // update the approval list (remove any for the same spender before adding)
-    let spender_addr = deps.api.addr_validate(spender)?;
-    token.approvals.retain(|apr| apr.spender != spender_addr);
-
-    // only difference between approve and revoke
-    if add {
-        // reject expired data as invalid
-        let expires = expires.unwrap_or_default();
-        if expires.is_expired(&env.block) {
-            return Err(Cw721ContractError::Expired {});
-        }
-        let approval = Approval {
-            spender: spender_addr,
-            expires,
-        };
-        token.approvals.push(approval);
+    let windows = config.burn_windows.may_load(storage)?.unwrap_or_default();
+    let now = env.block.time;
+    if windows.is_empty() || windows.iter().any(|w| w.contains(now)) {
+        return Ok(());
     }
-
-    config.nft_info.save(deps.storage, token_id, &token)?;
-
-    Ok(token)
+    let next_start = windows
+        .iter()
+        .map(|w| w.start)
+        .filter(|start| *start > now)
+        .min();
+    Err(Cw721ContractError::BurnWindowClosed { next_start })
 }
 
-/// returns true if the sender can execute approve or reject on the contract
-pub fn check_can_approve<TMetadataExtension>(
-    deps: Deps,
+/// Counts a mint towards the mint rate limit, returns an error if the current window is exhausted
+pub fn check_mint_rate_limit<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &mut dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
     env: &Env,
-    info: &MessageInfo,
-    token: &NftInfo<TMetadataExtension>,
 ) -> Result<(), Cw721ContractError>
 where
     TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
 {
-    // owner can approve
-    if token.owner == info.sender {
+    let Some(limit) = config.mint_rate_limit.may_load(storage)? else {
         return Ok(());
+    };
+    let window = limit.window_index(&env.block);
+    let minted = config
+        .mint_rate_usage
+        .may_load(storage)?
+        .filter(|usage| usage.window == window)
+        .map_or(0, |usage| usage.minted);
+    if minted >= limit.max_mints {
+        return Err(Cw721ContractError::MintRateLimited {
+            max_mints: limit.max_mints,
+            window: limit.window,
+        });
     }
-    // operator can approve
-    let config = Cw721Config::<TMetadataExtension, Empty, Empty>::default();
-    let op = config
-        .operators
-        .may_load(deps.storage, (&token.owner, &info.sender))?;
-    match op {
-        Some(ex) => {
-            if ex.is_expired(&env.block) {
-                Err(Cw721ContractError::Ownership(OwnershipError::NotOwner))
-            } else {
-                Ok(())
-            }
-        }
-        None => Err(Cw721ContractError::Ownership(OwnershipError::NotOwner)),
+    config.mint_rate_usage.save(
+        storage,
+        &MintRateUsage {
+            window,
+            minted: minted + 1,
+        },
+    )?;
+    Ok(())
+}
+
+/// Rejects token_uri or extension exceeding the collection's `MetadataLimits`
+pub fn check_metadata_size<TMetadataExtension>(
+    limits: &MetadataLimits,
+    token_uri: Option<&str>,
+    extension: &TMetadataExtension,
+) -> Result<(), Cw721ContractError>
+where
+    TMetadataExtension: Serialize,
+{
+    let token_uri_size = token_uri.map(str::len).unwrap_or_default();
+    if token_uri_size > limits.max_token_uri_bytes as usize {
+        return Err(Cw721ContractError::MetadataTooLarge {
+            field: "token_uri".to_string(),
+            size: token_uri_size,
+            max: limits.max_token_uri_bytes,
+        });
+    }
+    let extension_size = to_json_vec(extension)?.len();
+    if extension_size > limits.max_extension_bytes as usize {
+        return Err(Cw721ContractError::MetadataTooLarge {
+            field: "extension".to_string(),
+            size: extension_size,
+            max: limits.max_extension_bytes,
+        });
     }
+    Ok(())
 }
 
-/// returns true iff the sender can transfer ownership of the token
-pub fn check_can_send<TMetadataExtension>(
-    deps: Deps,
-    env: &Env,
-    info: &MessageInfo,
-    token: &NftInfo<TMetadataExtension>,
-) -> Result<(), Cw721ContractError> {
-    // owner can send
-    if token.owner == info.sender {
+/// Validates media assets in case extension is `Metadata`, other extensions are ignored.
+pub fn validate_media<TMetadataExtension>(
+    extension: &TMetadataExtension,
+) -> Result<(), Cw721ContractError>
+where
+    TMetadataExtension: Serialize,
+{
+    let Some(metadata) = as_metadata(extension)? else {
         return Ok(());
+    };
+    let media = metadata.media.unwrap_or_default();
+    if media.len() > MAX_MEDIA_ASSETS {
+        return Err(Cw721ContractError::TooManyMediaAssets {
+            max: MAX_MEDIA_ASSETS,
+        });
+    }
+    for asset in media {
+        let reason = if asset.uri.trim().is_empty() {
+            "uri must not be empty"
+        } else if !matches!(
+            asset.mime_type.split_once('/'),
+            Some((kind, subtype)) if !kind.is_empty() && !subtype.is_empty()
+        ) {
+            "mime type must be of form type/subtype"
+        } else {
+            continue;
+        };
+        return Err(Cw721ContractError::InvalidMediaAsset {
+            uri: asset.uri,
+            reason: reason.to_string(),
+        });
     }
+    Ok(())
+}
 
-    // any non-expired token approval can send
-    if token
-        .approvals
-        .iter()
-        .any(|apr| apr.spender == info.sender && !apr.is_expired(&env.block))
+/// Saves token id on the origin chain, which can't be changed once set
+pub fn save_origin_token_id<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &mut dyn Storage,
+    config: &Cw721Config<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    token_id: &'a str,
+    origin_token_id: &str,
+) -> Result<(), Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    if origin_token_id.trim().is_empty() {
+        return Err(Cw721ContractError::InvalidOrigin {
+            reason: format!("origin token id of {token_id} must not be empty"),
+        });
+    }
+    if config.origin_token_ids.has(storage, token_id) {
+        return Err(Cw721ContractError::OriginTokenIdAlreadySet {
+            token_id: token_id.to_string(),
+        });
+    }
+    config
+        .origin_token_ids
+        .save(storage, token_id, &origin_token_id.to_string())?;
+    Ok(())
+}
+
+/// Whether any token has been minted. Burned tokens count as minted, so burning all tokens doesn't
+/// unlock settings fixed before mint.
+fn minting_started<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+) -> StdResult<bool>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    Ok(config.token_count(storage)? > 0
+        || config
+            .burned_tokens
+            .keys_raw(storage, None, None, Order::Ascending)
+            .next()
+            .is_some())
+}
+
+/// Transfers, mints and burns, including offers to transfer or burn, are halted while the
+/// collection is frozen or paused. Cancelling an offer is still possible.
+pub fn is_transfer_or_mint<TMetadataExtension, TMetadataExtensionMsg>(
+    msg: &Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>,
+) -> bool {
+    matches!(
+        msg,
+        Cw721ExecuteMsg::TransferNft { .. }
+            | Cw721ExecuteMsg::SendNft { .. }
+            | Cw721ExecuteMsg::OfferTransfer { .. }
+            | Cw721ExecuteMsg::ClaimTransfer { .. }
+            | Cw721ExecuteMsg::LockGift { .. }
+            | Cw721ExecuteMsg::ClaimGift { .. }
+            | Cw721ExecuteMsg::ReclaimGift { .. }
+            | Cw721ExecuteMsg::Mint { .. }
+            | Cw721ExecuteMsg::MintAndSend { .. }
+            | Cw721ExecuteMsg::ImportTokens { .. }
+            | Cw721ExecuteMsg::Burn { .. }
+            | Cw721ExecuteMsg::OfferBurn { .. }
+            | Cw721ExecuteMsg::ConfirmBurn { .. }
+    )
+}
+
+/// Name of a privileged config change, which is queued instead of applied while a timelock is set.
+/// Ownership changes, emergency pause and one-off actions like `Reveal` are not timelocked.
+pub fn timelocked_action<TMetadataExtension, TMetadataExtensionMsg>(
+    msg: &Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>,
+) -> Option<&'static str> {
+    match msg {
+        Cw721ExecuteMsg::SetWithdrawAddress { .. }
+        | Cw721ExecuteMsg::RemoveWithdrawAddress {}
+        | Cw721ExecuteMsg::SetTransferCooldown { .. }
+        | Cw721ExecuteMsg::SetApprovalLimits { .. }
+        | Cw721ExecuteMsg::SetMetadataLimits { .. }
+        | Cw721ExecuteMsg::SetOperatorRegistry { .. }
+        | Cw721ExecuteMsg::SetTransferFee { .. }
+        | Cw721ExecuteMsg::SetRetireBurnedTokens { .. }
+        | Cw721ExecuteMsg::SetContractUri { .. }
+        | Cw721ExecuteMsg::SetRoyaltyPayoutDenoms { .. }
+        | Cw721ExecuteMsg::SetRevealPlaceholder { .. }
+        | Cw721ExecuteMsg::SetRandomnessSource { .. }
+        | Cw721ExecuteMsg::SetMintWindow { .. }
+        | Cw721ExecuteMsg::SetMintRateLimit { .. }
+        | Cw721ExecuteMsg::SetBurnable { .. }
+        | Cw721ExecuteMsg::SetBurnWindows { .. }
+        | Cw721ExecuteMsg::SetUniqueMetadata { .. }
+        | Cw721ExecuteMsg::AddPersistentOperator { .. }
+        | Cw721ExecuteMsg::RemovePersistentOperator { .. }
+        | Cw721ExecuteMsg::SetGuardian { .. }
+        | Cw721ExecuteMsg::SetRoyaltyAdmin { .. }
+        | Cw721ExecuteMsg::SetTimelock { .. } => audit_action(msg),
+        _ => None,
+    }
+}
+
+/// Owner can perform all privileged actions, royalty admin only royalty config changes
+fn assert_owner_or_royalty_admin(
+    storage: &dyn Storage,
+    sender: &Addr,
+    action: &str,
+) -> Result<(), Cw721ContractError> {
+    if action == "set_royalty_payout_denoms" && ROYALTY_ADMIN.assert_owner(storage, sender).is_ok()
     {
         return Ok(());
     }
+    CREATOR.assert_owner(storage, sender)?;
+    Ok(())
+}
+
+/// Name of the typed event emitted for `msg`, see `add_cw721_event`. Part of the event schema,
+/// so names must not be changed.
+pub fn event_name<TMetadataExtension, TMetadataExtensionMsg>(
+    msg: &Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>,
+) -> &'static str {
+    match msg {
+        Cw721ExecuteMsg::UpdateOwnership(_) | Cw721ExecuteMsg::UpdateMinterOwnership(_) => {
+            "update_minter_ownership"
+        }
+        Cw721ExecuteMsg::UpdateCreatorOwnership(_) => "update_creator_ownership",
+        Cw721ExecuteMsg::UpdateRoyaltyAdminOwnership(_) => "update_royalty_admin_ownership",
+        Cw721ExecuteMsg::TransferNft { .. } => "transfer",
+        Cw721ExecuteMsg::SendNft { .. } => "send",
+        Cw721ExecuteMsg::OfferTransfer { .. } => "offer_transfer",
+        Cw721ExecuteMsg::ClaimTransfer { .. } => "claim_transfer",
+        Cw721ExecuteMsg::CancelTransferOffer { .. } => "cancel_transfer_offer",
+        Cw721ExecuteMsg::LockGift { .. } => "lock_gift",
+        Cw721ExecuteMsg::CommitGiftClaim { .. } => "commit_gift_claim",
+        Cw721ExecuteMsg::ClaimGift { .. } => "claim_gift",
+        Cw721ExecuteMsg::ReclaimGift { .. } => "reclaim_gift",
+        Cw721ExecuteMsg::Approve { .. } => "approve",
+        Cw721ExecuteMsg::ApproveBurner { .. } => "approve_burner",
+        Cw721ExecuteMsg::ApproveBatch { .. } => "approve_batch",
+        Cw721ExecuteMsg::Revoke { .. } => "revoke",
+        Cw721ExecuteMsg::ApproveRange { .. } => "approve_range",
+        Cw721ExecuteMsg::RevokeRange { .. } => "revoke_range",
+        Cw721ExecuteMsg::ApproveAll { .. } => "approve_all",
+        Cw721ExecuteMsg::RevokeAll { .. } => "revoke_all",
+        Cw721ExecuteMsg::RevokeAllOperators { .. } => "revoke_all_operators",
+        Cw721ExecuteMsg::OptOutPersistentOperator { .. } => "opt_out_persistent_operator",
+        Cw721ExecuteMsg::OptInPersistentOperator { .. } => "opt_in_persistent_operator",
+        Cw721ExecuteMsg::Mint { .. } => "mint",
+        Cw721ExecuteMsg::MintAndSend { .. } => "mint_and_send",
+        Cw721ExecuteMsg::ImportTokens { .. } => "import_tokens",
+        Cw721ExecuteMsg::SetOrigin { .. } => "set_origin",
+        Cw721ExecuteMsg::SetOriginTokenIds { .. } => "set_origin_token_ids",
+        Cw721ExecuteMsg::CloseImport {} => "close_import",
+        Cw721ExecuteMsg::Burn { .. } => "burn",
+        Cw721ExecuteMsg::Extension { .. } => "extension",
+        Cw721ExecuteMsg::SetWithdrawAddress { .. } => "set_withdraw_address",
+        Cw721ExecuteMsg::RemoveWithdrawAddress { .. } => "remove_withdraw_address",
+        Cw721ExecuteMsg::WithdrawFunds { .. } => "withdraw_funds",
+        Cw721ExecuteMsg::SweepFunds { .. } => "sweep_funds",
+        Cw721ExecuteMsg::SetTransferCooldown { .. } => "set_transfer_cooldown",
+        Cw721ExecuteMsg::SetApprovalLimits { .. } => "set_approval_limits",
+        Cw721ExecuteMsg::SetMetadataLimits { .. } => "set_metadata_limits",
+        Cw721ExecuteMsg::SetOperatorRegistry { .. } => "set_operator_registry",
+        Cw721ExecuteMsg::SetTransferFee { .. } => "set_transfer_fee",
+        Cw721ExecuteMsg::SetRetireBurnedTokens { .. } => "set_retire_burned_tokens",
+        Cw721ExecuteMsg::SetContractUri { .. } => "set_contract_uri",
+        Cw721ExecuteMsg::SetRoyaltyPayoutDenoms { .. } => "set_royalty_payout_denoms",
+        Cw721ExecuteMsg::SetProvenanceHash { .. } => "set_provenance_hash",
+        Cw721ExecuteMsg::SetRevealPlaceholder { .. } => "set_reveal_placeholder",
+        Cw721ExecuteMsg::Reveal { .. } => "reveal",
+        Cw721ExecuteMsg::SetRandomnessSource { .. } => "set_randomness_source",
+        Cw721ExecuteMsg::RequestRandomness { .. } => "request_randomness",
+        Cw721ExecuteMsg::NoisReceive { .. } => "nois_receive",
+        Cw721ExecuteMsg::SetMintWindow { .. } => "set_mint_window",
+        Cw721ExecuteMsg::SetMintRateLimit { .. } => "set_mint_rate_limit",
+        Cw721ExecuteMsg::SetBurnable { .. } => "set_burnable",
+        Cw721ExecuteMsg::SetBurnWindows { .. } => "set_burn_windows",
+        Cw721ExecuteMsg::OfferBurn { .. } => "offer_burn",
+        Cw721ExecuteMsg::ConfirmBurn { .. } => "confirm_burn",
+        Cw721ExecuteMsg::SetUniqueMetadata { .. } => "set_unique_metadata",
+        Cw721ExecuteMsg::CommitMigration { .. } => "commit_migration",
+        Cw721ExecuteMsg::ContinueMigration { .. } => "continue_migration",
+        Cw721ExecuteMsg::AddPersistentOperator { .. } => "add_persistent_operator",
+        Cw721ExecuteMsg::RemovePersistentOperator { .. } => "remove_persistent_operator",
+        Cw721ExecuteMsg::SetGuardian { .. } => "set_guardian",
+        Cw721ExecuteMsg::SetRoyaltyAdmin { .. } => "set_royalty_admin",
+        Cw721ExecuteMsg::Pause { .. } => "pause",
+        Cw721ExecuteMsg::Unpause { .. } => "unpause",
+        Cw721ExecuteMsg::SetTimelock { .. } => "set_timelock",
+        Cw721ExecuteMsg::ExecuteQueued { .. } => "execute_queued",
+        Cw721ExecuteMsg::CancelQueued { .. } => "cancel_queued",
+    }
+}
+
+/// Adds a `cw721_{name}` event (`wasm-cw721_{name}` in tx logs) carrying the response attributes
+/// except `action`, so indexers can tell cw721 actions apart in multi-contract txs.
+pub fn add_cw721_event<T>(response: Response<T>, name: &str) -> Response<T> {
+    let attributes = response
+        .attributes
+        .iter()
+        .filter(|attr| attr.key != "action")
+        .cloned()
+        .collect::<Vec<_>>();
+    response.add_event(Event::new(format!("cw721_{name}")).add_attributes(attributes))
+}
 
-    // operator can send
-    let config = Cw721Config::<Empty, Empty, Empty>::default();
-    let op = config
-        .operators
-        // has token owner approved/gave grant to sender for full control over owner's NFTs?
-        .may_load(deps.storage, (&token.owner, &info.sender))?;
+/// Name of a privileged (owner-only) action recorded in the audit log, `None` for other messages.
+/// Mints are not recorded, since they are tracked by token history.
+pub fn audit_action<TMetadataExtension, TMetadataExtensionMsg>(
+    msg: &Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>,
+) -> Option<&'static str> {
+    let action = match msg {
+        Cw721ExecuteMsg::UpdateOwnership(Action::TransferOwnership { .. })
+        | Cw721ExecuteMsg::UpdateMinterOwnership(Action::TransferOwnership { .. }) => {
+            "transfer_ownership"
+        }
+        Cw721ExecuteMsg::UpdateOwnership(Action::AcceptOwnership)
+        | Cw721ExecuteMsg::UpdateMinterOwnership(Action::AcceptOwnership) => "accept_ownership",
+        Cw721ExecuteMsg::UpdateOwnership(Action::RenounceOwnership)
+        | Cw721ExecuteMsg::UpdateMinterOwnership(Action::RenounceOwnership) => "renounce_ownership",
+        Cw721ExecuteMsg::UpdateCreatorOwnership(Action::TransferOwnership { .. }) => {
+            "transfer_creator_ownership"
+        }
+        Cw721ExecuteMsg::UpdateCreatorOwnership(Action::AcceptOwnership) => {
+            "accept_creator_ownership"
+        }
+        Cw721ExecuteMsg::UpdateCreatorOwnership(Action::RenounceOwnership) => {
+            "renounce_creator_ownership"
+        }
+        Cw721ExecuteMsg::UpdateRoyaltyAdminOwnership(Action::TransferOwnership { .. }) => {
+            "transfer_royalty_admin"
+        }
+        Cw721ExecuteMsg::UpdateRoyaltyAdminOwnership(Action::AcceptOwnership) => {
+            "accept_royalty_admin"
+        }
+        Cw721ExecuteMsg::UpdateRoyaltyAdminOwnership(Action::RenounceOwnership) => {
+            "renounce_royalty_admin"
+        }
+        Cw721ExecuteMsg::SetWithdrawAddress { .. } => "set_withdraw_address",
+        Cw721ExecuteMsg::RemoveWithdrawAddress {} => "remove_withdraw_address",
+        Cw721ExecuteMsg::SetTransferCooldown { .. } => "set_transfer_cooldown",
+        Cw721ExecuteMsg::SetApprovalLimits { .. } => "set_approval_limits",
+        Cw721ExecuteMsg::SetMetadataLimits { .. } => "set_metadata_limits",
+        Cw721ExecuteMsg::SetOperatorRegistry { .. } => "set_operator_registry",
+        Cw721ExecuteMsg::SetTransferFee { .. } => "set_transfer_fee",
+        Cw721ExecuteMsg::SetRetireBurnedTokens { .. } => "set_retire_burned_tokens",
+        Cw721ExecuteMsg::SetContractUri { .. } => "set_contract_uri",
+        Cw721ExecuteMsg::SetRoyaltyPayoutDenoms { .. } => "set_royalty_payout_denoms",
+        Cw721ExecuteMsg::SetProvenanceHash { .. } => "set_provenance_hash",
+        Cw721ExecuteMsg::SetRevealPlaceholder { .. } => "set_reveal_placeholder",
+        Cw721ExecuteMsg::Reveal { .. } => "reveal",
+        Cw721ExecuteMsg::SetRandomnessSource { .. } => "set_randomness_source",
+        Cw721ExecuteMsg::RequestRandomness {} => "request_randomness",
+        Cw721ExecuteMsg::SetMintWindow { .. } => "set_mint_window",
+        Cw721ExecuteMsg::SetMintRateLimit { .. } => "set_mint_rate_limit",
+        Cw721ExecuteMsg::SetBurnable { .. } => "set_burnable",
+        Cw721ExecuteMsg::SetBurnWindows { .. } => "set_burn_windows",
+        Cw721ExecuteMsg::ConfirmBurn { .. } => "confirm_burn",
+        Cw721ExecuteMsg::SetUniqueMetadata { .. } => "set_unique_metadata",
+        Cw721ExecuteMsg::CommitMigration {} => "commit_migration",
+        Cw721ExecuteMsg::ContinueMigration {} => "continue_migration",
+        Cw721ExecuteMsg::AddPersistentOperator { .. } => "add_persistent_operator",
+        Cw721ExecuteMsg::RemovePersistentOperator { .. } => "remove_persistent_operator",
+        Cw721ExecuteMsg::SetGuardian { .. } => "set_guardian",
+        Cw721ExecuteMsg::SetRoyaltyAdmin { .. } => "set_royalty_admin",
+        Cw721ExecuteMsg::Pause {} => "pause",
+        Cw721ExecuteMsg::Unpause {} => "unpause",
+        Cw721ExecuteMsg::SetTimelock { .. } => "set_timelock",
+        Cw721ExecuteMsg::CancelQueued { .. } => "cancel_queued",
+        Cw721ExecuteMsg::CloseImport {} => "close_import",
+        Cw721ExecuteMsg::SetOrigin { .. } => "set_origin",
+        _ => return None,
+    };
+    Some(action)
+}
 
-    match op {
-        Some(ex) => {
-            if ex.is_expired(&env.block) {
-                Err(Cw721ContractError::Ownership(OwnershipError::NotOwner))
-            } else {
-                Ok(())
+/// Rejects token if `UniqueMetadata` is set and its token_uri or extension is already indexed
+#[cfg(feature = "uri-index")]
+pub fn check_unique_metadata<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    token: &NftInfo<TMetadataExtension>,
+) -> Result<(), Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    let unique = config
+        .unique_metadata
+        .may_load(storage)?
+        .unwrap_or_default();
+    if unique.token_uri {
+        if let Some(token_uri) = &token.token_uri {
+            let hash = uri_hash(token_uri);
+            if config
+                .uri_tokens
+                .prefix(hash.as_slice())
+                .keys_raw(storage, None, None, Order::Ascending)
+                .next()
+                .is_some()
+            {
+                return Err(Cw721ContractError::DuplicateTokenUri {
+                    token_uri: token_uri.clone(),
+                });
             }
         }
-        None => Err(Cw721ContractError::Ownership(OwnershipError::NotOwner)),
     }
+    if unique.extension {
+        if let Some(hash) = extension_hash(&token.extension)? {
+            if config
+                .extension_tokens
+                .prefix(hash.as_slice())
+                .keys_raw(storage, None, None, Order::Ascending)
+                .next()
+                .is_some()
+            {
+                return Err(Cw721ContractError::DuplicateExtension {});
+            }
+        }
+    }
+    Ok(())
 }
 
 // ------- migrate -------
-pub fn migrate_version(
+/// Max legacy tokens rewritten per migration or `ContinueMigration` call.
+pub const LEGACY_TOKEN_MIGRATION_LIMIT: u32 = 100;
+
+/// Contract names (as stored in cw2) which can be migrated to cw721-base.
+/// Derived contracts pass their own set to `migrate`.
+pub const BASE_COMPATIBLE_CONTRACT_NAMES: &[&str] =
+    &["crates.io:cw721-base", "crates.io:cw721-metadata-onchain"];
+
+/// Prevents migrating an unrelated contract (e.g. wrong wasm) onto a NFT address,
+/// unless `allow_unknown` is set. Besides `contract_name` itself, only contracts
+/// in `compatible_contract_names` are accepted.
+pub fn assert_migration_compatible(
+    storage: &dyn Storage,
+    msg: &Cw721MigrateMsg,
+    contract_name: &str,
+    compatible_contract_names: &[&str],
+) -> Result<(), Cw721ContractError> {
+    match msg {
+        Cw721MigrateMsg::WithUpdate { allow_unknown, .. } => {
+            if allow_unknown.unwrap_or(false) {
+                return Ok(());
+            }
+        }
+    }
+    let stored = cw2::get_contract_version(storage)?.contract;
+    if stored == contract_name || compatible_contract_names.contains(&stored.as_str()) {
+        return Ok(());
+    }
+    Err(Cw721ContractError::IncompatibleContract {
+        stored,
+        contract_name: contract_name.to_string(),
+    })
+}
+
+pub fn migrate_version<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
     storage: &mut dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
     contradct_name: &str,
     contract_version: &str,
-    response: Response,
-) -> StdResult<Response> {
+    response: Response<TCustomResponseMessage>,
+) -> StdResult<Response<TCustomResponseMessage>>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
     let response = response
         .add_attribute("from_version", cw2::get_contract_version(storage)?.version)
         .add_attribute("to_version", contract_version);
 
     // update contract version
     cw2::set_contract_version(storage, contradct_name, contract_version)?;
+    // a one-shot migration supersedes any prepared migration
+    config.pending_migration.remove(storage);
     Ok(response)
 }
 
-pub fn migrate_minter(
+/// First phase of a two-phase migration: new structures are written alongside legacy ones,
+/// while cw2 version and legacy storage are only updated by `CommitMigration`.
+/// Until then, the contract is locked and `ValidateMigration` can be used to check the result.
+pub fn prepare_migration<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &mut dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    env: &Env,
+    contract_name: &str,
+    contract_version: &str,
+    response: Response<TCustomResponseMessage>,
+) -> StdResult<Response<TCustomResponseMessage>>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    let pending = PendingMigration {
+        contract_name: contract_name.to_string(),
+        contract_version: contract_version.to_string(),
+        token_count: config.token_count.may_load(storage)?.unwrap_or_default(),
+        height: env.block.height,
+    };
+    config.pending_migration.save(storage, &pending)?;
+    Ok(response
+        .add_attribute("migration", "prepared")
+        .add_attribute("from_version", cw2::get_contract_version(storage)?.version)
+        .add_attribute("to_version", contract_version))
+}
+
+/// Removes pre-v0.19 minter and collection info, once migrated to current storage.
+pub fn remove_legacy_storage(storage: &mut dyn Storage) {
+    Item::<Addr>::new("minter").remove(storage);
+    Item::<cw721_016::ContractInfoResponse>::new("nft_info").remove(storage);
+    storage.remove(&legacy_singleton_key("minter"));
+    storage.remove(&legacy_singleton_key("nft_info"));
+    storage.remove(&legacy_singleton_key("num_tokens"));
+}
+
+/// Up to v0.5 `cosmwasm-storage` singletons were used, stored under a length-prefixed
+/// namespace instead of the plain key used by `cw_storage_plus::Item`.
+pub fn legacy_singleton_key(namespace: &str) -> Vec<u8> {
+    let mut key = (namespace.len() as u16).to_be_bytes().to_vec();
+    key.extend_from_slice(namespace.as_bytes());
+    key
+}
+
+fn load_legacy_singleton<T: DeserializeOwned>(
+    storage: &dyn Storage,
+    namespace: &str,
+) -> StdResult<Option<T>> {
+    storage
+        .get(&legacy_singleton_key(namespace))
+        .map(from_json)
+        .transpose()
+}
+
+/// Up to v0.5 addresses were stored canonical, serialized as base64.
+fn legacy_addr(api: &dyn Api, addr: &str) -> StdResult<Addr> {
+    match api.addr_validate(addr) {
+        Ok(addr) => Ok(addr),
+        Err(_) => api.addr_humanize(&CanonicalAddr::from(Binary::from_base64(addr)?)),
+    }
+}
+
+/// Versions before v0.16, i.e. `0.x` with x < 16. Unparsable versions are rejected.
+fn is_pre_v016(storage: &dyn Storage) -> StdResult<bool> {
+    let version = cw2::get_contract_version(storage)?.version;
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+    match (parts.next().flatten(), parts.next().flatten()) {
+        (Some(major), Some(minor)) => Ok(major == 0 && minor < 16),
+        _ => Err(StdError::generic_err(format!(
+            "Cannot parse contract version {version}"
+        ))),
+    }
+}
+
+pub fn migrate_minter<T>(
     storage: &mut dyn Storage,
     api: &dyn Api,
     _env: &Env,
     msg: &Cw721MigrateMsg,
-    response: Response,
-) -> StdResult<Response> {
+    response: Response<T>,
+) -> StdResult<Response<T>> {
     match msg {
         Cw721MigrateMsg::WithUpdate { minter, .. } => {
             if let Some(minter) = minter {
@@ -607,6 +3626,119 @@ pub fn migrate_minter(
     Ok(response)
 }
 
+/// Sets creator from migrate msg. Otherwise, in case creator is not present (before it had its own
+/// storage, it shared cw_ownable's record with minter), the current minter is copied as creator.
+pub fn migrate_creator<T>(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    _env: &Env,
+    msg: &Cw721MigrateMsg,
+    response: Response<T>,
+) -> StdResult<Response<T>> {
+    match msg {
+        Cw721MigrateMsg::WithUpdate {
+            creator: Some(creator),
+            ..
+        } => {
+            CREATOR.initialize_owner(storage, api, Some(creator.as_str()))?;
+            Ok(response.add_attribute("collection_creator", creator))
+        }
+        _ => {
+            if CREATOR.item.exists(storage) {
+                return Ok(response);
+            }
+            let owner = MINTER.item.may_load(storage)?.and_then(|o| o.owner);
+            CREATOR.initialize_owner(storage, api, owner.as_ref().map(Addr::as_str))?;
+            Ok(response.add_attribute("collection_creator", none_or(owner.as_ref())))
+        }
+    }
+}
+
+pub fn migrate_withdraw_address<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &mut dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    api: &dyn Api,
+    _env: &Env,
+    msg: &Cw721MigrateMsg,
+    response: Response<TCustomResponseMessage>,
+) -> StdResult<Response<TCustomResponseMessage>>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    match msg {
+        Cw721MigrateMsg::WithUpdate {
+            withdraw_address, ..
+        } => {
+            if let Some(address) = withdraw_address {
+                api.addr_validate(address)?;
+                config.withdraw_address.save(storage, address)?;
+                return Ok(response.add_attribute("withdraw_address", address));
+            }
+        }
+    }
+    Ok(response)
+}
+
+/// Opens genesis import mode, which can't be reopened once closed
+pub fn migrate_import_mode<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &mut dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    _env: &Env,
+    msg: &Cw721MigrateMsg,
+    response: Response<TCustomResponseMessage>,
+) -> Result<Response<TCustomResponseMessage>, Cw721ContractError>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    match msg {
+        Cw721MigrateMsg::WithUpdate {
+            import_mode: Some(true),
+            ..
+        } => {
+            if config.import_mode.may_load(storage)? == Some(false) {
+                return Err(Cw721ContractError::ImportClosed {});
+            }
+            config.import_mode.save(storage, &true)?;
+            Ok(response.add_attribute("import_mode", "open"))
+        }
+        _ => Ok(response),
+    }
+}
+
+/// Updates name and/or symbol of collection info, must run after legacy collection info migration
+pub fn migrate_collection_info<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &mut dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+    _env: &Env,
+    msg: &Cw721MigrateMsg,
+    response: Response<TCustomResponseMessage>,
+) -> StdResult<Response<TCustomResponseMessage>>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    match msg {
+        Cw721MigrateMsg::WithUpdate { name, symbol, .. } => {
+            if name.is_none() && symbol.is_none() {
+                return Ok(response);
+            }
+            let mut collection_info = config.collection_info.load(storage)?;
+            if let Some(name) = name {
+                collection_info.name = name.clone();
+            }
+            if let Some(symbol) = symbol {
+                collection_info.symbol = symbol.clone();
+            }
+            config.collection_info.save(storage, &collection_info)?;
+            Ok(response
+                .add_attribute("collection_name", collection_info.name)
+                .add_attribute("collection_symbol", collection_info.symbol))
+        }
+    }
+}
+
 /// Migrates only in case ownership is not present
 /// !!! Important note here: !!!
 /// - creator owns the contract and can update collection info
@@ -615,65 +3747,202 @@ pub fn migrate_minter(
 /// Before v0.19.0 there were confusing naming conventions:
 /// - v0.17.0: minter was replaced by cw_ownable, as a result minter is owner
 /// - v0.16.0 and below: minter was stored in dedicated `minter` store (so NOT using cw_ownable at all)
-pub fn migrate_legacy_minter_and_creator(
+/// - v0.5.0 and below: minter was stored as canonical address in a `minter` singleton
+pub fn migrate_legacy_minter_and_creator<T>(
     storage: &mut dyn Storage,
     api: &dyn Api,
     _env: &Env,
     _msg: &Cw721MigrateMsg,
-    response: Response,
-) -> Result<Response, Cw721ContractError> {
+    response: Response<T>,
+) -> Result<Response<T>, Cw721ContractError> {
     let minter = MINTER.item.may_load(storage)?;
     // no migration in case minter is already set
     if minter.is_some() {
-        return Ok(response);
+        return Ok(response.add_attribute("minter_source", "current"));
     }
     // in v0.17/18 cw_ownable::OWNERSHIP was used for minter, now it is used for creator
     let ownership_previously_used_as_minter = OwnershipStore::new("collection_minter")
         .item
         .may_load(storage)?;
-    let creator_and_minter = match ownership_previously_used_as_minter {
+    let (creator_and_minter, minter_source) = match ownership_previously_used_as_minter {
         // v0.18 migration
         Some(ownership) => {
             // owner is used for both: creator and minter
-            // since it is already set for creator, we only need to migrate minter
+            // creator is copied from minter afterwards, see `migrate_creator`
             let owner = ownership.owner.map(|a| a.to_string());
             MINTER.initialize_owner(storage, api, owner.as_deref())?;
-            owner
+            (owner, "legacy_ownership")
         }
         // v0.17 and older migration
         None => {
-            let legacy_minter_store: Item<Addr> = Item::new("minter");
-            let legacy_minter = legacy_minter_store.load(storage)?;
+            let legacy_minter_store: Item<String> = Item::new("minter");
+            let (legacy_minter, minter_source) = match legacy_minter_store.may_load(storage)? {
+                Some(legacy_minter) => (legacy_minter, "legacy_minter"),
+                // v0.5 and older migration
+                None => (
+                    load_legacy_singleton::<String>(storage, "minter")?
+                        .ok_or_else(|| StdError::not_found("minter"))?,
+                    "legacy_singleton_minter",
+                ),
+            };
+            let legacy_minter = legacy_addr(api, &legacy_minter)?;
             MINTER.initialize_owner(storage, api, Some(legacy_minter.as_str()))?;
-            Some(legacy_minter.to_string())
+            (Some(legacy_minter.to_string()), minter_source)
         }
     };
-    Ok(response.add_attribute("creator_and_minter", none_or(creator_and_minter.as_ref())))
+    Ok(response
+        .add_attribute("minter_source", minter_source)
+        .add_attribute("creator_and_minter", none_or(creator_and_minter.as_ref())))
 }
 
 /// Migrates only in case collection_info is not present
-pub fn migrate_legacy_collection_info(
+pub fn migrate_legacy_collection_info<T>(
     storage: &mut dyn Storage,
     _env: &Env,
     _msg: &Cw721MigrateMsg,
-    response: Response,
-) -> Result<Response, Cw721ContractError> {
+    response: Response<T>,
+) -> Result<Response<T>, Cw721ContractError> {
     let contract = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
     match contract.collection_info.may_load(storage)? {
-        Some(_) => Ok(response),
+        Some(_) => Ok(response.add_attribute("collection_source", "current")),
         None => {
             // contract info is legacy collection info
             let legacy_collection_info_store: Item<cw721_016::ContractInfoResponse> =
                 Item::new("nft_info");
-            let legacy_collection_info = legacy_collection_info_store.load(storage)?;
+            let (legacy_collection_info, collection_source) =
+                match legacy_collection_info_store.may_load(storage)? {
+                    Some(info) => (info, "legacy_contract_info"),
+                    // v0.5 and older migration
+                    None => (
+                        load_legacy_singleton(storage, "nft_info")?
+                            .ok_or_else(|| StdError::not_found("nft_info"))?,
+                        "legacy_singleton_contract_info",
+                    ),
+                };
             let collection_info = CollectionInfo {
                 name: legacy_collection_info.name.clone(),
                 symbol: legacy_collection_info.symbol.clone(),
+                royalty_payout_denoms: None,
+                origin: None,
             };
             contract.collection_info.save(storage, &collection_info)?;
             Ok(response
+                .add_attribute("collection_source", collection_source)
                 .add_attribute("migrated collection name", legacy_collection_info.name)
                 .add_attribute("migrated collection symbol", legacy_collection_info.symbol))
         }
     }
 }
+
+/// Tokens are stored using the same keys since v0.16, so there is nothing to migrate,
+/// but token count is reported for operators to verify migration.
+/// Tokens of versions before v0.16 are rewritten in case of a pre-v0.9 layout, see `migrate_legacy_token_layout`.
+/// In case more than `LEGACY_TOKEN_MIGRATION_LIMIT` tokens are stored, the migration is only prepared
+/// and remaining tokens are rewritten by `ContinueMigration`.
+/// Supply history starts with current token count, in case it isn't tracked yet.
+pub fn migrate_legacy_tokens<T>(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    _msg: &Cw721MigrateMsg,
+    response: Response<T>,
+) -> StdResult<Response<T>> {
+    let contract = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let mut response = response;
+    if contract.token_count.may_load(storage)?.is_none() {
+        // v0.5 and older migration
+        if let Some(count) = load_legacy_singleton::<u64>(storage, "num_tokens")? {
+            contract.token_count.save(storage, &count)?;
+        }
+    }
+    if is_pre_v016(storage)? {
+        // continue where a previous (prepared) migration stopped
+        let cursor = contract.legacy_token_cursor.may_load(storage)?;
+        let rewritten = migrate_legacy_token_layout(storage, api, cursor)?;
+        response = response
+            .add_attribute("legacy_tokens_rewritten", rewritten.to_string())
+            .add_attribute(
+                "legacy_tokens_pending",
+                contract.legacy_token_cursor.exists(storage).to_string(),
+            );
+    }
+    let token_count = contract.token_count.may_load(storage)?.unwrap_or_default();
+    if contract.supply.may_load(storage)?.is_none() {
+        contract
+            .supply
+            .save(storage, &token_count, env.block.height)?;
+    }
+    Ok(response.add_attribute("tokens_migrated", token_count.to_string()))
+}
+
+/// Rewrites tokens stored in a pre-v0.9 layout (see `LegacyTokenInfo`): canonical owner and spender
+/// addresses are humanized and `name`, `description` and `image` are moved into the `Metadata` extension.
+/// Tokens are written with `save_nft`, so owner index is built for them as well.
+/// At most `LEGACY_TOKEN_MIGRATION_LIMIT` tokens after `start_after` are processed, in case more are left
+/// the last processed token id is stored as `legacy_token_cursor`.
+/// NOTE: operators stored with canonical addresses are not migrated and must be approved again.
+fn migrate_legacy_token_layout(
+    storage: &mut dyn Storage,
+    api: &dyn Api,
+    start_after: Option<String>,
+) -> StdResult<u64> {
+    let contract = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let legacy_tokens: Map<&str, LegacyTokenInfo> = Map::new("tokens");
+    let limit = LEGACY_TOKEN_MIGRATION_LIMIT as usize;
+    let mut tokens = legacy_tokens
+        .range(
+            storage,
+            start_after.as_deref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+    if tokens.len() > limit {
+        tokens.truncate(limit);
+        let (cursor, _) = &tokens[limit - 1];
+        contract.legacy_token_cursor.save(storage, cursor)?;
+    } else {
+        contract.legacy_token_cursor.remove(storage);
+    }
+    let mut rewritten = 0;
+    for (token_id, legacy) in tokens {
+        let legacy_metadata =
+            legacy.name.is_some() || legacy.description.is_some() || legacy.image.is_some();
+        let canonical = api.addr_validate(&legacy.owner).is_err()
+            || legacy
+                .approvals
+                .iter()
+                .any(|approval| api.addr_validate(&approval.spender).is_err());
+        if !legacy_metadata && !canonical {
+            continue;
+        }
+        let approvals = legacy
+            .approvals
+            .iter()
+            .map(|approval| {
+                Ok(Approval {
+                    spender: legacy_addr(api, &approval.spender)?,
+                    expires: approval.expires,
+                    permissions: APPROVAL_PERMISSION_ALL,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        let token = NftInfo {
+            owner: legacy_addr(api, &legacy.owner)?,
+            approvals,
+            token_uri: legacy.token_uri,
+            extension: legacy_metadata.then(|| Metadata {
+                name: legacy.name,
+                description: legacy.description,
+                image: legacy.image,
+                ..Metadata::default()
+            }),
+            last_transferred_at: None,
+            metadata_hash: None,
+        };
+        contract.save_nft(storage, &token_id, &token, None)?;
+        rewritten += 1;
+    }
+    Ok(rewritten)
+}
@@ -1,15 +1,20 @@
 use std::marker::PhantomData;
 
+use crate::error::Cw721ReceiveError;
 use crate::msg::{
-    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, NftInfoResponse, NumTokensResponse,
-    OperatorsResponse, OwnerOfResponse, TokensResponse,
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, LastTransferredAtResponse,
+    NftInfoResponse, NumTokensResponse, OperatorsResponse, OwnerOfResponse,
+    ResolvedTokenUriResponse, SupportedInterfacesResponse, TokenStatusResponse, TokensResponse,
+    VerifyOwnershipProofResponse,
 };
 use crate::msg::{Cw721ExecuteMsg, Cw721QueryMsg};
-use crate::state::CollectionInfo;
+use crate::receiver::Cw721ReceiveMsg;
+use crate::state::{CollectionInfo, TransferredAt};
 use crate::Approval;
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    to_json_binary, Addr, CosmosMsg, CustomMsg, QuerierWrapper, StdResult, WasmMsg, WasmQuery,
+    from_json, to_json_binary, Addr, Binary, Coin, CosmosMsg, CustomMsg, QuerierWrapper, StdError,
+    StdResult, WasmMsg, WasmQuery,
 };
 use serde::de::DeserializeOwned;
 use serde::Serialize;
@@ -34,12 +39,21 @@ where
     pub fn call(
         &self,
         msg: Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>,
+    ) -> StdResult<CosmosMsg> {
+        self.call_with_funds(msg, vec![])
+    }
+
+    /// Same as `call`, attaching native funds, e.g. for mint prices or transfer fees
+    pub fn call_with_funds(
+        &self,
+        msg: Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>,
+        funds: Vec<Coin>,
     ) -> StdResult<CosmosMsg> {
         let msg = to_json_binary(&msg)?;
         Ok(WasmMsg::Execute {
             contract_addr: self.addr().into(),
             msg,
-            funds: vec![],
+            funds,
         }
         .into())
     }
@@ -97,6 +111,8 @@ where
         let req = Cw721QueryMsg::Approvals {
             token_id: token_id.into(),
             include_expired,
+            spender_prefix: None,
+            order_by_expiration: None,
         };
         let res: ApprovalsResponse = self.query(querier, req)?;
         Ok(res)
@@ -115,6 +131,7 @@ where
             include_expired: Some(include_expired),
             start_after,
             limit,
+            cursor: None,
         };
         let res: OperatorsResponse = self.query(querier, req)?;
         Ok(res.operators)
@@ -144,6 +161,20 @@ where
         self.query(querier, req)
     }
 
+    /// With metadata extension, name and description resolved for given locale
+    pub fn localized_nft_info<T: Into<String>, U: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: T,
+        locale: T,
+    ) -> StdResult<NftInfoResponse<U>> {
+        let req = Cw721QueryMsg::LocalizedNftInfo {
+            token_id: token_id.into(),
+            locale: locale.into(),
+        };
+        self.query(querier, req)
+    }
+
     /// With metadata extension
     pub fn all_nft_info<T: Into<String>, U: DeserializeOwned>(
         &self,
@@ -154,10 +185,64 @@ where
         let req = Cw721QueryMsg::AllNftInfo {
             token_id: token_id.into(),
             include_expired: Some(include_expired),
+            include_collection: None,
+        };
+        self.query(querier, req)
+    }
+
+    pub fn last_transferred_at<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: T,
+    ) -> StdResult<Option<TransferredAt>> {
+        let req = Cw721QueryMsg::LastTransferredAt {
+            token_id: token_id.into(),
+        };
+        let res: LastTransferredAtResponse = self.query(querier, req)?;
+        Ok(res.last_transferred_at)
+    }
+
+    pub fn resolved_token_uri<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: T,
+    ) -> StdResult<Option<String>> {
+        let req = Cw721QueryMsg::ResolvedTokenUri {
+            token_id: token_id.into(),
+        };
+        let res: ResolvedTokenUriResponse = self.query(querier, req)?;
+        Ok(res.token_uri)
+    }
+
+    pub fn token_status<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: T,
+    ) -> StdResult<TokenStatusResponse> {
+        let req = Cw721QueryMsg::TokenStatus {
+            token_id: token_id.into(),
         };
         self.query(querier, req)
     }
 
+    pub fn verify_ownership_proof<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: T,
+        pubkey: Binary,
+        signature: Binary,
+        message: String,
+    ) -> StdResult<bool> {
+        let req = Cw721QueryMsg::VerifyOwnershipProof {
+            token_id: token_id.into(),
+            pubkey,
+            signature,
+            message,
+        };
+        let res: VerifyOwnershipProofResponse = self.query(querier, req)?;
+        Ok(res.verified)
+    }
+
     /// With enumerable extension
     pub fn tokens<T: Into<String>>(
         &self,
@@ -170,6 +255,7 @@ where
             owner: owner.into(),
             start_after,
             limit,
+            cursor: None,
         };
         self.query(querier, req)
     }
@@ -181,12 +267,134 @@ where
         start_after: Option<String>,
         limit: Option<u32>,
     ) -> StdResult<TokensResponse> {
-        let req = Cw721QueryMsg::AllTokens { start_after, limit };
+        let req = Cw721QueryMsg::AllTokens {
+            start_after,
+            limit,
+            cursor: None,
+        };
+        self.query(querier, req)
+    }
+
+    pub fn contract_uri(&self, querier: &QuerierWrapper) -> StdResult<Option<String>> {
+        let req = Cw721QueryMsg::ContractUri {};
+        self.query(querier, req)
+    }
+
+    pub fn provenance_hash(&self, querier: &QuerierWrapper) -> StdResult<Option<String>> {
+        let req = Cw721QueryMsg::ProvenanceHash {};
+        self.query(querier, req)
+    }
+
+    pub fn is_burned<T: Into<String>>(
+        &self,
+        querier: &QuerierWrapper,
+        token_id: T,
+    ) -> StdResult<bool> {
+        let req = Cw721QueryMsg::IsBurned {
+            token_id: token_id.into(),
+        };
         self.query(querier, req)
     }
 
+    pub fn supported_interfaces(&self, querier: &QuerierWrapper) -> StdResult<Vec<String>> {
+        let req = Cw721QueryMsg::SupportedInterfaces {};
+        let res: SupportedInterfacesResponse = self.query(querier, req)?;
+        Ok(res.interfaces)
+    }
+
+    /// returns true if the contract lists given interface (see `INTERFACE_*` constants) as supported
+    pub fn supports_interface(&self, querier: &QuerierWrapper, interface: &str) -> bool {
+        self.supported_interfaces(querier)
+            .map(|interfaces| interfaces.iter().any(|i| i == interface))
+            .unwrap_or(false)
+    }
+
+    /// Queries cw2 contract info and supported interfaces. Interfaces are empty in case
+    /// contract doesn't support `SupportedInterfaces` query (e.g. before v0.19).
+    pub fn capabilities(&self, querier: &QuerierWrapper) -> StdResult<CapabilityReport> {
+        let info = cw2::query_contract_info(querier, self.addr())?;
+        let interfaces = self.supported_interfaces(querier).unwrap_or_default();
+        Ok(CapabilityReport {
+            contract: info.contract,
+            version: info.version,
+            interfaces,
+        })
+    }
+
+    /// Same as `capabilities`, but fails in case contract version is lower than given version.
+    pub fn assert_at_least(
+        &self,
+        querier: &QuerierWrapper,
+        version: &str,
+    ) -> StdResult<CapabilityReport> {
+        let report = self.capabilities(querier)?;
+        report.assert_at_least(version)?;
+        Ok(report)
+    }
+
     /// returns true if the contract supports the enumerable extension
     pub fn has_enumerable(&self, querier: &QuerierWrapper) -> bool {
         self.tokens(querier, self.addr(), None, Some(1)).is_ok()
     }
 }
+
+/// Version and capabilities of a remote cw721 contract.
+#[cw_serde]
+pub struct CapabilityReport {
+    /// cw2 contract name
+    pub contract: String,
+    /// cw2 contract version
+    pub version: String,
+    /// see `INTERFACE_*` constants
+    pub interfaces: Vec<String>,
+}
+
+impl CapabilityReport {
+    pub fn supports(&self, interface: &str) -> bool {
+        self.interfaces.iter().any(|i| i == interface)
+    }
+
+    /// Compares `major.minor.patch` of both versions, pre-release and build suffixes are ignored.
+    pub fn is_at_least(&self, version: &str) -> StdResult<bool> {
+        Ok(parse_version(&self.version)? >= parse_version(version)?)
+    }
+
+    pub fn assert_at_least(&self, version: &str) -> StdResult<()> {
+        if !self.is_at_least(version)? {
+            return Err(StdError::generic_err(format!(
+                "{} version {} is lower than required {}",
+                self.contract, self.version, version
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn parse_version(version: &str) -> StdResult<(u64, u64, u64)> {
+    let core = version
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default();
+    let mut parts = core.split('.').map(|part| part.parse::<u64>());
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => Ok((major, minor, patch)),
+        _ => Err(StdError::generic_err(format!("Invalid version: {version}"))),
+    }
+}
+
+/// Parses the `msg` of a `ReceiveNft` into the receiving contract's own message type.
+/// On failure, the error names the token and sender, which plain `from_json` doesn't.
+pub fn parse_receive<T: DeserializeOwned>(msg: &Cw721ReceiveMsg) -> Result<T, Cw721ReceiveError> {
+    if msg.msg.is_empty() {
+        return Err(Cw721ReceiveError::EmptyMsg {
+            token_id: msg.token_id.clone(),
+            sender: msg.sender.clone(),
+        });
+    }
+    from_json(&msg.msg).map_err(|err| Cw721ReceiveError::InvalidMsg {
+        token_id: msg.token_id.clone(),
+        sender: msg.sender.clone(),
+        reason: err.to_string(),
+    })
+}
@@ -0,0 +1,101 @@
+//! Execute message shapes of older cw721-base versions, translated to `Cw721ExecuteMsg`, so frontends
+//! and bots of migrated collections keep working. Enabled by the `legacy-msg` feature.
+//!
+//! Only shapes differing on the wire need translation:
+//! - `Mint(MintMsg{..})` of v0.9 to v0.16 serializes the same as the current `Mint{..}`.
+//! - `UpdateOwnership` of v0.17 and v0.18 is kept as an alias of `UpdateMinterOwnership`, the creator role
+//!   is transferred with `UpdateCreatorOwnership`.
+//! - before v0.9, `Mint` carried `name`, `description` and `image` instead of `token_uri` and `extension`,
+//!   and `SendNft` had an optional `msg`.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{from_json, to_json_binary, Binary, StdResult};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::msg::Cw721ExecuteMsg;
+use crate::state::Metadata;
+
+/// Accepts current and legacy execute messages, current shapes take precedence.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[serde(untagged)]
+pub enum Cw721CompatExecuteMsg<TMetadataExtension, TMetadataExtensionMsg> {
+    Current(Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>),
+    Legacy(Cw721LegacyExecuteMsg),
+}
+
+impl<TMetadataExtension, TMetadataExtensionMsg>
+    Cw721CompatExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>
+where
+    TMetadataExtension: DeserializeOwned,
+{
+    pub fn into_current(
+        self,
+    ) -> StdResult<Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>> {
+        match self {
+            Cw721CompatExecuteMsg::Current(msg) => Ok(msg),
+            Cw721CompatExecuteMsg::Legacy(msg) => msg.into_current(),
+        }
+    }
+}
+
+/// Execute messages of cw721-base before v0.9, which are rejected by `Cw721ExecuteMsg`.
+#[cw_serde]
+pub enum Cw721LegacyExecuteMsg {
+    Mint(LegacyMintMsg),
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Option<Binary>,
+    },
+}
+
+#[cw_serde]
+pub struct LegacyMintMsg {
+    pub token_id: String,
+    pub owner: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub image: Option<String>,
+}
+
+impl Cw721LegacyExecuteMsg {
+    /// Legacy metadata is converted to the extension via `Metadata` JSON, so it is kept for `Metadata`
+    /// extensions and ignored by extensions without these fields, like `Empty`.
+    pub fn into_current<TMetadataExtension, TMetadataExtensionMsg>(
+        self,
+    ) -> StdResult<Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg>>
+    where
+        TMetadataExtension: DeserializeOwned,
+    {
+        match self {
+            Cw721LegacyExecuteMsg::Mint(msg) => {
+                let metadata = Metadata {
+                    name: Some(msg.name),
+                    description: msg.description,
+                    image: msg.image,
+                    ..Metadata::default()
+                };
+                Ok(Cw721ExecuteMsg::Mint {
+                    token_id: msg.token_id,
+                    owner: msg.owner,
+                    token_uri: None,
+                    extension: from_json(to_json_binary(&metadata)?)?,
+                    approval: None,
+                    metadata_hash: None,
+                })
+            }
+            Cw721LegacyExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg,
+            } => Ok(Cw721ExecuteMsg::SendNft {
+                contract,
+                token_id,
+                msg: msg.unwrap_or_default(),
+            }),
+        }
+    }
+}
@@ -1,12 +1,16 @@
 pub mod error;
 pub mod execute;
 pub mod helpers;
+#[cfg(feature = "legacy-msg")]
+pub mod legacy;
 pub mod msg;
 pub mod query;
 pub mod receiver;
+pub mod registry;
+pub mod schema;
 pub mod state;
 
-pub use cw_utils::Expiration;
+pub use cw_utils::{Duration, Expiration};
 pub use state::Approval;
 
 #[cfg(test)]
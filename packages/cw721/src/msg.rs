@@ -1,13 +1,24 @@
+use std::collections::BTreeMap;
+
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Binary, Coin};
+use cosmwasm_std::{Addr, Binary, Coin, Decimal, HexBinary, Timestamp};
 use cw_ownable::{Action, Ownership};
-use cw_utils::Expiration;
+use cw_utils::{Duration, Expiration};
 
-use crate::state::CollectionInfo;
+use crate::state::{
+    ApprovalLimits, BurnOffer, BurnWindow, Burnable, CollectionInfo, Cw721Types, FreezeInfo,
+    GiftLock, MetadataLimits, MintRateLimit, MintWindow, OperatorFilter, OriginInfo, PauseInfo,
+    PayoutDenom, PendingMigration, ProtocolFee, RangeApproval, RevealInfo, TransferFee,
+    TransferOffer, TransferredAt, UniqueMetadata,
+};
 use crate::Approval;
 
 #[cw_serde]
 pub enum Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg> {
+    UpdateMinterOwnership(Action),
+    /// Transfer, accept or renounce the creator role (collection owner), minter is not affected.
+    UpdateCreatorOwnership(Action),
+    /// Deprecated, use `UpdateMinterOwnership` instead. Kept as an alias for existing clients.
     UpdateOwnership(Action),
 
     /// Transfer is a base message to move a token to another account without triggering actions
@@ -22,28 +33,117 @@ pub enum Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg> {
         token_id: String,
         msg: Binary,
     },
+    /// Offers the token to `recipient`, who receives it via `ClaimTransfer`, e.g. so high-value
+    /// tokens aren't lost to a wrong or dead address. Replaces a pending offer of the token.
+    /// Requires same permission as `TransferNft`. If expiration is set, the offer has a time/height limit
+    OfferTransfer {
+        recipient: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Receives a token offered to the sender, offer must be from current holder.
+    /// Transfer fee, if set, is paid by the sender.
+    ClaimTransfer {
+        token_id: String,
+    },
+    /// Withdraws a pending transfer offer. Requires same permission as `TransferNft`.
+    CancelTransferOffer {
+        token_id: String,
+    },
+    /// Escrows the token in the contract until anyone presents the secret hashed to `hash` via
+    /// `ClaimGift`, e.g. to gift a token to someone without a wallet yet. `expires` must be set,
+    /// afterwards the owner can `ReclaimGift`. Requires same permission as `TransferNft`.
+    LockGift {
+        token_id: String,
+        /// Sha256 of the secret
+        hash: HexBinary,
+        expires: Expiration,
+    },
+    /// Commits to a gift claim, binding the recipient to the secret without revealing it.
+    /// Must be done at least one block before `ClaimGift`, so a claim can't be front-run.
+    CommitGiftClaim {
+        token_id: String,
+        /// Sha256 of the secret followed by the recipient address, see `gift_claim_commitment`
+        commitment: HexBinary,
+    },
+    /// Receives an escrowed gift by presenting its secret before the gift expires.
+    /// Secret and recipient must have been committed via `CommitGiftClaim` in an earlier block.
+    ClaimGift {
+        token_id: String,
+        secret: Binary,
+        /// Receives the token, defaults to the sender
+        recipient: Option<String>,
+    },
+    /// Returns an expired, unclaimed gift to the owner who locked it. Only that owner can call this.
+    ReclaimGift {
+        token_id: String,
+    },
     /// Allows operator to transfer / send the token from the owner's account.
     /// If expiration is set, then this allowance has a time/height limit
     Approve {
         spender: String,
         token_id: String,
         expires: Option<Expiration>,
+        /// Bitmask of `APPROVAL_PERMISSION_*` flags, unset grants all permissions
+        permissions: Option<u8>,
+    },
+    /// Allows spender to only burn the token from the owner's account, e.g. game contracts
+    /// burning consumable items. Same as `Approve` with `APPROVAL_PERMISSION_BURN`.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveBurner {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    /// Allows spender to transfer / send each of the given tokens from the owner's account,
+    /// e.g. listing a set of tokens on a marketplace without granting `ApproveAll`.
+    /// If expiration is set, then this allowance has a time/height limit
+    ApproveBatch {
+        spender: String,
+        token_ids: Vec<String>,
+        expires: Option<Expiration>,
     },
     /// Remove previously granted Approval
     Revoke {
         spender: String,
         token_id: String,
     },
+    /// Allows spender to transfer / send all tokens of the sender with numeric ids in
+    /// `start_id..=end_id`, stored as a single record instead of one approval per token.
+    /// Replaces any range approval of the spender. If expiration is set, then this allowance has a time/height limit
+    ApproveRange {
+        spender: String,
+        start_id: u64,
+        end_id: u64,
+        expires: Option<Expiration>,
+    },
+    /// Remove previously granted ApproveRange
+    RevokeRange {
+        spender: String,
+    },
     /// Allows operator to transfer / send any token from the owner's account.
     /// If expiration is set, then this allowance has a time/height limit
     ApproveAll {
         operator: String,
         expires: Option<Expiration>,
+        /// Restricts the grant to matching tokens, e.g. editions but not 1/1s of a collection
+        filter: Option<OperatorFilter>,
     },
     /// Remove previously granted ApproveAll permission
     RevokeAll {
         operator: String,
     },
+    /// Remove all ApproveAll permissions granted by the sender
+    RevokeAllOperators {},
+    /// Sender opts out of a persistent operator for all their tokens
+    OptOutPersistentOperator {
+        operator: String,
+    },
+    /// Sender reverts an opt out of a persistent operator. Contracts holding tokens, e.g. escrows,
+    /// must opt in before a persistent operator applies to them.
+    OptInPersistentOperator {
+        operator: String,
+    },
 
     /// Mint a new NFT, can only be called by the contract minter
     Mint {
@@ -57,7 +157,31 @@ pub enum Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg> {
         token_uri: Option<String>,
         /// Any custom extension used by this contract
         extension: TMetadataExtension,
+        /// Optional approval granted on the new token, e.g. to list it on a marketplace
+        approval: Option<MintApproval>,
+        /// Optional sha256 of the metadata JSON at `token_uri`, anchoring its content on-chain
+        metadata_hash: Option<HexBinary>,
+    },
+
+    /// Mint a new NFT owned by `contract` and trigger an action on it, same as `SendNft`.
+    /// Can only be called by the contract minter
+    MintAndSend {
+        token_id: String,
+        contract: String,
+        token_uri: Option<String>,
+        extension: TMetadataExtension,
+        msg: Binary,
+        metadata_hash: Option<HexBinary>,
+    },
+
+    /// Bulk mint while genesis import mode is open, e.g. for chain forks or migrations from other
+    /// chains. Skips mint window and protocol fee and emits a single event per batch.
+    /// Can only be called by the contract minter
+    ImportTokens {
+        tokens: Vec<ImportedToken<TMetadataExtension>>,
     },
+    /// Permanently closes genesis import mode. Only owner can call this.
+    CloseImport {},
 
     /// Burn an NFT the sender has access to
     Burn {
@@ -80,6 +204,217 @@ pub enum Cw721ExecuteMsg<TMetadataExtension, TMetadataExtensionMsg> {
     WithdrawFunds {
         amount: Coin,
     },
+    /// Sends the contract's whole balance of given native denoms, or of all denoms if unset, to the
+    /// withdraw address, e.g. dust and airdrops. Anyone can call this, same as `WithdrawFunds`.
+    SweepFunds {
+        denoms: Option<Vec<String>>,
+    },
+
+    /// Sets minimum blocks or time between two transfers of the same token.
+    /// Unset removes the cooldown. Only owner can call this.
+    SetTransferCooldown {
+        cooldown: Option<Duration>,
+    },
+
+    /// Sets max approvals per token and operators per granter.
+    /// Unset restores default limits. Only owner can call this.
+    SetApprovalLimits {
+        limits: Option<ApprovalLimits>,
+    },
+
+    /// Sets max sizes of token_uri and extension, checked on mint, import and reveal.
+    /// Unset restores default limits. Only owner can call this.
+    SetMetadataLimits {
+        limits: Option<MetadataLimits>,
+    },
+
+    /// Enforces royalties by restricting `ApproveAll` operators and `SendNft` recipients
+    /// to addresses allowed by given registry contract (see `registry` module).
+    /// Unset disables enforcement. Only owner can call this.
+    SetOperatorRegistry {
+        registry: Option<String>,
+    },
+
+    /// Sets fee to be attached on `TransferNft` and `SendNft`, forwarded to withdraw address.
+    /// Unset removes the fee. Only owner can call this.
+    SetTransferFee {
+        fee: Option<TransferFee>,
+    },
+
+    /// If true, minting a previously burned token id is rejected. Only owner can call this.
+    SetRetireBurnedTokens {
+        retire: bool,
+    },
+
+    /// Sets URI of collection-level metadata, see OpenSea's `contractURI` convention.
+    /// Unset removes it. Only owner can call this.
+    SetContractUri {
+        contract_uri: Option<String>,
+    },
+
+    /// Declares denoms royalties are accepted in, stored in collection info. Unset removes them.
+    /// Owner or royalty admin can call this.
+    SetRoyaltyPayoutDenoms {
+        denoms: Option<Vec<PayoutDenom>>,
+    },
+
+    /// Records the origin of a collection bridged or migrated from another chain, stored in
+    /// collection info. Can only be set once, by the owner.
+    SetOrigin {
+        origin: OriginInfo,
+    },
+    /// Records token ids on the origin chain, by token id. Each can only be set once, by the minter.
+    SetOriginTokenIds {
+        origin_token_ids: BTreeMap<String, String>,
+    },
+
+    /// Appoints or removes the royalty admin, which can only update royalty config, e.g. a finance
+    /// multisig. Only owner can call this.
+    SetRoyaltyAdmin {
+        admin: Option<String>,
+    },
+    /// Transfer, accept or renounce the royalty admin role. Only royalty admin can call this.
+    UpdateRoyaltyAdminOwnership(Action),
+
+    /// Commits to the final metadata with a hex encoded sha256 `provenance_hash`, checked on `Reveal`.
+    /// Only owner can call this, and only before the first mint.
+    SetProvenanceHash {
+        provenance_hash: String,
+    },
+
+    /// Hides token uris behind `placeholder_uri` until `Reveal`. Only owner can call this before reveal.
+    SetRevealPlaceholder {
+        placeholder_uri: String,
+    },
+
+    /// Reveals token uris: `{base_uri}{token_id}` if `base_uri` is set, otherwise stored uris,
+    /// updated by the optional `token_id -> token_uri` mapping. `provenance` must match the
+    /// committed provenance hash, if any. Only owner can call this.
+    Reveal {
+        base_uri: Option<String>,
+        token_uris: Option<BTreeMap<String, String>>,
+        provenance: Option<String>,
+    },
+
+    /// Sets contract providing randomness (e.g. a nois proxy) for shuffling token ids on `Reveal`.
+    /// Unset removes it. Only owner can call this before the first mint.
+    SetRandomnessSource {
+        source: Option<String>,
+    },
+
+    /// Requests randomness from randomness source, attached funds are forwarded to pay for it.
+    /// Only owner can call this.
+    RequestRandomness {},
+
+    /// Randomness callback, only accepted from randomness source before reveal
+    NoisReceive {
+        callback: NoisCallback,
+    },
+
+    /// Restricts `Mint` and `MintAndSend` to a time window. Unset removes it. Only owner can call this.
+    SetMintWindow {
+        window: Option<MintWindow>,
+    },
+
+    /// Caps `Mint` and `MintAndSend` per block or time window, e.g. against bot bursts in public
+    /// mints. Unset removes it. Only owner can call this.
+    SetMintRateLimit {
+        limit: Option<MintRateLimit>,
+    },
+
+    /// Enables or disables `Burn`. With `creator_gated` set, tokens are only burned by the
+    /// creator on `ConfirmBurn` after the holder's `OfferBurn`. With `lock` set, flags can't be
+    /// changed anymore, e.g. to guarantee tickets or deeds can't be destroyed. Only owner can call this.
+    SetBurnable {
+        burnable: bool,
+        creator_gated: Option<bool>,
+        lock: Option<bool>,
+    },
+
+    /// Restricts `Burn` and `ConfirmBurn` to given time windows, e.g. redemption events.
+    /// Empty list removes the restriction. Rejected if burnable flag is locked. Only owner can call this.
+    SetBurnWindows {
+        windows: Vec<BurnWindow>,
+    },
+
+    /// Records holder's consent to burn the token in creator-gated mode.
+    /// Requires same permission as `Burn`.
+    OfferBurn {
+        token_id: String,
+    },
+
+    /// Burns an offered token in creator-gated mode, offer must be from current holder.
+    /// Only owner can call this.
+    ConfirmBurn {
+        token_id: String,
+    },
+
+    /// Rejects minting tokens whose token_uri or extension already exists, e.g. for 1/1 art.
+    /// Requires `uri-index` feature. Only owner can call this.
+    SetUniqueMetadata {
+        unique: UniqueMetadata,
+    },
+
+    /// Completes a two-phase migration, fails if `ValidateMigration` reports errors.
+    /// Only owner can call this.
+    CommitMigration {},
+
+    /// Rewrites the next chunk of legacy tokens of a prepared migration, see `LEGACY_TOKEN_MIGRATION_LIMIT`.
+    /// Only owner can call this.
+    ContinueMigration {},
+
+    /// Adds an operator which may `SendNft` all holders' tokens and stays approved across transfers,
+    /// e.g. the project's rental or staking contract. Holders can opt out.
+    /// Only owner can call this before the first mint.
+    AddPersistentOperator {
+        operator: String,
+    },
+
+    /// Only owner can call this.
+    RemovePersistentOperator {
+        operator: String,
+    },
+
+    /// Sets or unsets guardian, which can only `Pause`. Only owner can call this.
+    SetGuardian {
+        guardian: Option<String>,
+    },
+
+    /// Halts transfers, mints and burns until `Unpause`. Owner or guardian can call this.
+    Pause {},
+
+    /// Only owner can call this.
+    Unpause {},
+
+    /// Delays privileged config changes by `delay`, unset applies them immediately. While a timelock
+    /// is set, config changes (including this one) are queued instead. Only owner can call this.
+    SetTimelock {
+        delay: Option<Duration>,
+    },
+
+    /// Applies a queued config change once its timelock expired. Only owner can call this.
+    ExecuteQueued {
+        id: u64,
+    },
+
+    /// Drops a queued config change. Only owner can call this.
+    CancelQueued {
+        id: u64,
+    },
+}
+
+/// Sent to randomness source on `RequestRandomness`, same as nois proxy's execute msg
+#[cw_serde]
+pub enum RandomnessSourceMsg {
+    GetNextRandomness { job_id: String },
+}
+
+/// Randomness sent back by randomness source, same as nois proxy's callback
+#[cw_serde]
+pub struct NoisCallback {
+    pub job_id: String,
+    pub published: Timestamp,
+    pub randomness: HexBinary,
 }
 
 #[cw_serde]
@@ -95,6 +430,39 @@ pub struct Cw721InstantiateMsg {
     pub minter: Option<String>,
 
     pub withdraw_address: Option<String>,
+
+    /// Tokens minted by the minter during instantiation, at most `MAX_INITIAL_TOKENS`.
+    /// Lets small fixed collections deploy in a single transaction.
+    pub initial_tokens: Option<Vec<InitialToken>>,
+
+    /// Opens genesis import mode, see `ImportTokens`
+    pub import_mode: Option<bool>,
+}
+
+#[cw_serde]
+pub struct InitialToken {
+    pub token_id: String,
+    pub owner: String,
+    pub token_uri: Option<String>,
+    /// JSON encoded metadata extension, unset is the same as `null` (fails for non optional extensions)
+    pub extension: Option<Binary>,
+}
+
+#[cw_serde]
+pub struct ImportedToken<TMetadataExtension> {
+    pub token_id: String,
+    pub owner: String,
+    pub token_uri: Option<String>,
+    pub extension: TMetadataExtension,
+    /// Token id on the origin chain, see `SetOriginTokenIds`
+    pub origin_token_id: Option<String>,
+}
+
+#[cw_serde]
+pub enum Role {
+    Minter,
+    Creator,
+    RoyaltyAdmin,
 }
 
 #[cw_serde]
@@ -114,11 +482,15 @@ pub enum Cw721QueryMsg<TMetadataExtension> {
         spender: String,
         include_expired: Option<bool>,
     },
-    /// Return approvals that a token has
+    /// Return approvals that a token has, ordered by spender unless `order_by_expiration` is set
     #[returns(ApprovalsResponse)]
     Approvals {
         token_id: String,
         include_expired: Option<bool>,
+        /// Only approvals of spenders starting with this prefix
+        spender_prefix: Option<String>,
+        /// Approvals expiring soonest first, height based before time based expirations
+        order_by_expiration: Option<bool>,
     },
     /// Return approval of a given operator for all tokens of an owner, error if not set
     #[returns(OperatorResponse)]
@@ -135,17 +507,65 @@ pub enum Cw721QueryMsg<TMetadataExtension> {
         include_expired: Option<bool>,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
+    },
+    /// Operator grants of all owners expiring before `before`, soonest first, including expired
+    /// ones, e.g. for cleanup jobs. Height based and time based grants are listed separately,
+    /// depending on `before`. Grants made before this index existed are listed once renewed.
+    #[returns(OperatorGrantsResponse)]
+    OperatorsExpiringBefore {
+        before: Expiration,
+        limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page
+        cursor: Option<Binary>,
     },
     /// Total number of tokens issued
     #[returns(NumTokensResponse)]
     NumTokens {},
 
+    /// Requires `uri-index` feature. Tokens with given token_uri as stored, e.g. for duplicate detection.
+    #[returns(TokensResponse)]
+    TokensByUri {
+        uri: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
+    },
+
+    /// Total number of tokens at the beginning of given block height
+    #[returns(NumTokensResponse)]
+    NumTokensAtHeight { height: u64 },
+
     #[returns(CollectionInfo)]
     ContractInfo {},
 
     #[returns(Ownership<Addr>)]
     Ownership {},
 
+    /// Minter and creator ownership in one response, including pending transfers and their expiry
+    #[returns(RolesResponse)]
+    Roles {},
+
+    /// All addresses holding given role, ordered by address. Each role has a single holder in this
+    /// version, so it's the same as `Minter {}` for `Role::Minter`, but the shape stays the same
+    /// once roles can have multiple members.
+    #[returns(RoleMembersResponse)]
+    RoleMembers {
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Royalty related fields of collection info and config in one typed response
+    #[returns(RoyaltyConfigResponse)]
+    RoyaltyConfig {},
+
+    /// When minting and transfers are possible, in one typed response
+    #[returns(TradingScheduleResponse)]
+    TradingSchedule {},
+
     /// With MetaData Extension.
     /// Returns metadata about one particular token, based on *ERC721 Metadata JSON Schema*
     /// but directly from the contract
@@ -159,7 +579,43 @@ pub enum Cw721QueryMsg<TMetadataExtension> {
         token_id: String,
         /// unset or false will filter out expired approvals, you must set to true to see them
         include_expired: Option<bool>,
+        /// set to true to include the collection info, e.g. for detail pages
+        include_collection: Option<bool>,
     },
+    /// With MetaData Extension.
+    /// Same as `NftInfo`, but name and description are resolved for given locale,
+    /// with fallback to its language and then default values.
+    #[returns(NftInfoResponse<TMetadataExtension>)]
+    LocalizedNftInfo { token_id: String, locale: String },
+    /// Returns block height and time of the last transfer or send of the given token
+    #[returns(LastTransferredAtResponse)]
+    LastTransferredAt { token_id: String },
+    /// Token uri a wallet should fetch: reveal state and base uri are applied like in `NftInfo`,
+    /// tokens without token uri get their onchain metadata as `data:application/json;base64,..` uri.
+    #[returns(ResolvedTokenUriResponse)]
+    ResolvedTokenUri { token_id: String },
+    /// Owner and whether the token can currently be transferred, so marketplaces know if a
+    /// listing is actionable
+    #[returns(TokenStatusResponse)]
+    TokenStatus { token_id: String },
+    /// Origin chain lineage of a bridged token
+    #[returns(TokenOriginResponse)]
+    TokenOrigin { token_id: String },
+    /// Checks that `signature` of the message built by `ownership_proof_message` is signed by
+    /// the current owner, for off-chain gated access. `pubkey` is a compressed secp256k1 key, its
+    /// address is derived with the bech32 prefix of the owner. `message` should be a fresh
+    /// challenge of the verifier, chain, contract and token id are part of the signed message.
+    #[returns(VerifyOwnershipProofResponse)]
+    VerifyOwnershipProof {
+        token_id: String,
+        pubkey: Binary,
+        signature: Binary,
+        message: String,
+    },
+    /// Exact raw storage entries of a token, so ICS23 proofs of its ownership can be built for
+    /// verification on other chains. Keys are relative to the contract's store.
+    #[returns(TokenStorageEntriesResponse)]
+    TokenStorageEntries { token_id: String },
 
     /// With Enumerable extension.
     /// Returns all tokens owned by the given address, [] if unset.
@@ -168,6 +624,8 @@ pub enum Cw721QueryMsg<TMetadataExtension> {
         owner: String,
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
     },
     /// With Enumerable extension.
     /// Requires pagination. Lists all token_ids controlled by the contract.
@@ -175,15 +633,192 @@ pub enum Cw721QueryMsg<TMetadataExtension> {
     AllTokens {
         start_after: Option<String>,
         limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
     },
 
     /// Return the minter
     #[returns(MinterResponse)]
     Minter {},
 
+    /// Address fees and swept funds are sent to, if set
+    #[returns(Option<String>)]
+    WithdrawAddress {},
+    /// Deprecated, use `WithdrawAddress` instead. Kept as an alias for existing clients.
     #[returns(Option<String>)]
     GetWithdrawAddress {},
 
+    /// URI of collection-level metadata, if set
+    #[returns(Option<String>)]
+    ContractUri {},
+
+    /// Placeholder and reveal state, if reveal is used
+    #[returns(Option<RevealInfo>)]
+    RevealInfo {},
+
+    /// Committed provenance hash, if any
+    #[returns(Option<String>)]
+    ProvenanceHash {},
+
+    /// Randomness source and randomness received for shuffling token ids on reveal
+    #[returns(RandomnessResponse)]
+    Randomness {},
+
+    /// Time window for minting, if set
+    #[returns(Option<MintWindow>)]
+    MintWindow {},
+
+    /// Mint rate limit, if set, and mints in the current window
+    #[returns(MintRateLimitResponse)]
+    MintRateLimit {},
+
+    /// Minimum blocks or time between two transfers of the same token, if set
+    #[returns(Option<Duration>)]
+    TransferCooldown {},
+
+    /// Max approvals per token and operators per granter
+    #[returns(ApprovalLimits)]
+    ApprovalLimits {},
+
+    /// Max sizes of token_uri and extension
+    #[returns(MetadataLimits)]
+    MetadataLimits {},
+
+    /// Registry contract restricting operators, if royalties are enforced
+    #[returns(Option<Addr>)]
+    OperatorRegistry {},
+
+    /// Fee to be attached on transfer and send, if set
+    #[returns(Option<TransferFee>)]
+    TransferFee {},
+
+    /// Protocol fee taken from funds attached to mints, if set by governance
+    #[returns(Option<ProtocolFee>)]
+    ProtocolFee {},
+
+    /// Reason and height of a governance freeze, if frozen
+    #[returns(Option<FreezeInfo>)]
+    Frozen {},
+
+    /// Address allowed to pause, if set
+    #[returns(Option<Addr>)]
+    Guardian {},
+
+    /// Who paused at which height, if paused
+    #[returns(Option<PauseInfo>)]
+    Paused {},
+
+    /// Delay of privileged config changes, if set
+    #[returns(Option<Duration>)]
+    Timelock {},
+
+    /// Config changes waiting for the timelock, oldest first
+    #[returns(QueuedActionsResponse)]
+    QueuedActions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
+    },
+
+    /// Number of tokens per trait type and value, for tokens with `Metadata` extension.
+    /// Paginated by (trait_type, value).
+    #[returns(TraitStatsResponse)]
+    TraitStats {
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
+    },
+
+    /// Burned tokens with final owner, burner and block height. Requires pagination.
+    #[returns(BurnedTokensResponse)]
+    BurnedTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
+    },
+
+    #[returns(bool)]
+    IsBurned { token_id: String },
+
+    /// Whether burned token ids are retired and can't be minted again
+    #[returns(bool)]
+    RetireBurnedTokens {},
+
+    /// Whether tokens can be burned, and whether this can still be changed
+    #[returns(Burnable)]
+    Burnable {},
+
+    /// Time windows burning is restricted to, empty if unrestricted
+    #[returns(Vec<BurnWindow>)]
+    BurnWindows {},
+
+    /// Range approval owner has granted to spender, if any
+    #[returns(Option<RangeApproval>)]
+    RangeApproval { owner: String, spender: String },
+
+    /// Filter restricting the operator grant of owner, `None` if unrestricted or not granted
+    #[returns(Option<OperatorFilter>)]
+    OperatorFilter { owner: String, operator: String },
+
+    /// Persistent operators set by the creator. If `owner` is set, only operators applying to the
+    /// owner's tokens are returned.
+    #[returns(PersistentOperatorsResponse)]
+    PersistentOperators {
+        owner: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Cross-checks a prepared two-phase migration before it is committed
+    #[returns(ValidateMigrationResponse)]
+    ValidateMigration {},
+
+    /// Privileged actions like ownership transfers and collection updates, oldest first
+    #[returns(AuditLogResponse)]
+    AuditLog {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        /// Opaque `next_cursor` of the previous page, takes precedence over `start_after`
+        cursor: Option<Binary>,
+    },
+
+    /// Whether minting duplicate token_uri or extension is rejected
+    #[returns(UniqueMetadata)]
+    UniqueMetadata {},
+
+    /// Hashes `payload`, e.g. the off-chain metadata JSON, and compares it with the token's
+    /// `metadata_hash` committed at mint
+    #[returns(VerifyMetadataResponse)]
+    VerifyMetadata { token_id: String, payload: Binary },
+
+    /// Pending burn offer of a token in creator-gated mode, if any
+    #[returns(Option<BurnOffer>)]
+    BurnOffer { token_id: String },
+
+    /// Pending transfer offer of a token, if any. Void once the token changed hands.
+    #[returns(Option<TransferOffer>)]
+    TransferOffer { token_id: String },
+
+    /// Gift lock of a token escrowed via `LockGift`, if any
+    #[returns(Option<GiftLock>)]
+    GiftLock { token_id: String },
+
+    /// Rarity score of a token: sum of `token count / trait count` over all its traits
+    #[returns(RarityScoreResponse)]
+    RarityScore { token_id: String },
+
+    /// Returns identifiers of all interfaces (capabilities) implemented by this contract,
+    /// see `INTERFACE_*` constants
+    #[returns(SupportedInterfacesResponse)]
+    SupportedInterfaces {},
+
+    /// Whether genesis import mode is open
+    #[returns(bool)]
+    ImportMode {},
+
     // -- below queries, Extension and GetCollectionInfoExtension, are just dummies, since type annotations are required for
     // -- TMetadataExtension and TCollectionInfoExtension, Error:
     // -- "type annotations needed: cannot infer type for type parameter `TMetadataExtension` declared on the enum `Cw721QueryMsg`"
@@ -192,14 +827,57 @@ pub enum Cw721QueryMsg<TMetadataExtension> {
     Extension { msg: TMetadataExtension },
 }
 
+pub type Cw721ExecuteMsgOf<T> =
+    Cw721ExecuteMsg<<T as Cw721Types>::MetadataExtension, <T as Cw721Types>::MetadataExtensionMsg>;
+
+pub type Cw721QueryMsgOf<T> = Cw721QueryMsg<<T as Cw721Types>::MetadataExtension>;
+
+/// Privileged messages executed by chain governance, e.g. on chains embedding cw721-base
+/// as canonical collection contract
+#[cw_serde]
+pub enum Cw721SudoMsg {
+    /// Takes `bps` of funds attached to `Mint` and `MintAndSend` and forwards it to `recipient`
+    SetProtocolFee {
+        bps: u16,
+        recipient: String,
+    },
+    RemoveProtocolFee {},
+    /// Halts transfers, mints and burns until `Unfreeze`, `reason` is returned by `Frozen` query
+    Freeze {
+        reason: String,
+    },
+    Unfreeze {},
+}
+
 #[cw_serde]
 pub enum Cw721MigrateMsg {
     WithUpdate {
         minter: Option<String>,
         creator: Option<String>,
+        /// Validated the same way as on instantiate
+        withdraw_address: Option<String>,
+        /// Updates collection name, e.g. in case it can't be changed anymore by (lost) creator key
+        name: Option<String>,
+        symbol: Option<String>,
+        /// Skips check whether stored cw2 contract name is compatible. Use with care!
+        allow_unknown: Option<bool>,
+        /// Only prepares migration, keeping legacy storage and cw2 version until `CommitMigration`
+        two_phase: Option<bool>,
+        /// Opens genesis import mode, only if it has never been opened before
+        import_mode: Option<bool>,
     },
 }
 
+/// Approval written into a token at mint time, same as a subsequent `Approve` by the owner.
+#[cw_serde]
+pub struct MintApproval {
+    pub spender: String,
+    /// When the approval expires, never if not set
+    pub expires: Option<Expiration>,
+    /// Bitmask of `APPROVAL_PERMISSION_*` flags, all permissions if not set
+    pub permissions: Option<u8>,
+}
+
 #[cw_serde]
 pub struct CollectionInfoMsg {
     pub name: String,
@@ -232,6 +910,22 @@ pub struct OperatorResponse {
 #[cw_serde]
 pub struct OperatorsResponse {
     pub operators: Vec<Approval>,
+    /// Pass as `cursor` to get the next page, `None` once fewer than `limit` items are returned
+    pub next_cursor: Option<Binary>,
+}
+
+#[cw_serde]
+pub struct OperatorGrant {
+    pub granter: Addr,
+    pub operator: Addr,
+    pub expires: Expiration,
+}
+
+#[cw_serde]
+pub struct OperatorGrantsResponse {
+    pub grants: Vec<OperatorGrant>,
+    /// Pass as `cursor` to get the next page, `None` once fewer than `limit` items are returned
+    pub next_cursor: Option<Binary>,
 }
 
 #[cw_serde]
@@ -255,6 +949,176 @@ pub struct AllNftInfoResponse<TMetadataExtension> {
     pub access: OwnerOfResponse,
     /// Data on the token itself,
     pub info: NftInfoResponse<TMetadataExtension>,
+    /// Collection the token belongs to, only set if `include_collection` is requested
+    pub collection: Option<CollectionInfo>,
+}
+
+#[cw_serde]
+pub struct ValidateMigrationResponse {
+    /// `None` if there is no migration to commit
+    pub pending: Option<PendingMigration>,
+    pub token_count: u64,
+    /// Number of stored tokens checked to be readable
+    pub sampled: u32,
+    /// Mismatches found, empty if migration can be committed
+    pub errors: Vec<String>,
+}
+
+#[cw_serde]
+pub struct AuditEntryResponse {
+    /// Sequence number, use as `start_after` for pagination
+    pub id: u64,
+    pub actor: Addr,
+    pub action: String,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct AuditLogResponse {
+    pub entries: Vec<AuditEntryResponse>,
+    /// Pass as `cursor` to get the next page, `None` once fewer than `limit` items are returned
+    pub next_cursor: Option<Binary>,
+}
+
+#[cw_serde]
+pub struct RolesResponse {
+    /// Can mint new tokens
+    pub minter: Ownership<Addr>,
+    /// Owns the contract and can update collection config
+    pub creator: Ownership<Addr>,
+    /// Can update royalty config, no owner if not appointed
+    pub royalty_admin: Ownership<Addr>,
+}
+
+#[cw_serde]
+pub struct PersistentOperatorsResponse {
+    pub operators: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct RoleMembersResponse {
+    pub members: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct RoyaltyConfigResponse {
+    /// Denoms royalties are accepted in, unset if creator didn't declare any
+    pub payout_denoms: Option<Vec<PayoutDenom>>,
+    /// Can update royalty config, unset if not appointed
+    pub royalty_admin: Option<Addr>,
+    /// Registry restricting operators, set if royalties are enforced
+    pub operator_registry: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct MintRateLimitResponse {
+    pub limit: Option<MintRateLimit>,
+    /// Mints in the window of the current block, 0 if no limit is set
+    pub minted: u32,
+}
+
+#[cw_serde]
+pub struct TradingScheduleResponse {
+    /// Minting is restricted to this window, if set
+    pub mint_window: Option<MintWindow>,
+    /// Max mints per block or time window, if set
+    pub mint_rate_limit: Option<MintRateLimit>,
+    /// Min duration between transfers of a token, if set
+    pub transfer_cooldown: Option<Duration>,
+    /// Transfers and mints are halted while paused or frozen
+    pub paused: Option<PauseInfo>,
+    pub frozen: Option<FreezeInfo>,
+}
+
+#[cw_serde]
+pub struct QueuedActionResponse {
+    /// Sequence number, use as `id` of `ExecuteQueued` or `CancelQueued`
+    pub id: u64,
+    pub action: String,
+    /// The queued `Cw721ExecuteMsg` as json
+    pub msg: Binary,
+    pub proposer: Addr,
+    pub executable_at: Expiration,
+}
+
+#[cw_serde]
+pub struct QueuedActionsResponse {
+    pub actions: Vec<QueuedActionResponse>,
+    /// Pass as `cursor` to get the next page, `None` once fewer than `limit` items are returned
+    pub next_cursor: Option<Binary>,
+}
+
+#[cw_serde]
+pub struct VerifyMetadataResponse {
+    /// True if the token has a `metadata_hash` and it equals the payload's sha256
+    pub verified: bool,
+    /// Hash committed at mint, `None` if the token was minted without one
+    pub metadata_hash: Option<HexBinary>,
+}
+
+#[cw_serde]
+pub struct LastTransferredAtResponse {
+    /// `None` if the token has not been transferred since it was minted
+    pub last_transferred_at: Option<TransferredAt>,
+}
+
+#[cw_serde]
+pub struct TokenOriginResponse {
+    /// Origin of the collection, same as in `CollectionInfo`
+    pub origin: Option<OriginInfo>,
+    /// Token id on the origin chain
+    pub origin_token_id: Option<String>,
+}
+
+#[cw_serde]
+pub struct TokenStatusResponse {
+    pub owner: String,
+    /// False if frozen, paused, in transfer cooldown, escrowed as gift, expired, soulbound or a
+    /// migration is pending
+    pub transferable: bool,
+    /// Collection is frozen by governance
+    pub frozen: bool,
+    /// Collection is paused by guardian or owner
+    pub paused: bool,
+    /// Contract is locked until a prepared migration is committed
+    pub migration_pending: bool,
+    /// End of the transfer cooldown, if not yet over
+    pub cooldown_ends: Option<Expiration>,
+    /// Pending gift, token is escrowed by the contract until claimed or reclaimed
+    pub gift_lock: Option<GiftLock>,
+    /// Pending two-step transfer offer of the current holder, if not expired
+    pub transfer_offer: Option<TransferOffer>,
+    /// Token is expired, only set by cw721-expiration
+    pub expired: bool,
+    /// Token can't be transferred by its holder, only set by cw721-non-transferable
+    pub soulbound: bool,
+}
+
+#[cw_serde]
+pub struct VerifyOwnershipProofResponse {
+    /// True if the signature is valid and signed by the owner
+    pub verified: bool,
+    pub owner: String,
+}
+
+#[cw_serde]
+pub struct StorageEntry {
+    pub key: Binary,
+    pub value: Binary,
+}
+
+#[cw_serde]
+pub struct TokenStorageEntriesResponse {
+    /// Value is the json encoded `VersionedNftInfo` envelope, including the owner
+    pub nft_info: StorageEntry,
+    /// Entry listing the token under its owner, `None` without `enumerable` feature
+    pub owner_index: Option<StorageEntry>,
+}
+
+#[cw_serde]
+pub struct ResolvedTokenUriResponse {
+    /// `None` if the token has neither a token uri nor onchain metadata
+    pub token_uri: Option<String>,
 }
 
 #[cw_serde]
@@ -263,9 +1127,59 @@ pub struct TokensResponse {
     /// If there are more than `limit`, use `start_after` in future queries
     /// to achieve pagination.
     pub tokens: Vec<String>,
+    /// Pass as `cursor` to get the next page, `None` once fewer than `limit` items are returned
+    pub next_cursor: Option<Binary>,
+}
+
+#[cw_serde]
+pub struct BurnedTokenResponse {
+    pub token_id: String,
+    /// Owner at the time of burn
+    pub owner: Addr,
+    pub burner: Addr,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct BurnedTokensResponse {
+    pub tokens: Vec<BurnedTokenResponse>,
+    /// Pass as `cursor` to get the next page, `None` once fewer than `limit` items are returned
+    pub next_cursor: Option<Binary>,
+}
+
+#[cw_serde]
+pub struct TraitCount {
+    pub trait_type: String,
+    pub value: String,
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct TraitStatsResponse {
+    /// Total number of tokens
+    pub token_count: u64,
+    pub traits: Vec<TraitCount>,
+    /// Pass as `cursor` to get the next page, `None` once fewer than `limit` items are returned
+    pub next_cursor: Option<Binary>,
+}
+
+#[cw_serde]
+pub struct RarityScoreResponse {
+    pub score: Decimal,
+}
+
+#[cw_serde]
+pub struct RandomnessResponse {
+    pub source: Option<Addr>,
+    pub randomness: Option<HexBinary>,
+}
+
+#[cw_serde]
+pub struct SupportedInterfacesResponse {
+    pub interfaces: Vec<String>,
 }
 
-/// Deprecated: use Cw721QueryMsg::GetMinterOwnership instead!
+/// Deprecated: use Cw721QueryMsg::Ownership instead! Kept for pre-v0.19 frontends.
 /// Shows who can mint these tokens.
 #[cw_serde]
 pub struct MinterResponse {
@@ -1,30 +1,80 @@
+use bech32::{ToBase32, Variant};
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, BlockInfo, Deps, Empty, Env, Order, StdError, StdResult, Storage,
+    from_json, to_json_binary, Addr, Binary, BlockInfo, CustomMsg, Decimal, Deps, Empty, Env,
+    Order, StdError, StdResult, Storage,
 };
 use cw_ownable::Ownership;
 use cw_storage_plus::Bound;
-use cw_utils::{maybe_addr, Expiration};
+use cw_utils::{maybe_addr, Duration, Expiration};
+use ripemd::Ripemd160;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 
+#[cfg(any(feature = "enumerable", feature = "uri-index"))]
+use crate::msg::TokensResponse;
+#[cfg(feature = "uri-index")]
+use crate::state::uri_hash;
 use crate::{
     msg::{
-        AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, Cw721QueryMsg, MinterResponse,
-        NftInfoResponse, NumTokensResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse,
-        TokensResponse,
+        AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, AuditEntryResponse,
+        AuditLogResponse, BurnedTokenResponse, BurnedTokensResponse, Cw721QueryMsg,
+        LastTransferredAtResponse, MintRateLimitResponse, MinterResponse, NftInfoResponse,
+        NumTokensResponse, OperatorGrant, OperatorGrantsResponse, OperatorResponse,
+        OperatorsResponse, OwnerOfResponse, PersistentOperatorsResponse, QueuedActionResponse,
+        QueuedActionsResponse, RandomnessResponse, RarityScoreResponse, ResolvedTokenUriResponse,
+        Role, RoleMembersResponse, RolesResponse, RoyaltyConfigResponse, StorageEntry,
+        SupportedInterfacesResponse, TokenOriginResponse, TokenStatusResponse,
+        TokenStorageEntriesResponse, TradingScheduleResponse, TraitCount, TraitStatsResponse,
+        ValidateMigrationResponse, VerifyMetadataResponse, VerifyOwnershipProofResponse,
+    },
+    state::{
+        as_metadata, expiration_index_key, Approval, ApprovalLimits, BurnOffer, BurnWindow,
+        Burnable, CollectionInfo, Cw721Config, FreezeInfo, GiftLock, MetadataLimits, MintRateLimit,
+        MintWindow, OperatorFilter, PauseInfo, ProtocolFee, RangeApproval, RevealInfo, TransferFee,
+        TransferOffer, UniqueMetadata, APPROVAL_PERMISSION_ALL, CREATOR, MINTER, ROYALTY_ADMIN,
     },
-    state::{Approval, CollectionInfo, Cw721Config, NftInfo, MINTER},
 };
 
 pub const DEFAULT_LIMIT: u32 = 10;
 pub const MAX_LIMIT: u32 = 1000;
+/// Number of tokens checked by `ValidateMigration`, bounded to stay within query gas limits
+pub const MIGRATION_SAMPLE_SIZE: u32 = 20;
+
+// interface identifiers returned by `SupportedInterfaces` query
+pub const INTERFACE_BASE: &str = "base";
+pub const INTERFACE_ENUMERABLE: &str = "enumerable";
+pub const INTERFACE_METADATA_ONCHAIN: &str = "metadata-onchain";
+pub const INTERFACE_ROYALTIES: &str = "royalties";
+pub const INTERFACE_EXPIRATION: &str = "expiration";
+pub const INTERFACE_UPDATABLE: &str = "updatable";
+
+/// Hook for contracts building on cw721 as a library: `Cw721Query::query` routes
+/// `Cw721QueryMsg::Extension { msg }` here, so custom queries can be added without
+/// forking the query dispatcher.
+pub trait Cw721CustomQuery<
+    // Message passed via `Cw721QueryMsg::Extension`.
+    TExtensionQueryMsg,
+>
+{
+    /// Returns the already serialized response. No-op returning empty Binary by default.
+    fn query_custom(&self, _deps: Deps, _env: Env, _msg: TExtensionQueryMsg) -> StdResult<Binary> {
+        Ok(Binary::default())
+    }
+}
 
 pub trait Cw721Query<
     // Metadata defined in NftInfo.
     TMetadataExtension,
-> where
+>: Cw721CustomQuery<TMetadataExtension> where
     TMetadataExtension: Serialize + DeserializeOwned + Clone,
 {
+    /// Storage layout used by all default methods. Override to use keys set via
+    /// `Cw721Config::builder()`, keep in sync with `Cw721Execute::config`.
+    fn config(&self) -> Cw721Config<'static, TMetadataExtension, Empty, Empty> {
+        Cw721Config::default()
+    }
+
     fn query(
         &self,
         deps: Deps,
@@ -51,12 +101,42 @@ pub trait Cw721Query<
             Cw721QueryMsg::AllNftInfo {
                 token_id,
                 include_expired,
+                include_collection,
             } => to_json_binary(&self.query_all_nft_info(
                 deps,
                 env,
                 token_id,
                 include_expired.unwrap_or(false),
+                include_collection.unwrap_or(false),
             )?),
+            Cw721QueryMsg::LocalizedNftInfo { token_id, locale } => {
+                to_json_binary(&self.query_localized_nft_info(deps, env, token_id, locale)?)
+            }
+            Cw721QueryMsg::LastTransferredAt { token_id } => {
+                to_json_binary(&self.query_last_transferred_at(deps, env, token_id)?)
+            }
+            Cw721QueryMsg::ResolvedTokenUri { token_id } => {
+                to_json_binary(&self.query_resolved_token_uri(deps, env, token_id)?)
+            }
+            Cw721QueryMsg::TokenStatus { token_id } => {
+                to_json_binary(&self.query_token_status(deps, env, token_id)?)
+            }
+            Cw721QueryMsg::TokenOrigin { token_id } => {
+                to_json_binary(&self.query_token_origin(deps, token_id)?)
+            }
+            Cw721QueryMsg::VerifyOwnershipProof {
+                token_id,
+                pubkey,
+                signature,
+                message,
+            } => {
+                to_json_binary(&self.query_verify_ownership_proof(
+                    deps, env, token_id, pubkey, signature, message,
+                )?)
+            }
+            Cw721QueryMsg::TokenStorageEntries { token_id } => {
+                to_json_binary(&self.query_token_storage_entries(deps, token_id)?)
+            }
             Cw721QueryMsg::Operator {
                 owner,
                 operator,
@@ -73,23 +153,74 @@ pub trait Cw721Query<
                 include_expired,
                 start_after,
                 limit,
+                cursor,
             } => to_json_binary(&self.query_operators(
                 deps,
                 env,
                 owner,
                 include_expired.unwrap_or(false),
-                start_after,
+                cursor_start_after(start_after, cursor)?,
+                limit,
+            )?),
+            Cw721QueryMsg::OperatorsExpiringBefore {
+                before,
+                limit,
+                cursor,
+            } => to_json_binary(&self.query_operators_expiring_before(
+                deps,
+                before,
+                cursor_start_after(None, cursor)?,
                 limit,
             )?),
             Cw721QueryMsg::NumTokens {} => to_json_binary(&self.query_num_tokens(deps, env)?),
+            Cw721QueryMsg::NumTokensAtHeight { height } => {
+                to_json_binary(&self.query_num_tokens_at_height(deps, env, height)?)
+            }
+            #[cfg(feature = "enumerable")]
             Cw721QueryMsg::Tokens {
                 owner,
                 start_after,
                 limit,
-            } => to_json_binary(&self.query_tokens(deps, env, owner, start_after, limit)?),
-            Cw721QueryMsg::AllTokens { start_after, limit } => {
-                to_json_binary(&self.query_all_tokens(deps, env, start_after, limit)?)
-            }
+                cursor,
+            } => to_json_binary(&self.query_tokens(
+                deps,
+                env,
+                owner,
+                cursor_start_after(start_after, cursor)?,
+                limit,
+            )?),
+            #[cfg(feature = "enumerable")]
+            Cw721QueryMsg::AllTokens {
+                start_after,
+                limit,
+                cursor,
+            } => to_json_binary(&self.query_all_tokens(
+                deps,
+                env,
+                cursor_start_after(start_after, cursor)?,
+                limit,
+            )?),
+            #[cfg(not(feature = "enumerable"))]
+            Cw721QueryMsg::Tokens { .. } | Cw721QueryMsg::AllTokens { .. } => Err(
+                StdError::generic_err("Enumerable queries are not supported by this contract"),
+            ),
+            #[cfg(feature = "uri-index")]
+            Cw721QueryMsg::TokensByUri {
+                uri,
+                start_after,
+                limit,
+                cursor,
+            } => to_json_binary(&self.query_tokens_by_uri(
+                deps,
+                env,
+                uri,
+                cursor_start_after(start_after, cursor)?,
+                limit,
+            )?),
+            #[cfg(not(feature = "uri-index"))]
+            Cw721QueryMsg::TokensByUri { .. } => Err(StdError::generic_err(
+                "Token uri index is not supported by this contract",
+            )),
             Cw721QueryMsg::Approval {
                 token_id,
                 spender,
@@ -104,21 +235,143 @@ pub trait Cw721Query<
             Cw721QueryMsg::Approvals {
                 token_id,
                 include_expired,
-            } => to_json_binary(&self.query_approvals(
+                spender_prefix,
+                order_by_expiration,
+            } => to_json_binary(&self.query_approvals_filtered(
                 deps,
                 env,
                 token_id,
                 include_expired.unwrap_or(false),
+                spender_prefix,
+                order_by_expiration.unwrap_or(false),
             )?),
             Cw721QueryMsg::Ownership {} => {
                 to_json_binary(&self.query_minter_ownership(deps.storage)?)
             }
-            Cw721QueryMsg::Extension { msg } => {
-                to_json_binary(&self.query_extension(deps, env, msg)?)
+            Cw721QueryMsg::Roles {} => to_json_binary(&self.query_roles(deps.storage)?),
+            Cw721QueryMsg::RoleMembers {
+                role,
+                start_after,
+                limit,
+            } => {
+                to_json_binary(&self.query_role_members(deps.storage, role, start_after, limit)?)
+            }
+            Cw721QueryMsg::RoyaltyConfig {} => to_json_binary(&self.query_royalty_config(deps)?),
+            Cw721QueryMsg::TradingSchedule {} => {
+                to_json_binary(&self.query_trading_schedule(deps)?)
             }
-            Cw721QueryMsg::GetWithdrawAddress {} => {
+            Cw721QueryMsg::Extension { msg } => self.query_custom(deps, env, msg),
+            Cw721QueryMsg::WithdrawAddress {} | Cw721QueryMsg::GetWithdrawAddress {} => {
                 to_json_binary(&self.query_withdraw_address(deps)?)
             }
+            Cw721QueryMsg::ContractUri {} => to_json_binary(&self.query_contract_uri(deps)?),
+            Cw721QueryMsg::RevealInfo {} => to_json_binary(&self.query_reveal_info(deps)?),
+            Cw721QueryMsg::ProvenanceHash {} => to_json_binary(&self.query_provenance_hash(deps)?),
+            Cw721QueryMsg::Randomness {} => to_json_binary(&self.query_randomness(deps)?),
+            Cw721QueryMsg::MintWindow {} => to_json_binary(&self.query_mint_window(deps)?),
+            Cw721QueryMsg::MintRateLimit {} => {
+                to_json_binary(&self.query_mint_rate_limit(deps, &env)?)
+            }
+            Cw721QueryMsg::TransferCooldown {} => {
+                to_json_binary(&self.query_transfer_cooldown(deps)?)
+            }
+            Cw721QueryMsg::ApprovalLimits {} => to_json_binary(&self.query_approval_limits(deps)?),
+            Cw721QueryMsg::MetadataLimits {} => to_json_binary(&self.query_metadata_limits(deps)?),
+            Cw721QueryMsg::OperatorRegistry {} => {
+                to_json_binary(&self.query_operator_registry(deps)?)
+            }
+            Cw721QueryMsg::TransferFee {} => to_json_binary(&self.query_transfer_fee(deps)?),
+            Cw721QueryMsg::ProtocolFee {} => to_json_binary(&self.query_protocol_fee(deps)?),
+            Cw721QueryMsg::Frozen {} => to_json_binary(&self.query_frozen(deps)?),
+            Cw721QueryMsg::Guardian {} => to_json_binary(&self.query_guardian(deps)?),
+            Cw721QueryMsg::Paused {} => to_json_binary(&self.query_paused(deps)?),
+            Cw721QueryMsg::Timelock {} => to_json_binary(&self.query_timelock(deps)?),
+            Cw721QueryMsg::QueuedActions {
+                start_after,
+                limit,
+                cursor,
+            } => to_json_binary(&self.query_queued_actions(
+                deps,
+                cursor_start_after(start_after, cursor)?,
+                limit,
+            )?),
+            Cw721QueryMsg::TraitStats {
+                start_after,
+                limit,
+                cursor,
+            } => to_json_binary(&self.query_trait_stats(
+                deps,
+                env,
+                cursor_start_after(start_after, cursor)?,
+                limit,
+            )?),
+            Cw721QueryMsg::BurnedTokens {
+                start_after,
+                limit,
+                cursor,
+            } => to_json_binary(&self.query_burned_tokens(
+                deps,
+                env,
+                cursor_start_after(start_after, cursor)?,
+                limit,
+            )?),
+            Cw721QueryMsg::IsBurned { token_id } => {
+                to_json_binary(&self.query_is_burned(deps, env, token_id)?)
+            }
+            Cw721QueryMsg::RetireBurnedTokens {} => {
+                to_json_binary(&self.query_retire_burned_tokens(deps)?)
+            }
+            Cw721QueryMsg::Burnable {} => to_json_binary(&self.query_burnable(deps)?),
+            Cw721QueryMsg::BurnWindows {} => to_json_binary(&self.query_burn_windows(deps)?),
+            Cw721QueryMsg::VerifyMetadata { token_id, payload } => {
+                to_json_binary(&self.query_verify_metadata(deps, token_id, payload)?)
+            }
+            Cw721QueryMsg::RangeApproval { owner, spender } => {
+                to_json_binary(&self.query_range_approval(deps, owner, spender)?)
+            }
+            Cw721QueryMsg::OperatorFilter { owner, operator } => {
+                to_json_binary(&self.query_operator_filter(deps, owner, operator)?)
+            }
+            Cw721QueryMsg::PersistentOperators {
+                owner,
+                start_after,
+                limit,
+            } => to_json_binary(&self.query_persistent_operators(
+                deps,
+                &env,
+                owner,
+                start_after,
+                limit,
+            )?),
+            Cw721QueryMsg::ValidateMigration {} => {
+                to_json_binary(&validate_migration(deps.storage, &self.config())?)
+            }
+            Cw721QueryMsg::AuditLog {
+                start_after,
+                limit,
+                cursor,
+            } => to_json_binary(&self.query_audit_log(
+                deps,
+                cursor_start_after(start_after, cursor)?,
+                limit,
+            )?),
+            Cw721QueryMsg::UniqueMetadata {} => to_json_binary(&self.query_unique_metadata(deps)?),
+            Cw721QueryMsg::BurnOffer { token_id } => {
+                to_json_binary(&self.query_burn_offer(deps, token_id)?)
+            }
+            Cw721QueryMsg::TransferOffer { token_id } => {
+                to_json_binary(&self.query_transfer_offer(deps, token_id)?)
+            }
+            Cw721QueryMsg::GiftLock { token_id } => {
+                to_json_binary(&self.query_gift_lock(deps, token_id)?)
+            }
+            Cw721QueryMsg::RarityScore { token_id } => {
+                to_json_binary(&self.query_rarity_score(deps, env, token_id)?)
+            }
+            Cw721QueryMsg::SupportedInterfaces {} => {
+                to_json_binary(&self.query_supported_interfaces()?)
+            }
+            Cw721QueryMsg::ImportMode {} => to_json_binary(&self.query_import_mode(deps)?),
         }
     }
 
@@ -135,15 +388,88 @@ pub trait Cw721Query<
         MINTER.get_ownership(storage)
     }
 
+    fn query_roles(&self, storage: &dyn Storage) -> StdResult<RolesResponse> {
+        Ok(RolesResponse {
+            minter: MINTER.get_ownership(storage)?,
+            creator: CREATOR.get_ownership(storage)?,
+            royalty_admin: ROYALTY_ADMIN.item.may_load(storage)?.unwrap_or(Ownership {
+                owner: None,
+                pending_owner: None,
+                pending_expiry: None,
+            }),
+        })
+    }
+
+    fn query_role_members(
+        &self,
+        storage: &dyn Storage,
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<RoleMembersResponse> {
+        let holder = match role {
+            Role::Minter => MINTER.get_ownership(storage)?.owner,
+            Role::Creator => CREATOR.get_ownership(storage)?.owner,
+            Role::RoyaltyAdmin => ROYALTY_ADMIN
+                .item
+                .may_load(storage)?
+                .and_then(|ownership| ownership.owner),
+        };
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let members = holder
+            .into_iter()
+            .filter(|addr| {
+                start_after
+                    .as_deref()
+                    .map_or(true, |start| addr.as_str() > start)
+            })
+            .take(limit)
+            .collect();
+        Ok(RoleMembersResponse { members })
+    }
+
+    fn query_royalty_config(&self, deps: Deps) -> StdResult<RoyaltyConfigResponse> {
+        let config = self.config();
+        Ok(RoyaltyConfigResponse {
+            payout_denoms: config
+                .collection_info
+                .load(deps.storage)?
+                .royalty_payout_denoms,
+            royalty_admin: ROYALTY_ADMIN
+                .item
+                .may_load(deps.storage)?
+                .and_then(|ownership| ownership.owner),
+            operator_registry: config.operator_registry.may_load(deps.storage)?,
+        })
+    }
+
+    fn query_trading_schedule(&self, deps: Deps) -> StdResult<TradingScheduleResponse> {
+        let config = self.config();
+        Ok(TradingScheduleResponse {
+            mint_window: config.mint_window.may_load(deps.storage)?,
+            mint_rate_limit: config.mint_rate_limit.may_load(deps.storage)?,
+            transfer_cooldown: config.transfer_cooldown.may_load(deps.storage)?,
+            paused: config.paused.may_load(deps.storage)?,
+            frozen: config.frozen.may_load(deps.storage)?,
+        })
+    }
+
     fn query_collection_info(&self, deps: Deps, _env: Env) -> StdResult<CollectionInfo> {
-        Cw721Config::<TMetadataExtension, Empty, Empty>::default()
-            .collection_info
-            .load(deps.storage)
+        self.config().collection_info.load(deps.storage)
     }
 
     fn query_num_tokens(&self, deps: Deps, _env: Env) -> StdResult<NumTokensResponse> {
-        let count =
-            Cw721Config::<TMetadataExtension, Empty, Empty>::default().token_count(deps.storage)?;
+        let count = self.config().token_count(deps.storage)?;
+        Ok(NumTokensResponse { count })
+    }
+
+    fn query_num_tokens_at_height(
+        &self,
+        deps: Deps,
+        _env: Env,
+        height: u64,
+    ) -> StdResult<NumTokensResponse> {
+        let count = self.config().token_count_at_height(deps.storage, height)?;
         Ok(NumTokensResponse { count })
     }
 
@@ -153,15 +479,316 @@ pub trait Cw721Query<
         _env: Env,
         token_id: String,
     ) -> StdResult<NftInfoResponse<TMetadataExtension>> {
-        let info = Cw721Config::<TMetadataExtension, Empty, Empty>::default()
-            .nft_info
-            .load(deps.storage, &token_id)?;
+        let config = self.config();
+        let info = config.nft_info.load(deps.storage, &token_id)?;
         Ok(NftInfoResponse {
-            token_uri: info.token_uri,
+            token_uri: config.token_uri(deps.storage, &token_id, info.token_uri)?,
             extension: info.extension,
         })
     }
 
+    /// Localization applies to `Metadata` extension only, others are returned unchanged.
+    fn query_localized_nft_info(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+        locale: String,
+    ) -> StdResult<NftInfoResponse<TMetadataExtension>> {
+        let info = self.query_nft_info(deps, env, token_id)?;
+        let extension = match as_metadata(&info.extension)? {
+            Some(metadata) if metadata.localizations.is_some() => {
+                from_json(&to_json_binary(&metadata.localized(&locale))?)?
+            }
+            _ => info.extension,
+        };
+        Ok(NftInfoResponse {
+            token_uri: info.token_uri,
+            extension,
+        })
+    }
+
+    fn query_burned_tokens(
+        &self,
+        deps: Deps,
+        _env: Env,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<BurnedTokensResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_deref().map(Bound::exclusive);
+        let tokens = self
+            .config()
+            .burned_tokens
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (token_id, burned) = item?;
+                Ok(BurnedTokenResponse {
+                    token_id,
+                    owner: burned.owner,
+                    burner: burned.burner,
+                    height: burned.height,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        let next_cursor = next_cursor(tokens.last().map(|t| &t.token_id), tokens.len(), limit)?;
+        Ok(BurnedTokensResponse {
+            tokens,
+            next_cursor,
+        })
+    }
+
+    fn query_is_burned(&self, deps: Deps, _env: Env, token_id: String) -> StdResult<bool> {
+        Ok(self.config().burned_tokens.has(deps.storage, &token_id))
+    }
+
+    fn query_retire_burned_tokens(&self, deps: Deps) -> StdResult<bool> {
+        self.config().retire_burned_tokens(deps.storage)
+    }
+
+    fn query_burnable(&self, deps: Deps) -> StdResult<Burnable> {
+        self.config().burnable(deps.storage)
+    }
+
+    fn query_burn_windows(&self, deps: Deps) -> StdResult<Vec<BurnWindow>> {
+        Ok(self
+            .config()
+            .burn_windows
+            .may_load(deps.storage)?
+            .unwrap_or_default())
+    }
+
+    fn query_verify_metadata(
+        &self,
+        deps: Deps,
+        token_id: String,
+        payload: Binary,
+    ) -> StdResult<VerifyMetadataResponse> {
+        let token = self.config().nft_info.load(deps.storage, &token_id)?;
+        let verified = token
+            .metadata_hash
+            .as_ref()
+            .is_some_and(|hash| hash.as_slice() == Sha256::digest(payload.as_slice()).as_slice());
+        Ok(VerifyMetadataResponse {
+            verified,
+            metadata_hash: token.metadata_hash,
+        })
+    }
+
+    fn query_range_approval(
+        &self,
+        deps: Deps,
+        owner: String,
+        spender: String,
+    ) -> StdResult<Option<RangeApproval>> {
+        let owner = deps.api.addr_validate(&owner)?;
+        let spender = deps.api.addr_validate(&spender)?;
+        self.config()
+            .range_approvals
+            .may_load(deps.storage, (&owner, &spender))
+    }
+
+    fn query_operator_filter(
+        &self,
+        deps: Deps,
+        owner: String,
+        operator: String,
+    ) -> StdResult<Option<OperatorFilter>> {
+        let owner = deps.api.addr_validate(&owner)?;
+        let operator = deps.api.addr_validate(&operator)?;
+        self.config()
+            .operator_filters
+            .may_load(deps.storage, (&owner, &operator))
+    }
+
+    fn query_persistent_operators(
+        &self,
+        deps: Deps,
+        env: &Env,
+        owner: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<PersistentOperatorsResponse> {
+        let config = self.config();
+        let owner = owner.map(|o| deps.api.addr_validate(&o)).transpose()?;
+        let start_after = start_after
+            .map(|a| deps.api.addr_validate(&a))
+            .transpose()?;
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.as_ref().map(Bound::exclusive);
+        let mut operators = vec![];
+        for operator in
+            config
+                .persistent_operators
+                .keys(deps.storage, start, None, Order::Ascending)
+        {
+            if operators.len() == limit {
+                break;
+            }
+            let operator = operator?;
+            if let Some(owner) = &owner {
+                if !config.is_persistent_operator(deps, &env.contract.address, owner, &operator)? {
+                    continue;
+                }
+            }
+            operators.push(operator);
+        }
+        Ok(PersistentOperatorsResponse { operators })
+    }
+
+    fn query_audit_log(
+        &self,
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<AuditLogResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+        let entries = self
+            .config()
+            .audit_log
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (id, entry) = item?;
+                Ok(AuditEntryResponse {
+                    id,
+                    actor: entry.actor,
+                    action: entry.action,
+                    height: entry.height,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        let next_cursor = next_cursor(entries.last().map(|e| e.id), entries.len(), limit)?;
+        Ok(AuditLogResponse {
+            entries,
+            next_cursor,
+        })
+    }
+
+    fn query_queued_actions(
+        &self,
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<QueuedActionsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+        let actions = self
+            .config()
+            .queued_actions
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (id, queued) = item?;
+                Ok(QueuedActionResponse {
+                    id,
+                    action: queued.action,
+                    msg: queued.msg,
+                    proposer: queued.proposer,
+                    executable_at: queued.executable_at,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        let next_cursor = next_cursor(actions.last().map(|a| a.id), actions.len(), limit)?;
+        Ok(QueuedActionsResponse {
+            actions,
+            next_cursor,
+        })
+    }
+
+    fn query_unique_metadata(&self, deps: Deps) -> StdResult<UniqueMetadata> {
+        Ok(self
+            .config()
+            .unique_metadata
+            .may_load(deps.storage)?
+            .unwrap_or_default())
+    }
+
+    fn query_burn_offer(&self, deps: Deps, token_id: String) -> StdResult<Option<BurnOffer>> {
+        self.config().burn_offers.may_load(deps.storage, &token_id)
+    }
+
+    fn query_transfer_offer(
+        &self,
+        deps: Deps,
+        token_id: String,
+    ) -> StdResult<Option<TransferOffer>> {
+        self.config()
+            .transfer_offers
+            .may_load(deps.storage, &token_id)
+    }
+
+    fn query_gift_lock(&self, deps: Deps, token_id: String) -> StdResult<Option<GiftLock>> {
+        self.config().gift_locks.may_load(deps.storage, &token_id)
+    }
+
+    fn query_trait_stats(
+        &self,
+        deps: Deps,
+        _env: Env,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    ) -> StdResult<TraitStatsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after
+            .as_ref()
+            .map(|(trait_type, value)| Bound::exclusive((trait_type.as_str(), value.as_str())));
+        let config = self.config();
+        let traits = config
+            .trait_counts
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let ((trait_type, value), count) = item?;
+                Ok(TraitCount {
+                    trait_type,
+                    value,
+                    count,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        let next_cursor = next_cursor(
+            traits.last().map(|t| (&t.trait_type, &t.value)),
+            traits.len(),
+            limit,
+        )?;
+        Ok(TraitStatsResponse {
+            token_count: config.token_count(deps.storage)?,
+            traits,
+            next_cursor,
+        })
+    }
+
+    fn query_rarity_score(
+        &self,
+        deps: Deps,
+        _env: Env,
+        token_id: String,
+    ) -> StdResult<RarityScoreResponse> {
+        let config = self.config();
+        let token = config.nft_info.load(deps.storage, &token_id)?;
+        let token_count = config.token_count(deps.storage)?;
+        let mut score = Decimal::zero();
+        let attributes = as_metadata(&token.extension)?
+            .and_then(|metadata| metadata.attributes)
+            .unwrap_or_default();
+        for attribute in attributes {
+            let count = config
+                .trait_counts
+                .may_load(
+                    deps.storage,
+                    (attribute.trait_type.as_str(), attribute.value.as_str()),
+                )?
+                .unwrap_or_default();
+            if count > 0 {
+                score += Decimal::from_ratio(token_count, count);
+            }
+        }
+        Ok(RarityScoreResponse { score })
+    }
+
     fn query_owner_of(
         &self,
         deps: Deps,
@@ -169,12 +796,155 @@ pub trait Cw721Query<
         token_id: String,
         include_expired_approval: bool,
     ) -> StdResult<OwnerOfResponse> {
-        let nft_info = Cw721Config::<TMetadataExtension, Empty, Empty>::default()
-            .nft_info
-            .load(deps.storage, &token_id)?;
+        let config = self.config();
+        let nft_info = config.nft_info.load(deps.storage, &token_id)?;
+        let approvals = config.load_approvals(deps.storage, &token_id, &nft_info)?;
         Ok(OwnerOfResponse {
             owner: nft_info.owner.to_string(),
-            approvals: humanize_approvals(&env.block, &nft_info, include_expired_approval),
+            approvals: humanize_approvals(&env.block, &approvals, include_expired_approval),
+        })
+    }
+
+    fn query_last_transferred_at(
+        &self,
+        deps: Deps,
+        _env: Env,
+        token_id: String,
+    ) -> StdResult<LastTransferredAtResponse> {
+        let nft_info = self.config().nft_info.load(deps.storage, &token_id)?;
+        Ok(LastTransferredAtResponse {
+            last_transferred_at: nft_info.last_transferred_at,
+        })
+    }
+
+    fn query_resolved_token_uri(
+        &self,
+        deps: Deps,
+        _env: Env,
+        token_id: String,
+    ) -> StdResult<ResolvedTokenUriResponse> {
+        let config = self.config();
+        let nft_info = config.nft_info.load(deps.storage, &token_id)?;
+        let token_uri = match config.token_uri(deps.storage, &token_id, nft_info.token_uri)? {
+            Some(token_uri) => Some(token_uri),
+            None => metadata_data_uri(&nft_info.extension)?,
+        };
+        Ok(ResolvedTokenUriResponse { token_uri })
+    }
+
+    fn query_token_origin(&self, deps: Deps, token_id: String) -> StdResult<TokenOriginResponse> {
+        let config = self.config();
+        // errors for unknown tokens
+        config.nft_info.load(deps.storage, &token_id)?;
+        Ok(TokenOriginResponse {
+            origin: config.collection_info.load(deps.storage)?.origin,
+            origin_token_id: config.origin_token_ids.may_load(deps.storage, &token_id)?,
+        })
+    }
+
+    fn query_token_status(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+    ) -> StdResult<TokenStatusResponse> {
+        let config = self.config();
+        let nft_info = config.nft_info.load(deps.storage, &token_id)?;
+        let frozen = config.frozen.exists(deps.storage);
+        let paused = config.paused.exists(deps.storage);
+        let migration_pending = config.pending_migration.exists(deps.storage);
+        let cooldown_ends = match (
+            config.transfer_cooldown.may_load(deps.storage)?,
+            nft_info.last_transferred_at,
+        ) {
+            (Some(cooldown), Some(last_transferred_at)) => {
+                Some(last_transferred_at.cooldown_ends(cooldown)?)
+                    .filter(|ends| !ends.is_expired(&env.block))
+            }
+            _ => None,
+        };
+        // like on claim, only valid while the contract escrows the token
+        let gift_lock = config
+            .gift_locks
+            .may_load(deps.storage, &token_id)?
+            .filter(|_| nft_info.owner == env.contract.address);
+        let transfer_offer = config
+            .transfer_offers
+            .may_load(deps.storage, &token_id)?
+            .filter(|offer| offer.owner == nft_info.owner && !offer.is_expired(&env.block));
+        Ok(TokenStatusResponse {
+            owner: nft_info.owner.to_string(),
+            transferable: !frozen
+                && !paused
+                && !migration_pending
+                && cooldown_ends.is_none()
+                && gift_lock.is_none(),
+            frozen,
+            paused,
+            migration_pending,
+            cooldown_ends,
+            gift_lock,
+            transfer_offer,
+            expired: false,
+            soulbound: false,
+        })
+    }
+
+    fn query_token_storage_entries(
+        &self,
+        deps: Deps,
+        token_id: String,
+    ) -> StdResult<TokenStorageEntriesResponse> {
+        let config = self.config();
+        let token = config.nft_info.load(deps.storage, &token_id)?;
+        let key = config.nft_info_raw_key(&token_id);
+        let value = deps
+            .storage
+            .get(&key)
+            .ok_or_else(|| StdError::not_found("nft_info"))?;
+        let owner_key = config.owner_index_raw_key(&token.owner, &token_id);
+        let owner_index = deps.storage.get(&owner_key).map(|value| StorageEntry {
+            key: owner_key.into(),
+            value: value.into(),
+        });
+        Ok(TokenStorageEntriesResponse {
+            nft_info: StorageEntry {
+                key: key.into(),
+                value: value.into(),
+            },
+            owner_index,
+        })
+    }
+
+    fn query_verify_ownership_proof(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+        pubkey: Binary,
+        signature: Binary,
+        message: String,
+    ) -> StdResult<VerifyOwnershipProofResponse> {
+        let owner = self.config().nft_info.load(deps.storage, &token_id)?.owner;
+        let message = ownership_proof_message(
+            &env.block.chain_id,
+            &env.contract.address,
+            &token_id,
+            &message,
+        );
+        let signed = deps
+            .api
+            .secp256k1_verify(
+                Sha256::digest(message.as_bytes()).as_slice(),
+                &signature,
+                &pubkey,
+            )
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        let verified =
+            signed && pubkey_address(&pubkey, owner.as_str())?.as_deref() == Some(owner.as_str());
+        Ok(VerifyOwnershipProofResponse {
+            verified,
+            owner: owner.to_string(),
         })
     }
 
@@ -190,7 +960,8 @@ pub trait Cw721Query<
         let owner_addr = deps.api.addr_validate(&owner)?;
         let operator_addr = deps.api.addr_validate(&operator)?;
 
-        let info = Cw721Config::<TMetadataExtension, Empty, Empty>::default()
+        let info = self
+            .config()
             .operators
             .may_load(deps.storage, (&owner_addr, &operator_addr))?;
 
@@ -203,6 +974,7 @@ pub trait Cw721Query<
                 approval: Approval {
                     spender: operator_addr,
                     expires,
+                    permissions: APPROVAL_PERMISSION_ALL,
                 },
             });
         }
@@ -225,7 +997,8 @@ pub trait Cw721Query<
         let start = start_addr.as_ref().map(Bound::exclusive);
 
         let owner_addr = deps.api.addr_validate(&owner)?;
-        let res: StdResult<Vec<_>> = Cw721Config::<TMetadataExtension, Empty, Empty>::default()
+        let operators = self
+            .config()
             .operators
             .prefix(&owner_addr)
             .range(deps.storage, start, None, Order::Ascending)
@@ -236,8 +1009,65 @@ pub trait Cw721Query<
             })
             .take(limit)
             .map(parse_approval)
-            .collect();
-        Ok(OperatorsResponse { operators: res? })
+            .collect::<StdResult<Vec<_>>>()?;
+        let next_cursor =
+            next_cursor(operators.last().map(|a| &a.spender), operators.len(), limit)?;
+        Ok(OperatorsResponse {
+            operators,
+            next_cursor,
+        })
+    }
+
+    /// Operator grants by expiration, using the `operator_expirations` index
+    fn query_operators_expiring_before(
+        &self,
+        deps: Deps,
+        before: Expiration,
+        start_after: Option<(Binary, Addr, Addr)>,
+        limit: Option<u32>,
+    ) -> StdResult<OperatorGrantsResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let max_key = expiration_index_key(&before)
+            .ok_or_else(|| StdError::generic_err("before must be a height or time"))?;
+        // lowest key of the same kind, sorting before any grant since addresses aren't empty
+        let mut min_key = [0; 9];
+        min_key[0] = max_key[0];
+        let empty = Addr::unchecked("");
+        let min = match &start_after {
+            Some((key, granter, operator)) => Bound::exclusive((key.as_slice(), granter, operator)),
+            None => Bound::inclusive((min_key.as_slice(), &empty, &empty)),
+        };
+        let max = Bound::exclusive((max_key.as_slice(), &empty, &empty));
+
+        let config = self.config();
+        let grants = config
+            .operator_expirations
+            .keys(deps.storage, Some(min), Some(max), Order::Ascending)
+            .take(limit)
+            .map(|item| {
+                let (key, granter, operator) = item?;
+                let expires = config.operators.load(deps.storage, (&granter, &operator))?;
+                Ok((
+                    Binary::from(key),
+                    OperatorGrant {
+                        granter,
+                        operator,
+                        expires,
+                    },
+                ))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        let next_cursor = next_cursor(
+            grants
+                .last()
+                .map(|(key, grant)| (key, &grant.granter, &grant.operator)),
+            grants.len(),
+            limit,
+        )?;
+        Ok(OperatorGrantsResponse {
+            grants: grants.into_iter().map(|(_, grant)| grant).collect(),
+            next_cursor,
+        })
     }
 
     fn query_approval(
@@ -248,27 +1078,28 @@ pub trait Cw721Query<
         spender: String,
         include_expired_approval: bool,
     ) -> StdResult<ApprovalResponse> {
-        let token = Cw721Config::<TMetadataExtension, Empty, Empty>::default()
-            .nft_info
-            .load(deps.storage, &token_id)?;
+        let config = self.config();
+        let token = config.nft_info.load(deps.storage, &token_id)?;
 
         // token owner has absolute approval
         if token.owner == spender {
             let approval = Approval {
                 spender: token.owner,
                 expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_ALL,
             };
             return Ok(ApprovalResponse { approval });
         }
 
-        let filtered: Vec<_> = token
-            .approvals
+        let filtered: Vec<_> = config
+            .load_approvals(deps.storage, &token_id, &token)?
             .into_iter()
             .filter(|t| t.spender == spender)
             .filter(|t| include_expired_approval || !t.is_expired(&env.block))
             .map(|a| Approval {
                 spender: a.spender,
                 expires: a.expires,
+                permissions: a.permissions,
             })
             .collect();
 
@@ -289,22 +1120,45 @@ pub trait Cw721Query<
         token_id: String,
         include_expired_approval: bool,
     ) -> StdResult<ApprovalsResponse> {
-        let token = Cw721Config::<TMetadataExtension, Empty, Empty>::default()
-            .nft_info
-            .load(deps.storage, &token_id)?;
-        let approvals: Vec<_> = token
-            .approvals
+        self.query_approvals_filtered(deps, env, token_id, include_expired_approval, None, false)
+    }
+
+    fn query_approvals_filtered(
+        &self,
+        deps: Deps,
+        env: Env,
+        token_id: String,
+        include_expired_approval: bool,
+        spender_prefix: Option<String>,
+        order_by_expiration: bool,
+    ) -> StdResult<ApprovalsResponse> {
+        let config = self.config();
+        let token = config.nft_info.load(deps.storage, &token_id)?;
+        let mut approvals: Vec<_> = config
+            .load_approvals(deps.storage, &token_id, &token)?
             .into_iter()
             .filter(|t| include_expired_approval || !t.is_expired(&env.block))
+            .filter(|t| {
+                spender_prefix
+                    .as_ref()
+                    .map_or(true, |prefix| t.spender.as_str().starts_with(prefix))
+            })
             .map(|a| Approval {
                 spender: a.spender,
                 expires: a.expires,
+                permissions: a.permissions,
             })
             .collect();
+        if order_by_expiration {
+            // stable sort, so approvals expiring at the same time stay ordered by spender
+            approvals.sort_by_key(|approval| expiration_sort_key(&approval.expires));
+        }
 
         Ok(ApprovalsResponse { approvals })
     }
 
+    /// Requires `enumerable` feature, since tokens are looked up by owner index
+    #[cfg(feature = "enumerable")]
     fn query_tokens(
         &self,
         deps: Deps,
@@ -317,7 +1171,9 @@ pub trait Cw721Query<
         let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
 
         let owner_addr = deps.api.addr_validate(&owner)?;
-        let tokens: Vec<String> = Cw721Config::<TMetadataExtension, Empty, Empty>::default()
+        let config = self.config();
+        #[cfg(not(feature = "plain-storage"))]
+        let tokens: Vec<String> = config
             .nft_info
             .idx
             .owner
@@ -325,10 +1181,22 @@ pub trait Cw721Query<
             .keys(deps.storage, start, None, Order::Ascending)
             .take(limit)
             .collect::<StdResult<Vec<_>>>()?;
+        #[cfg(feature = "plain-storage")]
+        let tokens: Vec<String> = config
+            .owner_tokens
+            .prefix(&owner_addr)
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
 
-        Ok(TokensResponse { tokens })
+        let next_cursor = next_cursor(tokens.last(), tokens.len(), limit)?;
+        Ok(TokensResponse {
+            tokens,
+            next_cursor,
+        })
     }
 
+    #[cfg(feature = "enumerable")]
     fn query_all_tokens(
         &self,
         deps: Deps,
@@ -339,15 +1207,47 @@ pub trait Cw721Query<
         let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
         let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
 
-        let tokens: StdResult<Vec<String>> =
-            Cw721Config::<TMetadataExtension, Empty, Empty>::default()
-                .nft_info
-                .range(deps.storage, start, None, Order::Ascending)
-                .take(limit)
-                .map(|item| item.map(|(k, _)| k))
-                .collect();
+        let tokens = self
+            .config()
+            .nft_info
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(k, _)| k))
+            .collect::<StdResult<Vec<String>>>()?;
 
-        Ok(TokensResponse { tokens: tokens? })
+        let next_cursor = next_cursor(tokens.last(), tokens.len(), limit)?;
+        Ok(TokensResponse {
+            tokens,
+            next_cursor,
+        })
+    }
+
+    /// Requires `uri-index` feature, matches token_uri as stored (not as revealed)
+    #[cfg(feature = "uri-index")]
+    fn query_tokens_by_uri(
+        &self,
+        deps: Deps,
+        _env: Env,
+        uri: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<TokensResponse> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+        let tokens = self
+            .config()
+            .uri_tokens
+            .prefix(uri_hash(&uri).as_slice())
+            .keys(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let next_cursor = next_cursor(tokens.last(), tokens.len(), limit)?;
+        Ok(TokensResponse {
+            tokens,
+            next_cursor,
+        })
     }
 
     fn query_all_nft_info(
@@ -356,53 +1256,144 @@ pub trait Cw721Query<
         env: Env,
         token_id: String,
         include_expired_approval: bool,
+        include_collection: bool,
     ) -> StdResult<AllNftInfoResponse<TMetadataExtension>> {
-        let nft_info = Cw721Config::<TMetadataExtension, Empty, Empty>::default()
-            .nft_info
-            .load(deps.storage, &token_id)?;
+        let config = self.config();
+        let nft_info = config.nft_info.load(deps.storage, &token_id)?;
+        let approvals = config.load_approvals(deps.storage, &token_id, &nft_info)?;
+        let collection = if include_collection {
+            Some(config.collection_info.load(deps.storage)?)
+        } else {
+            None
+        };
         Ok(AllNftInfoResponse {
             access: OwnerOfResponse {
                 owner: nft_info.owner.to_string(),
-                approvals: humanize_approvals(&env.block, &nft_info, include_expired_approval),
+                approvals: humanize_approvals(&env.block, &approvals, include_expired_approval),
             },
             info: NftInfoResponse {
-                token_uri: nft_info.token_uri,
+                token_uri: config.token_uri(deps.storage, &token_id, nft_info.token_uri)?,
                 extension: nft_info.extension,
             },
+            collection,
         })
     }
 
-    /// No-op returning empty Binary
-    fn query_extension(
-        &self,
-        _deps: Deps,
-        _env: Env,
-        _msg: TMetadataExtension,
-    ) -> StdResult<Binary> {
-        Ok(Binary::default())
+    fn query_withdraw_address(&self, deps: Deps) -> StdResult<Option<String>> {
+        self.config().withdraw_address.may_load(deps.storage)
     }
 
-    fn query_withdraw_address(&self, deps: Deps) -> StdResult<Option<String>> {
-        Cw721Config::<TMetadataExtension, Empty, Empty>::default()
-            .withdraw_address
-            .may_load(deps.storage)
+    fn query_contract_uri(&self, deps: Deps) -> StdResult<Option<String>> {
+        self.config().contract_uri.may_load(deps.storage)
+    }
+
+    fn query_reveal_info(&self, deps: Deps) -> StdResult<Option<RevealInfo>> {
+        self.config().reveal.may_load(deps.storage)
+    }
+
+    fn query_provenance_hash(&self, deps: Deps) -> StdResult<Option<String>> {
+        self.config().provenance_hash.may_load(deps.storage)
+    }
+
+    fn query_mint_window(&self, deps: Deps) -> StdResult<Option<MintWindow>> {
+        self.config().mint_window.may_load(deps.storage)
+    }
+
+    fn query_mint_rate_limit(&self, deps: Deps, env: &Env) -> StdResult<MintRateLimitResponse> {
+        let config = self.config();
+        let limit = config.mint_rate_limit.may_load(deps.storage)?;
+        let minted = match &limit {
+            Some(limit) => config
+                .mint_rate_usage
+                .may_load(deps.storage)?
+                .filter(|usage| usage.window == limit.window_index(&env.block))
+                .map_or(0, |usage| usage.minted),
+            None => 0,
+        };
+        Ok(MintRateLimitResponse { limit, minted })
+    }
+
+    fn query_randomness(&self, deps: Deps) -> StdResult<RandomnessResponse> {
+        let config = self.config();
+        Ok(RandomnessResponse {
+            source: config.randomness_source.may_load(deps.storage)?,
+            randomness: config.randomness.may_load(deps.storage)?,
+        })
+    }
+
+    /// Contracts extending cw721 should override this and add their own interfaces
+    fn query_supported_interfaces(&self) -> StdResult<SupportedInterfacesResponse> {
+        let mut interfaces = vec![INTERFACE_BASE.to_string()];
+        if cfg!(feature = "enumerable") {
+            interfaces.push(INTERFACE_ENUMERABLE.to_string());
+        }
+        interfaces.push(INTERFACE_METADATA_ONCHAIN.to_string());
+        Ok(SupportedInterfacesResponse { interfaces })
+    }
+
+    fn query_import_mode(&self, deps: Deps) -> StdResult<bool> {
+        Ok(self
+            .config()
+            .import_mode
+            .may_load(deps.storage)?
+            .unwrap_or(false))
+    }
+
+    fn query_transfer_cooldown(&self, deps: Deps) -> StdResult<Option<Duration>> {
+        self.config().transfer_cooldown.may_load(deps.storage)
+    }
+
+    fn query_approval_limits(&self, deps: Deps) -> StdResult<ApprovalLimits> {
+        self.config().approval_limits(deps.storage)
+    }
+
+    fn query_metadata_limits(&self, deps: Deps) -> StdResult<MetadataLimits> {
+        self.config().metadata_limits(deps.storage)
+    }
+
+    fn query_operator_registry(&self, deps: Deps) -> StdResult<Option<Addr>> {
+        self.config().operator_registry.may_load(deps.storage)
+    }
+
+    fn query_transfer_fee(&self, deps: Deps) -> StdResult<Option<TransferFee>> {
+        self.config().transfer_fee.may_load(deps.storage)
+    }
+
+    fn query_protocol_fee(&self, deps: Deps) -> StdResult<Option<ProtocolFee>> {
+        self.config().protocol_fee.may_load(deps.storage)
+    }
+
+    fn query_frozen(&self, deps: Deps) -> StdResult<Option<FreezeInfo>> {
+        self.config().frozen.may_load(deps.storage)
+    }
+
+    fn query_guardian(&self, deps: Deps) -> StdResult<Option<Addr>> {
+        self.config().guardian.may_load(deps.storage)
+    }
+
+    fn query_paused(&self, deps: Deps) -> StdResult<Option<PauseInfo>> {
+        self.config().paused.may_load(deps.storage)
+    }
+
+    fn query_timelock(&self, deps: Deps) -> StdResult<Option<Duration>> {
+        self.config().timelock.may_load(deps.storage)
     }
 }
 
 pub fn parse_approval(item: StdResult<(Addr, Expiration)>) -> StdResult<Approval> {
-    item.map(|(spender, expires)| Approval { spender, expires })
+    item.map(|(spender, expires)| Approval {
+        spender,
+        expires,
+        permissions: APPROVAL_PERMISSION_ALL,
+    })
 }
 
-pub fn humanize_approvals<TMetadataExtension>(
+pub fn humanize_approvals(
     block: &BlockInfo,
-    nft_info: &NftInfo<TMetadataExtension>,
+    approvals: &[Approval],
     include_expired_approval: bool,
-) -> Vec<Approval>
-where
-    TMetadataExtension: Serialize + DeserializeOwned + Clone,
-{
-    nft_info
-        .approvals
+) -> Vec<Approval> {
+    approvals
         .iter()
         .filter(|apr| include_expired_approval || !apr.is_expired(block))
         .map(humanize_approval)
@@ -413,5 +1404,143 @@ pub fn humanize_approval(approval: &Approval) -> Approval {
     Approval {
         spender: approval.spender.clone(),
         expires: approval.expires,
+        permissions: approval.permissions,
+    }
+}
+
+/// Orders height based before time based expirations, `Never` last
+fn expiration_sort_key(expires: &Expiration) -> (u8, u64) {
+    match expires {
+        Expiration::AtHeight(height) => (0, *height),
+        Expiration::AtTime(time) => (1, time.nanos()),
+        Expiration::Never {} => (2, 0),
+    }
+}
+
+/// Resolves `start_after` of a list query, an opaque `cursor` returned as `next_cursor` takes precedence
+pub fn cursor_start_after<T: DeserializeOwned>(
+    start_after: Option<T>,
+    cursor: Option<Binary>,
+) -> StdResult<Option<T>> {
+    match cursor {
+        Some(cursor) => from_json(cursor).map(Some),
+        None => Ok(start_after),
+    }
+}
+
+/// Opaque cursor encoding the position of `last` item of a full page, so clients don't need to
+/// know key construction e.g. of composite or secondary index keys
+pub fn next_cursor<T: Serialize>(
+    last: Option<T>,
+    count: usize,
+    limit: usize,
+) -> StdResult<Option<Binary>> {
+    if count < limit {
+        return Ok(None);
+    }
+    last.map(|last| to_json_binary(&last)).transpose()
+}
+
+/// Cross-checks a prepared two-phase migration: token count must be unchanged since prepare,
+/// current collection info and minter must exist, legacy tokens must be rewritten,
+/// and sampled tokens must be readable.
+/// Small collections are fully sampled, so stored tokens are counted as well.
+pub fn validate_migration<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>(
+    storage: &dyn Storage,
+    config: &Cw721Config<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>,
+) -> StdResult<ValidateMigrationResponse>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    let pending = config.pending_migration.may_load(storage)?;
+    let token_count = config.token_count.may_load(storage)?.unwrap_or_default();
+    let mut errors = vec![];
+    match &pending {
+        Some(pending) if pending.token_count != token_count => errors.push(format!(
+            "Token count changed from {} to {}",
+            pending.token_count, token_count
+        )),
+        Some(_) => {}
+        None => errors.push("No pending migration".to_string()),
+    }
+    if !config.collection_info.exists(storage) {
+        errors.push("Collection info not migrated".to_string());
+    }
+    if !MINTER.item.exists(storage) {
+        errors.push("Minter not migrated".to_string());
+    }
+    if config.legacy_token_cursor.exists(storage) {
+        errors.push("Legacy tokens not fully rewritten, see `ContinueMigration`".to_string());
+    }
+    let token_ids = config
+        .nft_info
+        .keys(storage, None, None, Order::Ascending)
+        .take(MIGRATION_SAMPLE_SIZE as usize)
+        .collect::<StdResult<Vec<_>>>()?;
+    for token_id in &token_ids {
+        if let Err(err) = config.nft_info.load(storage, token_id) {
+            errors.push(format!("Token {token_id} not readable: {err}"));
+        }
+    }
+    if token_ids.len() < MIGRATION_SAMPLE_SIZE as usize && token_ids.len() as u64 != token_count {
+        errors.push(format!(
+            "Found {} tokens, but token count is {}",
+            token_ids.len(),
+            token_count
+        ));
+    }
+    Ok(ValidateMigrationResponse {
+        pending,
+        token_count,
+        sampled: token_ids.len() as u32,
+        errors,
+    })
+}
+
+/// Renders onchain metadata as data uri, `None` for empty extensions (`null` or `{}`)
+pub fn metadata_data_uri<TMetadataExtension>(
+    extension: &TMetadataExtension,
+) -> StdResult<Option<String>>
+where
+    TMetadataExtension: Serialize,
+{
+    let json = to_json_binary(extension)?;
+    if json.as_slice() == b"null" || json.as_slice() == b"{}" {
+        return Ok(None);
+    }
+    Ok(Some(format!(
+        "data:application/json;base64,{}",
+        json.to_base64()
+    )))
+}
+
+/// Message signed for `VerifyOwnershipProof`. Chain id, contract and token id bind a proof to one
+/// token, `message` is the verifier's challenge, e.g. a nonce with expiry.
+pub fn ownership_proof_message(
+    chain_id: &str,
+    contract: &Addr,
+    token_id: &str,
+    message: &str,
+) -> String {
+    format!(
+        "cw721 ownership proof\nchain_id: {chain_id}\ncontract: {contract}\ntoken_id: {token_id}\nmessage: {message}"
+    )
+}
+
+/// Account address of a compressed secp256k1 pubkey, with the bech32 prefix of `address`.
+/// `None` if `address` is not bech32.
+fn pubkey_address(pubkey: &Binary, address: &str) -> StdResult<Option<String>> {
+    if pubkey.len() != 33 {
+        return Err(StdError::generic_err(
+            "Pubkey must be a compressed secp256k1 key",
+        ));
     }
+    let Ok((prefix, _, _)) = bech32::decode(address) else {
+        return Ok(None);
+    };
+    let hash = Ripemd160::digest(Sha256::digest(pubkey.as_slice()));
+    bech32::encode(&prefix, hash.to_base32(), Variant::Bech32)
+        .map(Some)
+        .map_err(|err| StdError::generic_err(err.to_string()))
 }
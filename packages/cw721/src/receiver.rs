@@ -1,7 +1,14 @@
 use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{to_json_binary, Binary, CosmosMsg, StdResult, WasmMsg};
+use cosmwasm_std::{
+    to_json_binary, Binary, CosmosMsg, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+    WasmMsg,
+};
+
+use crate::error::Cw721ReceiveError;
+use crate::helpers::parse_receive;
 
 /// Cw721ReceiveMsg should be de/serialized under `Receive()` variant in a ExecuteMsg
 #[cw_serde]
@@ -36,6 +43,35 @@ impl Cw721ReceiveMsg {
     }
 }
 
+/// Implemented by contracts managing NFTs sent via `SendNft` or `MintAndSend`.
+/// `receive_nft` is called for `ExecuteMsg::ReceiveNft` and passes the decoded inner msg
+/// to `handle_receive`. Implementations must check `info.sender` is a cw721 contract they accept.
+pub trait Cw721Receiver<TCustomResponseMessage = Empty> {
+    /// Contract specific msg sent along with the token
+    type Msg: DeserializeOwned;
+    type Error: From<Cw721ReceiveError>;
+
+    fn handle_receive(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        receive_msg: Cw721ReceiveMsg,
+        msg: Self::Msg,
+    ) -> Result<Response<TCustomResponseMessage>, Self::Error>;
+
+    fn receive_nft(
+        &self,
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        receive_msg: Cw721ReceiveMsg,
+    ) -> Result<Response<TCustomResponseMessage>, Self::Error> {
+        let msg = parse_receive(&receive_msg)?;
+        self.handle_receive(deps, env, info, receive_msg, msg)
+    }
+}
+
 /// This is just a helper to properly serialize the above message.
 /// The actual receiver should include this variant in the larger ExecuteMsg enum
 #[cw_serde]
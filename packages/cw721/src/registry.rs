@@ -0,0 +1,32 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, QuerierWrapper, StdResult};
+
+/// Query interface an operator registry contract must implement for enforced royalties.
+/// The actual registry should include this variant in the larger QueryMsg enum
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum OperatorRegistryQueryMsg {
+    /// Returns whether operator (e.g. a marketplace honoring royalties) is allowed
+    #[returns(IsOperatorAllowedResponse)]
+    IsOperatorAllowed { operator: String },
+}
+
+#[cw_serde]
+pub struct IsOperatorAllowedResponse {
+    pub allowed: bool,
+}
+
+/// queries registry whether operator is allowed
+pub fn is_operator_allowed(
+    querier: &QuerierWrapper,
+    registry: &Addr,
+    operator: &Addr,
+) -> StdResult<bool> {
+    let res: IsOperatorAllowedResponse = querier.query_wasm_smart(
+        registry,
+        &OperatorRegistryQueryMsg::IsOperatorAllowed {
+            operator: operator.to_string(),
+        },
+    )?;
+    Ok(res.allowed)
+}
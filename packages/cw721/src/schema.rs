@@ -0,0 +1,26 @@
+/// Writes JSON schemas of all cw721 entry point messages, using `cosmwasm_schema::write_api!`,
+/// for the given metadata extension and extension msg types. Since messages are generic,
+/// a schema (and e.g. TS clients generated from it) must be written for each concrete type.
+///
+/// The calling crate must depend on `cosmwasm-schema`. Schema is written to `./schema/{name}.json`.
+///
+/// ```ignore
+/// use cosmwasm_std::Empty;
+/// use cw721::state::DefaultOptionMetadataExtension;
+///
+/// fn main() {
+///     cw721::write_cw721_api!("cw721-metadata", DefaultOptionMetadataExtension, Empty);
+/// }
+/// ```
+#[macro_export]
+macro_rules! write_cw721_api {
+    ($name:literal, $metadata_extension:ty, $metadata_extension_msg:ty) => {
+        ::cosmwasm_schema::write_api! {
+            name: $name,
+            instantiate: $crate::msg::Cw721InstantiateMsg,
+            execute: $crate::msg::Cw721ExecuteMsg<$metadata_extension, $metadata_extension_msg>,
+            query: $crate::msg::Cw721QueryMsg<$metadata_extension>,
+            migrate: $crate::msg::Cw721MigrateMsg,
+        }
+    };
+}
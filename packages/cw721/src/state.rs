@@ -1,19 +1,67 @@
+use std::collections::BTreeMap;
 use std::marker::PhantomData;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, BlockInfo, CustomMsg, StdResult, Storage};
+use cosmwasm_std::{
+    from_json, to_json_binary, to_json_vec, Addr, Binary, BlockInfo, Coin, CustomMsg, Decimal,
+    Deps, Empty, HexBinary, Order, StdResult, Storage, Timestamp, Uint64,
+};
 use cw_ownable::{OwnershipStore, OWNERSHIP_KEY};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
-use cw_utils::Expiration;
+use cw_storage_plus::{
+    Index, IndexList, IndexedMap, Item, Map, MultiIndex, SnapshotItem, Strategy,
+};
+use cw_utils::{Duration, Expiration};
+use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// - minter is stored in the contract storage using cw_ownable::OwnershipStore (same as for OWNERSHIP but with different key)
 pub const MINTER: OwnershipStore = OwnershipStore::new(OWNERSHIP_KEY);
+/// - creator (collection owner) may update collection config, stored separately from MINTER so each role is transferred on its own
+pub const CREATOR: OwnershipStore = OwnershipStore::new("collection_creator");
+/// - optional royalty admin, may only update royalty config. Unset until appointed by the creator.
+pub const ROYALTY_ADMIN: OwnershipStore = OwnershipStore::new("collection_royalty_admin");
 
 /// Default CollectionInfoExtension with RoyaltyInfo
 pub type DefaultOptionMetadataExtension = Option<Metadata>;
 
+/// Bundles the generic parameters of a cw721 contract into a single type,
+/// so downstream code can write e.g. `Cw721ConfigOf<'a, DefaultCw721>`.
+pub trait Cw721Types {
+    /// Metadata defined in NftInfo (used for mint).
+    type MetadataExtension: Serialize + DeserializeOwned + Clone;
+    /// Defines for `CosmosMsg::Custom<T>` in response. Barely used, so `Empty` can be used.
+    type CustomResponseMessage: CustomMsg;
+    /// Message passed for updating metadata.
+    type MetadataExtensionMsg: CustomMsg;
+}
+
+/// Onchain `Metadata` as optional extension, no custom messages.
+pub struct DefaultCw721;
+
+impl Cw721Types for DefaultCw721 {
+    type MetadataExtension = DefaultOptionMetadataExtension;
+    type CustomResponseMessage = Empty;
+    type MetadataExtensionMsg = Empty;
+}
+
+/// No metadata extension and no custom messages.
+pub struct EmptyCw721;
+
+impl Cw721Types for EmptyCw721 {
+    type MetadataExtension = Empty;
+    type CustomResponseMessage = Empty;
+    type MetadataExtensionMsg = Empty;
+}
+
+pub type Cw721ConfigOf<'a, T> = Cw721Config<
+    'a,
+    <T as Cw721Types>::MetadataExtension,
+    <T as Cw721Types>::CustomResponseMessage,
+    <T as Cw721Types>::MetadataExtensionMsg,
+>;
+
 pub struct Cw721Config<
     'a,
     // Metadata defined in NftInfo (used for mint).
@@ -29,12 +77,110 @@ pub struct Cw721Config<
     /// Note: replaces deprecated/legacy key "nft_info"!
     pub collection_info: Item<'a, CollectionInfo>,
     pub token_count: Item<'a, u64>,
+    /// Token count per block height, e.g. for quorum calculations of NFT DAOs
+    pub supply: SnapshotItem<'a, u64>,
     /// Stored as (granter, operator) giving operator full control over granter's account.
     /// NOTE: granter is the owner, so operator has only control for NFTs owned by granter!
     pub operators: Map<'a, (&'a Addr, &'a Addr), Expiration>,
+    /// Index of `operators` by `expiration_index_key`, grants that never expire aren't indexed.
+    /// Use `save_operator` and `remove_operator` to keep it in sync.
+    pub operator_expirations: Map<'a, (&'a [u8], &'a Addr, &'a Addr), Empty>,
+    /// Restricts an operator grant to a subset of the granter's tokens, unrestricted if unset
+    pub operator_filters: Map<'a, (&'a Addr, &'a Addr), OperatorFilter>,
+    /// Operators of all holders set by the creator, e.g. the project's staking contract.
+    /// Unlike approvals they survive transfers.
+    pub persistent_operators: Map<'a, &'a Addr, Empty>,
+    /// Stored as (holder, operator), holders who opted out of a persistent operator
+    pub persistent_operator_opt_outs: Map<'a, (&'a Addr, &'a Addr), Empty>,
+    /// Stored as (holder, operator), contract holders who opted in to a persistent operator
+    pub persistent_operator_opt_ins: Map<'a, (&'a Addr, &'a Addr), Empty>,
+    /// Use `save_nft` and `remove_nft` for writes, so owner lookups are maintained for either storage mode.
     pub nft_info:
         IndexedMap<'a, &'a str, NftInfo<TMetadataExtension>, TokenIndexes<'a, TMetadataExtension>>,
+    /// Namespace of the owner index of `nft_info`, see `owner_index_raw_key`
+    pub tokens_owner_namespace: &'a str,
+    /// Stored as (owner, token_id), explicitly maintained instead of owner index in `plain-storage` mode.
+    pub owner_tokens: Map<'a, (&'a Addr, &'a str), Empty>,
+    /// Stored as (sha256 of stored token_uri, token_id), maintained with `uri-index` feature only.
+    pub uri_tokens: Map<'a, (&'a [u8], &'a str), Empty>,
+    /// Stored as (sha256 of json extension, token_id), maintained with `uri-index` feature only.
+    pub extension_tokens: Map<'a, (&'a [u8], &'a str), Empty>,
+    /// Rejects minting duplicate token_uri or extension, not enforced if not set
+    pub unique_metadata: Item<'a, UniqueMetadata>,
+    /// Append-only log of privileged actions, stored by sequence number
+    pub audit_log: Map<'a, u64, AuditEntry>,
+    /// Prepared by a two-phase migration, cleared by `CommitMigration`
+    pub pending_migration: Item<'a, PendingMigration>,
+    /// Last legacy token rewritten by a chunked migration, cleared once all tokens are rewritten
+    pub legacy_token_cursor: Item<'a, String>,
+    /// Stored as (token_id, spender), so approving/revoking doesn't rewrite the token.
+    pub token_approvals: Map<'a, (&'a str, &'a Addr), Approval>,
+    /// Max approvals per token and operators per granter, defaults are used if not set
+    pub approval_limits: Item<'a, ApprovalLimits>,
+    /// Max serialized sizes of token_uri and extension, defaults are used if not set
+    pub metadata_limits: Item<'a, MetadataLimits>,
+    /// Enforced royalties: if set, only operators allowed by this registry contract
+    /// can be approved for all tokens or receive tokens via `SendNft`.
+    pub operator_registry: Item<'a, Addr>,
+    /// Fee to be attached on transfer and send, no fee if not set
+    pub transfer_fee: Item<'a, TransferFee>,
+    /// Stored as (trait_type, value) -> number of tokens, maintained on mint and burn for `Metadata` extension.
+    pub trait_counts: Map<'a, (&'a str, &'a str), u64>,
+    /// Burned tokens, for provenance. Entry is removed in case token id is minted again.
+    pub burned_tokens: Map<'a, &'a str, BurnedToken>,
+    /// If true, burned token ids can't be minted again
+    pub retire_burned_tokens: Item<'a, bool>,
+    /// Collection-level metadata (OpenSea's `contractURI`), e.g. storefront banner and description
+    pub contract_uri: Item<'a, String>,
+    /// If set, token uris are hidden behind a placeholder until revealed
+    pub reveal: Item<'a, RevealInfo>,
+    /// Hex encoded sha256 hash of all final metadata in order, can't be changed after first mint
+    pub provenance_hash: Item<'a, String>,
+    /// Contract providing randomness for shuffling token ids on reveal, e.g. a nois proxy
+    pub randomness_source: Item<'a, Addr>,
+    /// Randomness received from `randomness_source`, used on reveal
+    pub randomness: Item<'a, HexBinary>,
+    /// Minting is only possible within this window, if set
+    pub mint_window: Item<'a, MintWindow>,
+    /// Max mints per block or time window, unlimited if not set
+    pub mint_rate_limit: Item<'a, MintRateLimit>,
+    /// Mints in the current window of `mint_rate_limit`
+    pub mint_rate_usage: Item<'a, MintRateUsage>,
+    /// Whether holders can burn tokens, burnable and unlocked if not set
+    pub burnable: Item<'a, Burnable>,
+    /// Burning is only possible within one of these windows, e.g. redemption events. Unrestricted if not set
+    pub burn_windows: Item<'a, Vec<BurnWindow>>,
+    /// Burns offered by holders, awaiting creator's confirmation in creator-gated mode
+    pub burn_offers: Map<'a, &'a str, BurnOffer>,
+    /// Pending two-step transfers by token id, removed on transfer and burn
+    pub transfer_offers: Map<'a, &'a str, TransferOffer>,
+    /// Gifts escrowed by the contract by token id, removed on claim and reclaim
+    pub gift_locks: Map<'a, &'a str, GiftLock>,
+    /// Stored as (token_id, sha256(secret ‖ recipient)) with the height of the commitment
+    pub gift_claim_commitments: Map<'a, (&'a str, &'a [u8]), u64>,
     pub withdraw_address: Item<'a, String>,
+    /// Minimum blocks or time between two transfers of the same token, no cooldown if not set
+    pub transfer_cooldown: Item<'a, Duration>,
+    /// Stored as (owner, spender), grants spender all tokens of owner with numeric id in range
+    pub range_approvals: Map<'a, (&'a Addr, &'a Addr), RangeApproval>,
+    /// Share of funds attached to mints, set by chain governance via sudo. No fee if not set
+    pub protocol_fee: Item<'a, ProtocolFee>,
+    /// Set by chain governance via sudo, transfers and mints are halted while set
+    pub frozen: Item<'a, FreezeInfo>,
+    /// May only pause, never unpause or change config. Set by owner
+    pub guardian: Item<'a, Addr>,
+    /// Circuit breaker, transfers and mints are halted while set
+    pub paused: Item<'a, PauseInfo>,
+    /// Delay of privileged config changes, applied immediately if not set
+    pub timelock: Item<'a, Duration>,
+    /// Config changes waiting for timelock, by sequence number
+    pub queued_actions: Map<'a, u64, QueuedAction>,
+    /// Sequence number of the next queued action
+    pub queued_action_seq: Item<'a, u64>,
+    /// Genesis import mode, open while true and closed for good once false
+    pub import_mode: Item<'a, bool>,
+    /// Token id on the origin chain of bridged tokens, see `CollectionInfo::origin`
+    pub origin_token_ids: Map<'a, &'a str, String>,
 
     pub(crate) _custom_response: PhantomData<TCustomResponseMessage>,
     pub(crate) _custom_execute: PhantomData<TMetadataExtensionMsg>,
@@ -47,14 +193,7 @@ where
     TMetadataExtensionMsg: CustomMsg,
 {
     fn default() -> Self {
-        Self::new(
-            "collection_info", // Note: replaces deprecated/legacy key "nft_info"
-            "num_tokens",
-            "operators",
-            "tokens",
-            "tokens__owner",
-            "withdraw_address",
-        )
+        Self::builder().build()
     }
 }
 
@@ -64,54 +203,883 @@ where
     TMetadataExtension: Serialize + DeserializeOwned + Clone,
     TMetadataExtensionMsg: CustomMsg,
 {
-    fn new(
-        collection_info_key: &'a str,
-        token_count_key: &'a str,
-        operator_key: &'a str,
-        nft_info_key: &'a str,
-        nft_info_owner_key: &'a str,
-        withdraw_address_key: &'a str,
-    ) -> Self {
-        let indexes = TokenIndexes {
-            owner: MultiIndex::new(token_owner_idx, nft_info_key, nft_info_owner_key),
-        };
-        Self {
-            collection_info: Item::new(collection_info_key),
-            token_count: Item::new(token_count_key),
-            operators: Map::new(operator_key),
-            nft_info: IndexedMap::new(nft_info_key, indexes),
-            withdraw_address: Item::new(withdraw_address_key),
-            _custom_response: PhantomData,
-            _custom_execute: PhantomData,
-        }
+    /// Builder with default storage keys, e.g. `Cw721Config::builder().tokens_key("my_tokens").build()`
+    pub fn builder(
+    ) -> Cw721ConfigBuilder<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+    {
+        Cw721ConfigBuilder::default()
     }
 
     pub fn token_count(&self, storage: &dyn Storage) -> StdResult<u64> {
         Ok(self.token_count.may_load(storage)?.unwrap_or_default())
     }
 
-    pub fn increment_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
+    pub fn increment_tokens(&self, storage: &mut dyn Storage, height: u64) -> StdResult<u64> {
         let val = self.token_count(storage)? + 1;
         self.token_count.save(storage, &val)?;
+        self.supply.save(storage, &val, height)?;
         Ok(val)
     }
 
-    pub fn decrement_tokens(&self, storage: &mut dyn Storage) -> StdResult<u64> {
+    pub fn decrement_tokens(&self, storage: &mut dyn Storage, height: u64) -> StdResult<u64> {
         let val = self.token_count(storage)? - 1;
         self.token_count.save(storage, &val)?;
+        self.supply.save(storage, &val, height)?;
         Ok(val)
     }
+
+    /// Token count at the beginning of given block height
+    pub fn token_count_at_height(&self, storage: &dyn Storage, height: u64) -> StdResult<u64> {
+        Ok(self
+            .supply
+            .may_load_at_height(storage, height)?
+            .unwrap_or_default())
+    }
+
+    pub fn retire_burned_tokens(&self, storage: &dyn Storage) -> StdResult<bool> {
+        Ok(self
+            .retire_burned_tokens
+            .may_load(storage)?
+            .unwrap_or_default())
+    }
+
+    pub fn burnable(&self, storage: &dyn Storage) -> StdResult<Burnable> {
+        Ok(self.burnable.may_load(storage)?.unwrap_or_default())
+    }
+
+    /// Appends a privileged action to the audit log, returns its sequence number
+    pub fn record_audit(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        actor: &Addr,
+        action: &str,
+    ) -> StdResult<u64> {
+        let id = self
+            .audit_log
+            .keys(storage, None, None, Order::Descending)
+            .next()
+            .transpose()?
+            .map_or(0, |last| last + 1);
+        self.audit_log.save(
+            storage,
+            id,
+            &AuditEntry {
+                actor: actor.clone(),
+                action: action.to_string(),
+                height: block.height,
+            },
+        )?;
+        Ok(id)
+    }
+
+    /// Token uri as returned by queries, respecting reveal state
+    pub fn token_uri(
+        &self,
+        storage: &dyn Storage,
+        token_id: &str,
+        token_uri: Option<String>,
+    ) -> StdResult<Option<String>> {
+        Ok(match self.reveal.may_load(storage)? {
+            Some(reveal) => reveal.token_uri(token_id, token_uri),
+            None => token_uri,
+        })
+    }
+
+    pub fn approval_limits(&self, storage: &dyn Storage) -> StdResult<ApprovalLimits> {
+        Ok(self.approval_limits.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn metadata_limits(&self, storage: &dyn Storage) -> StdResult<MetadataLimits> {
+        Ok(self.metadata_limits.may_load(storage)?.unwrap_or_default())
+    }
+
+    /// Saves token and updates owner lookup. `old_token` is the currently stored token (`None` on mint),
+    /// passing it avoids re-loading the token before saving.
+    pub fn save_nft(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &'a str,
+        token: &'a NftInfo<TMetadataExtension>,
+        old_token: Option<&'a NftInfo<TMetadataExtension>>,
+    ) -> StdResult<()> {
+        self.nft_info
+            .replace(storage, token_id, Some(token), old_token)?;
+        if old_token.is_none() {
+            self.update_trait_counts(storage, token, true)?;
+        }
+        #[cfg(all(feature = "plain-storage", feature = "enumerable"))]
+        {
+            // only rewrite owner lookup if owner changed
+            if old_token.map(|old| &old.owner) != Some(&token.owner) {
+                if let Some(old) = old_token {
+                    self.owner_tokens.remove(storage, (&old.owner, token_id));
+                }
+                self.owner_tokens
+                    .save(storage, (&token.owner, token_id), &Empty {})?;
+            }
+        }
+        #[cfg(feature = "uri-index")]
+        if old_token.map(|old| &old.token_uri) != Some(&token.token_uri) {
+            if let Some(uri) = old_token.and_then(|old| old.token_uri.as_ref()) {
+                self.uri_tokens
+                    .remove(storage, (uri_hash(uri).as_slice(), token_id));
+            }
+            if let Some(uri) = &token.token_uri {
+                self.uri_tokens
+                    .save(storage, (uri_hash(uri).as_slice(), token_id), &Empty {})?;
+            }
+        }
+        #[cfg(feature = "uri-index")]
+        if old_token.is_none() {
+            // extension is only set on mint
+            if let Some(hash) = extension_hash(&token.extension)? {
+                self.extension_tokens
+                    .save(storage, (hash.as_slice(), token_id), &Empty {})?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes currently stored token, its owner lookup, approvals and pending offers or gift lock.
+    pub fn remove_nft(
+        &self,
+        storage: &mut dyn Storage,
+        token_id: &'a str,
+        token: &'a NftInfo<TMetadataExtension>,
+    ) -> StdResult<()> {
+        self.nft_info
+            .replace(storage, token_id, None, Some(token))?;
+        self.update_trait_counts(storage, token, false)?;
+        #[cfg(all(feature = "plain-storage", feature = "enumerable"))]
+        self.owner_tokens.remove(storage, (&token.owner, token_id));
+        #[cfg(feature = "uri-index")]
+        if let Some(uri) = &token.token_uri {
+            self.uri_tokens
+                .remove(storage, (uri_hash(uri).as_slice(), token_id));
+        }
+        #[cfg(feature = "uri-index")]
+        if let Some(hash) = extension_hash(&token.extension)? {
+            self.extension_tokens
+                .remove(storage, (hash.as_slice(), token_id));
+        }
+        self.clear_approvals(storage, token_id)?;
+        self.origin_token_ids.remove(storage, token_id);
+        self.burn_offers.remove(storage, token_id);
+        self.transfer_offers.remove(storage, token_id);
+        self.gift_locks.remove(storage, token_id);
+        Ok(())
+    }
+
+    /// Whether a persistent operator may send tokens of `owner`. Holders opt out, while contracts
+    /// like escrows must opt in, as they may hold tokens on behalf of others. Never applies to
+    /// tokens held by `contract` itself, e.g. locked gifts.
+    pub fn is_persistent_operator(
+        &self,
+        deps: Deps,
+        contract: &Addr,
+        owner: &Addr,
+        operator: &Addr,
+    ) -> StdResult<bool> {
+        if owner == contract || !self.persistent_operators.has(deps.storage, operator) {
+            return Ok(false);
+        }
+        if deps.querier.query_wasm_contract_info(owner).is_ok() {
+            return Ok(self
+                .persistent_operator_opt_ins
+                .has(deps.storage, (owner, operator)));
+        }
+        Ok(!self
+            .persistent_operator_opt_outs
+            .has(deps.storage, (owner, operator)))
+    }
+
+    fn update_trait_counts(
+        &self,
+        storage: &mut dyn Storage,
+        token: &NftInfo<TMetadataExtension>,
+        increment: bool,
+    ) -> StdResult<()> {
+        let Some(metadata) = as_metadata(&token.extension)? else {
+            return Ok(());
+        };
+        for attribute in metadata.attributes.unwrap_or_default() {
+            let key = (attribute.trait_type.as_str(), attribute.value.as_str());
+            let count = self
+                .trait_counts
+                .may_load(storage, key)?
+                .unwrap_or_default();
+            match (increment, count) {
+                (true, _) => self.trait_counts.save(storage, key, &(count + 1))?,
+                // tokens minted before trait counts were maintained are not counted
+                (false, 0) => {}
+                (false, 1) => self.trait_counts.remove(storage, key),
+                (false, _) => self.trait_counts.save(storage, key, &(count - 1))?,
+            }
+        }
+        Ok(())
+    }
+
+    /// All approvals of given token, including legacy approvals still stored in `NftInfo`.
+    pub fn load_approvals(
+        &self,
+        storage: &dyn Storage,
+        token_id: &'a str,
+        token: &NftInfo<TMetadataExtension>,
+    ) -> StdResult<Vec<Approval>> {
+        let mut approvals = token.approvals.clone();
+        for item in
+            self.token_approvals
+                .prefix(token_id)
+                .range(storage, None, None, Order::Ascending)
+        {
+            approvals.push(item?.1);
+        }
+        Ok(approvals)
+    }
+
+    /// Drops expired approvals of given token from `token_approvals` and from legacy approvals
+    /// in `token`. Persisting `token` is up to the caller.
+    pub fn prune_expired_approvals(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        token_id: &'a str,
+        token: &mut NftInfo<TMetadataExtension>,
+    ) -> StdResult<()> {
+        let mut expired = vec![];
+        for item in
+            self.token_approvals
+                .prefix(token_id)
+                .range(storage, None, None, Order::Ascending)
+        {
+            let (spender, approval) = item?;
+            if approval.is_expired(block) {
+                expired.push(spender);
+            }
+        }
+        for spender in expired.iter() {
+            self.token_approvals.remove(storage, (token_id, spender));
+        }
+        token.approvals.retain(|apr| !apr.is_expired(block));
+        Ok(())
+    }
+
+    /// Grants `operator` control over all tokens of `granter` matching `filter`, replacing a previous grant
+    pub fn save_operator(
+        &self,
+        storage: &mut dyn Storage,
+        granter: &'a Addr,
+        operator: &'a Addr,
+        expires: &Expiration,
+        filter: Option<&OperatorFilter>,
+    ) -> StdResult<()> {
+        self.remove_operator(storage, granter, operator)?;
+        self.operators.save(storage, (granter, operator), expires)?;
+        if let Some(filter) = filter {
+            self.operator_filters
+                .save(storage, (granter, operator), filter)?;
+        }
+        if let Some(key) = expiration_index_key(expires) {
+            self.operator_expirations.save(
+                storage,
+                (key.as_slice(), granter, operator),
+                &Empty {},
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn remove_operator(
+        &self,
+        storage: &mut dyn Storage,
+        granter: &'a Addr,
+        operator: &'a Addr,
+    ) -> StdResult<()> {
+        if let Some(expires) = self.operators.may_load(storage, (granter, operator))? {
+            if let Some(key) = expiration_index_key(&expires) {
+                self.operator_expirations
+                    .remove(storage, (key.as_slice(), granter, operator));
+            }
+            self.operators.remove(storage, (granter, operator));
+            self.operator_filters.remove(storage, (granter, operator));
+        }
+        Ok(())
+    }
+
+    /// Raw storage key of the collection info, e.g. for `QueryRaw` reads and storage proofs
+    pub fn collection_info_raw_key(&self) -> Vec<u8> {
+        self.collection_info.as_slice().to_vec()
+    }
+
+    /// Raw storage key of a token. Its value is the json encoded `VersionedNftInfo` envelope.
+    pub fn nft_info_raw_key(&self, token_id: &'a str) -> Vec<u8> {
+        self.nft_info.key(token_id).to_vec()
+    }
+
+    /// Raw storage key of the entry listing token under its owner, maintained with `enumerable`
+    /// feature only. Layout is the same as a `Map<(&Addr, &str), _>` in either storage mode:
+    /// `len(namespace) | namespace | len(owner) | owner | token_id`, lengths as 2 byte big endian.
+    pub fn owner_index_raw_key(&self, owner: &'a Addr, token_id: &'a str) -> Vec<u8> {
+        #[cfg(feature = "plain-storage")]
+        let key = self.owner_tokens.key((owner, token_id)).to_vec();
+        // multi index entries are stored as (owner, primary key)
+        #[cfg(not(feature = "plain-storage"))]
+        let key = Map::<(&Addr, &str), u32>::new(self.tokens_owner_namespace)
+            .key((owner, token_id))
+            .to_vec();
+        key
+    }
+
+    /// Raw storage key of an operator grant. Its value is the json encoded `Expiration`.
+    pub fn operator_raw_key(&self, granter: &'a Addr, operator: &'a Addr) -> Vec<u8> {
+        self.operators.key((granter, operator)).to_vec()
+    }
+
+    /// Removes all approvals of given token stored in `token_approvals`.
+    pub fn clear_approvals(&self, storage: &mut dyn Storage, token_id: &'a str) -> StdResult<()> {
+        let spenders = self
+            .token_approvals
+            .prefix(token_id)
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for spender in spenders.iter() {
+            self.token_approvals.remove(storage, (token_id, spender));
+        }
+        Ok(())
+    }
+}
+
+/// Key of `Cw721Config::operator_expirations`, height based before time based expirations.
+/// `None` for `Never`, which isn't indexed.
+pub fn expiration_index_key(expires: &Expiration) -> Option<[u8; 9]> {
+    let (kind, value) = match expires {
+        Expiration::AtHeight(height) => (0, *height),
+        Expiration::AtTime(time) => (1, time.nanos()),
+        Expiration::Never {} => return None,
+    };
+    let mut key = [kind; 9];
+    key[1..].copy_from_slice(&value.to_be_bytes());
+    Some(key)
+}
+
+/// Key of `Cw721Config::uri_tokens`
+pub fn uri_hash(uri: &str) -> [u8; 32] {
+    Sha256::digest(uri.as_bytes()).into()
+}
+
+/// Key of `Cw721Config::extension_tokens`, `None` for empty (null) extensions
+pub fn extension_hash<TMetadataExtension>(
+    extension: &TMetadataExtension,
+) -> StdResult<Option<[u8; 32]>>
+where
+    TMetadataExtension: Serialize,
+{
+    let json = to_json_vec(extension)?;
+    if json == b"null" {
+        return Ok(None);
+    }
+    Ok(Some(Sha256::digest(json).into()))
+}
+
+/// Storage keys of a `Cw721Config`, so contracts storing cw721 state alongside other storage
+/// can namespace its keys. Unset keys are the default ones.
+/// Default methods of `Cw721Execute` and `Cw721Query` use the built config once both traits'
+/// `config()` are overridden to return it.
+pub struct Cw721ConfigBuilder<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+{
+    collection_info_key: &'a str,
+    token_count_key: &'a str,
+    operator_key: &'a str,
+    tokens_key: &'a str,
+    tokens_owner_key: &'a str,
+    withdraw_address_key: &'a str,
+    transfer_cooldown_key: &'a str,
+    owner_tokens_key: &'a str,
+    token_approvals_key: &'a str,
+    approval_limits_key: &'a str,
+    metadata_limits_key: &'a str,
+    operator_registry_key: &'a str,
+    transfer_fee_key: &'a str,
+    trait_counts_key: &'a str,
+    burned_tokens_key: &'a str,
+    retire_burned_tokens_key: &'a str,
+    contract_uri_key: &'a str,
+    reveal_key: &'a str,
+    provenance_hash_key: &'a str,
+    randomness_source_key: &'a str,
+    randomness_key: &'a str,
+    mint_window_key: &'a str,
+    mint_rate_limit_key: &'a str,
+    mint_rate_usage_key: &'a str,
+    burnable_key: &'a str,
+    burn_windows_key: &'a str,
+    burn_offers_key: &'a str,
+    transfer_offers_key: &'a str,
+    gift_locks_key: &'a str,
+    gift_claim_commitments_key: &'a str,
+    supply_key: &'a str,
+    supply_checkpoints_key: &'a str,
+    supply_changelog_key: &'a str,
+    uri_tokens_key: &'a str,
+    extension_tokens_key: &'a str,
+    unique_metadata_key: &'a str,
+    audit_log_key: &'a str,
+    pending_migration_key: &'a str,
+    legacy_token_cursor_key: &'a str,
+    range_approvals_key: &'a str,
+    protocol_fee_key: &'a str,
+    frozen_key: &'a str,
+    guardian_key: &'a str,
+    paused_key: &'a str,
+    timelock_key: &'a str,
+    queued_actions_key: &'a str,
+    queued_action_seq_key: &'a str,
+    import_mode_key: &'a str,
+    origin_token_ids_key: &'a str,
+    operator_expirations_key: &'a str,
+    operator_filters_key: &'a str,
+    persistent_operators_key: &'a str,
+    persistent_operator_opt_outs_key: &'a str,
+    persistent_operator_opt_ins_key: &'a str,
+    _types: PhantomData<(
+        TMetadataExtension,
+        TCustomResponseMessage,
+        TMetadataExtensionMsg,
+    )>,
+}
+
+impl<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg> Default
+    for Cw721ConfigBuilder<'_, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+{
+    fn default() -> Self {
+        Self {
+            collection_info_key: "collection_info", // Note: replaces deprecated/legacy key "nft_info"
+            token_count_key: "num_tokens",
+            operator_key: "operators",
+            tokens_key: "tokens",
+            tokens_owner_key: "tokens__owner",
+            withdraw_address_key: "withdraw_address",
+            transfer_cooldown_key: "transfer_cooldown",
+            owner_tokens_key: "owner_tokens",
+            token_approvals_key: "token_approvals",
+            approval_limits_key: "approval_limits",
+            metadata_limits_key: "metadata_limits",
+            operator_registry_key: "operator_registry",
+            transfer_fee_key: "transfer_fee",
+            trait_counts_key: "trait_counts",
+            burned_tokens_key: "burned_tokens",
+            retire_burned_tokens_key: "retire_burned_tokens",
+            contract_uri_key: "contract_uri",
+            reveal_key: "reveal",
+            provenance_hash_key: "provenance_hash",
+            randomness_source_key: "randomness_source",
+            randomness_key: "randomness",
+            mint_window_key: "mint_window",
+            mint_rate_limit_key: "mint_rate_limit",
+            mint_rate_usage_key: "mint_rate_usage",
+            burnable_key: "burnable",
+            burn_windows_key: "burn_windows",
+            burn_offers_key: "burn_offers",
+            transfer_offers_key: "transfer_offers",
+            gift_locks_key: "gift_locks",
+            gift_claim_commitments_key: "gift_claim_commitments",
+            supply_key: "supply",
+            supply_checkpoints_key: "supply__checkpoints",
+            supply_changelog_key: "supply__changelog",
+            uri_tokens_key: "uri_tokens",
+            extension_tokens_key: "extension_tokens",
+            unique_metadata_key: "unique_metadata",
+            audit_log_key: "audit_log",
+            pending_migration_key: "pending_migration",
+            legacy_token_cursor_key: "legacy_token_cursor",
+            range_approvals_key: "range_approvals",
+            protocol_fee_key: "protocol_fee",
+            frozen_key: "frozen",
+            guardian_key: "guardian",
+            paused_key: "paused",
+            timelock_key: "timelock",
+            queued_actions_key: "queued_actions",
+            queued_action_seq_key: "queued_action_seq",
+            import_mode_key: "import_mode",
+            origin_token_ids_key: "origin_token_ids",
+            operator_expirations_key: "operator_expirations",
+            operator_filters_key: "operator_filters",
+            persistent_operators_key: "persistent_operators",
+            persistent_operator_opt_outs_key: "persistent_operator_opt_outs",
+            persistent_operator_opt_ins_key: "persistent_operator_opt_ins",
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+    Cw721ConfigBuilder<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TMetadataExtensionMsg: CustomMsg,
+{
+    pub fn collection_info_key(mut self, key: &'a str) -> Self {
+        self.collection_info_key = key;
+        self
+    }
+
+    pub fn token_count_key(mut self, key: &'a str) -> Self {
+        self.token_count_key = key;
+        self
+    }
+
+    pub fn operator_key(mut self, key: &'a str) -> Self {
+        self.operator_key = key;
+        self
+    }
+
+    pub fn tokens_key(mut self, key: &'a str) -> Self {
+        self.tokens_key = key;
+        self
+    }
+
+    pub fn tokens_owner_key(mut self, key: &'a str) -> Self {
+        self.tokens_owner_key = key;
+        self
+    }
+
+    pub fn withdraw_address_key(mut self, key: &'a str) -> Self {
+        self.withdraw_address_key = key;
+        self
+    }
+
+    pub fn transfer_cooldown_key(mut self, key: &'a str) -> Self {
+        self.transfer_cooldown_key = key;
+        self
+    }
+
+    pub fn owner_tokens_key(mut self, key: &'a str) -> Self {
+        self.owner_tokens_key = key;
+        self
+    }
+
+    pub fn token_approvals_key(mut self, key: &'a str) -> Self {
+        self.token_approvals_key = key;
+        self
+    }
+
+    pub fn approval_limits_key(mut self, key: &'a str) -> Self {
+        self.approval_limits_key = key;
+        self
+    }
+
+    pub fn metadata_limits_key(mut self, key: &'a str) -> Self {
+        self.metadata_limits_key = key;
+        self
+    }
+
+    pub fn operator_registry_key(mut self, key: &'a str) -> Self {
+        self.operator_registry_key = key;
+        self
+    }
+
+    pub fn transfer_fee_key(mut self, key: &'a str) -> Self {
+        self.transfer_fee_key = key;
+        self
+    }
+
+    pub fn trait_counts_key(mut self, key: &'a str) -> Self {
+        self.trait_counts_key = key;
+        self
+    }
+
+    pub fn burned_tokens_key(mut self, key: &'a str) -> Self {
+        self.burned_tokens_key = key;
+        self
+    }
+
+    pub fn retire_burned_tokens_key(mut self, key: &'a str) -> Self {
+        self.retire_burned_tokens_key = key;
+        self
+    }
+
+    pub fn contract_uri_key(mut self, key: &'a str) -> Self {
+        self.contract_uri_key = key;
+        self
+    }
+
+    pub fn reveal_key(mut self, key: &'a str) -> Self {
+        self.reveal_key = key;
+        self
+    }
+
+    pub fn provenance_hash_key(mut self, key: &'a str) -> Self {
+        self.provenance_hash_key = key;
+        self
+    }
+
+    pub fn randomness_source_key(mut self, key: &'a str) -> Self {
+        self.randomness_source_key = key;
+        self
+    }
+
+    pub fn randomness_key(mut self, key: &'a str) -> Self {
+        self.randomness_key = key;
+        self
+    }
+
+    pub fn mint_window_key(mut self, key: &'a str) -> Self {
+        self.mint_window_key = key;
+        self
+    }
+
+    pub fn mint_rate_limit_key(mut self, key: &'a str) -> Self {
+        self.mint_rate_limit_key = key;
+        self
+    }
+
+    pub fn mint_rate_usage_key(mut self, key: &'a str) -> Self {
+        self.mint_rate_usage_key = key;
+        self
+    }
+
+    pub fn burnable_key(mut self, key: &'a str) -> Self {
+        self.burnable_key = key;
+        self
+    }
+
+    pub fn burn_windows_key(mut self, key: &'a str) -> Self {
+        self.burn_windows_key = key;
+        self
+    }
+
+    pub fn burn_offers_key(mut self, key: &'a str) -> Self {
+        self.burn_offers_key = key;
+        self
+    }
+
+    pub fn transfer_offers_key(mut self, key: &'a str) -> Self {
+        self.transfer_offers_key = key;
+        self
+    }
+
+    pub fn gift_locks_key(mut self, key: &'a str) -> Self {
+        self.gift_locks_key = key;
+        self
+    }
+
+    pub fn gift_claim_commitments_key(mut self, key: &'a str) -> Self {
+        self.gift_claim_commitments_key = key;
+        self
+    }
+
+    pub fn supply_key(mut self, key: &'a str) -> Self {
+        self.supply_key = key;
+        self
+    }
+
+    pub fn supply_checkpoints_key(mut self, key: &'a str) -> Self {
+        self.supply_checkpoints_key = key;
+        self
+    }
+
+    pub fn supply_changelog_key(mut self, key: &'a str) -> Self {
+        self.supply_changelog_key = key;
+        self
+    }
+
+    pub fn uri_tokens_key(mut self, key: &'a str) -> Self {
+        self.uri_tokens_key = key;
+        self
+    }
+
+    pub fn extension_tokens_key(mut self, key: &'a str) -> Self {
+        self.extension_tokens_key = key;
+        self
+    }
+
+    pub fn unique_metadata_key(mut self, key: &'a str) -> Self {
+        self.unique_metadata_key = key;
+        self
+    }
+
+    pub fn audit_log_key(mut self, key: &'a str) -> Self {
+        self.audit_log_key = key;
+        self
+    }
+
+    pub fn pending_migration_key(mut self, key: &'a str) -> Self {
+        self.pending_migration_key = key;
+        self
+    }
+
+    pub fn legacy_token_cursor_key(mut self, key: &'a str) -> Self {
+        self.legacy_token_cursor_key = key;
+        self
+    }
+
+    pub fn range_approvals_key(mut self, key: &'a str) -> Self {
+        self.range_approvals_key = key;
+        self
+    }
+
+    pub fn protocol_fee_key(mut self, key: &'a str) -> Self {
+        self.protocol_fee_key = key;
+        self
+    }
+
+    pub fn frozen_key(mut self, key: &'a str) -> Self {
+        self.frozen_key = key;
+        self
+    }
+
+    pub fn guardian_key(mut self, key: &'a str) -> Self {
+        self.guardian_key = key;
+        self
+    }
+
+    pub fn paused_key(mut self, key: &'a str) -> Self {
+        self.paused_key = key;
+        self
+    }
+
+    pub fn timelock_key(mut self, key: &'a str) -> Self {
+        self.timelock_key = key;
+        self
+    }
+
+    pub fn queued_actions_key(mut self, key: &'a str) -> Self {
+        self.queued_actions_key = key;
+        self
+    }
+
+    pub fn queued_action_seq_key(mut self, key: &'a str) -> Self {
+        self.queued_action_seq_key = key;
+        self
+    }
+
+    pub fn import_mode_key(mut self, key: &'a str) -> Self {
+        self.import_mode_key = key;
+        self
+    }
+
+    pub fn origin_token_ids_key(mut self, key: &'a str) -> Self {
+        self.origin_token_ids_key = key;
+        self
+    }
+
+    pub fn operator_expirations_key(mut self, key: &'a str) -> Self {
+        self.operator_expirations_key = key;
+        self
+    }
+
+    pub fn operator_filters_key(mut self, key: &'a str) -> Self {
+        self.operator_filters_key = key;
+        self
+    }
+
+    pub fn persistent_operators_key(mut self, key: &'a str) -> Self {
+        self.persistent_operators_key = key;
+        self
+    }
+
+    pub fn persistent_operator_opt_outs_key(mut self, key: &'a str) -> Self {
+        self.persistent_operator_opt_outs_key = key;
+        self
+    }
+
+    pub fn persistent_operator_opt_ins_key(mut self, key: &'a str) -> Self {
+        self.persistent_operator_opt_ins_key = key;
+        self
+    }
+
+    pub fn build(
+        self,
+    ) -> Cw721Config<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg> {
+        let indexes = TokenIndexes {
+            owner: MultiIndex::new(token_owner_idx, self.tokens_key, self.tokens_owner_key),
+        };
+        Cw721Config {
+            collection_info: Item::new(self.collection_info_key),
+            token_count: Item::new(self.token_count_key),
+            supply: SnapshotItem::new(
+                self.supply_key,
+                self.supply_checkpoints_key,
+                self.supply_changelog_key,
+                Strategy::EveryBlock,
+            ),
+            operators: Map::new(self.operator_key),
+            operator_expirations: Map::new(self.operator_expirations_key),
+            operator_filters: Map::new(self.operator_filters_key),
+            persistent_operators: Map::new(self.persistent_operators_key),
+            persistent_operator_opt_outs: Map::new(self.persistent_operator_opt_outs_key),
+            persistent_operator_opt_ins: Map::new(self.persistent_operator_opt_ins_key),
+            nft_info: IndexedMap::new(self.tokens_key, indexes),
+            tokens_owner_namespace: self.tokens_owner_key,
+            withdraw_address: Item::new(self.withdraw_address_key),
+            transfer_cooldown: Item::new(self.transfer_cooldown_key),
+            owner_tokens: Map::new(self.owner_tokens_key),
+            uri_tokens: Map::new(self.uri_tokens_key),
+            extension_tokens: Map::new(self.extension_tokens_key),
+            unique_metadata: Item::new(self.unique_metadata_key),
+            audit_log: Map::new(self.audit_log_key),
+            pending_migration: Item::new(self.pending_migration_key),
+            legacy_token_cursor: Item::new(self.legacy_token_cursor_key),
+            token_approvals: Map::new(self.token_approvals_key),
+            approval_limits: Item::new(self.approval_limits_key),
+            metadata_limits: Item::new(self.metadata_limits_key),
+            operator_registry: Item::new(self.operator_registry_key),
+            transfer_fee: Item::new(self.transfer_fee_key),
+            trait_counts: Map::new(self.trait_counts_key),
+            burned_tokens: Map::new(self.burned_tokens_key),
+            retire_burned_tokens: Item::new(self.retire_burned_tokens_key),
+            contract_uri: Item::new(self.contract_uri_key),
+            reveal: Item::new(self.reveal_key),
+            provenance_hash: Item::new(self.provenance_hash_key),
+            randomness_source: Item::new(self.randomness_source_key),
+            randomness: Item::new(self.randomness_key),
+            mint_window: Item::new(self.mint_window_key),
+            mint_rate_limit: Item::new(self.mint_rate_limit_key),
+            mint_rate_usage: Item::new(self.mint_rate_usage_key),
+            burnable: Item::new(self.burnable_key),
+            burn_windows: Item::new(self.burn_windows_key),
+            burn_offers: Map::new(self.burn_offers_key),
+            transfer_offers: Map::new(self.transfer_offers_key),
+            gift_locks: Map::new(self.gift_locks_key),
+            gift_claim_commitments: Map::new(self.gift_claim_commitments_key),
+            range_approvals: Map::new(self.range_approvals_key),
+            protocol_fee: Item::new(self.protocol_fee_key),
+            frozen: Item::new(self.frozen_key),
+            guardian: Item::new(self.guardian_key),
+            paused: Item::new(self.paused_key),
+            timelock: Item::new(self.timelock_key),
+            queued_actions: Map::new(self.queued_actions_key),
+            queued_action_seq: Item::new(self.queued_action_seq_key),
+            import_mode: Item::new(self.import_mode_key),
+            origin_token_ids: Map::new(self.origin_token_ids_key),
+            _custom_response: PhantomData,
+            _custom_execute: PhantomData,
+        }
+    }
 }
 
 pub fn token_owner_idx<TMetadataExtension>(_pk: &[u8], d: &NftInfo<TMetadataExtension>) -> Addr {
     d.owner.clone()
 }
 
-#[cw_serde]
+/// Stored as `VersionedNftInfo` envelope and upgraded to the current layout on load,
+/// so fields can be added without migrating all tokens.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[serde(
+    from = "VersionedNftInfo<TMetadataExtension>",
+    into = "VersionedNftInfo<TMetadataExtension>",
+    bound(
+        serialize = "TMetadataExtension: Serialize + Clone",
+        deserialize = "TMetadataExtension: DeserializeOwned"
+    )
+)]
 pub struct NftInfo<TMetadataExtension> {
     /// The owner of the newly minted NFT
     pub owner: Addr,
-    /// Approvals are stored here, as we clear them all upon transfer and cannot accumulate much
+    /// Legacy approvals only, approvals are stored in `Cw721Config::token_approvals`.
+    /// Cleared upon transfer.
     pub approvals: Vec<Approval>,
 
     /// Universal resource identifier for this NFT
@@ -121,20 +1089,534 @@ pub struct NftInfo<TMetadataExtension> {
 
     /// You can add any custom metadata here when you extend cw721-base
     pub extension: TMetadataExtension,
+
+    /// Block height and time of the last transfer or send, `None` if never transferred since mint
+    pub last_transferred_at: Option<TransferredAt>,
+
+    /// Sha256 of the off-chain metadata JSON, set at mint, see `VerifyMetadata` query
+    pub metadata_hash: Option<HexBinary>,
+}
+
+/// Token as stored before v0.9, only used for migrating pre-v0.16 layouts. Up to v0.5 owner and
+/// spenders are canonical addresses (base64), and metadata is stored in `name`, `description` and `image`
+/// instead of `token_uri` and `extension`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LegacyTokenInfo {
+    pub owner: String,
+    #[serde(default)]
+    pub approvals: Vec<LegacyApproval>,
+    #[serde(default)]
+    pub token_uri: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub image: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LegacyApproval {
+    pub spender: String,
+    pub expires: Expiration,
+}
+
+/// Storage envelope of `NftInfo`, tokens saved before versioning are stored without it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[serde(untagged)]
+pub enum VersionedNftInfo<TMetadataExtension> {
+    Versioned(NftInfoVersion<TMetadataExtension>),
+    Unversioned(NftInfoV1<TMetadataExtension>),
+}
+
+/// Stored as `{"version": "v1", "data": {..}}`. To change the `NftInfo` layout, add a variant
+/// with the new layout and upgrade older ones in `From<VersionedNftInfo>` for `NftInfo`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[serde(tag = "version", content = "data", rename_all = "snake_case")]
+pub enum NftInfoVersion<TMetadataExtension> {
+    V1(NftInfoV1<TMetadataExtension>),
+}
+
+/// Layout of `NftInfo` version 1, must not be changed once stored
+#[cw_serde]
+pub struct NftInfoV1<TMetadataExtension> {
+    pub owner: Addr,
+    pub approvals: Vec<Approval>,
+    pub token_uri: Option<String>,
+    pub extension: TMetadataExtension,
+    pub last_transferred_at: Option<TransferredAt>,
+    pub metadata_hash: Option<HexBinary>,
+}
+
+impl<TMetadataExtension> From<VersionedNftInfo<TMetadataExtension>>
+    for NftInfo<TMetadataExtension>
+{
+    fn from(stored: VersionedNftInfo<TMetadataExtension>) -> Self {
+        let v1 = match stored {
+            VersionedNftInfo::Versioned(NftInfoVersion::V1(v1)) => v1,
+            VersionedNftInfo::Unversioned(v1) => v1,
+        };
+        NftInfo {
+            owner: v1.owner,
+            approvals: v1.approvals,
+            token_uri: v1.token_uri,
+            extension: v1.extension,
+            last_transferred_at: v1.last_transferred_at,
+            metadata_hash: v1.metadata_hash,
+        }
+    }
+}
+
+impl<TMetadataExtension> From<NftInfo<TMetadataExtension>>
+    for VersionedNftInfo<TMetadataExtension>
+{
+    fn from(token: NftInfo<TMetadataExtension>) -> Self {
+        VersionedNftInfo::Versioned(NftInfoVersion::V1(NftInfoV1 {
+            owner: token.owner,
+            approvals: token.approvals,
+            token_uri: token.token_uri,
+            extension: token.extension,
+            last_transferred_at: token.last_transferred_at,
+            metadata_hash: token.metadata_hash,
+        }))
+    }
+}
+
+#[cw_serde]
+pub struct TransferredAt {
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+impl From<&BlockInfo> for TransferredAt {
+    fn from(block: &BlockInfo) -> Self {
+        TransferredAt {
+            height: block.height,
+            time: block.time,
+        }
+    }
+}
+
+impl TransferredAt {
+    /// End of the transfer cooldown started by this transfer, errors on overflow of huge cooldowns
+    pub fn cooldown_ends(&self, cooldown: Duration) -> StdResult<Expiration> {
+        match cooldown {
+            Duration::Height(blocks) => Ok(Expiration::AtHeight(
+                Uint64::new(self.height)
+                    .checked_add(Uint64::new(blocks))?
+                    .u64(),
+            )),
+            Duration::Time(seconds) => {
+                let nanos = Uint64::new(seconds).checked_mul(Uint64::new(1_000_000_000))?;
+                let ends = Uint64::new(self.time.nanos()).checked_add(nanos)?;
+                Ok(Expiration::AtTime(Timestamp::from_nanos(ends.u64())))
+            }
+        }
+    }
+}
+
+/// Scheduled launch: minting is rejected before `start` and from `end` on
+#[cw_serde]
+pub struct MintWindow {
+    pub start: Option<Timestamp>,
+    pub end: Option<Timestamp>,
+}
+
+/// Throttles minting to `max_mints` per window, e.g. `Duration::Height(1)` for a per block cap.
+/// Windows are fixed, i.e. block height or time divided by window length.
+#[cw_serde]
+pub struct MintRateLimit {
+    pub max_mints: u32,
+    pub window: Duration,
+}
+
+impl MintRateLimit {
+    /// Index of the window containing given block
+    pub fn window_index(&self, block: &BlockInfo) -> u64 {
+        match self.window {
+            Duration::Height(blocks) => block.height / blocks,
+            Duration::Time(seconds) => block.time.seconds() / seconds,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct MintRateUsage {
+    /// See `MintRateLimit::window_index`
+    pub window: u64,
+    pub minted: u32,
+}
+
+#[cw_serde]
+pub struct Burnable {
+    pub burnable: bool,
+    /// If true, tokens are only burned by creator on `ConfirmBurn` after holder's `OfferBurn`
+    pub creator_gated: bool,
+    /// If true, `burnable` and `creator_gated` can't be changed anymore
+    pub locked: bool,
 }
 
+pub const MAX_BURN_WINDOWS: usize = 20;
+
+/// Burning is allowed from `start` until before `end`
+#[cw_serde]
+pub struct BurnWindow {
+    pub start: Timestamp,
+    pub end: Timestamp,
+}
+
+impl BurnWindow {
+    pub fn contains(&self, time: Timestamp) -> bool {
+        self.start <= time && time < self.end
+    }
+}
+
+impl Default for Burnable {
+    fn default() -> Self {
+        Self {
+            burnable: true,
+            creator_gated: false,
+            locked: false,
+        }
+    }
+}
+
+/// Collection flags rejecting mints of tokens whose metadata already exists, e.g. for 1/1 art.
+/// Requires `uri-index` feature.
+#[cw_serde]
+#[derive(Default)]
+pub struct UniqueMetadata {
+    /// Rejects minting a token_uri already stored for another token
+    pub token_uri: bool,
+    /// Rejects minting an extension equal to another token's extension
+    pub extension: bool,
+}
+
+/// Holder's consent to burn a token, only valid as long as `owner` holds the token
+#[cw_serde]
+pub struct BurnOffer {
+    pub owner: Addr,
+    pub height: u64,
+}
+
+/// Two-step transfer of a token to `recipient`, only valid as long as `owner` holds the token
+#[cw_serde]
+pub struct TransferOffer {
+    pub owner: Addr,
+    pub recipient: Addr,
+    pub expires: Expiration,
+}
+
+impl TransferOffer {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+}
+
+/// Token escrowed by the contract, claimable by anyone presenting the secret of `hash` until expired.
+/// Claims must be committed in an earlier block, see `gift_claim_commitments`.
+#[cw_serde]
+pub struct GiftLock {
+    /// Owner who locked the gift and may reclaim it once expired
+    pub owner: Addr,
+    /// Sha256 of the secret
+    pub hash: HexBinary,
+    pub expires: Expiration,
+}
+
+impl GiftLock {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+}
+
+#[cw_serde]
+pub struct BurnedToken {
+    /// Owner at the time of burn
+    pub owner: Addr,
+    pub burner: Addr,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct AuditEntry {
+    /// Sender of the privileged action
+    pub actor: Addr,
+    /// Name of the action, e.g. `set_contract_uri` or `transfer_ownership`
+    pub action: String,
+    pub height: u64,
+}
+
+/// Target of a prepared two-phase migration, cw2 version is only updated on commit.
+#[cw_serde]
+pub struct PendingMigration {
+    pub contract_name: String,
+    pub contract_version: String,
+    /// Token count at prepare time, must be unchanged on commit
+    pub token_count: u64,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct RevealInfo {
+    /// Returned as token uri of all tokens until revealed
+    pub placeholder_uri: String,
+    pub revealed: bool,
+    /// Once revealed, token uri is `{base_uri}{token_id}`. If not set, stored token uri is used.
+    pub base_uri: Option<String>,
+    /// Set on reveal in case a randomness source is used, shuffles `base_uri` token uris
+    pub shuffle: Option<TokenShuffle>,
+}
+
+impl RevealInfo {
+    pub fn token_uri(&self, token_id: &str, token_uri: Option<String>) -> Option<String> {
+        if !self.revealed {
+            return Some(self.placeholder_uri.clone());
+        }
+        match &self.base_uri {
+            Some(base_uri) => {
+                let metadata_id = match &self.shuffle {
+                    Some(shuffle) => shuffle.metadata_id(token_id),
+                    None => token_id.to_string(),
+                };
+                Some(format!("{base_uri}{metadata_id}"))
+            }
+            None => token_uri,
+        }
+    }
+}
+
+/// Job id sent to randomness source on `RequestRandomness`
+pub const RANDOMNESS_JOB_ID: &str = "cw721-reveal";
+
+/// Assigns numeric token id `n` (in `0..supply`) the metadata of `(n + offset) % supply`, where
+/// `offset` is derived from the randomness received before reveal. Anyone can verify the
+/// assignment against the committed provenance hash.
+#[cw_serde]
+pub struct TokenShuffle {
+    pub randomness: HexBinary,
+    /// Number of tokens at reveal
+    pub supply: u64,
+    pub offset: u64,
+}
+
+impl TokenShuffle {
+    pub fn new(randomness: HexBinary, supply: u64) -> Self {
+        let mut bytes = [0u8; 8];
+        let len = randomness.len().min(8);
+        bytes[..len].copy_from_slice(&randomness.as_slice()[..len]);
+        let offset = match supply {
+            0 => 0,
+            supply => u64::from_be_bytes(bytes) % supply,
+        };
+        TokenShuffle {
+            randomness,
+            supply,
+            offset,
+        }
+    }
+
+    /// Ids which are not numeric or out of `0..supply` keep their own metadata
+    pub fn metadata_id(&self, token_id: &str) -> String {
+        match token_id.parse::<u64>() {
+            Ok(n) if n < self.supply => ((n + self.offset) % self.supply).to_string(),
+            _ => token_id.to_string(),
+        }
+    }
+}
+
+/// Approval permission to transfer the token
+pub const APPROVAL_PERMISSION_TRANSFER: u8 = 1;
+/// Approval permission to send the token to a contract
+pub const APPROVAL_PERMISSION_SEND: u8 = 1 << 1;
+/// Approval permission to burn the token
+pub const APPROVAL_PERMISSION_BURN: u8 = 1 << 2;
+/// Approval permission to update the token's metadata, enforced by contracts supporting metadata updates
+pub const APPROVAL_PERMISSION_UPDATE_METADATA: u8 = 1 << 3;
+/// All approval permissions, default for approvals without explicit permissions
+pub const APPROVAL_PERMISSION_ALL: u8 = APPROVAL_PERMISSION_TRANSFER
+    | APPROVAL_PERMISSION_SEND
+    | APPROVAL_PERMISSION_BURN
+    | APPROVAL_PERMISSION_UPDATE_METADATA;
+
 #[cw_serde]
 pub struct Approval {
     /// Account that can transfer/send the token
     pub spender: Addr,
     /// When the Approval expires (maybe Expiration::never)
     pub expires: Expiration,
+    /// Bitmask of `APPROVAL_PERMISSION_*` flags granted to spender.
+    /// Legacy approvals without permissions have all permissions.
+    #[serde(default = "default_approval_permissions")]
+    pub permissions: u8,
+}
+
+fn default_approval_permissions() -> u8 {
+    APPROVAL_PERMISSION_ALL
 }
 
 impl Approval {
     pub fn is_expired(&self, block: &BlockInfo) -> bool {
         self.expires.is_expired(block)
     }
+
+    pub fn has_permission(&self, permission: u8) -> bool {
+        self.permissions & permission == permission
+    }
+}
+
+/// Approval of all tokens with numeric id in `start_id..=end_id`, checked against the token id on
+/// transfer instead of writing an approval per token. Applies to tokens the granter owns at that time.
+#[cw_serde]
+pub struct RangeApproval {
+    pub start_id: u64,
+    pub end_id: u64,
+    pub expires: Expiration,
+}
+
+impl RangeApproval {
+    pub fn is_expired(&self, block: &BlockInfo) -> bool {
+        self.expires.is_expired(block)
+    }
+
+    /// Whether token id is a number within range, e.g. "42"
+    pub fn contains(&self, token_id: &str) -> bool {
+        token_id
+            .parse::<u64>()
+            .map_or(false, |id| self.start_id <= id && id <= self.end_id)
+    }
+}
+
+/// Subset of the granter's tokens an `ApproveAll` operator may transfer, send, burn and approve.
+/// Applies to tokens the granter owns at that time.
+#[cw_serde]
+pub enum OperatorFilter {
+    /// Token ids starting with prefix, e.g. "edition-"
+    TokenIdPrefix(String),
+    /// Explicit token ids, at most `MAX_OPERATOR_FILTER_TOKEN_IDS`
+    TokenIds(Vec<String>),
+}
+
+impl OperatorFilter {
+    pub fn matches(&self, token_id: &str) -> bool {
+        match self {
+            OperatorFilter::TokenIdPrefix(prefix) => token_id.starts_with(prefix.as_str()),
+            OperatorFilter::TokenIds(token_ids) => token_ids.iter().any(|id| id == token_id),
+        }
+    }
+}
+
+pub const MAX_OPERATOR_FILTER_TOKEN_IDS: usize = 100;
+
+/// Fee in a native denom, forwarded to withdraw address (kept in contract if not set)
+#[cw_serde]
+pub enum TransferFee {
+    /// Exactly this amount must be attached
+    Flat(Coin),
+    /// Share of funds attached in denom (e.g. sale price paid on transfer) is taken as fee,
+    /// remaining funds are paid to the previous owner
+    Percentage { denom: String, share: Decimal },
+}
+
+/// Protocol fee taken from funds attached to mints, e.g. by a sale contract acting as minter
+#[cw_serde]
+pub struct ProtocolFee {
+    /// Fee in basis points of attached funds, max `MAX_PROTOCOL_FEE_BPS`
+    pub bps: u16,
+    /// Receives the fee, e.g. community pool
+    pub recipient: Addr,
+}
+
+impl ProtocolFee {
+    /// Fee of each coin, rounded down. Zero amounts are skipped.
+    pub fn fees(&self, funds: &[Coin]) -> Vec<Coin> {
+        funds
+            .iter()
+            .map(|coin| {
+                Coin::new(
+                    coin.amount
+                        .multiply_ratio(self.bps, MAX_PROTOCOL_FEE_BPS)
+                        .u128(),
+                    &coin.denom,
+                )
+            })
+            .filter(|coin| !coin.amount.is_zero())
+            .collect()
+    }
+}
+
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 10_000;
+
+/// Privileged config change queued by the timelock
+#[cw_serde]
+pub struct QueuedAction {
+    /// Name of the action, e.g. `set_contract_uri`
+    pub action: String,
+    /// The queued `Cw721ExecuteMsg` as json
+    pub msg: Binary,
+    pub proposer: Addr,
+    /// Owner can execute it once expired
+    pub executable_at: Expiration,
+}
+
+/// Emergency pause by owner or guardian, only owner can unpause
+#[cw_serde]
+pub struct PauseInfo {
+    pub paused_by: Addr,
+    /// Block height of the pause
+    pub height: u64,
+}
+
+/// Collection freeze by chain governance, e.g. for exploit response on permissioned chains
+#[cw_serde]
+pub struct FreezeInfo {
+    pub reason: String,
+    /// Block height of the freeze
+    pub height: u64,
+}
+
+pub const DEFAULT_MAX_TOKEN_APPROVALS: u32 = 25;
+pub const DEFAULT_MAX_OPERATORS: u32 = 100;
+
+#[cw_serde]
+pub struct ApprovalLimits {
+    /// Max number of approvals a single token can have
+    pub max_token_approvals: u32,
+    /// Max number of operators a granter can register
+    pub max_operators: u32,
+}
+
+impl Default for ApprovalLimits {
+    fn default() -> Self {
+        ApprovalLimits {
+            max_token_approvals: DEFAULT_MAX_TOKEN_APPROVALS,
+            max_operators: DEFAULT_MAX_OPERATORS,
+        }
+    }
+}
+
+pub const DEFAULT_MAX_TOKEN_URI_BYTES: u32 = 2048;
+pub const DEFAULT_MAX_EXTENSION_BYTES: u32 = 16 * 1024;
+
+/// Storage writes cost gas per byte and a token is rewritten on every transfer, so limits keep
+/// batch operations within block gas limits.
+#[cw_serde]
+pub struct MetadataLimits {
+    /// Max length of token_uri in bytes
+    pub max_token_uri_bytes: u32,
+    /// Max size of JSON serialized extension in bytes
+    pub max_extension_bytes: u32,
+}
+
+impl Default for MetadataLimits {
+    fn default() -> Self {
+        MetadataLimits {
+            max_token_uri_bytes: DEFAULT_MAX_TOKEN_URI_BYTES,
+            max_extension_bytes: DEFAULT_MAX_EXTENSION_BYTES,
+        }
+    }
 }
 
 pub struct TokenIndexes<'a, TMetadataExtension>
@@ -152,15 +1634,63 @@ where
     fn get_indexes(
         &'_ self,
     ) -> Box<dyn Iterator<Item = &'_ dyn Index<NftInfo<TMetadataExtension>>> + '_> {
+        // owner index is only maintained for enumerable queries, saving writes on mint/transfer/burn otherwise.
+        // In `plain-storage` mode owner lookup is maintained in `Cw721Config::owner_tokens` instead.
+        #[cfg(all(feature = "enumerable", not(feature = "plain-storage")))]
         let v: Vec<&dyn Index<NftInfo<TMetadataExtension>>> = vec![&self.owner];
+        #[cfg(any(not(feature = "enumerable"), feature = "plain-storage"))]
+        let v: Vec<&dyn Index<NftInfo<TMetadataExtension>>> = vec![];
         Box::new(v.into_iter())
     }
 }
 
+/// Returns extension as `Metadata`, or `None` in case it is a different type of extension (or not set).
+pub fn as_metadata<TMetadataExtension: Serialize>(
+    extension: &TMetadataExtension,
+) -> StdResult<Option<Metadata>> {
+    Ok(from_json::<Option<Metadata>>(to_json_binary(extension)?)
+        .ok()
+        .flatten())
+}
+
 #[cw_serde]
 pub struct CollectionInfo {
     pub name: String,
     pub symbol: String,
+    /// Denoms royalties are accepted in, unset if creator didn't declare any
+    #[serde(default)]
+    pub royalty_payout_denoms: Option<Vec<PayoutDenom>>,
+    /// Lineage of a collection bridged or migrated from another chain, set once by the owner
+    #[serde(default)]
+    pub origin: Option<OriginInfo>,
+}
+
+/// Origin of a collection on another chain, e.g. Ethereum
+#[cw_serde]
+pub struct OriginInfo {
+    /// Chain id of the origin chain, e.g. "1" for Ethereum mainnet
+    pub chain_id: String,
+    /// Collection contract address on the origin chain
+    pub contract: String,
+    /// Hash of the bridge or migration transaction, if any
+    pub bridge_tx: Option<String>,
+}
+
+/// Denom a royalty payout may be made in
+#[cw_serde]
+pub enum PayoutDenom {
+    Native { denom: String },
+    Cw20 { address: String },
+}
+
+impl PayoutDenom {
+    /// Native denom or cw20 contract address
+    pub fn as_str(&self) -> &str {
+        match self {
+            PayoutDenom::Native { denom } => denom,
+            PayoutDenom::Cw20 { address } => address,
+        }
+    }
 }
 
 // see: https://docs.opensea.io/docs/metadata-standards
@@ -176,6 +1706,62 @@ pub struct Metadata {
     pub background_color: Option<String>,
     pub animation_url: Option<String>,
     pub youtube_url: Option<String>,
+    /// Name and description per locale (e.g. "de", "pt-BR"), see `Metadata::localized`
+    pub localizations: Option<BTreeMap<String, LocalizedText>>,
+    /// Additional media assets besides `image` and `animation_url`, validated on mint
+    pub media: Option<Vec<MediaAsset>>,
+}
+
+impl Metadata {
+    /// Resolves name and description for given locale. Falls back to its language
+    /// (e.g. "pt" for "pt-BR") and then to default name and description.
+    pub fn localized(mut self, locale: &str) -> Self {
+        let Some(localizations) = &self.localizations else {
+            return self;
+        };
+        let language = locale.split(['-', '_']).next().unwrap_or(locale);
+        let text = localizations
+            .get(locale)
+            .or_else(|| localizations.get(language))
+            .cloned()
+            .unwrap_or_default();
+        if let Some(name) = text.name {
+            self.name = Some(name);
+        }
+        if let Some(description) = text.description {
+            self.description = Some(description);
+        }
+        self
+    }
+}
+
+pub const MAX_MEDIA_ASSETS: usize = 20;
+
+pub const MAX_INITIAL_TOKENS: usize = 50;
+
+#[cw_serde]
+pub struct MediaAsset {
+    pub uri: String,
+    /// e.g. "image/png" or "model/gltf-binary"
+    pub mime_type: String,
+    pub role: MediaRole,
+}
+
+#[cw_serde]
+pub enum MediaRole {
+    Thumbnail,
+    Image,
+    HighResolution,
+    Animation,
+    Model,
+    Other,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct LocalizedText {
+    pub name: Option<String>,
+    pub description: Option<String>,
 }
 
 #[cw_serde]
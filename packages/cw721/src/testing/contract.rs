@@ -2,8 +2,8 @@ use cosmwasm_std::CustomMsg;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::execute::Cw721Execute;
-use crate::query::Cw721Query;
+use crate::execute::{Cw721CustomExecute, Cw721Execute};
+use crate::query::{Cw721CustomQuery, Cw721Query};
 use crate::state::Cw721Config;
 
 pub struct Cw721Contract<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
@@ -46,3 +46,23 @@ where
     TMetadataExtensionMsg: CustomMsg,
 {
 }
+
+impl<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+    Cw721CustomQuery<TMetadataExtension>
+    for Cw721Contract<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TCustomResponseMessage: CustomMsg,
+    TMetadataExtensionMsg: CustomMsg,
+{
+}
+
+impl<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+    Cw721CustomExecute<TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+    for Cw721Contract<'a, TMetadataExtension, TCustomResponseMessage, TMetadataExtensionMsg>
+where
+    TMetadataExtension: Serialize + DeserializeOwned + Clone,
+    TCustomResponseMessage: CustomMsg,
+    TMetadataExtensionMsg: CustomMsg,
+{
+}
@@ -1,20 +1,50 @@
 #![cfg(test)]
 
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use std::collections::BTreeMap;
+
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
+};
 
 use cosmwasm_std::{
-    from_json, to_json_binary, Addr, Coin, CosmosMsg, DepsMut, Empty, Response, StdError, WasmMsg,
+    coins, from_json, to_json_binary, Addr, Attribute, BankMsg, Binary, Coin, ContractInfoResponse,
+    ContractResult, CosmosMsg, Decimal, Deps, DepsMut, Empty, Event, HexBinary, Response, StdError,
+    SubMsg, SystemError, SystemResult, WasmMsg, WasmQuery,
 };
+use sha2::{Digest, Sha256};
 
 use crate::error::Cw721ContractError;
+use crate::execute::{add_cw721_event, gift_claim_commitment};
+use crate::msg::{
+    AllNftInfoResponse, ApprovalResponse, ApprovalsResponse, AuditEntryResponse, AuditLogResponse,
+    BurnedTokenResponse, MintRateLimitResponse, NftInfoResponse, OperatorGrant,
+    OperatorGrantsResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse,
+    PersistentOperatorsResponse, QueuedActionResponse, QueuedActionsResponse, Role,
+    RoleMembersResponse, RolesResponse, RoyaltyConfigResponse, SupportedInterfacesResponse,
+    TokenOriginResponse, TokenStatusResponse, TokensResponse, TradingScheduleResponse, TraitCount,
+    TraitStatsResponse, VerifyMetadataResponse, VerifyOwnershipProofResponse,
+};
 use crate::msg::{
-    ApprovalResponse, NftInfoResponse, OperatorResponse, OperatorsResponse, OwnerOfResponse,
+    Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721QueryMsg, Cw721SudoMsg, ImportedToken, InitialToken,
+    MintApproval, NoisCallback, RandomnessSourceMsg,
+};
+use crate::query::{
+    ownership_proof_message, INTERFACE_BASE, INTERFACE_ENUMERABLE, INTERFACE_METADATA_ONCHAIN,
+    INTERFACE_ROYALTIES,
 };
-use crate::msg::{Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721QueryMsg};
 use crate::receiver::Cw721ReceiveMsg;
-use crate::state::{CollectionInfo, DefaultOptionMetadataExtension, MINTER};
+use crate::registry::{IsOperatorAllowedResponse, OperatorRegistryQueryMsg};
+use crate::state::{
+    ApprovalLimits, BurnOffer, BurnWindow, Burnable, CollectionInfo, Cw721Config,
+    DefaultOptionMetadataExtension, FreezeInfo, GiftLock, Metadata, MetadataLimits, MintRateLimit,
+    MintWindow, OperatorFilter, OriginInfo, PauseInfo, PayoutDenom, ProtocolFee, RangeApproval,
+    RevealInfo, Trait, TransferFee, TransferOffer, TransferredAt, APPROVAL_PERMISSION_ALL,
+    APPROVAL_PERMISSION_TRANSFER, MAX_BURN_WINDOWS, MAX_INITIAL_TOKENS,
+    MAX_OPERATOR_FILTER_TOKEN_IDS, MINTER, RANDOMNESS_JOB_ID,
+};
 use crate::{execute::Cw721Execute, query::Cw721Query, Approval, Expiration};
 use cw_ownable::{Action, Ownership, OwnershipError};
+use cw_utils::Duration;
 
 use super::contract::Cw721Contract;
 
@@ -32,17 +62,12 @@ fn setup_contract(
         symbol: SYMBOL.to_string(),
         minter: Some(String::from(MINTER_ADDR)),
         withdraw_address: None,
+        initial_tokens: None,
+        import_mode: None,
     };
     let info = mock_info("creator", &[]);
     let res = contract
-        .instantiate(
-            deps,
-            mock_env(),
-            info,
-            msg,
-            "contract_name",
-            "contract_version",
-        )
+        .instantiate(deps, mock_env(), info, msg, "contract_name", "1.0.0")
         .unwrap();
     assert_eq!(0, res.messages.len());
     contract
@@ -58,6 +83,8 @@ fn proper_instantiation() {
         symbol: SYMBOL.to_string(),
         minter: Some(String::from(MINTER_ADDR)),
         withdraw_address: Some(String::from(CREATOR_ADDR)),
+        initial_tokens: None,
+        import_mode: None,
     };
     let info = mock_info("creator", &[]);
     let env = mock_env();
@@ -70,7 +97,7 @@ fn proper_instantiation() {
             info,
             msg,
             "contract_name",
-            "contract_version",
+            "1.0.0",
         )
         .unwrap();
     assert_eq!(0, res.messages.len());
@@ -86,6 +113,8 @@ fn proper_instantiation() {
         CollectionInfo {
             name: CONTRACT_NAME.to_string(),
             symbol: SYMBOL.to_string(),
+            royalty_payout_denoms: None,
+            origin: None,
         }
     );
 
@@ -118,6 +147,8 @@ fn proper_instantiation_with_collection_info() {
         symbol: SYMBOL.to_string(),
         minter: Some(String::from(MINTER_ADDR)),
         withdraw_address: Some(String::from(CREATOR_ADDR)),
+        initial_tokens: None,
+        import_mode: None,
     };
     let collection_info = mock_info("creator", &[]);
     let env = mock_env();
@@ -130,7 +161,7 @@ fn proper_instantiation_with_collection_info() {
             collection_info,
             msg,
             "contract_name",
-            "contract_version",
+            "1.0.0",
         )
         .unwrap();
     assert_eq!(0, res.messages.len());
@@ -146,6 +177,8 @@ fn proper_instantiation_with_collection_info() {
         CollectionInfo {
             name: CONTRACT_NAME.to_string(),
             symbol: SYMBOL.to_string(),
+            royalty_payout_denoms: None,
+            origin: None,
         }
     );
 
@@ -168,6 +201,273 @@ fn proper_instantiation_with_collection_info() {
     assert_eq!(0, tokens.tokens.len());
 }
 
+#[test]
+fn instantiation_with_initial_tokens() {
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let deed = |token_id: &str| InitialToken {
+        token_id: token_id.to_string(),
+        owner: "demeter".to_string(),
+        token_uri: Some(format!("ipfs://deeds/{token_id}")),
+        extension: None,
+    };
+    let mut msg = Cw721InstantiateMsg {
+        name: CONTRACT_NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        minter: Some(String::from(MINTER_ADDR)),
+        withdraw_address: None,
+        initial_tokens: Some(
+            (0..=MAX_INITIAL_TOKENS)
+                .map(|i| deed(&i.to_string()))
+                .collect(),
+        ),
+        import_mode: None,
+    };
+
+    // list is bounded
+    let err = contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            msg.clone(),
+            "contract_name",
+            "1.0.0",
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::TooManyInitialTokens {
+            max: MAX_INITIAL_TOKENS
+        }
+    );
+
+    // tokens are minted by the minter, not the instantiating account
+    let metadata = Metadata {
+        name: Some("Deed of the north field".to_string()),
+        ..Metadata::default()
+    };
+    msg.initial_tokens = Some(vec![
+        deed("deed-1"),
+        InitialToken {
+            extension: Some(to_json_binary(&Some(metadata.clone())).unwrap()),
+            ..deed("deed-2")
+        },
+    ]);
+    let res = contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            msg.clone(),
+            "contract_name",
+            "1.0.0",
+        )
+        .unwrap();
+    assert_eq!(0, res.messages.len());
+    assert_eq!(
+        res.attributes,
+        vec![
+            Attribute::new("minter", MINTER_ADDR),
+            Attribute::new("initial_token", "deed-1"),
+            Attribute::new("initial_token", "deed-2"),
+        ]
+    );
+
+    let count = contract
+        .query_num_tokens(deps.as_ref(), mock_env())
+        .unwrap();
+    assert_eq!(2, count.count);
+    let owner = contract
+        .query_owner_of(deps.as_ref(), mock_env(), "deed-1".to_string(), false)
+        .unwrap();
+    assert_eq!("demeter", owner.owner);
+    let info = contract
+        .query_nft_info(deps.as_ref(), mock_env(), "deed-1".to_string())
+        .unwrap();
+    assert_eq!(Some("ipfs://deeds/deed-1".to_string()), info.token_uri);
+    assert_eq!(None, info.extension);
+    let info = contract
+        .query_nft_info(deps.as_ref(), mock_env(), "deed-2".to_string())
+        .unwrap();
+    assert_eq!(Some(metadata), info.extension);
+
+    // duplicates fail the whole instantiation
+    let mut deps = mock_dependencies();
+    msg.initial_tokens = Some(vec![deed("deed-1"), deed("deed-1")]);
+    let err = contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            msg,
+            "contract_name",
+            "1.0.0",
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Claimed {});
+}
+
+#[test]
+fn genesis_import() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let import = |token_ids: &[&str]| Cw721ExecuteMsg::ImportTokens {
+        tokens: token_ids
+            .iter()
+            .map(|token_id| ImportedToken {
+                token_id: token_id.to_string(),
+                owner: "demeter".to_string(),
+                token_uri: None,
+                extension: None,
+                origin_token_id: None,
+            })
+            .collect(),
+    };
+    let import_mode = |deps: Deps| -> bool {
+        from_json(
+            contract
+                .query(deps, mock_env(), Cw721QueryMsg::ImportMode {})
+                .unwrap(),
+        )
+        .unwrap()
+    };
+
+    // not open unless flagged at instantiation
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            import(&["1"]),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::ImportNotOpen {});
+    assert!(!import_mode(deps.as_ref()));
+
+    let mut deps = mock_dependencies();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            Cw721InstantiateMsg {
+                name: CONTRACT_NAME.to_string(),
+                symbol: SYMBOL.to_string(),
+                minter: Some(String::from(MINTER_ADDR)),
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: Some(true),
+            },
+            "contract_name",
+            "1.0.0",
+        )
+        .unwrap();
+    assert!(import_mode(deps.as_ref()));
+
+    // only minter can import
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            import(&["1"]),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // a single event per batch, ignoring the mint window
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::SetMintWindow {
+                window: Some(MintWindow {
+                    start: Some(mock_env().block.time.plus_seconds(1)),
+                    end: None,
+                }),
+            },
+        )
+        .unwrap();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            import(&["1", "2", "3"]),
+        )
+        .unwrap();
+    assert_eq!(
+        res.attributes,
+        vec![
+            Attribute::new("action", "import_tokens"),
+            Attribute::new("minter", MINTER_ADDR),
+            Attribute::new("count", "3"),
+        ]
+    );
+    assert_eq!(1, res.events.len());
+    let count = contract
+        .query_num_tokens(deps.as_ref(), mock_env())
+        .unwrap();
+    assert_eq!(3, count.count);
+    let tokens = contract
+        .query_tokens(deps.as_ref(), mock_env(), "demeter".to_string(), None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["1", "2", "3"]);
+
+    // existing tokens aren't overwritten
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            import(&["4", "2"]),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Claimed {});
+
+    // only owner can close
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            Cw721ExecuteMsg::CloseImport {},
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::CloseImport {},
+        )
+        .unwrap();
+    assert!(!import_mode(deps.as_ref()));
+
+    // closed for good
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            import(&["4"]),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::ImportClosed {});
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::CloseImport {},
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::ImportNotOpen {});
+}
+
 #[test]
 fn minting() {
     let mut deps = mock_dependencies();
@@ -181,6 +481,8 @@ fn minting() {
         owner: String::from("medusa"),
         token_uri: Some(token_uri.clone()),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     // random cannot mint
@@ -238,6 +540,8 @@ fn minting() {
         owner: String::from("hercules"),
         token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     let allowed = mock_info(MINTER_ADDR, &[]);
@@ -254,6 +558,91 @@ fn minting() {
     assert_eq!(vec![token_id], tokens.tokens);
 }
 
+#[test]
+fn mint_window() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let mut env = mock_env();
+    let start = env.block.time.plus_seconds(100);
+    let end = env.block.time.plus_seconds(200);
+
+    // end must be after start
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetMintWindow {
+                window: Some(MintWindow {
+                    start: Some(end),
+                    end: Some(start),
+                }),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::InvalidMintWindow {});
+
+    let window = MintWindow {
+        start: Some(start),
+        end: Some(end),
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetMintWindow {
+                window: Some(window.clone()),
+            },
+        )
+        .unwrap();
+    let res: Option<MintWindow> = from_json(
+        contract
+            .query(deps.as_ref(), env.clone(), Cw721QueryMsg::MintWindow {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, Some(window));
+
+    let mint_msg = |token_id: &str| Cw721ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: String::from("medusa"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("1"))
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::MintNotStarted { start });
+
+    env.block.time = start;
+    contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("1"))
+        .unwrap();
+
+    env.block.time = end;
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("2"))
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::MintEnded { end });
+
+    // unset window allows minting again
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetMintWindow { window: None },
+        )
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), env, minter, mint_msg("2"))
+        .unwrap();
+}
+
 #[test]
 fn test_update_minter() {
     let mut deps = mock_dependencies();
@@ -267,6 +656,8 @@ fn test_update_minter() {
         owner: String::from("medusa"),
         token_uri: Some(token_uri.clone()),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     // Minter can mint
@@ -307,6 +698,31 @@ fn test_update_minter() {
         }
     );
 
+    // both roles including pending transfer in one response
+    let roles: RolesResponse = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), Cw721QueryMsg::Roles {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        roles,
+        RolesResponse {
+            minter: ownership,
+            // creator is not affected by minter transfer
+            creator: Ownership {
+                owner: Some(Addr::unchecked(MINTER_ADDR)),
+                pending_owner: None,
+                pending_expiry: None,
+            },
+            royalty_admin: Ownership {
+                owner: None,
+                pending_owner: None,
+                pending_expiry: None,
+            },
+        }
+    );
+
     // Accept the ownership transfer.
     let random_info = mock_info("random", &[]);
     contract
@@ -332,6 +748,8 @@ fn test_update_minter() {
         owner: String::from("medusa"),
         token_uri: Some(token_uri),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     // Old owner can not mint.
@@ -359,6 +777,8 @@ fn burning() {
         owner: MINTER_ADDR.to_string(),
         token_uri: Some(token_uri),
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
 
     let burn_msg = Cw721ExecuteMsg::Burn { token_id };
@@ -395,740 +815,6861 @@ fn burning() {
 
     // list the token_ids
     let tokens = contract
-        .query_all_tokens(deps.as_ref(), env, None, None)
+        .query_all_tokens(deps.as_ref(), env.clone(), None, None)
         .unwrap();
     assert!(tokens.tokens.is_empty());
+
+    // burned token is recorded
+    assert!(contract
+        .query_is_burned(deps.as_ref(), env.clone(), "petrify".to_string())
+        .unwrap());
+    let burned = contract
+        .query_burned_tokens(deps.as_ref(), env.clone(), None, None)
+        .unwrap();
+    assert_eq!(
+        burned.tokens,
+        vec![BurnedTokenResponse {
+            token_id: "petrify".to_string(),
+            owner: Addr::unchecked(MINTER_ADDR),
+            burner: Addr::unchecked(MINTER_ADDR),
+            height: env.block.height,
+        }]
+    );
+
+    // minting same token id again removes it from burned tokens
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::Mint {
+                token_id: "petrify".to_string(),
+                owner: MINTER_ADDR.to_string(),
+                token_uri: None,
+                extension: None,
+                approval: None,
+                metadata_hash: None,
+            },
+        )
+        .unwrap();
+    assert!(!contract
+        .query_is_burned(deps.as_ref(), env, "petrify".to_string())
+        .unwrap());
 }
 
 #[test]
-fn transferring_nft() {
+fn retire_burned_tokens() {
     let mut deps = mock_dependencies();
     let contract = setup_contract(deps.as_mut());
-
-    // Mint a token
-    let token_id = "melt".to_string();
-    let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
-
+    let minter = mock_info(MINTER_ADDR, &[]);
     let mint_msg = Cw721ExecuteMsg::Mint {
-        token_id: token_id.clone(),
-        owner: String::from("venus"),
-        token_uri: Some(token_uri),
+        token_id: "petrify".to_string(),
+        owner: MINTER_ADDR.to_string(),
+        token_uri: None,
         extension: None,
+        approval: None,
+        metadata_hash: None,
     };
-
-    let minter = mock_info(MINTER_ADDR, &[]);
-    contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
-        .unwrap();
-
-    // random cannot transfer
-    let random = mock_info("random", &[]);
-    let transfer_msg = Cw721ExecuteMsg::TransferNft {
-        recipient: String::from("random"),
-        token_id: token_id.clone(),
+    let burn_msg = Cw721ExecuteMsg::Burn {
+        token_id: "petrify".to_string(),
     };
 
+    // only owner can retire burned tokens
     let err = contract
-        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            Cw721ExecuteMsg::SetRetireBurnedTokens { retire: true },
+        )
         .unwrap_err();
     assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetRetireBurnedTokens { retire: true },
+        )
+        .unwrap();
+    assert!(contract.query_retire_burned_tokens(deps.as_ref()).unwrap());
 
-    // owner can
-    let random = mock_info("venus", &[]);
-    let transfer_msg = Cw721ExecuteMsg::TransferNft {
-        recipient: String::from("random"),
-        token_id: token_id.clone(),
-    };
-
-    let res = contract
-        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg.clone())
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), burn_msg)
         .unwrap();
 
+    // burned token id can't be minted again
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg.clone())
+        .unwrap_err();
     assert_eq!(
-        res,
-        Response::new()
-            .add_attribute("action", "transfer_nft")
-            .add_attribute("sender", "venus")
-            .add_attribute("recipient", "random")
-            .add_attribute("token_id", token_id)
+        err,
+        Cw721ContractError::TokenRetired {
+            token_id: "petrify".to_string()
+        }
     );
+
+    // unless flag is unset
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetRetireBurnedTokens { retire: false },
+        )
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
 }
 
 #[test]
-fn sending_nft() {
+fn num_tokens_at_height() {
     let mut deps = mock_dependencies();
     let contract = setup_contract(deps.as_mut());
-
-    // Mint a token
-    let token_id = "melt".to_string();
-    let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
-
-    let mint_msg = Cw721ExecuteMsg::Mint {
-        token_id: token_id.clone(),
-        owner: String::from("venus"),
-        token_uri: Some(token_uri),
-        extension: None,
-    };
-
     let minter = mock_info(MINTER_ADDR, &[]);
-    contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
-        .unwrap();
-
-    let msg = to_json_binary("You now have the melting power").unwrap();
-    let target = String::from("another_contract");
-    let send_msg = Cw721ExecuteMsg::SendNft {
-        contract: target.clone(),
-        token_id: token_id.clone(),
-        msg: msg.clone(),
-    };
-
-    let random = mock_info("random", &[]);
-    let err = contract
-        .execute(deps.as_mut(), mock_env(), random, send_msg.clone())
-        .unwrap_err();
-    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    let mut env = mock_env();
+    let start = env.block.height;
 
-    // but owner can
-    let random = mock_info("venus", &[]);
-    let res = contract
-        .execute(deps.as_mut(), mock_env(), random, send_msg)
+    env.block.height = start + 1;
+    for token_id in ["1", "2"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: MINTER_ADDR.to_string(),
+                    token_uri: None,
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    env.block.height = start + 2;
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter,
+            Cw721ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+        )
         .unwrap();
 
-    let payload = Cw721ReceiveMsg {
-        sender: String::from("venus"),
-        token_id: token_id.clone(),
-        msg,
+    // count at the beginning of each block
+    let count_at = |deps: Deps, height: u64| {
+        contract
+            .query_num_tokens_at_height(deps, mock_env(), height)
+            .unwrap()
+            .count
     };
-    let expected = payload.into_cosmos_msg(target.clone()).unwrap();
-    // ensure expected serializes as we think it should
-    match &expected {
-        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
-            assert_eq!(contract_addr, &target)
-        }
-        m => panic!("Unexpected message type: {m:?}"),
-    }
-    // and make sure this is the request sent by the contract
+    assert_eq!(count_at(deps.as_ref(), start), 0);
+    assert_eq!(count_at(deps.as_ref(), start + 1), 0);
+    assert_eq!(count_at(deps.as_ref(), start + 2), 2);
+    assert_eq!(count_at(deps.as_ref(), start + 3), 1);
     assert_eq!(
-        res,
-        Response::new()
-            .add_message(expected)
-            .add_attribute("action", "send_nft")
-            .add_attribute("sender", "venus")
-            .add_attribute("recipient", "another_contract")
-            .add_attribute("token_id", token_id)
+        contract.query_num_tokens(deps.as_ref(), env).unwrap().count,
+        1
     );
 }
 
+#[cfg(feature = "uri-index")]
 #[test]
-fn approving_revoking() {
+fn tokens_by_uri() {
     let mut deps = mock_dependencies();
     let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let uri = "https://example.com/same.json";
 
-    // Mint a token
-    let token_id = "grow".to_string();
-    let token_uri = "https://www.merriam-webster.com/dictionary/grow".to_string();
+    for (token_id, token_uri) in [("1", uri), ("2", "https://example.com/2.json"), ("3", uri)] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: MINTER_ADDR.to_string(),
+                    token_uri: Some(token_uri.to_string()),
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
 
-    let mint_msg = Cw721ExecuteMsg::Mint {
-        token_id: token_id.clone(),
-        owner: String::from("demeter"),
-        token_uri: Some(token_uri),
-        extension: None,
-    };
+    let tokens = contract
+        .query_tokens_by_uri(deps.as_ref(), mock_env(), uri.to_string(), None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["1", "3"]);
 
-    let minter = mock_info(MINTER_ADDR, &[]);
+    // paginated
+    let tokens = contract
+        .query_tokens_by_uri(
+            deps.as_ref(),
+            mock_env(),
+            uri.to_string(),
+            Some("1".to_string()),
+            None,
+        )
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["3"]);
+
+    // burned tokens are removed from the index
     contract
-        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            Cw721ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+    let tokens = contract
+        .query_tokens_by_uri(deps.as_ref(), mock_env(), uri.to_string(), None, None)
         .unwrap();
+    assert_eq!(tokens.tokens, vec!["3"]);
 
-    // token owner shows in approval query
-    let res = contract
-        .query_approval(
+    let tokens = contract
+        .query_tokens_by_uri(
             deps.as_ref(),
             mock_env(),
-            token_id.clone(),
-            String::from("demeter"),
-            false,
+            "https://example.com/unknown.json".to_string(),
+            None,
+            None,
         )
         .unwrap();
+    assert!(tokens.tokens.is_empty());
+}
+
+#[cfg(feature = "uri-index")]
+#[test]
+fn unique_metadata() {
+    use crate::state::UniqueMetadata;
+
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let mint = |token_id: &str, token_uri: &str, name: &str| Cw721ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: MINTER_ADDR.to_string(),
+        token_uri: Some(token_uri.to_string()),
+        extension: Some(Metadata {
+            name: Some(name.to_string()),
+            ..Metadata::default()
+        }),
+        approval: None,
+        metadata_hash: None,
+    };
+
+    // duplicates allowed by default
     assert_eq!(
-        res,
-        ApprovalResponse {
-            approval: Approval {
-                spender: Addr::unchecked("demeter"),
-                expires: Expiration::Never {}
-            }
-        }
+        contract.query_unique_metadata(deps.as_ref()).unwrap(),
+        UniqueMetadata::default()
     );
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            mint("1", "ipfs://a", "a"),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            mint("2", "ipfs://a", "a"),
+        )
+        .unwrap();
 
-    // Give random transferring power
-    let approve_msg = Cw721ExecuteMsg::Approve {
-        spender: String::from("random"),
-        token_id: token_id.clone(),
-        expires: None,
+    // only owner can set flags
+    let unique = UniqueMetadata {
+        token_uri: true,
+        extension: true,
     };
-    let owner = mock_info("demeter", &[]);
-    let res = contract
-        .execute(deps.as_mut(), mock_env(), owner, approve_msg)
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            Cw721ExecuteMsg::SetUniqueMetadata {
+                unique: unique.clone(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetUniqueMetadata {
+                unique: unique.clone(),
+            },
+        )
         .unwrap();
     assert_eq!(
-        res,
-        Response::new()
-            .add_attribute("action", "approve")
-            .add_attribute("sender", "demeter")
-            .add_attribute("spender", "random")
-            .add_attribute("token_id", token_id.clone())
+        contract.query_unique_metadata(deps.as_ref()).unwrap(),
+        unique
     );
 
-    // test approval query
-    let res = contract
-        .query_approval(
-            deps.as_ref(),
+    let err = contract
+        .execute(
+            deps.as_mut(),
             mock_env(),
-            token_id.clone(),
-            String::from("random"),
-            true,
+            minter.clone(),
+            mint("3", "ipfs://a", "b"),
         )
-        .unwrap();
+        .unwrap_err();
     assert_eq!(
-        res,
-        ApprovalResponse {
-            approval: Approval {
-                spender: Addr::unchecked("random"),
-                expires: Expiration::Never {}
-            }
+        err,
+        Cw721ContractError::DuplicateTokenUri {
+            token_uri: "ipfs://a".to_string()
         }
     );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            mint("3", "ipfs://b", "a"),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::DuplicateExtension {});
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            mint("3", "ipfs://b", "b"),
+        )
+        .unwrap();
 
-    // random can now transfer
-    let random = mock_info("random", &[]);
-    let transfer_msg = Cw721ExecuteMsg::TransferNft {
-        recipient: String::from("person"),
-        token_id: token_id.clone(),
-    };
+    // burned metadata can be minted again
+    for token_id in ["1", "2"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                minter.clone(),
+                Cw721ExecuteMsg::Burn {
+                    token_id: token_id.to_string(),
+                },
+            )
+            .unwrap();
+    }
     contract
-        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            mint("4", "ipfs://a", "a"),
+        )
         .unwrap();
+}
 
-    // Approvals are removed / cleared
-    let query_msg = Cw721QueryMsg::OwnerOf {
-        token_id: token_id.clone(),
-        include_expired: None,
+#[test]
+fn burnable() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: "deed".to_string(),
+        owner: "holder".to_string(),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
     };
-    let res: OwnerOfResponse = from_json(
-        contract
-            .query(deps.as_ref(), mock_env(), query_msg.clone())
-            .unwrap(),
-    )
-    .unwrap();
+    let burn_msg = Cw721ExecuteMsg::Burn {
+        token_id: "deed".to_string(),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
+
+    // burnable by default
     assert_eq!(
-        res,
-        OwnerOfResponse {
-            owner: String::from("person"),
-            approvals: vec![],
+        contract.query_burnable(deps.as_ref()).unwrap(),
+        Burnable {
+            burnable: true,
+            creator_gated: false,
+            locked: false
         }
     );
 
-    // Approve, revoke, and check for empty, to test revoke
-    let approve_msg = Cw721ExecuteMsg::Approve {
-        spender: String::from("random"),
-        token_id: token_id.clone(),
-        expires: None,
+    // only owner can disable burning
+    let disable_msg = Cw721ExecuteMsg::SetBurnable {
+        burnable: false,
+        creator_gated: None,
+        lock: None,
     };
-    let owner = mock_info("person", &[]);
-    contract
-        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_msg)
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("holder", &[]),
+            disable_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), disable_msg)
         .unwrap();
 
-    let revoke_msg = Cw721ExecuteMsg::Revoke {
-        spender: String::from("random"),
-        token_id,
-    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("holder", &[]),
+            burn_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::BurnDisabled {});
+
+    // once locked, flag can't be changed anymore
     contract
-        .execute(deps.as_mut(), mock_env(), owner, revoke_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetBurnable {
+                burnable: false,
+                creator_gated: None,
+                lock: Some(true),
+            },
+        )
         .unwrap();
-
-    // Approvals are now removed / cleared
-    let res: OwnerOfResponse = from_json(
-        contract
-            .query(deps.as_ref(), mock_env(), query_msg)
-            .unwrap(),
-    )
-    .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            Cw721ExecuteMsg::SetBurnable {
+                burnable: true,
+                creator_gated: None,
+                lock: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::BurnableLocked {});
     assert_eq!(
-        res,
-        OwnerOfResponse {
-            owner: String::from("person"),
-            approvals: vec![],
+        contract.query_burnable(deps.as_ref()).unwrap(),
+        Burnable {
+            burnable: false,
+            creator_gated: false,
+            locked: true
         }
     );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("holder", &[]),
+            burn_msg,
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::BurnDisabled {});
 }
 
 #[test]
-fn approving_all_revoking_all() {
+fn creator_gated_burn() {
     let mut deps = mock_dependencies();
     let contract = setup_contract(deps.as_mut());
-
-    // Mint a couple tokens (from the same owner)
-    let token_id1 = "grow1".to_string();
-    let token_uri1 = "https://www.merriam-webster.com/dictionary/grow1".to_string();
-
-    let token_id2 = "grow2".to_string();
-    let token_uri2 = "https://www.merriam-webster.com/dictionary/grow2".to_string();
-
-    let mint_msg1 = Cw721ExecuteMsg::Mint {
-        token_id: token_id1.clone(),
-        owner: String::from("demeter"),
-        token_uri: Some(token_uri1),
-        extension: None,
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let holder = mock_info("holder", &[]);
+    for token_id in ["bond1", "bond2"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: "holder".to_string(),
+                    token_uri: None,
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    let offer_msg = |token_id: &str| Cw721ExecuteMsg::OfferBurn {
+        token_id: token_id.to_string(),
+    };
+    let confirm_msg = |token_id: &str| Cw721ExecuteMsg::ConfirmBurn {
+        token_id: token_id.to_string(),
     };
 
-    let minter = mock_info(MINTER_ADDR, &[]);
+    // offers are only accepted in creator-gated mode
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            holder.clone(),
+            offer_msg("bond1"),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::BurnNotCreatorGated {});
     contract
-        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg1)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetBurnable {
+                burnable: true,
+                creator_gated: Some(true),
+                lock: None,
+            },
+        )
         .unwrap();
 
-    let mint_msg2 = Cw721ExecuteMsg::Mint {
-        token_id: token_id2.clone(),
-        owner: String::from("demeter"),
-        token_uri: Some(token_uri2),
-        extension: None,
-    };
+    // holder can't burn directly
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            holder.clone(),
+            Cw721ExecuteMsg::Burn {
+                token_id: "bond1".to_string(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::BurnCreatorGated {});
+
+    // creator can't burn without offer
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            confirm_msg("bond1"),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::NoBurnOffer {
+            token_id: "bond1".to_string()
+        }
+    );
 
+    // only holder can offer
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            offer_msg("bond1"),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
     let env = mock_env();
     contract
-        .execute(deps.as_mut(), env.clone(), minter, mint_msg2)
-        .unwrap();
-
-    // paginate the token_ids
-    let tokens = contract
-        .query_all_tokens(deps.as_ref(), env.clone(), None, Some(1))
-        .unwrap();
-    assert_eq!(1, tokens.tokens.len());
-    assert_eq!(vec![token_id1.clone()], tokens.tokens);
-    let tokens = contract
-        .query_all_tokens(deps.as_ref(), env, Some(token_id1.clone()), Some(3))
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            holder.clone(),
+            offer_msg("bond1"),
+        )
         .unwrap();
-    assert_eq!(1, tokens.tokens.len());
-    assert_eq!(vec![token_id2.clone()], tokens.tokens);
+    assert_eq!(
+        contract
+            .query_burn_offer(deps.as_ref(), "bond1".to_string())
+            .unwrap(),
+        Some(BurnOffer {
+            owner: Addr::unchecked("holder"),
+            height: env.block.height,
+        })
+    );
 
-    // demeter gives random full (operator) power over her tokens
-    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
-        operator: String::from("random"),
-        expires: None,
-    };
-    let owner = mock_info("demeter", &[]);
-    let res = contract
-        .execute(deps.as_mut(), mock_env(), owner, approve_all_msg)
+    // only creator can confirm
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            holder.clone(),
+            confirm_msg("bond1"),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            confirm_msg("bond1"),
+        )
         .unwrap();
+    assert!(contract
+        .query_is_burned(deps.as_ref(), mock_env(), "bond1".to_string())
+        .unwrap());
     assert_eq!(
-        res,
-        Response::new()
-            .add_attribute("action", "approve_all")
-            .add_attribute("sender", "demeter")
-            .add_attribute("operator", "random")
+        contract
+            .query_burn_offer(deps.as_ref(), "bond1".to_string())
+            .unwrap(),
+        None
     );
 
-    // random can now transfer
-    let random = mock_info("random", &[]);
-    let transfer_msg = Cw721ExecuteMsg::TransferNft {
-        recipient: String::from("person"),
-        token_id: token_id1,
-    };
+    // offer is void once token changed hands
     contract
-        .execute(deps.as_mut(), mock_env(), random.clone(), transfer_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            holder.clone(),
+            offer_msg("bond2"),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            holder,
+            Cw721ExecuteMsg::TransferNft {
+                recipient: "buyer".to_string(),
+                token_id: "bond2".to_string(),
+            },
+        )
         .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter, confirm_msg("bond2"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::NoBurnOffer {
+            token_id: "bond2".to_string()
+        }
+    );
+}
 
-    // random can now send
-    let inner_msg = WasmMsg::Execute {
-        contract_addr: "another_contract".into(),
-        msg: to_json_binary("You now also have the growing power").unwrap(),
-        funds: vec![],
+#[test]
+fn verify_metadata() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let payload = Binary::from(br#"{"name":"Magic Power #1"}"#.as_slice());
+    let metadata_hash = HexBinary::from(Sha256::digest(payload.as_slice()).as_slice());
+    let mint = |token_id: &str, metadata_hash: Option<HexBinary>| Cw721ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: MINTER_ADDR.to_string(),
+        token_uri: Some("ipfs://QmMagicPower1".to_string()),
+        extension: None,
+        approval: None,
+        metadata_hash,
     };
-    let msg: CosmosMsg = CosmosMsg::Wasm(inner_msg);
 
-    let send_msg = Cw721ExecuteMsg::SendNft {
-        contract: String::from("another_contract"),
-        token_id: token_id2,
-        msg: to_json_binary(&msg).unwrap(),
-    };
+    // hash must be a sha256 hash
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            mint("1", Some(HexBinary::from(b"short".as_slice()))),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::Std(StdError::generic_err("Metadata hash must be a sha256 hash"))
+    );
+
     contract
-        .execute(deps.as_mut(), mock_env(), random, send_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            mint("1", Some(metadata_hash.clone())),
+        )
         .unwrap();
-
-    // Approve_all, revoke_all, and check for empty, to test revoke_all
-    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
-        operator: String::from("operator"),
-        expires: None,
-    };
-    // person is now the owner of the tokens
-    let owner = mock_info("person", &[]);
     contract
-        .execute(deps.as_mut(), mock_env(), owner, approve_all_msg)
+        .execute(deps.as_mut(), mock_env(), minter, mint("2", None))
         .unwrap();
 
-    // query for operator should return approval
     let res = contract
-        .query_operator(
-            deps.as_ref(),
-            mock_env(),
-            String::from("person"),
-            String::from("operator"),
-            true,
-        )
+        .query_verify_metadata(deps.as_ref(), "1".to_string(), payload.clone())
         .unwrap();
     assert_eq!(
         res,
-        OperatorResponse {
-            approval: Approval {
-                spender: Addr::unchecked("operator"),
-                expires: Expiration::Never {}
-            }
+        VerifyMetadataResponse {
+            verified: true,
+            metadata_hash: Some(metadata_hash.clone()),
         }
     );
 
-    // query for other should throw error
-    let res = contract.query_operator(
-        deps.as_ref(),
-        mock_env(),
-        String::from("person"),
-        String::from("other"),
-        true,
-    );
-    match res {
-        Err(StdError::NotFound { kind }) => assert_eq!(kind, "Approval not found"),
-        _ => panic!("Unexpected error"),
-    }
-
+    // swapped content doesn't verify
     let res = contract
-        .query_operators(
+        .query_verify_metadata(
             deps.as_ref(),
-            mock_env(),
-            String::from("person"),
-            true,
-            None,
-            None,
+            "1".to_string(),
+            Binary::from(br#"{"name":"Swapped"}"#.as_slice()),
         )
         .unwrap();
     assert_eq!(
         res,
-        OperatorsResponse {
-            operators: vec![Approval {
-                spender: Addr::unchecked("operator"),
-                expires: Expiration::Never {}
-            }]
+        VerifyMetadataResponse {
+            verified: false,
+            metadata_hash: Some(metadata_hash),
         }
     );
 
-    // second approval
-    let buddy_expires = Expiration::AtHeight(1234567);
-    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
-        operator: String::from("buddy"),
-        expires: Some(buddy_expires),
-    };
-    let owner = mock_info("person", &[]);
+    // nothing to verify against without hash
+    let res = contract
+        .query_verify_metadata(deps.as_ref(), "2".to_string(), payload)
+        .unwrap();
+    assert_eq!(
+        res,
+        VerifyMetadataResponse {
+            verified: false,
+            metadata_hash: None,
+        }
+    );
+}
+
+#[test]
+fn contract_uri() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let uri = "https://starships.example.com/collection.json".to_string();
+
+    // not set by default
+    let res = contract.query_contract_uri(deps.as_ref()).unwrap();
+    assert_eq!(res, None);
+
+    // only owner can set contract uri
+    let set_msg = Cw721ExecuteMsg::SetContractUri {
+        contract_uri: Some(uri.clone()),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            set_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // empty uri is rejected
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::SetContractUri {
+                contract_uri: Some(" ".to_string()),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::InvalidContractUri {});
+
     contract
-        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_all_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            set_msg,
+        )
         .unwrap();
+    let res: Option<String> = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), Cw721QueryMsg::ContractUri {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, Some(uri));
 
-    // and paginate queries
+    // unset removes it
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::SetContractUri { contract_uri: None },
+        )
+        .unwrap();
+    let res = contract.query_contract_uri(deps.as_ref()).unwrap();
+    assert_eq!(res, None);
+}
+
+#[test]
+fn royalty_payout_denoms() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let denoms = vec![
+        PayoutDenom::Native {
+            denom: "uatom".to_string(),
+        },
+        PayoutDenom::Cw20 {
+            address: "usdc_token".to_string(),
+        },
+    ];
+    let set_msg =
+        |denoms: Option<Vec<PayoutDenom>>| Cw721ExecuteMsg::SetRoyaltyPayoutDenoms { denoms };
+
+    // not declared by default
     let res = contract
-        .query_operators(
-            deps.as_ref(),
+        .query_collection_info(deps.as_ref(), mock_env())
+        .unwrap();
+    assert_eq!(res.royalty_payout_denoms, None);
+
+    // only owner can declare denoms
+    let err = contract
+        .execute(
+            deps.as_mut(),
             mock_env(),
-            String::from("person"),
-            true,
-            None,
-            Some(1),
+            mock_info("random", &[]),
+            set_msg(Some(denoms.clone())),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // empty list, empty native denom and duplicates are rejected
+    let invalid = [
+        vec![],
+        vec![PayoutDenom::Native {
+            denom: "".to_string(),
+        }],
+        vec![denoms[0].clone(), denoms[0].clone()],
+    ];
+    for denoms in invalid {
+        let err = contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(MINTER_ADDR, &[]),
+                set_msg(Some(denoms)),
+            )
+            .unwrap_err();
+        assert!(matches!(err, Cw721ContractError::InvalidPayoutDenom { .. }));
+    }
+
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            set_msg(Some(denoms.clone())),
         )
         .unwrap();
+    let res: CollectionInfo = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), Cw721QueryMsg::ContractInfo {})
+            .unwrap(),
+    )
+    .unwrap();
     assert_eq!(
         res,
-        OperatorsResponse {
-            operators: vec![Approval {
-                spender: Addr::unchecked("buddy"),
-                expires: buddy_expires,
-            }]
+        CollectionInfo {
+            name: CONTRACT_NAME.to_string(),
+            symbol: SYMBOL.to_string(),
+            royalty_payout_denoms: Some(denoms),
+            origin: None,
         }
     );
+
+    // unset removes them
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            set_msg(None),
+        )
+        .unwrap();
     let res = contract
-        .query_operators(
-            deps.as_ref(),
+        .query_collection_info(deps.as_ref(), mock_env())
+        .unwrap();
+    assert_eq!(res.royalty_payout_denoms, None);
+}
+
+#[test]
+fn bridged_origin() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let origin = OriginInfo {
+        chain_id: "1".to_string(),
+        contract: "0xbc4ca0eda7647a8ab7c2061c2e118a18a936f13d".to_string(),
+        bridge_tx: Some(
+            "0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060".to_string(),
+        ),
+    };
+    contract
+        .execute(
+            deps.as_mut(),
             mock_env(),
-            String::from("person"),
-            true,
-            Some(String::from("buddy")),
-            Some(2),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::Mint {
+                token_id: "ape".to_string(),
+                owner: "demeter".to_string(),
+                token_uri: None,
+                extension: None,
+                approval: None,
+                metadata_hash: None,
+            },
         )
         .unwrap();
+    let token_origin = |deps: Deps, token_id: &str| -> TokenOriginResponse {
+        from_json(
+            contract
+                .query(
+                    deps,
+                    mock_env(),
+                    Cw721QueryMsg::TokenOrigin {
+                        token_id: token_id.to_string(),
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap()
+    };
     assert_eq!(
-        res,
-        OperatorsResponse {
-            operators: vec![Approval {
-                spender: Addr::unchecked("operator"),
-                expires: Expiration::Never {}
-            }]
+        token_origin(deps.as_ref(), "ape"),
+        TokenOriginResponse {
+            origin: None,
+            origin_token_id: None,
         }
     );
 
-    let revoke_all_msg = Cw721ExecuteMsg::RevokeAll {
-        operator: String::from("operator"),
-    };
+    // only owner can set origin, which must be complete
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            Cw721ExecuteMsg::SetOrigin {
+                origin: origin.clone(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::SetOrigin {
+                origin: OriginInfo {
+                    contract: "".to_string(),
+                    ..origin.clone()
+                },
+            },
+        )
+        .unwrap_err();
+    assert!(matches!(err, Cw721ContractError::InvalidOrigin { .. }));
+
+    // set once
     contract
-        .execute(deps.as_mut(), mock_env(), owner, revoke_all_msg)
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::SetOrigin {
+                origin: origin.clone(),
+            },
+        )
         .unwrap();
-
-    // query for operator should return error
-    let res = contract.query_operator(
-        deps.as_ref(),
-        mock_env(),
-        String::from("person"),
-        String::from("operator"),
-        true,
-    );
-    match res {
-        Err(StdError::NotFound { kind }) => assert_eq!(kind, "Approval not found"),
-        _ => panic!("Unexpected error"),
-    }
-
-    // Approvals are removed / cleared without affecting others
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::SetOrigin {
+                origin: origin.clone(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::OriginAlreadySet {});
     let res = contract
-        .query_operators(
-            deps.as_ref(),
+        .query_collection_info(deps.as_ref(), mock_env())
+        .unwrap();
+    assert_eq!(res.origin, Some(origin.clone()));
+
+    // origin token ids of existing tokens, set once by minter
+    let set_origin_token_id =
+        |token_id: &str, origin_token_id: &str| Cw721ExecuteMsg::SetOriginTokenIds {
+            origin_token_ids: BTreeMap::from([(token_id.to_string(), origin_token_id.to_string())]),
+        };
+    let err = contract
+        .execute(
+            deps.as_mut(),
             mock_env(),
-            String::from("person"),
-            false,
-            None,
-            None,
+            mock_info("random", &[]),
+            set_origin_token_id("ape", "8817"),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            set_origin_token_id("unknown", "1"),
+        )
+        .unwrap_err();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            set_origin_token_id("ape", "8817"),
         )
         .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            set_origin_token_id("ape", "1"),
+        )
+        .unwrap_err();
     assert_eq!(
-        res,
-        OperatorsResponse {
-            operators: vec![Approval {
-                spender: Addr::unchecked("buddy"),
-                expires: buddy_expires,
-            }]
+        err,
+        Cw721ContractError::OriginTokenIdAlreadySet {
+            token_id: "ape".to_string()
+        }
+    );
+    assert_eq!(
+        token_origin(deps.as_ref(), "ape"),
+        TokenOriginResponse {
+            origin: Some(origin),
+            origin_token_id: Some("8817".to_string()),
         }
     );
 
-    // ensure the filter works (nothing should be here
-    let mut late_env = mock_env();
-    late_env.block.height = 1234568; //expired
-    let res = contract
-        .query_operators(
-            deps.as_ref(),
-            late_env.clone(),
-            String::from("person"),
-            false,
-            None,
-            None,
+    // removed on burn
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &[]),
+            Cw721ExecuteMsg::Burn {
+                token_id: "ape".to_string(),
+            },
         )
         .unwrap();
-    assert_eq!(0, res.operators.len());
-
-    // query operator should also return error
-    let res = contract.query_operator(
-        deps.as_ref(),
-        late_env,
-        String::from("person"),
-        String::from("buddy"),
-        false,
-    );
-
-    match res {
-        Err(StdError::NotFound { kind }) => assert_eq!(kind, "Approval not found"),
-        _ => panic!("Unexpected error"),
-    }
+    assert!(!contract
+        .config
+        .origin_token_ids
+        .has(deps.as_ref().storage, "ape"));
 }
 
 #[test]
-fn test_set_withdraw_address() {
+fn royalty_admin() {
     let mut deps = mock_dependencies();
     let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let set_denoms = |denom: &str| Cw721ExecuteMsg::SetRoyaltyPayoutDenoms {
+        denoms: Some(vec![PayoutDenom::Native {
+            denom: denom.to_string(),
+        }]),
+    };
+    let royalty_admin = |deps: Deps| {
+        contract
+            .query_roles(deps.storage)
+            .unwrap()
+            .royalty_admin
+            .owner
+    };
 
-    // other than minter cant set
+    // only owner can appoint royalty admin
+    assert_eq!(royalty_admin(deps.as_ref()), None);
     let err = contract
-        .set_withdraw_address(deps.as_mut(), &Addr::unchecked("other"), "foo".to_string())
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("finance", &[]),
+            Cw721ExecuteMsg::SetRoyaltyAdmin {
+                admin: Some("finance".to_string()),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetRoyaltyAdmin {
+                admin: Some("finance".to_string()),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        royalty_admin(deps.as_ref()),
+        Some(Addr::unchecked("finance"))
+    );
+
+    // royalty admin updates royalty config, but nothing else
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("finance", &[]),
+            set_denoms("uatom"),
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("finance", &[]),
+            Cw721ExecuteMsg::SetContractUri {
+                contract_uri: Some("ipfs://collection".to_string()),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // owner can remove royalty admin
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetRoyaltyAdmin { admin: None },
+        )
+        .unwrap();
+    assert_eq!(royalty_admin(deps.as_ref()), None);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("finance", &[]),
+            set_denoms("uosmo"),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // role is handed over by royalty admin itself
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetRoyaltyAdmin {
+                admin: Some("finance".to_string()),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("finance", &[]),
+            Cw721ExecuteMsg::UpdateRoyaltyAdminOwnership(Action::TransferOwnership {
+                new_owner: "multisig".to_string(),
+                expiry: None,
+            }),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("multisig", &[]),
+            Cw721ExecuteMsg::UpdateRoyaltyAdminOwnership(Action::AcceptOwnership),
+        )
+        .unwrap();
+    assert_eq!(
+        royalty_admin(deps.as_ref()),
+        Some(Addr::unchecked("multisig"))
+    );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("finance", &[]),
+            set_denoms("uosmo"),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // royalty config changes are timelocked for royalty admin as well
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            Cw721ExecuteMsg::SetTimelock {
+                delay: Some(Duration::Height(10)),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("multisig", &[]),
+            set_denoms("uosmo"),
+        )
+        .unwrap();
+    let mut env = mock_env();
+    env.block.height += 10;
+    contract
+        .execute(
+            deps.as_mut(),
+            env,
+            mock_info("multisig", &[]),
+            Cw721ExecuteMsg::ExecuteQueued { id: 0 },
+        )
+        .unwrap();
+    let res = contract
+        .query_collection_info(deps.as_ref(), mock_env())
+        .unwrap();
+    assert_eq!(
+        res.royalty_payout_denoms,
+        Some(vec![PayoutDenom::Native {
+            denom: "uosmo".to_string()
+        }])
+    );
+}
+
+#[test]
+fn provenance_hash() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let provenance_hash = HexBinary::from(Sha256::digest(b"final metadata").as_slice()).to_hex();
+
+    // only owner can commit
+    let commit_msg = |provenance_hash: &str| Cw721ExecuteMsg::SetProvenanceHash {
+        provenance_hash: provenance_hash.to_string(),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            commit_msg(&provenance_hash),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // must be a sha256 hash
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            commit_msg("abcd"),
+        )
+        .unwrap_err();
+
+    // hash is stored lower case
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            commit_msg(&provenance_hash.to_uppercase()),
+        )
+        .unwrap();
+    let res: Option<String> = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), Cw721QueryMsg::ProvenanceHash {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, Some(provenance_hash.clone()));
+
+    // locked after first mint, also in case token is burned
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::Mint {
+                token_id: "1".to_string(),
+                owner: MINTER_ADDR.to_string(),
+                token_uri: None,
+                extension: None,
+                approval: None,
+                metadata_hash: None,
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            commit_msg(&provenance_hash),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::ProvenanceHashLocked {});
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            commit_msg(&provenance_hash),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::ProvenanceHashLocked {});
+}
+
+#[test]
+fn reveal() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let provenance_hash = HexBinary::from(Sha256::digest(b"final metadata").as_slice()).to_hex();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetProvenanceHash {
+                provenance_hash: provenance_hash.clone(),
+            },
+        )
+        .unwrap();
+    for token_id in ["1", "2"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: String::from("demeter"),
+                    token_uri: Some(format!("ipfs://tokens/{token_id}")),
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+
+    // reveal requires placeholder
+    let reveal_msg = |provenance: &str| Cw721ExecuteMsg::Reveal {
+        base_uri: None,
+        token_uris: Some(BTreeMap::from([(
+            "2".to_string(),
+            "ipfs://final/2".to_string(),
+        )])),
+        provenance: Some(provenance.to_string()),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            reveal_msg("final metadata"),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::NoRevealPlaceholder {});
+
+    // only owner can set placeholder
+    let placeholder_msg = Cw721ExecuteMsg::SetRevealPlaceholder {
+        placeholder_uri: "ipfs://placeholder".to_string(),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            placeholder_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), placeholder_msg)
+        .unwrap();
+
+    // all tokens return placeholder
+    for token_id in ["1", "2"] {
+        let info = contract
+            .query_nft_info(deps.as_ref(), mock_env(), token_id.to_string())
+            .unwrap();
+        assert_eq!(info.token_uri, Some("ipfs://placeholder".to_string()));
+    }
+
+    // provenance must match committed hash
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            reveal_msg("reshuffled metadata"),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::InvalidProvenance { provenance_hash }
+    );
+
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            reveal_msg("final metadata"),
+        )
+        .unwrap();
+    // updated token uris are emitted for pinning services
+    assert_eq!(
+        res.attributes[2..],
+        [
+            Attribute::new("token_id", "2"),
+            Attribute::new("metadata_uri", "ipfs://final/2")
+        ]
+    );
+    let info = contract
+        .query_all_nft_info(deps.as_ref(), mock_env(), "1".to_string(), false, false)
+        .unwrap();
+    assert_eq!(info.info.token_uri, Some("ipfs://tokens/1".to_string()));
+    let info = contract
+        .query_nft_info(deps.as_ref(), mock_env(), "2".to_string())
+        .unwrap();
+    assert_eq!(info.token_uri, Some("ipfs://final/2".to_string()));
+    let reveal: Option<RevealInfo> = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), Cw721QueryMsg::RevealInfo {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        reveal,
+        Some(RevealInfo {
+            placeholder_uri: "ipfs://placeholder".to_string(),
+            revealed: true,
+            base_uri: None,
+            shuffle: None,
+        })
+    );
+
+    // reveal is final
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            reveal_msg("final metadata"),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::AlreadyRevealed {});
+}
+
+#[test]
+fn reveal_with_base_uri() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetRevealPlaceholder {
+                placeholder_uri: "ipfs://placeholder".to_string(),
+            },
+        )
+        .unwrap();
+    let mint_msg = |token_id: &str| Cw721ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: String::from("demeter"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg("1"))
+        .unwrap();
+    // metadata uri is emitted as resolved by `NftInfo` query
+    assert!(res
+        .attributes
+        .contains(&Attribute::new("metadata_uri", "ipfs://placeholder")));
+    let info = contract
+        .query_nft_info(deps.as_ref(), mock_env(), "1".to_string())
+        .unwrap();
+    assert_eq!(info.token_uri, Some("ipfs://placeholder".to_string()));
+
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::Reveal {
+                base_uri: Some("ipfs://revealed/".to_string()),
+                token_uris: None,
+                provenance: None,
+            },
+        )
+        .unwrap();
+    let info = contract
+        .query_nft_info(deps.as_ref(), mock_env(), "1".to_string())
+        .unwrap();
+    assert_eq!(info.token_uri, Some("ipfs://revealed/1".to_string()));
+
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg("2"))
+        .unwrap();
+    assert!(res
+        .attributes
+        .contains(&Attribute::new("metadata_uri", "ipfs://revealed/2")));
+}
+
+#[test]
+fn reveal_with_randomness() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let nois_proxy = mock_info("nois_proxy", &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetRevealPlaceholder {
+                placeholder_uri: "ipfs://placeholder".to_string(),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetRandomnessSource {
+                source: Some(nois_proxy.sender.to_string()),
+            },
+        )
+        .unwrap();
+    for token_id in 0..4 {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: String::from("demeter"),
+                    token_uri: None,
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    // source is locked once token ids are known
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetRandomnessSource {
+                source: Some(MINTER_ADDR.to_string()),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::RandomnessSourceLocked {});
+
+    // reveal requires randomness
+    let reveal_msg = Cw721ExecuteMsg::Reveal {
+        base_uri: Some("ipfs://revealed/".to_string()),
+        token_uris: None,
+        provenance: None,
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            reveal_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::RandomnessNotReceived {});
+
+    // request forwards funds to randomness source
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &coins(50, "unois")),
+            Cw721ExecuteMsg::RequestRandomness {},
+        )
+        .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: nois_proxy.sender.to_string(),
+            msg: to_json_binary(&RandomnessSourceMsg::GetNextRandomness {
+                job_id: RANDOMNESS_JOB_ID.to_string()
+            })
+            .unwrap(),
+            funds: coins(50, "unois"),
+        })
+    );
+
+    // only randomness source can deliver randomness, once
+    let mut randomness = [0u8; 32];
+    randomness[7] = 6;
+    let receive_msg = Cw721ExecuteMsg::NoisReceive {
+        callback: NoisCallback {
+            job_id: RANDOMNESS_JOB_ID.to_string(),
+            published: mock_env().block.time,
+            randomness: HexBinary::from(randomness),
+        },
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            receive_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::UnauthorizedRandomnessSource {
+            sender: "random".to_string()
+        }
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            nois_proxy.clone(),
+            receive_msg.clone(),
+        )
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), nois_proxy, receive_msg)
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::RandomnessAlreadyReceived {});
+
+    // offset is 6 % 4 = 2
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter, reveal_msg)
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "shuffle_offset" && attr.value == "2"));
+    for (token_id, metadata_id) in [("0", "2"), ("1", "3"), ("2", "0"), ("3", "1")] {
+        let info = contract
+            .query_nft_info(deps.as_ref(), mock_env(), token_id.to_string())
+            .unwrap();
+        assert_eq!(
+            info.token_uri,
+            Some(format!("ipfs://revealed/{metadata_id}"))
+        );
+    }
+}
+
+#[test]
+fn transferring_nft() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // Mint a token
+    let token_id = "melt".to_string();
+    let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
+
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("venus"),
+        token_uri: Some(token_uri),
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    // random cannot transfer
+    let random = mock_info("random", &[]);
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: token_id.clone(),
+    };
+
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // owner can
+    let random = mock_info("venus", &[]);
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: token_id.clone(),
+    };
+
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .unwrap();
+
+    // typed event (`wasm-cw721_transfer`) carries all attributes but action
+    assert_eq!(
+        res,
+        Response::new()
+            .add_attribute("action", "transfer_nft")
+            .add_attribute("sender", "venus")
+            .add_attribute("recipient", "random")
+            .add_attribute("token_id", token_id.clone())
+            .add_event(
+                Event::new("cw721_transfer")
+                    .add_attribute("sender", "venus")
+                    .add_attribute("recipient", "random")
+                    .add_attribute("token_id", token_id)
+            )
+    );
+}
+
+#[test]
+fn tracking_last_transfer() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let token_id = "melt".to_string();
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("venus"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    // not transferred yet
+    let res = contract
+        .query_last_transferred_at(deps.as_ref(), mock_env(), token_id.clone())
+        .unwrap();
+    assert_eq!(res.last_transferred_at, None);
+
+    // transfer at a later block
+    let mut env = mock_env();
+    env.block.height += 10;
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: token_id.clone(),
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("venus", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+
+    let res = contract
+        .query_last_transferred_at(deps.as_ref(), mock_env(), token_id)
+        .unwrap();
+    assert_eq!(
+        res.last_transferred_at,
+        Some(TransferredAt {
+            height: env.block.height,
+            time: env.block.time,
+        })
+    );
+}
+
+#[test]
+fn transfer_cooldown() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let token_id = "melt".to_string();
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("venus"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
+
+    // only owner can set cooldown
+    let cooldown_msg = Cw721ExecuteMsg::SetTransferCooldown {
+        cooldown: Some(Duration::Height(5)),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            cooldown_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, cooldown_msg)
+        .unwrap();
+    assert_eq!(
+        contract.query_transfer_cooldown(deps.as_ref()).unwrap(),
+        Some(Duration::Height(5))
+    );
+
+    // first transfer after mint is allowed
+    let mut env = mock_env();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("venus", &[]),
+            Cw721ExecuteMsg::TransferNft {
+                recipient: String::from("random"),
+                token_id: token_id.clone(),
+            },
+        )
+        .unwrap();
+
+    // transfer back within cooldown fails
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: String::from("venus"),
+        token_id: token_id.clone(),
+    };
+    env.block.height += 4;
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            transfer_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::TransferCooldown {
+            token_id,
+            ends: Expiration::AtHeight(mock_env().block.height + 5),
+        }
+    );
+
+    // transfer after cooldown succeeds
+    env.block.height += 1;
+    contract
+        .execute(deps.as_mut(), env, mock_info("random", &[]), transfer_msg)
+        .unwrap();
+}
+
+#[test]
+fn token_status() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+
+    let token_id = "melt".to_string();
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("venus"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
+    let status = contract
+        .query_token_status(deps.as_ref(), mock_env(), token_id.clone())
+        .unwrap();
+    assert_eq!(
+        status,
+        TokenStatusResponse {
+            owner: String::from("venus"),
+            transferable: true,
+            frozen: false,
+            paused: false,
+            migration_pending: false,
+            cooldown_ends: None,
+            gift_lock: None,
+            transfer_offer: None,
+            expired: false,
+            soulbound: false,
+        }
+    );
+
+    // pending transfer offer is reported, but doesn't block transfers
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            Cw721ExecuteMsg::OfferTransfer {
+                recipient: String::from("random"),
+                token_id: token_id.clone(),
+                expires: None,
+            },
+        )
+        .unwrap();
+    let status = contract
+        .query_token_status(deps.as_ref(), mock_env(), token_id.clone())
+        .unwrap();
+    assert!(status.transferable);
+    assert_eq!(
+        status.transfer_offer.map(|offer| offer.recipient),
+        Some(Addr::unchecked("random"))
+    );
+
+    // transfer starts cooldown
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetTransferCooldown {
+                cooldown: Some(Duration::Height(5)),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("venus", &[]),
+            Cw721ExecuteMsg::TransferNft {
+                recipient: String::from("random"),
+                token_id: token_id.clone(),
+            },
+        )
+        .unwrap();
+    let status = contract
+        .query_token_status(deps.as_ref(), mock_env(), token_id.clone())
+        .unwrap();
+    assert_eq!(status.owner, "random");
+    assert!(!status.transferable);
+    assert_eq!(
+        status.cooldown_ends,
+        Some(Expiration::AtHeight(mock_env().block.height + 5))
+    );
+
+    // cooldown is over, but collection is paused
+    let mut env = mock_env();
+    env.block.height += 5;
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter,
+            Cw721ExecuteMsg::Pause {},
+        )
+        .unwrap();
+    let status = contract
+        .query_token_status(deps.as_ref(), env, token_id)
+        .unwrap();
+    assert_eq!(status.cooldown_ends, None);
+    assert!(status.paused);
+    assert!(!status.transferable);
+}
+
+#[test]
+fn sending_nft() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // Mint a token
+    let token_id = "melt".to_string();
+    let token_uri = "https://www.merriam-webster.com/dictionary/melt".to_string();
+
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("venus"),
+        token_uri: Some(token_uri),
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    let msg = to_json_binary("You now have the melting power").unwrap();
+    let target = String::from("another_contract");
+    let send_msg = Cw721ExecuteMsg::SendNft {
+        contract: target.clone(),
+        token_id: token_id.clone(),
+        msg: msg.clone(),
+    };
+
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, send_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // but owner can
+    let random = mock_info("venus", &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), random, send_msg)
+        .unwrap();
+
+    let payload = Cw721ReceiveMsg {
+        sender: String::from("venus"),
+        token_id: token_id.clone(),
+        msg,
+    };
+    let expected = payload.into_cosmos_msg(target.clone()).unwrap();
+    // ensure expected serializes as we think it should
+    match &expected {
+        CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => {
+            assert_eq!(contract_addr, &target)
+        }
+        m => panic!("Unexpected message type: {m:?}"),
+    }
+    // and make sure this is the request sent by the contract
+    assert_eq!(
+        res,
+        add_cw721_event(
+            Response::new()
+                .add_message(expected)
+                .add_attribute("action", "send_nft")
+                .add_attribute("sender", "venus")
+                .add_attribute("recipient", "another_contract")
+                .add_attribute("token_id", token_id),
+            "send",
+        ),
+    );
+}
+
+#[test]
+fn minting_and_sending() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let token_id = "melt".to_string();
+    let msg = to_json_binary("You now have the melting power").unwrap();
+    let target = String::from("another_contract");
+    let mint_msg = Cw721ExecuteMsg::MintAndSend {
+        token_id: token_id.clone(),
+        contract: target.clone(),
+        token_uri: None,
+        extension: None,
+        msg: msg.clone(),
+        metadata_hash: None,
+    };
+
+    // only minter can mint
+    let random = mock_info("random", &[]);
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), random, mint_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    let payload = Cw721ReceiveMsg {
+        sender: String::from(MINTER_ADDR),
+        token_id: token_id.clone(),
+        msg,
+    };
+    assert_eq!(
+        res,
+        add_cw721_event(
+            Response::new()
+                .add_message(payload.into_cosmos_msg(target.clone()).unwrap())
+                .add_attribute("action", "mint_and_send")
+                .add_attribute("minter", MINTER_ADDR)
+                .add_attribute("recipient", "another_contract")
+                .add_attribute("token_id", token_id.clone()),
+            "mint_and_send",
+        ),
+    );
+
+    // contract owns the token
+    let owner = contract
+        .query_owner_of(deps.as_ref(), mock_env(), token_id, false)
+        .unwrap();
+    assert_eq!(owner.owner, target);
+}
+
+#[test]
+fn approving_revoking() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // Mint a token
+    let token_id = "grow".to_string();
+    let token_uri = "https://www.merriam-webster.com/dictionary/grow".to_string();
+
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("demeter"),
+        token_uri: Some(token_uri),
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    // token owner shows in approval query
+    let res = contract
+        .query_approval(
+            deps.as_ref(),
+            mock_env(),
+            token_id.clone(),
+            String::from("demeter"),
+            false,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ApprovalResponse {
+            approval: Approval {
+                spender: Addr::unchecked("demeter"),
+                expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_ALL,
+            }
+        }
+    );
+
+    // Give random transferring power
+    let approve_msg = Cw721ExecuteMsg::Approve {
+        spender: String::from("random"),
+        token_id: token_id.clone(),
+        expires: None,
+        permissions: None,
+    };
+    let owner = mock_info("demeter", &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), owner, approve_msg)
+        .unwrap();
+    assert_eq!(
+        res,
+        add_cw721_event(
+            Response::new()
+                .add_attribute("action", "approve")
+                .add_attribute("sender", "demeter")
+                .add_attribute("spender", "random")
+                .add_attribute("token_id", token_id.clone()),
+            "approve",
+        ),
+    );
+
+    // test approval query
+    let res = contract
+        .query_approval(
+            deps.as_ref(),
+            mock_env(),
+            token_id.clone(),
+            String::from("random"),
+            true,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ApprovalResponse {
+            approval: Approval {
+                spender: Addr::unchecked("random"),
+                expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_ALL,
+            }
+        }
+    );
+
+    // random can now transfer
+    let random = mock_info("random", &[]);
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: String::from("person"),
+        token_id: token_id.clone(),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), random, transfer_msg)
+        .unwrap();
+
+    // Approvals are removed / cleared
+    let query_msg = Cw721QueryMsg::OwnerOf {
+        token_id: token_id.clone(),
+        include_expired: None,
+    };
+    let res: OwnerOfResponse = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), query_msg.clone())
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        OwnerOfResponse {
+            owner: String::from("person"),
+            approvals: vec![],
+        }
+    );
+
+    // Approve, revoke, and check for empty, to test revoke
+    let approve_msg = Cw721ExecuteMsg::Approve {
+        spender: String::from("random"),
+        token_id: token_id.clone(),
+        expires: None,
+        permissions: None,
+    };
+    let owner = mock_info("person", &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_msg)
+        .unwrap();
+
+    let revoke_msg = Cw721ExecuteMsg::Revoke {
+        spender: String::from("random"),
+        token_id,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), owner, revoke_msg)
+        .unwrap();
+
+    // Approvals are now removed / cleared
+    let res: OwnerOfResponse = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), query_msg)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        OwnerOfResponse {
+            owner: String::from("person"),
+            approvals: vec![],
+        }
+    );
+}
+
+#[test]
+fn scoped_approvals() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let token_id = "grow".to_string();
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("demeter"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    // no permissions is invalid
+    let owner = mock_info("demeter", &[]);
+    let approve_msg = Cw721ExecuteMsg::Approve {
+        spender: String::from("market"),
+        token_id: token_id.clone(),
+        expires: None,
+        permissions: Some(0),
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_msg)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::InvalidApprovalPermissions { permissions: 0 }
+    );
+
+    // approve market for transfers only
+    let approve_msg = Cw721ExecuteMsg::Approve {
+        spender: String::from("market"),
+        token_id: token_id.clone(),
+        expires: None,
+        permissions: Some(APPROVAL_PERMISSION_TRANSFER),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), owner, approve_msg)
+        .unwrap();
+
+    // market cannot burn or send
+    let market = mock_info("market", &[]);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            market.clone(),
+            Cw721ExecuteMsg::Burn {
+                token_id: token_id.clone(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            market.clone(),
+            Cw721ExecuteMsg::SendNft {
+                contract: String::from("another_contract"),
+                token_id: token_id.clone(),
+                msg: to_json_binary("You now have the melting power").unwrap(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // market can transfer
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            market,
+            Cw721ExecuteMsg::TransferNft {
+                recipient: String::from("buyer"),
+                token_id: token_id.clone(),
+            },
+        )
+        .unwrap();
+    let owner = contract
+        .query_owner_of(deps.as_ref(), mock_env(), token_id, false)
+        .unwrap();
+    assert_eq!(owner.owner, "buyer");
+}
+
+#[test]
+fn minting_with_approval() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+
+    // expired approval is rejected, token is not minted
+    let env = mock_env();
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: "listed".to_string(),
+        owner: String::from("demeter"),
+        token_uri: None,
+        extension: None,
+        approval: Some(MintApproval {
+            spender: String::from("market"),
+            expires: Some(Expiration::AtHeight(env.block.height)),
+            permissions: None,
+        }),
+        metadata_hash: None,
+    };
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg)
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Expired {});
+
+    // mint with transfer approval for market
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: "listed".to_string(),
+        owner: String::from("demeter"),
+        token_uri: None,
+        extension: None,
+        approval: Some(MintApproval {
+            spender: String::from("market"),
+            expires: None,
+            permissions: Some(APPROVAL_PERMISSION_TRANSFER),
+        }),
+        metadata_hash: None,
+    };
+    let res = contract
+        .execute(deps.as_mut(), env.clone(), minter, mint_msg)
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "spender" && attr.value == "market"));
+
+    let res = contract
+        .query_approval(
+            deps.as_ref(),
+            env.clone(),
+            "listed".to_string(),
+            String::from("market"),
+            false,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        ApprovalResponse {
+            approval: Approval {
+                spender: Addr::unchecked("market"),
+                expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_TRANSFER,
+            }
+        }
+    );
+
+    // market can transfer right away
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("market", &[]),
+            Cw721ExecuteMsg::TransferNft {
+                recipient: String::from("buyer"),
+                token_id: "listed".to_string(),
+            },
+        )
+        .unwrap();
+    let owner = contract
+        .query_owner_of(deps.as_ref(), env, "listed".to_string(), false)
+        .unwrap();
+    assert_eq!(owner.owner, "buyer");
+}
+
+#[test]
+fn approving_burner() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let token_id = "potion".to_string();
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("player"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    let approve_msg = Cw721ExecuteMsg::ApproveBurner {
+        spender: String::from("game"),
+        token_id: token_id.clone(),
+        expires: None,
+    };
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("player", &[]),
+            approve_msg,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        add_cw721_event(
+            Response::new()
+                .add_attribute("action", "approve_burner")
+                .add_attribute("sender", "player")
+                .add_attribute("spender", "game")
+                .add_attribute("token_id", token_id.clone()),
+            "approve_burner",
+        ),
+    );
+
+    // game cannot transfer
+    let game = mock_info("game", &[]);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            game.clone(),
+            Cw721ExecuteMsg::TransferNft {
+                recipient: String::from("game"),
+                token_id: token_id.clone(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // game can burn
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            game,
+            Cw721ExecuteMsg::Burn { token_id },
+        )
+        .unwrap();
+    let count = contract
+        .query_num_tokens(deps.as_ref(), mock_env())
+        .unwrap();
+    assert_eq!(0, count.count);
+}
+
+#[test]
+fn approving_all_revoking_all() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // Mint a couple tokens (from the same owner)
+    let token_id1 = "grow1".to_string();
+    let token_uri1 = "https://www.merriam-webster.com/dictionary/grow1".to_string();
+
+    let token_id2 = "grow2".to_string();
+    let token_uri2 = "https://www.merriam-webster.com/dictionary/grow2".to_string();
+
+    let mint_msg1 = Cw721ExecuteMsg::Mint {
+        token_id: token_id1.clone(),
+        owner: String::from("demeter"),
+        token_uri: Some(token_uri1),
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg1)
+        .unwrap();
+
+    let mint_msg2 = Cw721ExecuteMsg::Mint {
+        token_id: token_id2.clone(),
+        owner: String::from("demeter"),
+        token_uri: Some(token_uri2),
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+
+    let env = mock_env();
+    contract
+        .execute(deps.as_mut(), env.clone(), minter, mint_msg2)
+        .unwrap();
+
+    // paginate the token_ids
+    let tokens = contract
+        .query_all_tokens(deps.as_ref(), env.clone(), None, Some(1))
+        .unwrap();
+    assert_eq!(1, tokens.tokens.len());
+    assert_eq!(vec![token_id1.clone()], tokens.tokens);
+    let tokens = contract
+        .query_all_tokens(deps.as_ref(), env, Some(token_id1.clone()), Some(3))
+        .unwrap();
+    assert_eq!(1, tokens.tokens.len());
+    assert_eq!(vec![token_id2.clone()], tokens.tokens);
+
+    // demeter gives random full (operator) power over her tokens
+    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
+        operator: String::from("random"),
+        expires: None,
+        filter: None,
+    };
+    let owner = mock_info("demeter", &[]);
+    let res = contract
+        .execute(deps.as_mut(), mock_env(), owner, approve_all_msg)
+        .unwrap();
+    assert_eq!(
+        res,
+        add_cw721_event(
+            Response::new()
+                .add_attribute("action", "approve_all")
+                .add_attribute("sender", "demeter")
+                .add_attribute("operator", "random"),
+            "approve_all",
+        ),
+    );
+
+    // random can now transfer
+    let random = mock_info("random", &[]);
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: String::from("person"),
+        token_id: token_id1,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), random.clone(), transfer_msg)
+        .unwrap();
+
+    // random can now send
+    let inner_msg = WasmMsg::Execute {
+        contract_addr: "another_contract".into(),
+        msg: to_json_binary("You now also have the growing power").unwrap(),
+        funds: vec![],
+    };
+    let msg: CosmosMsg = CosmosMsg::Wasm(inner_msg);
+
+    let send_msg = Cw721ExecuteMsg::SendNft {
+        contract: String::from("another_contract"),
+        token_id: token_id2,
+        msg: to_json_binary(&msg).unwrap(),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), random, send_msg)
+        .unwrap();
+
+    // Approve_all, revoke_all, and check for empty, to test revoke_all
+    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
+        operator: String::from("operator"),
+        expires: None,
+        filter: None,
+    };
+    // person is now the owner of the tokens
+    let owner = mock_info("person", &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), owner, approve_all_msg)
+        .unwrap();
+
+    // query for operator should return approval
+    let res = contract
+        .query_operator(
+            deps.as_ref(),
+            mock_env(),
+            String::from("person"),
+            String::from("operator"),
+            true,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        OperatorResponse {
+            approval: Approval {
+                spender: Addr::unchecked("operator"),
+                expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_ALL,
+            }
+        }
+    );
+
+    // query for other should throw error
+    let res = contract.query_operator(
+        deps.as_ref(),
+        mock_env(),
+        String::from("person"),
+        String::from("other"),
+        true,
+    );
+    match res {
+        Err(StdError::NotFound { kind }) => assert_eq!(kind, "Approval not found"),
+        _ => panic!("Unexpected error"),
+    }
+
+    let res = contract
+        .query_operators(
+            deps.as_ref(),
+            mock_env(),
+            String::from("person"),
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        OperatorsResponse {
+            operators: vec![Approval {
+                spender: Addr::unchecked("operator"),
+                expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_ALL,
+            }],
+            next_cursor: None,
+        }
+    );
+
+    // second approval
+    let buddy_expires = Expiration::AtHeight(1234567);
+    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
+        operator: String::from("buddy"),
+        expires: Some(buddy_expires),
+        filter: None,
+    };
+    let owner = mock_info("person", &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_all_msg)
+        .unwrap();
+
+    // and paginate queries
+    let res = contract
+        .query_operators(
+            deps.as_ref(),
+            mock_env(),
+            String::from("person"),
+            true,
+            None,
+            Some(1),
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        OperatorsResponse {
+            operators: vec![Approval {
+                spender: Addr::unchecked("buddy"),
+                expires: buddy_expires,
+                permissions: APPROVAL_PERMISSION_ALL,
+            }],
+            next_cursor: Some(to_json_binary("buddy").unwrap()),
+        }
+    );
+    let res = contract
+        .query_operators(
+            deps.as_ref(),
+            mock_env(),
+            String::from("person"),
+            true,
+            Some(String::from("buddy")),
+            Some(2),
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        OperatorsResponse {
+            operators: vec![Approval {
+                spender: Addr::unchecked("operator"),
+                expires: Expiration::Never {},
+                permissions: APPROVAL_PERMISSION_ALL,
+            }],
+            next_cursor: None,
+        }
+    );
+
+    let revoke_all_msg = Cw721ExecuteMsg::RevokeAll {
+        operator: String::from("operator"),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), owner, revoke_all_msg)
+        .unwrap();
+
+    // query for operator should return error
+    let res = contract.query_operator(
+        deps.as_ref(),
+        mock_env(),
+        String::from("person"),
+        String::from("operator"),
+        true,
+    );
+    match res {
+        Err(StdError::NotFound { kind }) => assert_eq!(kind, "Approval not found"),
+        _ => panic!("Unexpected error"),
+    }
+
+    // Approvals are removed / cleared without affecting others
+    let res = contract
+        .query_operators(
+            deps.as_ref(),
+            mock_env(),
+            String::from("person"),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        OperatorsResponse {
+            operators: vec![Approval {
+                spender: Addr::unchecked("buddy"),
+                expires: buddy_expires,
+                permissions: APPROVAL_PERMISSION_ALL,
+            }],
+            next_cursor: None,
+        }
+    );
+
+    // ensure the filter works (nothing should be here
+    let mut late_env = mock_env();
+    late_env.block.height = 1234568; //expired
+    let res = contract
+        .query_operators(
+            deps.as_ref(),
+            late_env.clone(),
+            String::from("person"),
+            false,
+            None,
+            None,
+        )
+        .unwrap();
+    assert_eq!(0, res.operators.len());
+
+    // query operator should also return error
+    let res = contract.query_operator(
+        deps.as_ref(),
+        late_env,
+        String::from("person"),
+        String::from("buddy"),
+        false,
+    );
+
+    match res {
+        Err(StdError::NotFound { kind }) => assert_eq!(kind, "Approval not found"),
+        _ => panic!("Unexpected error"),
+    }
+}
+
+#[test]
+fn test_set_withdraw_address() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // other than minter cant set
+    let err = contract
+        .set_withdraw_address(deps.as_mut(), &Addr::unchecked("other"), "foo".to_string())
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // minter can set
+    contract
+        .set_withdraw_address(
+            deps.as_mut(),
+            &Addr::unchecked(MINTER_ADDR),
+            "foo".to_string(),
+        )
+        .unwrap();
+
+    let withdraw_address = contract
+        .config
+        .withdraw_address
+        .load(deps.as_ref().storage)
+        .unwrap();
+    assert_eq!(withdraw_address, "foo".to_string())
+}
+
+#[test]
+fn test_remove_withdraw_address() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // other than creator cant remove
+    let err = contract
+        .remove_withdraw_address(deps.as_mut().storage, &Addr::unchecked("other"))
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // no withdraw address set yet
+    let err = contract
+        .remove_withdraw_address(deps.as_mut().storage, &Addr::unchecked(MINTER_ADDR))
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::NoWithdrawAddress {});
+
+    // set and remove
+    contract
+        .set_withdraw_address(
+            deps.as_mut(),
+            &Addr::unchecked(MINTER_ADDR),
+            "foo".to_string(),
+        )
+        .unwrap();
+    contract
+        .remove_withdraw_address(deps.as_mut().storage, &Addr::unchecked(MINTER_ADDR))
+        .unwrap();
+    assert!(!contract
+        .config
+        .withdraw_address
+        .exists(deps.as_ref().storage));
+
+    // test that we can set again
+    contract
+        .set_withdraw_address(
+            deps.as_mut(),
+            &Addr::unchecked(MINTER_ADDR),
+            "foo".to_string(),
+        )
+        .unwrap();
+    let withdraw_address = contract
+        .config
+        .withdraw_address
+        .load(deps.as_ref().storage)
+        .unwrap();
+    assert_eq!(withdraw_address, "foo".to_string())
+}
+
+#[test]
+fn test_withdraw_funds() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // no withdraw address set
+    let err = contract
+        .withdraw_funds(deps.as_mut().storage, &Coin::new(100, "uark"))
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::NoWithdrawAddress {});
+
+    // set and withdraw by non-owner
+    contract
+        .set_withdraw_address(
+            deps.as_mut(),
+            &Addr::unchecked(MINTER_ADDR),
+            "foo".to_string(),
+        )
+        .unwrap();
+    contract
+        .withdraw_funds(deps.as_mut().storage, &Coin::new(100, "uark"))
+        .unwrap();
+}
+
+#[test]
+fn sweep_funds() {
+    let mut deps = mock_dependencies_with_balance(&[
+        Coin::new(100, "uark"),
+        Coin::new(7, "airdrop"),
+        Coin::new(0, "empty"),
+    ]);
+    let contract = setup_contract(deps.as_mut());
+    let sweep = |denoms: Option<Vec<&str>>| Cw721ExecuteMsg::SweepFunds {
+        denoms: denoms.map(|denoms| denoms.into_iter().map(String::from).collect()),
+    };
+
+    // no withdraw address set
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            sweep(None),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::NoWithdrawAddress {});
+
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::SetWithdrawAddress {
+                address: "treasury".to_string(),
+            },
+        )
+        .unwrap();
+    let withdraw_address: Option<String> = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), Cw721QueryMsg::WithdrawAddress {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(withdraw_address, Some("treasury".to_string()));
+
+    // anyone can sweep given denoms, zero balances are skipped
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            sweep(Some(vec!["airdrop", "empty", "unknown"])),
+        )
+        .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: vec![Coin::new(7, "airdrop")],
+        })]
+    );
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            sweep(Some(vec!["empty"])),
+        )
+        .unwrap();
+    assert!(res.messages.is_empty());
+
+    // unset sweeps all balances
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            sweep(None),
+        )
+        .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: vec![Coin::new(100, "uark"), Coin::new(7, "airdrop")],
+        })]
+    );
+}
+
+#[test]
+fn query_tokens_by_owner() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+
+    // Mint a couple tokens (from the same owner)
+    let token_id1 = "grow1".to_string();
+    let demeter = String::from("demeter");
+    let token_id2 = "grow2".to_string();
+    let ceres = String::from("ceres");
+    let token_id3 = "sing".to_string();
+
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id1.clone(),
+        owner: demeter.clone(),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
+
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id2.clone(),
+        owner: ceres.clone(),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+        .unwrap();
+
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id3.clone(),
+        owner: demeter.clone(),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    let env = mock_env();
+    contract
+        .execute(deps.as_mut(), env.clone(), minter, mint_msg)
+        .unwrap();
+
+    // get all tokens in order:
+    let expected = vec![token_id1.clone(), token_id2.clone(), token_id3.clone()];
+    let tokens = contract
+        .query_all_tokens(deps.as_ref(), env.clone(), None, None)
+        .unwrap();
+    assert_eq!(&expected, &tokens.tokens);
+    // paginate
+    let tokens = contract
+        .query_all_tokens(deps.as_ref(), env.clone(), None, Some(2))
+        .unwrap();
+    assert_eq!(&expected[..2], &tokens.tokens[..]);
+    let tokens = contract
+        .query_all_tokens(deps.as_ref(), env.clone(), Some(expected[1].clone()), None)
+        .unwrap();
+    assert_eq!(&expected[2..], &tokens.tokens[..]);
+
+    // get by owner
+    let by_ceres = vec![token_id2];
+    let by_demeter = vec![token_id1, token_id3];
+    // all tokens by owner
+    let tokens = contract
+        .query_tokens(deps.as_ref(), env.clone(), demeter.clone(), None, None)
+        .unwrap();
+    assert_eq!(&by_demeter, &tokens.tokens);
+    let tokens = contract
+        .query_tokens(deps.as_ref(), env.clone(), ceres, None, None)
+        .unwrap();
+    assert_eq!(&by_ceres, &tokens.tokens);
+
+    // paginate for demeter
+    let tokens = contract
+        .query_tokens(deps.as_ref(), env.clone(), demeter.clone(), None, Some(1))
+        .unwrap();
+    assert_eq!(&by_demeter[..1], &tokens.tokens[..]);
+    let tokens = contract
+        .query_tokens(
+            deps.as_ref(),
+            env,
+            demeter,
+            Some(by_demeter[0].clone()),
+            Some(3),
+        )
+        .unwrap();
+    assert_eq!(&by_demeter[1..], &tokens.tokens[..]);
+}
+
+#[test]
+fn cursor_pagination() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+
+    let traits = [
+        ("background", "blue"),
+        ("background", "red"),
+        ("eyes", "blue"),
+    ];
+    for (i, (trait_type, value)) in traits.iter().enumerate() {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: format!("token{i}"),
+                    owner: String::from("demeter"),
+                    token_uri: None,
+                    extension: Some(Metadata {
+                        attributes: Some(vec![Trait {
+                            display_type: None,
+                            trait_type: trait_type.to_string(),
+                            value: value.to_string(),
+                        }]),
+                        ..Metadata::default()
+                    }),
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+
+    // tokens by owner use the secondary owner index, cursor hides its key construction
+    let mut tokens = vec![];
+    let mut cursor = None;
+    loop {
+        let page: TokensResponse = from_json(
+            contract
+                .query(
+                    deps.as_ref(),
+                    mock_env(),
+                    Cw721QueryMsg::Tokens {
+                        owner: String::from("demeter"),
+                        start_after: None,
+                        limit: Some(2),
+                        cursor,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        tokens.extend(page.tokens);
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(tokens, vec!["token0", "token1", "token2"]);
+
+    // composite (trait_type, value) keys
+    let page: TraitStatsResponse = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                Cw721QueryMsg::TraitStats {
+                    start_after: None,
+                    limit: Some(2),
+                    cursor: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(page.traits.len(), 2);
+    let page: TraitStatsResponse = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                Cw721QueryMsg::TraitStats {
+                    start_after: None,
+                    limit: Some(2),
+                    cursor: page.next_cursor,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        page.traits,
+        vec![TraitCount {
+            trait_type: "eyes".to_string(),
+            value: "blue".to_string(),
+            count: 1,
+        }]
+    );
+    assert_eq!(page.next_cursor, None);
+}
+
+#[test]
+fn query_supported_interfaces() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let res: SupportedInterfacesResponse = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                Cw721QueryMsg::SupportedInterfaces {},
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.interfaces,
+        vec![
+            INTERFACE_BASE.to_string(),
+            INTERFACE_ENUMERABLE.to_string(),
+            INTERFACE_METADATA_ONCHAIN.to_string(),
+        ]
+    );
+    assert!(!res.interfaces.contains(&INTERFACE_ROYALTIES.to_string()));
+}
+
+#[test]
+fn approvals_stored_apart_from_token() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let config = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+
+    let token_id = "grow".to_string();
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("demeter"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+
+    // approval is not stored in token
+    let owner = mock_info("demeter", &[]);
+    let approve_msg = Cw721ExecuteMsg::Approve {
+        spender: String::from("market"),
+        token_id: token_id.clone(),
+        expires: None,
+        permissions: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_msg)
+        .unwrap();
+    let token = config.nft_info.load(&deps.storage, &token_id).unwrap();
+    assert!(token.approvals.is_empty());
+    let approval = contract
+        .query_approval(
+            deps.as_ref(),
+            mock_env(),
+            token_id.clone(),
+            String::from("market"),
+            false,
+        )
+        .unwrap();
+    assert_eq!(approval.approval.spender, Addr::unchecked("market"));
+
+    // legacy approvals stored in token are still honored and can be revoked
+    let mut legacy_token = token.clone();
+    legacy_token.approvals = vec![Approval {
+        spender: Addr::unchecked("legacy_market"),
+        expires: Expiration::Never {},
+        permissions: APPROVAL_PERMISSION_ALL,
+    }];
+    config
+        .save_nft(&mut deps.storage, &token_id, &legacy_token, Some(&token))
+        .unwrap();
+    let approvals = contract
+        .query_approvals(deps.as_ref(), mock_env(), token_id.clone(), false)
+        .unwrap();
+    assert_eq!(approvals.approvals.len(), 2);
+    let revoke_msg = Cw721ExecuteMsg::Revoke {
+        spender: String::from("legacy_market"),
+        token_id: token_id.clone(),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), owner, revoke_msg)
+        .unwrap();
+    let token = config.nft_info.load(&deps.storage, &token_id).unwrap();
+    assert!(token.approvals.is_empty());
+
+    // transfer clears all approvals
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("market", &[]),
+            Cw721ExecuteMsg::TransferNft {
+                recipient: String::from("buyer"),
+                token_id: token_id.clone(),
+            },
+        )
+        .unwrap();
+    let approvals = contract
+        .query_approvals(deps.as_ref(), mock_env(), token_id, true)
+        .unwrap();
+    assert!(approvals.approvals.is_empty());
+}
+
+#[test]
+fn approving_batch() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let minter = mock_info(MINTER_ADDR, &[]);
+    for token_id in ["grow", "sing", "shine"] {
+        let mint_msg = Cw721ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: String::from("demeter"),
+            token_uri: None,
+            extension: None,
+            approval: None,
+            metadata_hash: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    // random cannot approve
+    let approve_msg = Cw721ExecuteMsg::ApproveBatch {
+        spender: String::from("market"),
+        token_ids: vec!["grow".to_string(), "sing".to_string()],
+        expires: None,
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            approve_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // owner approves a subset of tokens
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &[]),
+            approve_msg,
+        )
+        .unwrap();
+    assert_eq!(
+        res,
+        add_cw721_event(
+            Response::new()
+                .add_attribute("action", "approve_batch")
+                .add_attribute("sender", "demeter")
+                .add_attribute("spender", "market")
+                .add_attribute("token_ids", "grow,sing"),
+            "approve_batch",
+        ),
+    );
+    for token_id in ["grow", "sing"] {
+        contract
+            .query_approval(
+                deps.as_ref(),
+                mock_env(),
+                token_id.to_string(),
+                String::from("market"),
+                false,
+            )
+            .unwrap();
+    }
+    let err = contract
+        .query_approval(
+            deps.as_ref(),
+            mock_env(),
+            "shine".to_string(),
+            String::from("market"),
+            false,
+        )
+        .unwrap_err();
+    assert_eq!(err, StdError::not_found("Approval not found"));
+}
+
+#[test]
+fn expired_approvals_are_pruned() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let owner = mock_info("demeter", &[]);
+    let token_id = "grow".to_string();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetApprovalLimits {
+                limits: Some(ApprovalLimits {
+                    max_token_approvals: 1,
+                    max_operators: 1,
+                }),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            Cw721ExecuteMsg::Mint {
+                token_id: token_id.clone(),
+                owner: String::from("demeter"),
+                token_uri: None,
+                extension: None,
+                approval: None,
+                metadata_hash: None,
+            },
+        )
+        .unwrap();
+    let mut env = mock_env();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            owner.clone(),
+            Cw721ExecuteMsg::Approve {
+                spender: String::from("market"),
+                token_id: token_id.clone(),
+                expires: Some(Expiration::AtHeight(env.block.height + 1)),
+                permissions: None,
+            },
+        )
+        .unwrap();
+
+    // expired approval is excluded from queries, unless requested
+    env.block.height += 1;
+    let res = contract
+        .query_approvals(deps.as_ref(), env.clone(), token_id.clone(), false)
+        .unwrap();
+    assert_eq!(res.approvals, vec![]);
+    let res = contract
+        .query_approvals(deps.as_ref(), env.clone(), token_id.clone(), true)
+        .unwrap();
+    assert_eq!(res.approvals.len(), 1);
+
+    // and dropped on next approval, so it doesn't count towards the cap
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            owner,
+            Cw721ExecuteMsg::Approve {
+                spender: String::from("another_market"),
+                token_id: token_id.clone(),
+                expires: None,
+                permissions: None,
+            },
+        )
+        .unwrap();
+    let res = contract
+        .query_approvals(deps.as_ref(), env, token_id, true)
+        .unwrap();
+    assert_eq!(
+        res.approvals,
+        vec![Approval {
+            spender: Addr::unchecked("another_market"),
+            expires: Expiration::Never {},
+            permissions: APPROVAL_PERMISSION_ALL,
+        }]
+    );
+}
+
+#[test]
+fn approvals_filter_and_order() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let env = mock_env();
+    let token_id = "grow".to_string();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::Mint {
+                token_id: token_id.clone(),
+                owner: String::from("demeter"),
+                token_uri: None,
+                extension: None,
+                approval: None,
+                metadata_hash: None,
+            },
+        )
+        .unwrap();
+    let grants = [
+        (
+            "market_a",
+            Expiration::AtTime(env.block.time.plus_seconds(100)),
+        ),
+        ("market_b", Expiration::AtHeight(env.block.height + 10)),
+        ("market_c", Expiration::AtHeight(env.block.height + 5)),
+        ("wallet", Expiration::Never {}),
+    ];
+    for (spender, expires) in grants {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("demeter", &[]),
+                Cw721ExecuteMsg::Approve {
+                    spender: spender.to_string(),
+                    token_id: token_id.clone(),
+                    expires: Some(expires),
+                    permissions: None,
+                },
+            )
+            .unwrap();
+    }
+    let spenders = |spender_prefix: Option<&str>, order_by_expiration: Option<bool>| {
+        let res: ApprovalsResponse = from_json(
+            contract
+                .query(
+                    deps.as_ref(),
+                    env.clone(),
+                    Cw721QueryMsg::Approvals {
+                        token_id: token_id.clone(),
+                        include_expired: None,
+                        spender_prefix: spender_prefix.map(String::from),
+                        order_by_expiration,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        res.approvals
+            .into_iter()
+            .map(|approval| approval.spender.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // ordered by spender by default
+    assert_eq!(
+        spenders(None, None),
+        vec!["market_a", "market_b", "market_c", "wallet"]
+    );
+    assert_eq!(
+        spenders(Some("market"), Some(true)),
+        vec!["market_c", "market_b", "market_a"]
+    );
+    assert_eq!(
+        spenders(None, Some(true)),
+        vec!["market_c", "market_b", "market_a", "wallet"]
+    );
+}
+
+#[test]
+fn approval_limits() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // defaults apply
+    let limits = contract.query_approval_limits(deps.as_ref()).unwrap();
+    assert_eq!(limits, ApprovalLimits::default());
+
+    // only owner can set limits
+    let set_msg = Cw721ExecuteMsg::SetApprovalLimits {
+        limits: Some(ApprovalLimits {
+            max_token_approvals: 1,
+            max_operators: 1,
+        }),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            set_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            set_msg,
+        )
+        .unwrap();
+
+    let token_id = "grow".to_string();
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("demeter"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            mint_msg,
+        )
+        .unwrap();
+
+    // second spender exceeds approvals cap, renewing existing spender is fine
+    let owner = mock_info("demeter", &[]);
+    for spender in ["market", "market"] {
+        let approve_msg = Cw721ExecuteMsg::Approve {
+            spender: String::from(spender),
+            token_id: token_id.clone(),
+            expires: None,
+            permissions: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), owner.clone(), approve_msg)
+            .unwrap();
+    }
+    let approve_msg = Cw721ExecuteMsg::Approve {
+        spender: String::from("another_market"),
+        token_id: token_id.clone(),
+        expires: None,
+        permissions: None,
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_msg)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::TooManyApprovals { token_id, max: 1 }
+    );
+
+    // second operator exceeds operators cap
+    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
+        operator: String::from("operator"),
+        expires: None,
+        filter: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), owner.clone(), approve_all_msg)
+        .unwrap();
+    let approve_all_msg = Cw721ExecuteMsg::ApproveAll {
+        operator: String::from("another_operator"),
+        expires: None,
+        filter: None,
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), owner, approve_all_msg)
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::TooManyOperators { max: 1 });
+}
+
+#[test]
+fn enforced_royalties() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    // registry only allows "market"
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { msg, .. } => {
+            let OperatorRegistryQueryMsg::IsOperatorAllowed { operator } = from_json(msg).unwrap();
+            let res = IsOperatorAllowedResponse {
+                allowed: operator == "market",
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+        }
+        _ => unimplemented!(),
+    });
+
+    // only owner can set registry
+    let registry_msg = Cw721ExecuteMsg::SetOperatorRegistry {
+        registry: Some(String::from("registry")),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            registry_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            registry_msg,
+        )
+        .unwrap();
+    assert_eq!(
+        contract.query_operator_registry(deps.as_ref()).unwrap(),
+        Some(Addr::unchecked("registry"))
+    );
+
+    let token_id = "grow".to_string();
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: token_id.clone(),
+        owner: String::from("demeter"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            mint_msg,
+        )
+        .unwrap();
+
+    // operator not in registry cannot be approved for all, nor receive tokens
+    let owner = mock_info("demeter", &[]);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            Cw721ExecuteMsg::ApproveAll {
+                operator: String::from("royalty_evader"),
+                expires: None,
+                filter: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::OperatorNotAllowed {
+            operator: String::from("royalty_evader")
+        }
+    );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            Cw721ExecuteMsg::SendNft {
+                contract: String::from("royalty_evader"),
+                token_id: token_id.clone(),
+                msg: to_json_binary("sell").unwrap(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::OperatorNotAllowed {
+            operator: String::from("royalty_evader")
+        }
+    );
+
+    // allowed operator is fine
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            Cw721ExecuteMsg::ApproveAll {
+                operator: String::from("market"),
+                expires: None,
+                filter: None,
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            owner,
+            Cw721ExecuteMsg::SendNft {
+                contract: String::from("market"),
+                token_id,
+                msg: to_json_binary("sell").unwrap(),
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn approve_range_enforced_royalties() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let env = mock_env();
+    // registry only allows "market"
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { msg, .. } => {
+            let OperatorRegistryQueryMsg::IsOperatorAllowed { operator } = from_json(msg).unwrap();
+            let res = IsOperatorAllowedResponse {
+                allowed: operator == "market",
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+        }
+        _ => unimplemented!(),
+    });
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetOperatorRegistry {
+                registry: Some(String::from("registry")),
+            },
+        )
+        .unwrap();
+    for token_id in ["1", "2"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: "alice".to_string(),
+                    token_uri: None,
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    let alice = mock_info("alice", &[]);
+    let approve_range = |spender: &str| Cw721ExecuteMsg::ApproveRange {
+        spender: spender.to_string(),
+        start_id: 1,
+        end_id: 2,
+        expires: None,
+    };
+    let transfer = |token_id: &str| Cw721ExecuteMsg::TransferNft {
+        recipient: "bob".to_string(),
+        token_id: token_id.to_string(),
+    };
+
+    // spender not in registry cannot be approved for a range
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            approve_range("royalty_evader"),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::OperatorNotAllowed {
+            operator: String::from("royalty_evader")
+        }
+    );
+    contract
+        .execute(deps.as_mut(), env.clone(), alice, approve_range("market"))
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("market", &[]),
+            transfer("1"),
+        )
+        .unwrap();
+
+    // spender removed from registry loses range access
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Smart { .. } => {
+            let res = IsOperatorAllowedResponse { allowed: false };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+        }
+        _ => unimplemented!(),
+    });
+    let err = contract
+        .execute(deps.as_mut(), env, mock_info("market", &[]), transfer("2"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::OperatorNotAllowed {
+            operator: String::from("market")
+        }
+    );
+}
+
+#[test]
+fn protocol_fee() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let mint_msg = |token_id: &str| Cw721ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: String::from("demeter"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+
+    // disabled by default
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &coins(1000, "uatom")),
+            mint_msg("grow"),
+        )
+        .unwrap();
+    assert!(res.messages.is_empty());
+
+    let err = contract
+        .sudo(
+            deps.as_mut(),
+            mock_env(),
+            Cw721SudoMsg::SetProtocolFee {
+                bps: 10_001,
+                recipient: String::from("community_pool"),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::InvalidProtocolFee {
+            bps: 10_001,
+            max: 10_000
+        }
+    );
+    contract
+        .sudo(
+            deps.as_mut(),
+            mock_env(),
+            Cw721SudoMsg::SetProtocolFee {
+                bps: 250,
+                recipient: String::from("community_pool"),
+            },
+        )
+        .unwrap();
+    let res: Option<ProtocolFee> = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), Cw721QueryMsg::ProtocolFee {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        Some(ProtocolFee {
+            bps: 250,
+            recipient: Addr::unchecked("community_pool"),
+        })
+    );
+
+    // fee of each attached coin is rounded down, zero fees are skipped
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(
+                MINTER_ADDR,
+                &[Coin::new(1000, "uatom"), Coin::new(39, "ujuno")],
+            ),
+            mint_msg("sing"),
+        )
+        .unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: String::from("community_pool"),
+            amount: coins(25, "uatom"),
+        })]
+    );
+
+    // mint and send keeps fee messages
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &coins(1000, "uatom")),
+            Cw721ExecuteMsg::MintAndSend {
+                token_id: String::from("dance"),
+                contract: String::from("staking"),
+                token_uri: None,
+                extension: None,
+                msg: Binary::default(),
+                metadata_hash: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        res.messages[0],
+        SubMsg::new(BankMsg::Send {
+            to_address: String::from("community_pool"),
+            amount: coins(25, "uatom"),
+        })
+    );
+    assert_eq!(res.messages.len(), 2);
+
+    contract
+        .sudo(
+            deps.as_mut(),
+            mock_env(),
+            Cw721SudoMsg::RemoveProtocolFee {},
+        )
+        .unwrap();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &coins(1000, "uatom")),
+            mint_msg("paint"),
+        )
+        .unwrap();
+    assert!(res.messages.is_empty());
+}
+
+#[test]
+fn governance_freeze() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let mint_msg = |token_id: &str| Cw721ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: String::from("demeter"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg("grow"))
+        .unwrap();
+
+    let err = contract
+        .sudo(deps.as_mut(), mock_env(), Cw721SudoMsg::Unfreeze {})
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::NotFrozen {});
+
+    let reason = String::from("exploit under investigation");
+    contract
+        .sudo(
+            deps.as_mut(),
+            mock_env(),
+            Cw721SudoMsg::Freeze {
+                reason: reason.clone(),
+            },
+        )
+        .unwrap();
+    let res: Option<FreezeInfo> = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), Cw721QueryMsg::Frozen {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        Some(FreezeInfo {
+            reason: reason.clone(),
+            height: mock_env().block.height,
+        })
+    );
+
+    // transfers and mints are halted
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: String::from("grow"),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &[]),
+            transfer_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::Frozen {
+            reason: reason.clone()
+        }
+    );
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg("sing"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::Frozen {
+            reason: reason.clone()
+        }
+    );
+    // as well as burns and offers
+    for msg in [
+        Cw721ExecuteMsg::Burn {
+            token_id: String::from("grow"),
+        },
+        Cw721ExecuteMsg::OfferBurn {
+            token_id: String::from("grow"),
+        },
+        Cw721ExecuteMsg::OfferTransfer {
+            recipient: String::from("random"),
+            token_id: String::from("grow"),
+            expires: None,
+        },
+    ] {
+        let err = contract
+            .execute(deps.as_mut(), mock_env(), mock_info("demeter", &[]), msg)
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Cw721ContractError::Frozen {
+                reason: reason.clone()
+            }
+        );
+    }
+
+    // other actions are still possible
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &[]),
+            Cw721ExecuteMsg::ApproveAll {
+                operator: String::from("random"),
+                expires: None,
+                filter: None,
+            },
+        )
+        .unwrap();
+
+    contract
+        .sudo(deps.as_mut(), mock_env(), Cw721SudoMsg::Unfreeze {})
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+    assert_eq!(contract.query_frozen(deps.as_ref()).unwrap(), None);
+}
+
+#[test]
+fn guardian_pause() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let guardian = mock_info("guardian", &[]);
+    let mint_msg = Cw721ExecuteMsg::Mint {
+        token_id: String::from("grow"),
+        owner: String::from("demeter"),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+
+    // only owner can set guardian
+    let set_msg = Cw721ExecuteMsg::SetGuardian {
+        guardian: Some(String::from("guardian")),
+    };
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), guardian.clone(), set_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), set_msg)
+        .unwrap();
+    assert_eq!(
+        contract.query_guardian(deps.as_ref()).unwrap(),
+        Some(Addr::unchecked("guardian"))
+    );
+
+    // others can't pause
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            Cw721ExecuteMsg::Pause {},
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // guardian pauses, mints are halted
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            guardian.clone(),
+            Cw721ExecuteMsg::Pause {},
+        )
+        .unwrap();
+    let res: Option<PauseInfo> = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), Cw721QueryMsg::Paused {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        Some(PauseInfo {
+            paused_by: Addr::unchecked("guardian"),
+            height: mock_env().block.height,
+        })
+    );
+    let err = contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg.clone())
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Paused {});
+
+    // guardian can neither unpause nor change config
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            guardian.clone(),
+            Cw721ExecuteMsg::Unpause {},
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            guardian,
+            Cw721ExecuteMsg::SetGuardian { guardian: None },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::Unpause {},
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::NotPaused {});
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, mint_msg)
+        .unwrap();
+}
+
+#[test]
+fn timelock() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let uri = String::from("https://starships.example.com/collection.json");
+
+    // applied immediately without timelock
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetTimelock {
+                delay: Some(Duration::Height(10)),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        contract.query_timelock(deps.as_ref()).unwrap(),
+        Some(Duration::Height(10))
+    );
+
+    // config changes are queued, others can't queue
+    let set_msg = Cw721ExecuteMsg::SetContractUri {
+        contract_uri: Some(uri.clone()),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            set_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), set_msg.clone())
+        .unwrap();
+    assert_eq!(contract.query_contract_uri(deps.as_ref()).unwrap(), None);
+    let executable_at = Expiration::AtHeight(mock_env().block.height + 10);
+    let res: QueuedActionsResponse = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                Cw721QueryMsg::QueuedActions {
+                    start_after: None,
+                    limit: None,
+                    cursor: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.actions,
+        vec![QueuedActionResponse {
+            id: 0,
+            action: String::from("set_contract_uri"),
+            msg: to_json_binary(&set_msg).unwrap(),
+            proposer: Addr::unchecked(MINTER_ADDR),
+            executable_at,
+        }]
+    );
+
+    // executable once timelock expired
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::ExecuteQueued { id: 0 },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Timelocked { executable_at });
+    let mut env = mock_env();
+    env.block.height += 10;
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::ExecuteQueued { id: 0 },
+        )
+        .unwrap();
+    assert_eq!(
+        contract.query_contract_uri(deps.as_ref()).unwrap(),
+        Some(uri)
+    );
+
+    // removing the timelock is timelocked as well, queued actions can be cancelled
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetTimelock { delay: None },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::CancelQueued { id: 1 },
+        )
+        .unwrap();
+    env.block.height += 10;
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env,
+            minter,
+            Cw721ExecuteMsg::ExecuteQueued { id: 1 },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::QueuedActionNotFound { id: 1 });
+    assert_eq!(
+        contract.query_timelock(deps.as_ref()).unwrap(),
+        Some(Duration::Height(10))
+    );
+}
+
+#[test]
+fn update_minter_and_creator_ownership() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::UpdateCreatorOwnership(Action::TransferOwnership {
+                new_owner: "creator".to_string(),
+                expiry: None,
+            }),
+        )
+        .unwrap();
+    assert_eq!(
+        res.attributes[0],
+        Attribute::new("update_creator_ownership", MINTER_ADDR)
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            Cw721ExecuteMsg::UpdateCreatorOwnership(Action::AcceptOwnership),
+        )
+        .unwrap();
+
+    // creator is transferred on its own, minter stays
+    let roles: RolesResponse = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), Cw721QueryMsg::Roles {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(roles.creator.owner, Some(Addr::unchecked("creator")));
+    assert_eq!(roles.minter.owner, Some(Addr::unchecked(MINTER_ADDR)));
+
+    // creator can't transfer minter role
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            Cw721ExecuteMsg::UpdateMinterOwnership(Action::RenounceOwnership),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // old owner no longer owns the collection config, new creator does
+    let set_contract_uri = Cw721ExecuteMsg::SetContractUri {
+        contract_uri: Some("ipfs://collection".to_string()),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            set_contract_uri.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            set_contract_uri,
+        )
+        .unwrap();
+
+    // minter is still able to mint and to transfer its role
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::Mint {
+                token_id: "1".to_string(),
+                owner: "owner".to_string(),
+                token_uri: None,
+                extension: None,
+                approval: None,
+                metadata_hash: None,
+            },
+        )
+        .unwrap();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::UpdateMinterOwnership(Action::TransferOwnership {
+                new_owner: "minter".to_string(),
+                expiry: None,
+            }),
+        )
+        .unwrap();
+    assert_eq!(
+        res.attributes[0],
+        Attribute::new("update_minter_ownership", MINTER_ADDR)
+    );
+}
+
+#[test]
+fn roles_with_distinct_minter_and_creator() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let env = mock_env();
+    let expiry = Expiration::AtHeight(env.block.height + 10);
+
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::UpdateCreatorOwnership(Action::TransferOwnership {
+                new_owner: "creator".to_string(),
+                expiry: None,
+            }),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            Cw721ExecuteMsg::UpdateCreatorOwnership(Action::AcceptOwnership),
+        )
+        .unwrap();
+    // pending creator transfer is reported on creator only
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &[]),
+            Cw721ExecuteMsg::UpdateCreatorOwnership(Action::TransferOwnership {
+                new_owner: "next_creator".to_string(),
+                expiry: Some(expiry),
+            }),
+        )
+        .unwrap();
+
+    let roles: RolesResponse = from_json(
+        contract
+            .query(deps.as_ref(), env.clone(), Cw721QueryMsg::Roles {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        roles,
+        RolesResponse {
+            minter: Ownership {
+                owner: Some(Addr::unchecked(MINTER_ADDR)),
+                pending_owner: None,
+                pending_expiry: None,
+            },
+            creator: Ownership {
+                owner: Some(Addr::unchecked("creator")),
+                pending_owner: Some(Addr::unchecked("next_creator")),
+                pending_expiry: Some(expiry),
+            },
+            royalty_admin: Ownership {
+                owner: None,
+                pending_owner: None,
+                pending_expiry: None,
+            },
+        }
+    );
+    let members: RoleMembersResponse = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                env,
+                Cw721QueryMsg::RoleMembers {
+                    role: Role::Creator,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(members.members, vec![Addr::unchecked("creator")]);
+}
+
+#[test]
+fn resolved_token_uri() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let metadata = Metadata {
+        name: Some("Onchain".to_string()),
+        ..Metadata::default()
+    };
+    let mints = [
+        ("uri", Some("ipfs://tokens/uri".to_string()), None),
+        ("onchain", None, Some(metadata.clone())),
+        ("empty", None, None),
+    ];
+    for (token_id, token_uri, extension) in mints {
+        contract
+            .execute(
+                deps.as_mut(),
+                mock_env(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: String::from("demeter"),
+                    token_uri,
+                    extension,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    let resolve = |deps: Deps, token_id: &str| {
+        contract
+            .query_resolved_token_uri(deps, mock_env(), token_id.to_string())
+            .unwrap()
+            .token_uri
+    };
+
+    // token uri as stored, onchain metadata as data uri
+    assert_eq!(
+        resolve(deps.as_ref(), "uri"),
+        Some("ipfs://tokens/uri".to_string())
+    );
+    let data_uri = format!(
+        "data:application/json;base64,{}",
+        to_json_binary(&Some(metadata)).unwrap().to_base64()
+    );
+    assert_eq!(resolve(deps.as_ref(), "onchain"), Some(data_uri));
+    assert_eq!(resolve(deps.as_ref(), "empty"), None);
+
+    // placeholder before reveal, base uri after reveal
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetRevealPlaceholder {
+                placeholder_uri: "ipfs://placeholder".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        resolve(deps.as_ref(), "onchain"),
+        Some("ipfs://placeholder".to_string())
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            minter,
+            Cw721ExecuteMsg::Reveal {
+                base_uri: Some("ipfs://final/".to_string()),
+                token_uris: None,
+                provenance: None,
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        resolve(deps.as_ref(), "uri"),
+        Some("ipfs://final/uri".to_string())
+    );
+
+    // unknown token
+    contract
+        .query_resolved_token_uri(deps.as_ref(), mock_env(), "unknown".to_string())
+        .unwrap_err();
+}
+
+#[test]
+fn all_nft_info_with_collection() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::Mint {
+                token_id: "1".to_string(),
+                owner: String::from("demeter"),
+                token_uri: None,
+                extension: None,
+                approval: None,
+                metadata_hash: None,
+            },
+        )
+        .unwrap();
+    let query_msg = |include_collection| Cw721QueryMsg::AllNftInfo {
+        token_id: "1".to_string(),
+        include_expired: None,
+        include_collection,
+    };
+
+    // collection is only included on request
+    let res: AllNftInfoResponse<DefaultOptionMetadataExtension> = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), query_msg(None))
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.collection, None);
+    let res: AllNftInfoResponse<DefaultOptionMetadataExtension> = from_json(
+        contract
+            .query(deps.as_ref(), mock_env(), query_msg(Some(true)))
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.access.owner, "demeter");
+    assert_eq!(
+        res.collection,
+        Some(
+            contract
+                .query_collection_info(deps.as_ref(), mock_env())
+                .unwrap()
+        )
+    );
+}
+
+#[test]
+fn verify_ownership_proof() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    // account of a fixed secp256k1 key, signature of the proof message for "petrify" and "nonce-1"
+    let owner = "cosmos1ptqd6902tgrvl5zsj2dm5q4hvvdajh35j0n5cv";
+    let pubkey = Binary::from(
+        HexBinary::from_hex("03f973a0b87062c389d125d8199e803b832b6ac6bf7867a4f6cd87506060fc4c58")
+            .unwrap(),
+    );
+    let signature = Binary::from(HexBinary::from_hex("41836a52ca7af021c6595b863d7c1165ff58fa2dd4e5a35176f01742fbd635a2355d750fefeab6b8bbe5273f2aa29fa1340fd8bf399a308bd944538486bd5312").unwrap());
+    assert_eq!(
+        ownership_proof_message(
+            &mock_env().block.chain_id,
+            &mock_env().contract.address,
+            "petrify",
+            "nonce-1"
+        ),
+        "cw721 ownership proof\nchain_id: cosmos-testnet-14002\ncontract: cosmos2contract\ntoken_id: petrify\nmessage: nonce-1"
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::Mint {
+                token_id: "petrify".to_string(),
+                owner: owner.to_string(),
+                token_uri: None,
+                extension: None,
+                approval: None,
+                metadata_hash: None,
+            },
+        )
+        .unwrap();
+    let verify = |deps: Deps, message: &str, pubkey: &Binary| {
+        contract.query_verify_ownership_proof(
+            deps,
+            mock_env(),
+            "petrify".to_string(),
+            pubkey.clone(),
+            signature.clone(),
+            message.to_string(),
+        )
+    };
+
+    assert_eq!(
+        verify(deps.as_ref(), "nonce-1", &pubkey).unwrap(),
+        VerifyOwnershipProofResponse {
+            verified: true,
+            owner: owner.to_string(),
+        }
+    );
+    // signature doesn't match other messages
+    assert!(!verify(deps.as_ref(), "nonce-2", &pubkey).unwrap().verified);
+    // uncompressed keys are rejected
+    verify(deps.as_ref(), "nonce-1", &Binary::from(vec![4u8; 65])).unwrap_err();
+
+    // proof is void once the token is transferred
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(owner, &[]),
+            Cw721ExecuteMsg::TransferNft {
+                recipient: "demeter".to_string(),
+                token_id: "petrify".to_string(),
+            },
+        )
+        .unwrap();
+    let res = verify(deps.as_ref(), "nonce-1", &pubkey).unwrap();
+    assert!(!res.verified);
+    assert_eq!(res.owner, "demeter");
+}
+
+#[test]
+fn transfer_fee() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    contract
+        .set_withdraw_address(deps.as_mut(), &minter.sender, String::from("dao"))
+        .unwrap();
+    for token_id in ["grow", "sing"] {
+        let mint_msg = Cw721ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: String::from("demeter"),
+            token_uri: None,
+            extension: None,
+            approval: None,
+            metadata_hash: None,
+        };
+        contract
+            .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
+            .unwrap();
+    }
+
+    // only owner can set fee
+    let fee_msg = Cw721ExecuteMsg::SetTransferFee {
+        fee: Some(TransferFee::Flat(Coin::new(100, "uatom"))),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            fee_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(deps.as_mut(), mock_env(), minter.clone(), fee_msg)
+        .unwrap();
+
+    // flat fee must be attached and is forwarded to withdraw address
+    let transfer_msg = Cw721ExecuteMsg::TransferNft {
+        recipient: String::from("random"),
+        token_id: String::from("grow"),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &coins(99, "uatom")),
+            transfer_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::IncorrectPayment {
+            expected: Coin::new(100, "uatom"),
+            got: coins(99, "uatom"),
+        }
+    );
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &coins(100, "uatom")),
+            transfer_msg,
+        )
+        .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: String::from("dao"),
+            amount: coins(100, "uatom"),
+        })
+    );
+
+    // percentage fee, remaining funds are paid to previous owner
+    let fee_msg = Cw721ExecuteMsg::SetTransferFee {
+        fee: Some(TransferFee::Percentage {
+            denom: String::from("uatom"),
+            share: Decimal::percent(5),
+        }),
+    };
+    contract
+        .execute(deps.as_mut(), mock_env(), minter, fee_msg)
+        .unwrap();
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("demeter", &coins(1000, "uatom")),
+            Cw721ExecuteMsg::TransferNft {
+                recipient: String::from("random"),
+                token_id: String::from("sing"),
+            },
+        )
+        .unwrap();
+    let msgs: Vec<_> = res.messages.into_iter().map(|m| m.msg).collect();
+    assert_eq!(
+        msgs,
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("demeter"),
+                amount: coins(950, "uatom"),
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: String::from("dao"),
+                amount: coins(50, "uatom"),
+            }),
+        ]
+    );
+}
+
+#[test]
+fn query_capabilities() {
+    let mut deps = mock_dependencies();
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::Raw { .. } => {
+            let info = cw2::ContractVersion {
+                contract: "crates.io:cw721-base".to_string(),
+                version: "0.19.0".to_string(),
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&info).unwrap()))
+        }
+        WasmQuery::Smart { .. } => {
+            let res = SupportedInterfacesResponse {
+                interfaces: vec![INTERFACE_BASE.to_string()],
+            };
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+        }
+        _ => unimplemented!(),
+    });
+    let cw721 = crate::helpers::Cw721Contract::<Empty, Empty>(
+        Addr::unchecked("cw721"),
+        std::marker::PhantomData,
+        std::marker::PhantomData,
+    );
+    let querier = deps.as_ref().querier;
+
+    let report = cw721.capabilities(&querier).unwrap();
+    assert_eq!(report.contract, "crates.io:cw721-base");
+    assert!(report.supports(INTERFACE_BASE));
+    assert!(!report.supports(INTERFACE_ROYALTIES));
+
+    cw721.assert_at_least(&querier, "0.18.0").unwrap();
+    cw721.assert_at_least(&querier, "0.19.0-rc1").unwrap();
+    cw721.assert_at_least(&querier, "0.19.1").unwrap_err();
+    cw721.assert_at_least(&querier, "invalid").unwrap_err();
+}
+
+#[test]
+fn audit_log() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let env = mock_env();
+    let set_contract_uri = Cw721ExecuteMsg::SetContractUri {
+        contract_uri: Some("ipfs://QmCollection".to_string()),
+    };
+
+    // failed privileged actions are not recorded
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            set_contract_uri.clone(),
+        )
+        .unwrap_err();
+    // mints are not recorded
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::Mint {
+                token_id: "1".to_string(),
+                owner: MINTER_ADDR.to_string(),
+                token_uri: None,
+                extension: None,
+                approval: None,
+                metadata_hash: None,
+            },
+        )
+        .unwrap();
+    contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), set_contract_uri)
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter,
+            Cw721ExecuteMsg::UpdateOwnership(Action::TransferOwnership {
+                new_owner: "new_owner".to_string(),
+                expiry: None,
+            }),
+        )
+        .unwrap();
+
+    let entry = |id: u64, action: &str| AuditEntryResponse {
+        id,
+        actor: Addr::unchecked(MINTER_ADDR),
+        action: action.to_string(),
+        height: env.block.height,
+    };
+    let log = contract
+        .query_audit_log(deps.as_ref(), None, Some(1))
+        .unwrap();
+    assert_eq!(
+        log,
+        AuditLogResponse {
+            entries: vec![entry(0, "set_contract_uri")],
+            next_cursor: Some(to_json_binary(&0u64).unwrap()),
+        }
+    );
+    let log: AuditLogResponse = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                env.clone(),
+                Cw721QueryMsg::AuditLog {
+                    start_after: None,
+                    limit: Some(1),
+                    cursor: log.next_cursor,
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(log.entries, vec![entry(1, "transfer_ownership")]);
+}
+
+#[test]
+fn helpers_call_with_funds_and_parse_receive() {
+    use crate::error::Cw721ReceiveError;
+    use crate::helpers::parse_receive;
+
+    let cw721 = crate::helpers::Cw721Contract::<Empty, Empty>(
+        Addr::unchecked("cw721"),
+        std::marker::PhantomData,
+        std::marker::PhantomData,
+    );
+    let msg = cw721
+        .call_with_funds(
+            Cw721ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+            coins(100, "uatom"),
+        )
+        .unwrap();
+    match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds,
+            ..
+        }) => {
+            assert_eq!(contract_addr, "cw721");
+            assert_eq!(funds, coins(100, "uatom"));
+        }
+        _ => panic!("unexpected msg: {msg:?}"),
+    }
+
+    let receive = |msg: &[u8]| Cw721ReceiveMsg {
+        sender: "alice".to_string(),
+        token_id: "1".to_string(),
+        msg: Binary::from(msg),
+    };
+    let parsed: Vec<String> = parse_receive(&receive(br#"["stake"]"#)).unwrap();
+    assert_eq!(parsed, vec!["stake".to_string()]);
+    assert_eq!(
+        parse_receive::<Vec<String>>(&receive(b"")).unwrap_err(),
+        Cw721ReceiveError::EmptyMsg {
+            token_id: "1".to_string(),
+            sender: "alice".to_string(),
+        }
+    );
+    let err = parse_receive::<Vec<String>>(&receive(b"{}")).unwrap_err();
+    assert!(matches!(err, Cw721ReceiveError::InvalidMsg { .. }));
+    assert!(err.to_string().contains("token 1 from alice"));
+}
+
+#[test]
+fn approve_range() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let env = mock_env();
+    for token_id in ["1", "2", "3", "special"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: "alice".to_string(),
+                    token_uri: None,
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    let alice = mock_info("alice", &[]);
+    let market = mock_info("market", &[]);
+    let transfer = |token_id: &str| Cw721ExecuteMsg::TransferNft {
+        recipient: "bob".to_string(),
+        token_id: token_id.to_string(),
+    };
+
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            Cw721ExecuteMsg::ApproveRange {
+                spender: "market".to_string(),
+                start_id: 2,
+                end_id: 1,
+                expires: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::InvalidTokenRange {
+            start_id: 2,
+            end_id: 1
+        }
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            Cw721ExecuteMsg::ApproveRange {
+                spender: "market".to_string(),
+                start_id: 1,
+                end_id: 2,
+                expires: None,
+            },
+        )
+        .unwrap();
+    let range: Option<RangeApproval> = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                env.clone(),
+                Cw721QueryMsg::RangeApproval {
+                    owner: "alice".to_string(),
+                    spender: "market".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        range,
+        Some(RangeApproval {
+            start_id: 1,
+            end_id: 2,
+            expires: Expiration::Never {},
+        })
+    );
+
+    // tokens in range can be transferred, others not
+    contract
+        .execute(deps.as_mut(), env.clone(), market.clone(), transfer("1"))
+        .unwrap();
+    for token_id in ["3", "special"] {
+        let err = contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                market.clone(),
+                transfer(token_id),
+            )
+            .unwrap_err();
+        assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    }
+    // range doesn't grant burning
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            market.clone(),
+            Cw721ExecuteMsg::Burn {
+                token_id: "2".to_string(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // only applies to tokens owned by granter, token 1 is owned by bob now
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), market.clone(), transfer("1"))
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            Cw721ExecuteMsg::RevokeRange {
+                spender: "market".to_string(),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), env, market, transfer("2"))
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+}
+
+#[test]
+fn revoke_all_operators() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let env = mock_env();
+    let alice = mock_info("alice", &[]);
+    for operator in ["market1", "market2", "market3"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                alice.clone(),
+                Cw721ExecuteMsg::ApproveAll {
+                    operator: operator.to_string(),
+                    expires: None,
+                    filter: None,
+                },
+            )
+            .unwrap();
+    }
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob", &[]),
+            Cw721ExecuteMsg::ApproveAll {
+                operator: "market1".to_string(),
+                expires: None,
+                filter: None,
+            },
+        )
+        .unwrap();
+
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice,
+            Cw721ExecuteMsg::RevokeAllOperators {},
+        )
+        .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "operators_revoked" && attr.value == "3"));
+    let operators = contract
+        .query_operators(
+            deps.as_ref(),
+            env.clone(),
+            "alice".to_string(),
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+    assert!(operators.operators.is_empty());
+    // operators of other granters are kept
+    let operators = contract
+        .query_operators(deps.as_ref(), env, "bob".to_string(), true, None, None)
+        .unwrap();
+    assert_eq!(operators.operators.len(), 1);
+}
+
+#[test]
+fn operators_expiring_before() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let env = mock_env();
+    let height = env.block.height;
+    let approve_all = |deps: DepsMut, granter: &str, operator: &str, expires: Expiration| {
+        contract
+            .execute(
+                deps,
+                mock_env(),
+                mock_info(granter, &[]),
+                Cw721ExecuteMsg::ApproveAll {
+                    operator: operator.to_string(),
+                    expires: Some(expires),
+                    filter: None,
+                },
+            )
+            .unwrap();
+    };
+    let expiring_before = |deps: Deps, before: Expiration, limit: u32, cursor: Option<Binary>| {
+        let res: OperatorGrantsResponse = from_json(
+            contract
+                .query(
+                    deps,
+                    mock_env(),
+                    Cw721QueryMsg::OperatorsExpiringBefore {
+                        before,
+                        limit: Some(limit),
+                        cursor,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        res
+    };
+    let grant = |granter: &str, operator: &str, expires: Expiration| OperatorGrant {
+        granter: Addr::unchecked(granter),
+        operator: Addr::unchecked(operator),
+        expires,
+    };
+
+    approve_all(
+        deps.as_mut(),
+        "alice",
+        "market1",
+        Expiration::AtHeight(height + 30),
+    );
+    approve_all(
+        deps.as_mut(),
+        "bob",
+        "market1",
+        Expiration::AtHeight(height + 10),
+    );
+    approve_all(
+        deps.as_mut(),
+        "alice",
+        "market2",
+        Expiration::AtHeight(height + 20),
+    );
+    approve_all(deps.as_mut(), "alice", "market3", Expiration::Never {});
+    approve_all(
+        deps.as_mut(),
+        "bob",
+        "market2",
+        Expiration::AtTime(env.block.time.plus_seconds(60)),
+    );
+
+    // soonest first, time based and never expiring grants aren't listed
+    let res = expiring_before(deps.as_ref(), Expiration::AtHeight(height + 30), 10, None);
+    assert_eq!(
+        res.grants,
+        vec![
+            grant("bob", "market1", Expiration::AtHeight(height + 10)),
+            grant("alice", "market2", Expiration::AtHeight(height + 20)),
+        ]
+    );
+    assert_eq!(res.next_cursor, None);
+    let res = expiring_before(
+        deps.as_ref(),
+        Expiration::AtTime(env.block.time.plus_seconds(61)),
+        10,
+        None,
+    );
+    assert_eq!(
+        res.grants,
+        vec![grant(
+            "bob",
+            "market2",
+            Expiration::AtTime(env.block.time.plus_seconds(60))
+        )]
+    );
+
+    // paginated
+    let res = expiring_before(deps.as_ref(), Expiration::AtHeight(height + 100), 2, None);
+    assert_eq!(res.grants.len(), 2);
+    let res = expiring_before(
+        deps.as_ref(),
+        Expiration::AtHeight(height + 100),
+        2,
+        res.next_cursor,
+    );
+    assert_eq!(
+        res.grants,
+        vec![grant("alice", "market1", Expiration::AtHeight(height + 30))]
+    );
+
+    // renewed and revoked grants are reindexed
+    approve_all(
+        deps.as_mut(),
+        "bob",
+        "market1",
+        Expiration::AtHeight(height + 40),
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            Cw721ExecuteMsg::RevokeAll {
+                operator: "market2".to_string(),
+            },
+        )
+        .unwrap();
+    let res = expiring_before(deps.as_ref(), Expiration::AtHeight(height + 100), 10, None);
+    assert_eq!(
+        res.grants,
+        vec![
+            grant("alice", "market1", Expiration::AtHeight(height + 30)),
+            grant("bob", "market1", Expiration::AtHeight(height + 40)),
+        ]
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            env,
+            mock_info("alice", &[]),
+            Cw721ExecuteMsg::RevokeAllOperators {},
+        )
+        .unwrap();
+    let res = expiring_before(deps.as_ref(), Expiration::AtHeight(height + 100), 10, None);
+    assert_eq!(
+        res.grants,
+        vec![grant("bob", "market1", Expiration::AtHeight(height + 40))]
+    );
+}
+
+#[test]
+fn approve_all_with_filter() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let env = mock_env();
+    for token_id in ["edition-1", "edition-2", "one-of-one", "special"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: "alice".to_string(),
+                    token_uri: None,
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    let alice = mock_info("alice", &[]);
+    let market = mock_info("market", &[]);
+    let approve_all = |filter: Option<OperatorFilter>| Cw721ExecuteMsg::ApproveAll {
+        operator: "market".to_string(),
+        expires: None,
+        filter,
+    };
+    let transfer = |token_id: &str| Cw721ExecuteMsg::TransferNft {
+        recipient: "bob".to_string(),
+        token_id: token_id.to_string(),
+    };
+
+    // empty and oversized filters are rejected
+    for filter in [
+        OperatorFilter::TokenIdPrefix("".to_string()),
+        OperatorFilter::TokenIds(vec![]),
+        OperatorFilter::TokenIds(vec!["1".to_string(); MAX_OPERATOR_FILTER_TOKEN_IDS + 1]),
+    ] {
+        let err = contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                alice.clone(),
+                approve_all(Some(filter)),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Cw721ContractError::InvalidOperatorFilter {
+                max: MAX_OPERATOR_FILTER_TOKEN_IDS
+            }
+        );
+    }
+
+    // prefix filter covers editions only
+    let filter = OperatorFilter::TokenIdPrefix("edition-".to_string());
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            approve_all(Some(filter.clone())),
+        )
+        .unwrap();
+    let stored: Option<OperatorFilter> = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                env.clone(),
+                Cw721QueryMsg::OperatorFilter {
+                    owner: "alice".to_string(),
+                    operator: "market".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stored, Some(filter));
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            market.clone(),
+            transfer("edition-1"),
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            market.clone(),
+            transfer("one-of-one"),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    // approving others is restricted as well
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            market.clone(),
+            Cw721ExecuteMsg::Approve {
+                spender: "random".to_string(),
+                token_id: "one-of-one".to_string(),
+                expires: None,
+                permissions: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // renewing replaces the filter
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            approve_all(Some(OperatorFilter::TokenIds(vec!["special".to_string()]))),
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            market.clone(),
+            transfer("edition-2"),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            market.clone(),
+            transfer("special"),
+        )
+        .unwrap();
+
+    // unfiltered grant covers all tokens
+    contract
+        .execute(deps.as_mut(), env.clone(), alice, approve_all(None))
+        .unwrap();
+    let stored: Option<OperatorFilter> = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                env.clone(),
+                Cw721QueryMsg::OperatorFilter {
+                    owner: "alice".to_string(),
+                    operator: "market".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(stored, None);
+    for token_id in ["edition-2", "one-of-one"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                market.clone(),
+                transfer(token_id),
+            )
+            .unwrap();
+    }
+}
+
+#[test]
+fn transfer_offer() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let alice = mock_info("alice", &[]);
+    let bob = mock_info("bob", &[]);
+    let env = mock_env();
+    for token_id in ["grail", "common"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: "alice".to_string(),
+                    token_uri: None,
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    let offer_msg = |token_id: &str, expires: Option<Expiration>| Cw721ExecuteMsg::OfferTransfer {
+        recipient: "bob".to_string(),
+        token_id: token_id.to_string(),
+        expires,
+    };
+    let claim_msg = |token_id: &str| Cw721ExecuteMsg::ClaimTransfer {
+        token_id: token_id.to_string(),
+    };
+    let owner_of = |deps: Deps, token_id: &str| {
+        contract
+            .query_owner_of(deps, mock_env(), token_id.to_string(), false)
+            .unwrap()
+            .owner
+    };
+
+    // only holder can offer
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            bob.clone(),
+            offer_msg("grail", None),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            offer_msg("grail", None),
+        )
+        .unwrap();
+    assert_eq!(
+        contract
+            .query_transfer_offer(deps.as_ref(), "grail".to_string())
+            .unwrap(),
+        Some(TransferOffer {
+            owner: Addr::unchecked("alice"),
+            recipient: Addr::unchecked("bob"),
+            expires: Expiration::Never {},
+        })
+    );
+    // offering doesn't transfer
+    assert_eq!(owner_of(deps.as_ref(), "grail"), "alice");
+
+    // only recipient can claim
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            claim_msg("grail"),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::NoTransferOffer {
+            token_id: "grail".to_string()
+        }
+    );
+    let res = contract
+        .execute(deps.as_mut(), env.clone(), bob.clone(), claim_msg("grail"))
+        .unwrap();
+    assert_eq!(
+        res.events,
+        vec![Event::new("cw721_claim_transfer").add_attributes(vec![
+            ("sender", "bob"),
+            ("owner", "alice"),
+            ("token_id", "grail"),
+        ])]
+    );
+    assert_eq!(owner_of(deps.as_ref(), "grail"), "bob");
+    assert_eq!(
+        contract
+            .query_transfer_offer(deps.as_ref(), "grail".to_string())
+            .unwrap(),
+        None
+    );
+
+    // expired offers can't be claimed
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            offer_msg("common", Some(Expiration::AtHeight(env.block.height + 10))),
+        )
+        .unwrap();
+    let mut later = env.clone();
+    later.block.height += 10;
+    let err = contract
+        .execute(deps.as_mut(), later, bob.clone(), claim_msg("common"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::NoTransferOffer {
+            token_id: "common".to_string()
+        }
+    );
+
+    // offers are void once the token changed hands, and can be cancelled
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            offer_msg("common", None),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            Cw721ExecuteMsg::TransferNft {
+                recipient: "carol".to_string(),
+                token_id: "common".to_string(),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), bob.clone(), claim_msg("common"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::NoTransferOffer {
+            token_id: "common".to_string()
+        }
+    );
+    let carol = mock_info("carol", &[]);
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            carol.clone(),
+            offer_msg("common", None),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            carol,
+            Cw721ExecuteMsg::CancelTransferOffer {
+                token_id: "common".to_string(),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), env, bob, claim_msg("common"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::NoTransferOffer {
+            token_id: "common".to_string()
+        }
+    );
+}
+
+#[test]
+fn gift_lock() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let alice = mock_info("alice", &[]);
+    let env = mock_env();
+    for token_id in ["gift1", "gift2"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: "alice".to_string(),
+                    token_uri: None,
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    let secret = Binary::from(b"happy birthday".to_vec());
+    let hash = HexBinary::from(Sha256::digest(secret.as_slice()).as_slice());
+    let expires = Expiration::AtHeight(env.block.height + 100);
+    let lock_msg = |token_id: &str, expires: Expiration| Cw721ExecuteMsg::LockGift {
+        token_id: token_id.to_string(),
+        hash: hash.clone(),
+        expires,
+    };
+    let claim_msg = |token_id: &str, secret: &Binary| Cw721ExecuteMsg::ClaimGift {
+        token_id: token_id.to_string(),
+        secret: secret.clone(),
+        recipient: None,
+    };
+    let commit_msg = |token_id: &str, recipient: &str| Cw721ExecuteMsg::CommitGiftClaim {
+        token_id: token_id.to_string(),
+        commitment: gift_claim_commitment(secret.as_slice(), &Addr::unchecked(recipient)),
+    };
+    let owner_of = |deps: Deps, token_id: &str| {
+        contract
+            .query_owner_of(deps, mock_env(), token_id.to_string(), false)
+            .unwrap()
+            .owner
+    };
+
+    // gifts must expire, so they can be reclaimed
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            lock_msg("gift1", Expiration::Never {}),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::GiftMustExpire {});
+    // only holder can lock
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            lock_msg("gift1", expires),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    for token_id in ["gift1", "gift2"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                alice.clone(),
+                lock_msg(token_id, expires),
+            )
+            .unwrap();
+    }
+    // escrowed in contract
+    assert_eq!(
+        owner_of(deps.as_ref(), "gift1"),
+        env.contract.address.as_str()
+    );
+    assert_eq!(
+        contract
+            .query_gift_lock(deps.as_ref(), "gift1".to_string())
+            .unwrap(),
+        Some(GiftLock {
+            owner: Addr::unchecked("alice"),
+            hash: hash.clone(),
+            expires,
+        })
+    );
+    let status = contract
+        .query_token_status(deps.as_ref(), env.clone(), "gift1".to_string())
+        .unwrap();
+    assert!(!status.transferable);
+    assert_eq!(
+        status.gift_lock.map(|lock| lock.owner),
+        Some(Addr::unchecked("alice"))
+    );
+
+    // claimable by anyone with the secret
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob", &[]),
+            claim_msg("gift1", &Binary::from(b"wrong guess".to_vec())),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::InvalidGiftSecret {
+            token_id: "gift1".to_string()
+        }
+    );
+    // owner can't reclaim before expiration
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            alice.clone(),
+            Cw721ExecuteMsg::ReclaimGift {
+                token_id: "gift1".to_string(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::GiftNotExpired {
+            token_id: "gift1".to_string()
+        }
+    );
+
+    // claim must be committed in an earlier block
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob", &[]),
+            commit_msg("gift1", "bob"),
+        )
+        .unwrap();
+    let not_committed = Cw721ContractError::GiftClaimNotCommitted {
+        token_id: "gift1".to_string(),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob", &[]),
+            claim_msg("gift1", &secret),
+        )
+        .unwrap_err();
+    assert_eq!(err, not_committed);
+
+    // front-running with the revealed secret fails, since recipient is bound to it
+    let mut next = env.clone();
+    next.block.height += 1;
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            next.clone(),
+            mock_info("mallory", &[]),
+            claim_msg("gift1", &secret),
+        )
+        .unwrap_err();
+    assert_eq!(err, not_committed);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            next.clone(),
+            mock_info("mallory", &[]),
+            Cw721ExecuteMsg::ClaimGift {
+                token_id: "gift1".to_string(),
+                secret: secret.clone(),
+                recipient: Some("mallory".to_string()),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, not_committed);
+    contract
+        .execute(
+            deps.as_mut(),
+            next,
+            mock_info("bob", &[]),
+            claim_msg("gift1", &secret),
+        )
+        .unwrap();
+    assert_eq!(owner_of(deps.as_ref(), "gift1"), "bob");
+    assert_eq!(
+        contract
+            .query_gift_lock(deps.as_ref(), "gift1".to_string())
+            .unwrap(),
+        None
+    );
+
+    // expired gifts can't be claimed, but reclaimed by owner only
+    let mut later = env.clone();
+    later.block.height += 100;
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            later.clone(),
+            mock_info("bob", &[]),
+            claim_msg("gift2", &secret),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::GiftExpired {
+            token_id: "gift2".to_string()
+        }
+    );
+    let reclaim_msg = Cw721ExecuteMsg::ReclaimGift {
+        token_id: "gift2".to_string(),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            later.clone(),
+            mock_info("bob", &[]),
+            reclaim_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            later.clone(),
+            alice.clone(),
+            reclaim_msg.clone(),
+        )
+        .unwrap();
+    assert_eq!(owner_of(deps.as_ref(), "gift2"), "alice");
+    let err = contract
+        .execute(deps.as_mut(), later, alice, reclaim_msg)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::NoGift {
+            token_id: "gift2".to_string()
+        }
+    );
+}
+
+#[test]
+fn gift_lock_removed_on_burn() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let env = mock_env();
+    let mint_msg = |owner: &str| Cw721ExecuteMsg::Mint {
+        token_id: "gift".to_string(),
+        owner: owner.to_string(),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    let secret = Binary::from(b"happy birthday".to_vec());
+    let claim_msg = Cw721ExecuteMsg::ClaimGift {
+        token_id: "gift".to_string(),
+        secret: secret.clone(),
+        recipient: None,
+    };
+    let no_gift = Cw721ContractError::NoGift {
+        token_id: "gift".to_string(),
+    };
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            mint_msg("alice"),
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            Cw721ExecuteMsg::LockGift {
+                token_id: "gift".to_string(),
+                hash: HexBinary::from(Sha256::digest(secret.as_slice()).as_slice()),
+                expires: Expiration::AtHeight(env.block.height + 100),
+            },
+        )
+        .unwrap();
+
+    // burned by escrow holder, lock goes with it
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(env.contract.address.as_str(), &[]),
+            Cw721ExecuteMsg::Burn {
+                token_id: "gift".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        contract
+            .query_gift_lock(deps.as_ref(), "gift".to_string())
+            .unwrap(),
+        None
+    );
+
+    // re-minted token can't be claimed with the old secret
+    contract
+        .execute(deps.as_mut(), env.clone(), minter, mint_msg("carol"))
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob", &[]),
+            claim_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, no_gift);
+
+    // stale lock of a token outside escrow is rejected as well
+    contract
+        .config
+        .gift_locks
+        .save(
+            deps.as_mut().storage,
+            "gift",
+            &GiftLock {
+                owner: Addr::unchecked("alice"),
+                hash: HexBinary::from(Sha256::digest(secret.as_slice()).as_slice()),
+                expires: Expiration::AtHeight(env.block.height + 100),
+            },
+        )
+        .unwrap();
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), claim_msg)
+        .unwrap_err();
+    assert_eq!(err, no_gift);
+    let mut later = env;
+    later.block.height += 100;
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            later,
+            mock_info("alice", &[]),
+            Cw721ExecuteMsg::ReclaimGift {
+                token_id: "gift".to_string(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, no_gift);
+}
+
+#[test]
+fn royalty_config_and_trading_schedule() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let env = mock_env();
+
+    // unset by default
+    assert_eq!(
+        contract.query_royalty_config(deps.as_ref()).unwrap(),
+        RoyaltyConfigResponse {
+            payout_denoms: None,
+            royalty_admin: None,
+            operator_registry: None,
+        }
+    );
+    assert_eq!(
+        contract.query_trading_schedule(deps.as_ref()).unwrap(),
+        TradingScheduleResponse {
+            mint_window: None,
+            transfer_cooldown: None,
+            paused: None,
+            frozen: None,
+        }
+    );
+
+    let denoms = vec![PayoutDenom::Native {
+        denom: "uatom".to_string(),
+    }];
+    let window = MintWindow {
+        start: Some(env.block.time.plus_seconds(60)),
+        end: None,
+    };
+    for msg in [
+        Cw721ExecuteMsg::SetRoyaltyPayoutDenoms {
+            denoms: Some(denoms.clone()),
+        },
+        Cw721ExecuteMsg::SetRoyaltyAdmin {
+            admin: Some("finance".to_string()),
+        },
+        Cw721ExecuteMsg::SetMintWindow {
+            window: Some(window.clone()),
+        },
+        Cw721ExecuteMsg::SetTransferCooldown {
+            cooldown: Some(Duration::Height(10)),
+        },
+    ] {
+        contract
+            .execute(deps.as_mut(), env.clone(), minter.clone(), msg)
+            .unwrap();
+    }
+    let res: RoyaltyConfigResponse = from_json(
+        contract
+            .query(deps.as_ref(), env.clone(), Cw721QueryMsg::RoyaltyConfig {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        RoyaltyConfigResponse {
+            payout_denoms: Some(denoms),
+            royalty_admin: Some(Addr::unchecked("finance")),
+            operator_registry: None,
+        }
+    );
+    let res: TradingScheduleResponse = from_json(
+        contract
+            .query(deps.as_ref(), env, Cw721QueryMsg::TradingSchedule {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        TradingScheduleResponse {
+            mint_window: Some(window),
+            mint_rate_limit: None,
+            transfer_cooldown: Some(Duration::Height(10)),
+            paused: None,
+            frozen: None,
+        }
+    );
+}
+
+#[test]
+fn metadata_limits() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    // defaults apply
+    let limits = contract.query_metadata_limits(deps.as_ref()).unwrap();
+    assert_eq!(limits, MetadataLimits::default());
+
+    // only owner can set limits
+    let set_msg = Cw721ExecuteMsg::SetMetadataLimits {
+        limits: Some(MetadataLimits {
+            max_token_uri_bytes: 10,
+            max_extension_bytes: 256,
+        }),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("random", &[]),
+            set_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            set_msg,
+        )
+        .unwrap();
+
+    let mint_msg = |token_uri: &str, description: &str| Cw721ExecuteMsg::Mint {
+        token_id: "grow".to_string(),
+        owner: String::from("demeter"),
+        token_uri: Some(token_uri.to_string()),
+        extension: Some(Metadata {
+            description: Some(description.to_string()),
+            ..Metadata::default()
+        }),
+        approval: None,
+        metadata_hash: None,
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            mint_msg("ipfs://too-long", "small"),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::MetadataTooLarge {
+            field: "token_uri".to_string(),
+            size: 15,
+            max: 10,
+        }
+    );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            mint_msg("ipfs://a", &"x".repeat(64)),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Cw721ContractError::MetadataTooLarge { field, max: 256, .. } if field == "extension"
+    ));
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            mint_msg("ipfs://a", "small"),
+        )
+        .unwrap();
+
+    // unset restores defaults
+    contract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(MINTER_ADDR, &[]),
+            Cw721ExecuteMsg::SetMetadataLimits { limits: None },
+        )
+        .unwrap();
+    let limits = contract.query_metadata_limits(deps.as_ref()).unwrap();
+    assert_eq!(limits, MetadataLimits::default());
+}
+
+#[test]
+fn role_members() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+
+    let query_members = |deps: Deps, role: Role, start_after: Option<String>| {
+        let res: RoleMembersResponse = from_json(
+            contract
+                .query(
+                    deps,
+                    mock_env(),
+                    Cw721QueryMsg::RoleMembers {
+                        role,
+                        start_after,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        res.members
+    };
+
+    // same holder as `Minter {}`
+    assert_eq!(
+        query_members(deps.as_ref(), Role::Minter, None),
+        vec![Addr::unchecked(MINTER_ADDR)]
+    );
+    assert_eq!(
+        query_members(deps.as_ref(), Role::Creator, None),
+        vec![Addr::unchecked(MINTER_ADDR)]
+    );
+    // paginated past the only holder
+    assert_eq!(
+        query_members(deps.as_ref(), Role::Minter, Some(MINTER_ADDR.to_string())),
+        Vec::<Addr>::new()
+    );
+    // no royalty admin appointed
+    assert_eq!(
+        query_members(deps.as_ref(), Role::RoyaltyAdmin, None),
+        Vec::<Addr>::new()
+    );
+}
+
+#[test]
+fn persistent_operator() {
+    let mut deps = mock_dependencies();
+    let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let staking = mock_info("staking", &[]);
+    let env = mock_env();
+    let mint_msg = |token_id: &str| Cw721ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: "alice".to_string(),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    let send_msg = |contract: &str| Cw721ExecuteMsg::SendNft {
+        contract: contract.to_string(),
+        token_id: "grail".to_string(),
+        msg: to_json_binary("stake").unwrap(),
+    };
+    let query_operators = |deps: Deps, owner: Option<String>| {
+        let res: PersistentOperatorsResponse = from_json(
+            contract
+                .query(
+                    deps,
+                    mock_env(),
+                    Cw721QueryMsg::PersistentOperators {
+                        owner,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        res.operators
+    };
+
+    // only creator can add persistent operators
+    let add_msg = Cw721ExecuteMsg::AddPersistentOperator {
+        operator: "staking".to_string(),
+    };
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("alice", &[]),
+            add_msg.clone(),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), add_msg)
+        .unwrap();
+    assert_eq!(
+        query_operators(deps.as_ref(), None),
+        vec![Addr::unchecked("staking")]
+    );
+
+    // can't be added once holders exist
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            mint_msg("grail"),
+        )
+        .unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::AddPersistentOperator {
+                operator: "rental".to_string(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::PersistentOperatorLocked {});
+
+    // operator may only send, but neither transfer nor approve
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            staking.clone(),
+            Cw721ExecuteMsg::TransferNft {
+                recipient: "staking".to_string(),
+                token_id: "grail".to_string(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            staking.clone(),
+            Cw721ExecuteMsg::Approve {
+                spender: "staking".to_string(),
+                token_id: "grail".to_string(),
+                expires: None,
+                permissions: None,
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+
+    // operator stays approved across transfers
+    contract
+        .execute(deps.as_mut(), env.clone(), staking.clone(), send_msg("bob"))
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            staking.clone(),
+            send_msg("carol"),
+        )
+        .unwrap();
+
+    // holder can opt out
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("carol", &[]),
+            Cw721ExecuteMsg::OptOutPersistentOperator {
+                operator: "staking".to_string(),
+            },
+        )
+        .unwrap();
+    assert_eq!(
+        query_operators(deps.as_ref(), Some("carol".to_string())),
+        Vec::<Addr>::new()
+    );
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            staking.clone(),
+            send_msg("dave"),
+        )
         .unwrap_err();
     assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
 
-    // minter can set
+    // and opt in again
     contract
-        .set_withdraw_address(
+        .execute(
             deps.as_mut(),
-            &Addr::unchecked(MINTER_ADDR),
-            "foo".to_string(),
+            env.clone(),
+            mock_info("carol", &[]),
+            Cw721ExecuteMsg::OptInPersistentOperator {
+                operator: "staking".to_string(),
+            },
+        )
+        .unwrap();
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            staking.clone(),
+            send_msg("dave"),
         )
         .unwrap();
 
-    let withdraw_address = contract
-        .config
-        .withdraw_address
-        .load(deps.as_ref().storage)
+    // removed operator loses access
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter,
+            Cw721ExecuteMsg::RemovePersistentOperator {
+                operator: "staking".to_string(),
+            },
+        )
         .unwrap();
-    assert_eq!(withdraw_address, "foo".to_string())
+    let err = contract
+        .execute(deps.as_mut(), env, staking, send_msg("erin"))
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
 }
 
 #[test]
-fn test_remove_withdraw_address() {
+fn persistent_operator_excludes_contract_holders() {
     let mut deps = mock_dependencies();
     let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let staking = mock_info("staking", &[]);
+    let env = mock_env();
+    // "escrow" is a contract holding tokens on behalf of others
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::ContractInfo { contract_addr } if contract_addr == "escrow" => {
+            let res = ContractInfoResponse::new(1, "creator");
+            SystemResult::Ok(ContractResult::Ok(to_json_binary(&res).unwrap()))
+        }
+        _ => SystemResult::Err(SystemError::NoSuchContract {
+            addr: "unknown".to_string(),
+        }),
+    });
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::AddPersistentOperator {
+                operator: "staking".to_string(),
+            },
+        )
+        .unwrap();
+    for (token_id, owner) in [("escrowed", "escrow"), ("gift", "alice")] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: owner.to_string(),
+                    token_uri: None,
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    let send_msg = |token_id: &str| Cw721ExecuteMsg::SendNft {
+        contract: "vault".to_string(),
+        token_id: token_id.to_string(),
+        msg: to_json_binary("stake").unwrap(),
+    };
+    let query_operators = |deps: Deps, owner: &str| {
+        let res: PersistentOperatorsResponse = from_json(
+            contract
+                .query(
+                    deps,
+                    mock_env(),
+                    Cw721QueryMsg::PersistentOperators {
+                        owner: Some(owner.to_string()),
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap(),
+        )
+        .unwrap();
+        res.operators
+    };
 
-    // other than creator cant remove
+    // contract holders must opt in
+    assert_eq!(query_operators(deps.as_ref(), "escrow"), Vec::<Addr>::new());
     let err = contract
-        .remove_withdraw_address(deps.as_mut().storage, &Addr::unchecked("other"))
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            staking.clone(),
+            send_msg("escrowed"),
+        )
         .unwrap_err();
     assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
-
-    // no withdraw address set yet
-    let err = contract
-        .remove_withdraw_address(deps.as_mut().storage, &Addr::unchecked(MINTER_ADDR))
-        .unwrap_err();
-    assert_eq!(err, Cw721ContractError::NoWithdrawAddress {});
-
-    // set and remove
     contract
-        .set_withdraw_address(
+        .execute(
             deps.as_mut(),
-            &Addr::unchecked(MINTER_ADDR),
-            "foo".to_string(),
+            env.clone(),
+            mock_info("escrow", &[]),
+            Cw721ExecuteMsg::OptInPersistentOperator {
+                operator: "staking".to_string(),
+            },
         )
         .unwrap();
+    assert_eq!(
+        query_operators(deps.as_ref(), "escrow"),
+        vec![Addr::unchecked("staking")]
+    );
     contract
-        .remove_withdraw_address(deps.as_mut().storage, &Addr::unchecked(MINTER_ADDR))
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            staking.clone(),
+            send_msg("escrowed"),
+        )
         .unwrap();
-    assert!(!contract
-        .config
-        .withdraw_address
-        .exists(deps.as_ref().storage));
 
-    // test that we can set again
+    // gifts escrowed by this contract are never operated
     contract
-        .set_withdraw_address(
+        .execute(
             deps.as_mut(),
-            &Addr::unchecked(MINTER_ADDR),
-            "foo".to_string(),
+            env.clone(),
+            mock_info("alice", &[]),
+            Cw721ExecuteMsg::LockGift {
+                token_id: "gift".to_string(),
+                hash: HexBinary::from(Sha256::digest(b"secret").as_slice()),
+                expires: Expiration::AtHeight(env.block.height + 100),
+            },
         )
         .unwrap();
-    let withdraw_address = contract
-        .config
-        .withdraw_address
-        .load(deps.as_ref().storage)
-        .unwrap();
-    assert_eq!(withdraw_address, "foo".to_string())
+    assert_eq!(
+        query_operators(deps.as_ref(), env.contract.address.as_str()),
+        Vec::<Addr>::new()
+    );
+    let err = contract
+        .execute(deps.as_mut(), env, staking, send_msg("gift"))
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
 }
 
 #[test]
-fn test_withdraw_funds() {
+fn mint_rate_limit() {
     let mut deps = mock_dependencies();
     let contract = setup_contract(deps.as_mut());
+    let minter = mock_info(MINTER_ADDR, &[]);
+    let mut env = mock_env();
+    let mint_msg = |token_id: &str| Cw721ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: "alice".to_string(),
+        token_uri: None,
+        extension: None,
+        approval: None,
+        metadata_hash: None,
+    };
+    let set_msg = |limit: Option<MintRateLimit>| Cw721ExecuteMsg::SetMintRateLimit { limit };
+    let limit = MintRateLimit {
+        max_mints: 2,
+        window: Duration::Height(1),
+    };
 
-    // no withdraw address set
+    // only owner can set a limit, and it must be non-zero
     let err = contract
-        .withdraw_funds(deps.as_mut().storage, &Coin::new(100, "uark"))
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            set_msg(Some(limit.clone())),
+        )
         .unwrap_err();
-    assert_eq!(err, Cw721ContractError::NoWithdrawAddress {});
-
-    // set and withdraw by non-owner
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            set_msg(Some(MintRateLimit {
+                max_mints: 2,
+                window: Duration::Time(0),
+            })),
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::InvalidMintRateLimit {});
     contract
-        .set_withdraw_address(
+        .execute(
             deps.as_mut(),
-            &Addr::unchecked(MINTER_ADDR),
-            "foo".to_string(),
+            env.clone(),
+            minter.clone(),
+            set_msg(Some(limit.clone())),
         )
         .unwrap();
+
+    // two mints per block
+    for token_id in ["1", "2"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                minter.clone(),
+                mint_msg(token_id),
+            )
+            .unwrap();
+    }
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("3"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::MintRateLimited {
+            max_mints: 2,
+            window: Duration::Height(1),
+        }
+    );
+    let res: MintRateLimitResponse = from_json(
+        contract
+            .query(deps.as_ref(), env.clone(), Cw721QueryMsg::MintRateLimit {})
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res,
+        MintRateLimitResponse {
+            limit: Some(limit),
+            minted: 2,
+        }
+    );
+
+    // next block opens a new window
+    env.block.height += 1;
+    contract
+        .execute(deps.as_mut(), env.clone(), minter.clone(), mint_msg("3"))
+        .unwrap();
+
+    // unset removes the limit
     contract
-        .withdraw_funds(deps.as_mut().storage, &Coin::new(100, "uark"))
+        .execute(deps.as_mut(), env.clone(), minter.clone(), set_msg(None))
         .unwrap();
+    for token_id in ["4", "5", "6"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                minter.clone(),
+                mint_msg(token_id),
+            )
+            .unwrap();
+    }
 }
 
 #[test]
-fn query_tokens_by_owner() {
+fn burn_windows() {
     let mut deps = mock_dependencies();
     let contract = setup_contract(deps.as_mut());
     let minter = mock_info(MINTER_ADDR, &[]);
-
-    // Mint a couple tokens (from the same owner)
-    let token_id1 = "grow1".to_string();
-    let demeter = String::from("demeter");
-    let token_id2 = "grow2".to_string();
-    let ceres = String::from("ceres");
-    let token_id3 = "sing".to_string();
-
-    let mint_msg = Cw721ExecuteMsg::Mint {
-        token_id: token_id1.clone(),
-        owner: demeter.clone(),
-        token_uri: None,
-        extension: None,
+    let holder = mock_info("holder", &[]);
+    let mut env = mock_env();
+    let now = env.block.time;
+    for token_id in ["1", "2"] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                minter.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: "holder".to_string(),
+                    token_uri: None,
+                    extension: None,
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    let burn_msg = |token_id: &str| Cw721ExecuteMsg::Burn {
+        token_id: token_id.to_string(),
     };
-    contract
-        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
-        .unwrap();
-
-    let mint_msg = Cw721ExecuteMsg::Mint {
-        token_id: token_id2.clone(),
-        owner: ceres.clone(),
-        token_uri: None,
-        extension: None,
+    let window = BurnWindow {
+        start: now.plus_seconds(100),
+        end: now.plus_seconds(200),
     };
-    contract
-        .execute(deps.as_mut(), mock_env(), minter.clone(), mint_msg)
-        .unwrap();
 
-    let mint_msg = Cw721ExecuteMsg::Mint {
-        token_id: token_id3.clone(),
-        owner: demeter.clone(),
-        token_uri: None,
-        extension: None,
-    };
-    let env = mock_env();
+    // only owner can set windows, and they must end after they start
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            holder.clone(),
+            Cw721ExecuteMsg::SetBurnWindows {
+                windows: vec![window.clone()],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetBurnWindows {
+                windows: vec![BurnWindow {
+                    start: window.end,
+                    end: window.start,
+                }],
+            },
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::InvalidBurnWindows {
+            max: MAX_BURN_WINDOWS
+        }
+    );
     contract
-        .execute(deps.as_mut(), env.clone(), minter, mint_msg)
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::SetBurnWindows {
+                windows: vec![window.clone()],
+            },
+        )
         .unwrap();
+    assert_eq!(
+        contract.query_burn_windows(deps.as_ref()).unwrap(),
+        vec![window.clone()]
+    );
 
-    // get all tokens in order:
-    let expected = vec![token_id1.clone(), token_id2.clone(), token_id3.clone()];
-    let tokens = contract
-        .query_all_tokens(deps.as_ref(), env.clone(), None, None)
-        .unwrap();
-    assert_eq!(&expected, &tokens.tokens);
-    // paginate
-    let tokens = contract
-        .query_all_tokens(deps.as_ref(), env.clone(), None, Some(2))
-        .unwrap();
-    assert_eq!(&expected[..2], &tokens.tokens[..]);
-    let tokens = contract
-        .query_all_tokens(deps.as_ref(), env.clone(), Some(expected[1].clone()), None)
-        .unwrap();
-    assert_eq!(&expected[2..], &tokens.tokens[..]);
+    // before window, error names the next window
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), holder.clone(), burn_msg("1"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::BurnWindowClosed {
+            next_start: Some(window.start)
+        }
+    );
 
-    // get by owner
-    let by_ceres = vec![token_id2];
-    let by_demeter = vec![token_id1, token_id3];
-    // all tokens by owner
-    let tokens = contract
-        .query_tokens(deps.as_ref(), env.clone(), demeter.clone(), None, None)
-        .unwrap();
-    assert_eq!(&by_demeter, &tokens.tokens);
-    let tokens = contract
-        .query_tokens(deps.as_ref(), env.clone(), ceres, None, None)
+    // within window
+    env.block.time = window.start;
+    contract
+        .execute(deps.as_mut(), env.clone(), holder.clone(), burn_msg("1"))
         .unwrap();
-    assert_eq!(&by_ceres, &tokens.tokens);
 
-    // paginate for demeter
-    let tokens = contract
-        .query_tokens(deps.as_ref(), env.clone(), demeter.clone(), None, Some(1))
-        .unwrap();
-    assert_eq!(&by_demeter[..1], &tokens.tokens[..]);
-    let tokens = contract
-        .query_tokens(
-            deps.as_ref(),
-            env,
-            demeter,
-            Some(by_demeter[0].clone()),
-            Some(3),
+    // after window
+    env.block.time = window.end;
+    let err = contract
+        .execute(deps.as_mut(), env.clone(), holder.clone(), burn_msg("2"))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::BurnWindowClosed { next_start: None }
+    );
+
+    // empty list removes restriction
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter,
+            Cw721ExecuteMsg::SetBurnWindows { windows: vec![] },
         )
         .unwrap();
-    assert_eq!(&by_demeter[1..], &tokens.tokens[..]);
+    contract
+        .execute(deps.as_mut(), env, holder, burn_msg("2"))
+        .unwrap();
 }
@@ -1,6 +1,6 @@
 use crate::{
     error::Cw721ContractError,
-    execute::Cw721Execute,
+    execute::{Cw721Execute, BASE_COMPATIBLE_CONTRACT_NAMES},
     msg::{
         Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721MigrateMsg, Cw721QueryMsg, MinterResponse,
         OwnerOfResponse,
@@ -30,7 +30,7 @@ pub fn instantiate(
     msg: Cw721InstantiateMsg,
 ) -> Result<Response, Cw721ContractError> {
     let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
-    contract.instantiate(deps, env, info, msg, "contract_name", "contract_version")
+    contract.instantiate(deps, env, info, msg, "contract_name", "1.0.0")
 }
 
 pub fn execute(
@@ -58,7 +58,14 @@ pub fn migrate(
     msg: Cw721MigrateMsg,
 ) -> Result<Response, Cw721ContractError> {
     let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
-    contract.migrate(deps, env, msg, "contract_name", "contract_version")
+    contract.migrate(
+        deps,
+        env,
+        msg,
+        "contract_name",
+        "1.0.0",
+        BASE_COMPATIBLE_CONTRACT_NAMES,
+    )
 }
 
 fn cw721_base_latest_contract() -> Box<dyn Contract<Empty>> {
@@ -118,6 +125,8 @@ fn mint_transfer_and_burn(app: &mut App, cw721: Addr, sender: Addr, token_id: St
             owner: sender.to_string(),
             token_uri: None,
             extension: Empty::default(),
+            approval: None,
+            metadata_hash: None,
         },
         &[],
     )
@@ -165,6 +174,8 @@ fn test_operator() {
                 symbol: "symbol".to_string(),
                 minter: Some(MINTER_ADDR.to_string()),
                 withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
             },
             &[],
             "cw721-base",
@@ -182,6 +193,8 @@ fn test_operator() {
             owner: nft_owner.to_string(),
             token_uri: None,
             extension: Empty::default(),
+            approval: None,
+            metadata_hash: None,
         },
         &[],
     )
@@ -195,6 +208,7 @@ fn test_operator() {
         &Cw721ExecuteMsg::<Empty, Empty>::ApproveAll {
             operator: other.to_string(),
             expires: Some(Expiration::Never {}),
+            filter: None,
         },
         &[],
     )
@@ -374,6 +388,12 @@ fn test_migration_legacy_to_latest() {
                 msg: to_json_binary(&Cw721MigrateMsg::WithUpdate {
                     minter: None,
                     creator: None,
+                    withdraw_address: None,
+                    name: None,
+                    symbol: None,
+                    allow_unknown: None,
+                    two_phase: None,
+                    import_mode: None,
                 })
                 .unwrap(),
             }
@@ -392,6 +412,8 @@ fn test_migration_legacy_to_latest() {
                     owner: other.to_string(),
                     token_uri: None,
                     extension: Empty::default(),
+                    approval: None,
+                    metadata_hash: None,
                 },
                 &[],
             )
@@ -472,6 +494,12 @@ fn test_migration_legacy_to_latest() {
                 msg: to_json_binary(&Cw721MigrateMsg::WithUpdate {
                     minter: Some(MINTER_ADDR.to_string()),
                     creator: Some(CREATOR_ADDR.to_string()),
+                    withdraw_address: None,
+                    name: None,
+                    symbol: None,
+                    allow_unknown: None,
+                    two_phase: None,
+                    import_mode: None,
                 })
                 .unwrap(),
             }
@@ -489,6 +517,8 @@ fn test_migration_legacy_to_latest() {
                     owner: legacy_creator_and_minter.to_string(),
                     token_uri: None,
                     extension: Empty::default(),
+                    approval: None,
+                    metadata_hash: None,
                 },
                 &[],
             )
@@ -565,6 +595,12 @@ fn test_migration_legacy_to_latest() {
                 msg: to_json_binary(&Cw721MigrateMsg::WithUpdate {
                     minter: None,
                     creator: None,
+                    withdraw_address: None,
+                    name: None,
+                    symbol: None,
+                    allow_unknown: None,
+                    two_phase: None,
+                    import_mode: None,
                 })
                 .unwrap(),
             }
@@ -583,6 +619,8 @@ fn test_migration_legacy_to_latest() {
                     owner: other.to_string(),
                     token_uri: None,
                     extension: Empty::default(),
+                    approval: None,
+                    metadata_hash: None,
                 },
                 &[],
             )
@@ -663,6 +701,12 @@ fn test_migration_legacy_to_latest() {
                 msg: to_json_binary(&Cw721MigrateMsg::WithUpdate {
                     minter: Some(MINTER_ADDR.to_string()),
                     creator: Some(CREATOR_ADDR.to_string()),
+                    withdraw_address: None,
+                    name: None,
+                    symbol: None,
+                    allow_unknown: None,
+                    two_phase: None,
+                    import_mode: None,
                 })
                 .unwrap(),
             }
@@ -680,6 +724,8 @@ fn test_migration_legacy_to_latest() {
                     owner: legacy_creator_and_minter.to_string(),
                     token_uri: None,
                     extension: Empty::default(),
+                    approval: None,
+                    metadata_hash: None,
                 },
                 &[],
             )
@@ -756,6 +802,12 @@ fn test_migration_legacy_to_latest() {
                 msg: to_json_binary(&Cw721MigrateMsg::WithUpdate {
                     minter: None,
                     creator: None,
+                    withdraw_address: None,
+                    name: None,
+                    symbol: None,
+                    allow_unknown: None,
+                    two_phase: None,
+                    import_mode: None,
                 })
                 .unwrap(),
             }
@@ -774,6 +826,8 @@ fn test_migration_legacy_to_latest() {
                     owner: other.to_string(),
                     token_uri: None,
                     extension: Empty::default(),
+                    approval: None,
+                    metadata_hash: None,
                 },
                 &[],
             )
@@ -854,6 +908,12 @@ fn test_migration_legacy_to_latest() {
                 msg: to_json_binary(&Cw721MigrateMsg::WithUpdate {
                     minter: Some(MINTER_ADDR.to_string()),
                     creator: Some(CREATOR_ADDR.to_string()),
+                    withdraw_address: None,
+                    name: None,
+                    symbol: None,
+                    allow_unknown: None,
+                    two_phase: None,
+                    import_mode: None,
                 })
                 .unwrap(),
             }
@@ -871,6 +931,8 @@ fn test_migration_legacy_to_latest() {
                     owner: legacy_creator_and_minter.to_string(),
                     token_uri: None,
                     extension: Empty::default(),
+                    approval: None,
+                    metadata_hash: None,
                 },
                 &[],
             )
@@ -1,12 +1,16 @@
 use crate::{
-    execute::Cw721Execute,
-    msg::{Cw721ExecuteMsg, Cw721InstantiateMsg},
-    query::{Cw721Query, MAX_LIMIT},
-    state::{CollectionInfo, DefaultOptionMetadataExtension, Metadata, MINTER},
+    error::Cw721ContractError,
+    execute::{Cw721Execute, BASE_COMPATIBLE_CONTRACT_NAMES},
+    msg::{Cw721ExecuteMsg, Cw721InstantiateMsg, Cw721MigrateMsg, TraitCount},
+    query::{validate_migration, Cw721Query, MAX_LIMIT},
+    state::{
+        CollectionInfo, Cw721Config, DefaultOptionMetadataExtension, LocalizedText, MediaAsset,
+        MediaRole, Metadata, NftInfo, Trait, TransferredAt, CREATOR, MAX_MEDIA_ASSETS, MINTER,
+    },
 };
 use cosmwasm_std::{
     testing::{mock_dependencies, mock_env, mock_info},
-    Addr, Empty,
+    Addr, Decimal, Empty,
 };
 use cw2::ContractVersion;
 use cw_storage_plus::Item;
@@ -29,9 +33,11 @@ fn proper_cw2_initialization() {
                 symbol: "collection_symbol".into(),
                 minter: Some("minter".into()),
                 withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
             },
             "contract_name",
-            "contract_version",
+            "1.0.0",
         )
         .unwrap();
 
@@ -47,7 +53,7 @@ fn proper_cw2_initialization() {
         version,
         ContractVersion {
             contract: "contract_name".into(),
-            version: "contract_version".into(),
+            version: "1.0.0".into(),
         },
     );
 }
@@ -67,9 +73,11 @@ fn proper_owner_initialization() {
                 symbol: "collection_symbol".into(),
                 minter: None,
                 withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
             },
             "contract_name",
-            "contract_version",
+            "1.0.0",
         )
         .unwrap();
 
@@ -88,6 +96,8 @@ fn use_metadata_extension() {
         symbol: "collection_symbol".into(),
         minter: None,
         withdraw_address: None,
+        initial_tokens: None,
+        import_mode: None,
     };
     let env = mock_env();
     contract
@@ -97,7 +107,7 @@ fn use_metadata_extension() {
             info.clone(),
             init_msg,
             "contract_name",
-            "contract_version",
+            "1.0.0",
         )
         .unwrap();
 
@@ -113,6 +123,8 @@ fn use_metadata_extension() {
         owner: "john".to_string(),
         token_uri: token_uri.clone(),
         extension: extension.clone(),
+        approval: None,
+        metadata_hash: None,
     };
     contract
         .execute(deps.as_mut(), env.clone(), info, exec_msg)
@@ -125,6 +137,297 @@ fn use_metadata_extension() {
     assert_eq!(res.extension, extension);
 }
 
+#[test]
+fn media_assets() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let info = mock_info(CREATOR_ADDR, &[]);
+    contract
+        .instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            Cw721InstantiateMsg {
+                name: "collection_name".into(),
+                symbol: "collection_symbol".into(),
+                minter: None,
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+        )
+        .unwrap();
+
+    let mint_msg = |token_id: &str, media: Vec<MediaAsset>| Cw721ExecuteMsg::Mint {
+        token_id: token_id.to_string(),
+        owner: "john".to_string(),
+        token_uri: None,
+        extension: Some(Metadata {
+            media: Some(media),
+            ..Metadata::default()
+        }),
+        approval: None,
+        metadata_hash: None,
+    };
+    let thumbnail = MediaAsset {
+        uri: "ipfs://thumbnail.png".to_string(),
+        mime_type: "image/png".to_string(),
+        role: MediaRole::Thumbnail,
+    };
+    let model = MediaAsset {
+        uri: "ipfs://model.glb".to_string(),
+        mime_type: "model/gltf-binary".to_string(),
+        role: MediaRole::Model,
+    };
+
+    // invalid mime type
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            mint_msg(
+                "1",
+                vec![MediaAsset {
+                    mime_type: "png".to_string(),
+                    ..thumbnail.clone()
+                }],
+            ),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::InvalidMediaAsset {
+            uri: "ipfs://thumbnail.png".to_string(),
+            reason: "mime type must be of form type/subtype".to_string(),
+        }
+    );
+    // too many assets
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            mint_msg("1", vec![thumbnail.clone(); MAX_MEDIA_ASSETS + 1]),
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::TooManyMediaAssets {
+            max: MAX_MEDIA_ASSETS
+        }
+    );
+
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            mint_msg("1", vec![thumbnail.clone(), model.clone()]),
+        )
+        .unwrap();
+    let res = contract
+        .query_nft_info(deps.as_ref(), env, "1".into())
+        .unwrap();
+    assert_eq!(res.extension.unwrap().media, Some(vec![thumbnail, model]));
+}
+
+#[test]
+fn trait_stats() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let info = mock_info(CREATOR_ADDR, &[]);
+    contract
+        .instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            Cw721InstantiateMsg {
+                name: "collection_name".into(),
+                symbol: "collection_symbol".into(),
+                minter: None,
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+        )
+        .unwrap();
+
+    let background = |value: &str| Trait {
+        display_type: None,
+        trait_type: "background".to_string(),
+        value: value.to_string(),
+    };
+    for (token_id, value) in [("1", "blue"), ("2", "blue"), ("3", "gold"), ("4", "blue")] {
+        contract
+            .execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                Cw721ExecuteMsg::Mint {
+                    token_id: token_id.to_string(),
+                    owner: "john".to_string(),
+                    token_uri: None,
+                    extension: Some(Metadata {
+                        attributes: Some(vec![background(value)]),
+                        ..Metadata::default()
+                    }),
+                    approval: None,
+                    metadata_hash: None,
+                },
+            )
+            .unwrap();
+    }
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("john", &[]),
+            Cw721ExecuteMsg::Burn {
+                token_id: "4".to_string(),
+            },
+        )
+        .unwrap();
+
+    let stats = contract
+        .query_trait_stats(deps.as_ref(), env.clone(), None, None)
+        .unwrap();
+    assert_eq!(stats.token_count, 3);
+    assert_eq!(
+        stats.traits,
+        vec![
+            TraitCount {
+                trait_type: "background".to_string(),
+                value: "blue".to_string(),
+                count: 2,
+            },
+            TraitCount {
+                trait_type: "background".to_string(),
+                value: "gold".to_string(),
+                count: 1,
+            },
+        ]
+    );
+    let stats = contract
+        .query_trait_stats(
+            deps.as_ref(),
+            env.clone(),
+            Some(("background".to_string(), "blue".to_string())),
+            None,
+        )
+        .unwrap();
+    assert_eq!(stats.traits.len(), 1);
+
+    // 3 tokens, 2 blue and 1 gold
+    let score = contract
+        .query_rarity_score(deps.as_ref(), env.clone(), "1".to_string())
+        .unwrap()
+        .score;
+    assert_eq!(score, Decimal::from_ratio(3u64, 2u64));
+    let score = contract
+        .query_rarity_score(deps.as_ref(), env, "3".to_string())
+        .unwrap()
+        .score;
+    assert_eq!(score, Decimal::from_ratio(3u64, 1u64));
+}
+
+#[test]
+fn localized_metadata() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let info = mock_info(CREATOR_ADDR, &[]);
+    contract
+        .instantiate(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            Cw721InstantiateMsg {
+                name: "collection_name".into(),
+                symbol: "collection_symbol".into(),
+                minter: None,
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+        )
+        .unwrap();
+
+    let localizations = [
+        (
+            "de".to_string(),
+            LocalizedText {
+                name: Some("Raumschiff".to_string()),
+                description: Some("Raumschiff mit Warpantrieb".to_string()),
+            },
+        ),
+        (
+            "pt-BR".to_string(),
+            LocalizedText {
+                name: Some("Nave".to_string()),
+                description: None,
+            },
+        ),
+    ];
+    let extension = Some(Metadata {
+        description: Some("Spaceship with Warp Drive".into()),
+        name: Some("Starship".to_string()),
+        localizations: Some(localizations.into_iter().collect()),
+        ..Metadata::default()
+    });
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            Cw721ExecuteMsg::Mint {
+                token_id: "1".to_string(),
+                owner: "john".to_string(),
+                token_uri: None,
+                extension,
+                approval: None,
+                metadata_hash: None,
+            },
+        )
+        .unwrap();
+
+    let localized = |locale: &str| {
+        contract
+            .query_localized_nft_info(deps.as_ref(), env.clone(), "1".into(), locale.into())
+            .unwrap()
+            .extension
+            .unwrap()
+    };
+    // exact locale
+    let metadata = localized("de");
+    assert_eq!(metadata.name, Some("Raumschiff".to_string()));
+    assert_eq!(
+        metadata.description,
+        Some("Raumschiff mit Warpantrieb".to_string())
+    );
+    // fallback to language
+    let metadata = localized("de-AT");
+    assert_eq!(metadata.name, Some("Raumschiff".to_string()));
+    // fallback to default description
+    let metadata = localized("pt-BR");
+    assert_eq!(metadata.name, Some("Nave".to_string()));
+    assert_eq!(
+        metadata.description,
+        Some("Spaceship with Warp Drive".to_string())
+    );
+    // unknown locale
+    let metadata = localized("fr");
+    assert_eq!(metadata.name, Some("Starship".to_string()));
+}
+
 #[test]
 fn test_migrate() {
     let mut deps = mock_dependencies();
@@ -187,24 +490,48 @@ fn test_migrate() {
         assert_eq!(token.owner.as_str(), "owner");
     }
 
-    Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default()
+    let res = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default()
         .migrate(
             deps.as_mut(),
             env.clone(),
             crate::msg::Cw721MigrateMsg::WithUpdate {
                 minter: None,
                 creator: None,
+                withdraw_address: None,
+                name: None,
+                symbol: None,
+                allow_unknown: None,
+                two_phase: None,
+                import_mode: None,
             },
             "contract_name",
-            "contract_version",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
         )
         .unwrap();
 
+    // migrate steps are reported
+    let attribute = |key: &str| {
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+    };
+    assert_eq!(
+        attribute("minter_source"),
+        Some("legacy_minter".to_string())
+    );
+    assert_eq!(
+        attribute("collection_source"),
+        Some("legacy_contract_info".to_string())
+    );
+    assert_eq!(attribute("tokens_migrated"), Some("200".to_string()));
+
     // version
     let version = cw2::get_contract_version(deps.as_ref().storage)
         .unwrap()
         .version;
-    assert_eq!(version, "contract_version");
+    assert_eq!(version, "1.0.0");
     assert_ne!(version, "0.16.0");
 
     // assert minter ownership
@@ -222,6 +549,8 @@ fn test_migrate() {
     let legacy_contract_info = CollectionInfo {
         name: "legacy_name".to_string(),
         symbol: "legacy_symbol".to_string(),
+        royalty_payout_denoms: None,
+        origin: None,
     };
     assert_eq!(collection_info, legacy_contract_info);
 
@@ -253,3 +582,1451 @@ fn test_migrate() {
         assert_eq!(token.owner.as_str(), "owner");
     }
 }
+
+/// v0.5 and older stored collection info, minter and token count in length-prefixed singletons,
+/// canonical addresses and metadata directly in token. Written directly, since these versions
+/// don't build against current cosmwasm-std.
+#[test]
+fn test_migrate_pre_v016() {
+    use crate::execute::legacy_singleton_key;
+    use crate::state::{LegacyApproval, LegacyTokenInfo, NftInfoV1};
+    use cosmwasm_std::{to_json_vec, Binary};
+    use cw_storage_plus::Map;
+    use cw_utils::Expiration;
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let canonical =
+        |addr: &str| Binary::from(deps.api.addr_canonicalize(addr).unwrap().as_slice()).to_base64();
+    let (legacy_minter, legacy_owner, legacy_spender) = (
+        canonical("minter"),
+        canonical("owner"),
+        canonical("spender"),
+    );
+
+    let storage = deps.as_mut().storage;
+    cw2::set_contract_version(storage, "crates.io:cw721-base", "0.5.0").unwrap();
+    storage.set(
+        &legacy_singleton_key("nft_info"),
+        &to_json_vec(&cw721_016::ContractInfoResponse {
+            name: "legacy_name".to_string(),
+            symbol: "legacy_symbol".to_string(),
+        })
+        .unwrap(),
+    );
+    storage.set(
+        &legacy_singleton_key("minter"),
+        &to_json_vec(&legacy_minter).unwrap(),
+    );
+    storage.set(
+        &legacy_singleton_key("num_tokens"),
+        &to_json_vec(&2u64).unwrap(),
+    );
+    Map::<&str, LegacyTokenInfo>::new("tokens")
+        .save(
+            storage,
+            "1",
+            &LegacyTokenInfo {
+                owner: legacy_owner,
+                approvals: vec![LegacyApproval {
+                    spender: legacy_spender,
+                    expires: Expiration::Never {},
+                }],
+                token_uri: None,
+                name: Some("Cosmos".to_string()),
+                description: Some("first".to_string()),
+                image: None,
+            },
+        )
+        .unwrap();
+    // v0.9 layout, kept as is
+    Map::<&str, NftInfoV1<DefaultOptionMetadataExtension>>::new("tokens")
+        .save(
+            storage,
+            "2",
+            &NftInfoV1 {
+                owner: Addr::unchecked("owner"),
+                approvals: vec![],
+                token_uri: Some("ipfs://2".to_string()),
+                extension: None,
+                last_transferred_at: None,
+                metadata_hash: None,
+            },
+        )
+        .unwrap();
+
+    let res = contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            crate::msg::Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+                withdraw_address: None,
+                name: None,
+                symbol: None,
+                allow_unknown: None,
+                two_phase: None,
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap();
+    let attribute = |key: &str| {
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+    };
+    assert_eq!(
+        attribute("minter_source"),
+        Some("legacy_singleton_minter".to_string())
+    );
+    assert_eq!(
+        attribute("collection_source"),
+        Some("legacy_singleton_contract_info".to_string())
+    );
+    assert_eq!(attribute("legacy_tokens_rewritten"), Some("1".to_string()));
+    assert_eq!(attribute("tokens_migrated"), Some("2".to_string()));
+
+    let minter = MINTER.get_ownership(deps.as_ref().storage).unwrap().owner;
+    assert_eq!(minter, Some(Addr::unchecked("minter")));
+    let collection_info = contract
+        .query_collection_info(deps.as_ref(), env.clone())
+        .unwrap();
+    assert_eq!(collection_info.name, "legacy_name");
+    assert_eq!(collection_info.symbol, "legacy_symbol");
+
+    // canonical addresses are humanized and metadata is moved into extension
+    let token = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default()
+        .nft_info
+        .load(deps.as_ref().storage, "1")
+        .unwrap();
+    assert_eq!(token.owner, Addr::unchecked("owner"));
+    assert_eq!(token.approvals[0].spender, Addr::unchecked("spender"));
+    assert_eq!(
+        token.extension,
+        Some(Metadata {
+            name: Some("Cosmos".to_string()),
+            description: Some("first".to_string()),
+            ..Metadata::default()
+        })
+    );
+
+    let token = contract
+        .query_nft_info(deps.as_ref(), env.clone(), "2".to_string())
+        .unwrap();
+    assert_eq!(token.token_uri, Some("ipfs://2".to_string()));
+    assert_eq!(token.extension, None);
+
+    // rewritten tokens are indexed by owner (token 2 was stored without index by this test)
+    let tokens = contract
+        .query_tokens(deps.as_ref(), env, "owner".to_string(), None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["1".to_string()]);
+}
+
+/// Large pre-v0.16 collections are rewritten in chunks, continued by `ContinueMigration`.
+#[test]
+fn migrate_pre_v016_in_chunks() {
+    use crate::execute::{legacy_singleton_key, LEGACY_TOKEN_MIGRATION_LIMIT};
+    use crate::state::LegacyTokenInfo;
+    use cosmwasm_std::to_json_vec;
+    use cw_storage_plus::Map;
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let token_count = LEGACY_TOKEN_MIGRATION_LIMIT as u64 + 5;
+
+    let storage = deps.as_mut().storage;
+    cw2::set_contract_version(storage, "crates.io:cw721-base", "0.5.0").unwrap();
+    storage.set(
+        &legacy_singleton_key("nft_info"),
+        &to_json_vec(&cw721_016::ContractInfoResponse {
+            name: "legacy_name".to_string(),
+            symbol: "legacy_symbol".to_string(),
+        })
+        .unwrap(),
+    );
+    storage.set(
+        &legacy_singleton_key("minter"),
+        &to_json_vec(&"minter").unwrap(),
+    );
+    storage.set(
+        &legacy_singleton_key("num_tokens"),
+        &to_json_vec(&token_count).unwrap(),
+    );
+    for i in 0..token_count {
+        Map::<&str, LegacyTokenInfo>::new("tokens")
+            .save(
+                storage,
+                &format!("{i:03}"),
+                &LegacyTokenInfo {
+                    owner: "owner".to_string(),
+                    approvals: vec![],
+                    token_uri: None,
+                    name: Some(format!("Cosmos {i}")),
+                    description: None,
+                    image: None,
+                },
+            )
+            .unwrap();
+    }
+
+    // first chunk is rewritten, and migration is only prepared
+    let res = contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+                withdraw_address: None,
+                name: None,
+                symbol: None,
+                allow_unknown: None,
+                two_phase: None,
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap();
+    let attribute = |attributes: &[cosmwasm_std::Attribute], key: &str| {
+        attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+    };
+    assert_eq!(
+        attribute(&res.attributes, "legacy_tokens_rewritten"),
+        Some(LEGACY_TOKEN_MIGRATION_LIMIT.to_string())
+    );
+    assert_eq!(
+        attribute(&res.attributes, "legacy_tokens_pending"),
+        Some("true".to_string())
+    );
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, "0.5.0");
+    let validation = validate_migration(deps.as_ref().storage, &contract.config).unwrap();
+    assert_eq!(
+        validation.errors,
+        vec!["Legacy tokens not fully rewritten, see `ContinueMigration`".to_string()]
+    );
+    let minter = mock_info("minter", &[]);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::CommitMigration {},
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::InvalidMigration {
+            errors: "Legacy tokens not fully rewritten, see `ContinueMigration`".to_string()
+        }
+    );
+
+    // only owner can continue
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("random", &[]),
+            Cw721ExecuteMsg::ContinueMigration {},
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::Ownership(cw_ownable::OwnershipError::NotOwner)
+    );
+    let res = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::ContinueMigration {},
+        )
+        .unwrap();
+    assert_eq!(
+        attribute(&res.attributes, "legacy_tokens_rewritten"),
+        Some("5".to_string())
+    );
+    assert_eq!(
+        attribute(&res.attributes, "legacy_tokens_pending"),
+        Some("false".to_string())
+    );
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter,
+            Cw721ExecuteMsg::CommitMigration {},
+        )
+        .unwrap();
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, "1.0.0");
+    let last_token_id = format!("{:03}", token_count - 1);
+    let token = contract
+        .query_nft_info(deps.as_ref(), env, last_token_id)
+        .unwrap();
+    assert_eq!(
+        token.extension.and_then(|metadata| metadata.name),
+        Some(format!("Cosmos {}", token_count - 1))
+    );
+}
+
+#[test]
+fn migrate_rejects_unparsable_version() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("larry", &[]),
+            Cw721InstantiateMsg {
+                name: "collection_name".into(),
+                symbol: "collection_symbol".into(),
+                minter: Some("minter".into()),
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
+            },
+            "crates.io:cw721-base",
+            "1.0.0",
+        )
+        .unwrap();
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw721-base", "latest").unwrap();
+
+    let err = contract
+        .migrate(
+            deps.as_mut(),
+            env,
+            Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+                withdraw_address: None,
+                name: None,
+                symbol: None,
+                allow_unknown: None,
+                two_phase: None,
+                import_mode: None,
+            },
+            "crates.io:cw721-base",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::Std(cosmwasm_std::StdError::generic_err(
+            "Cannot parse contract version latest"
+        ))
+    );
+}
+
+#[test]
+fn migrate_with_update() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("larry", &[]),
+            Cw721InstantiateMsg {
+                name: "collection_name".into(),
+                symbol: "collection_symbol".into(),
+                minter: Some("minter".into()),
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+        )
+        .unwrap();
+
+    // invalid withdraw address is rejected
+    contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            crate::msg::Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+                withdraw_address: Some("".into()),
+                name: None,
+                symbol: None,
+                allow_unknown: None,
+                two_phase: None,
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap_err();
+
+    contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            crate::msg::Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+                withdraw_address: Some("withdraw".into()),
+                name: Some("new_name".into()),
+                symbol: None,
+                allow_unknown: None,
+                two_phase: None,
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap();
+
+    let withdraw_address = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default()
+        .withdraw_address
+        .may_load(deps.as_ref().storage)
+        .unwrap();
+    assert_eq!(withdraw_address, Some("withdraw".to_string()));
+    let collection_info = contract.query_collection_info(deps.as_ref(), env).unwrap();
+    assert_eq!(
+        collection_info,
+        CollectionInfo {
+            name: "new_name".to_string(),
+            symbol: "collection_symbol".to_string(),
+            royalty_payout_denoms: None,
+            origin: None,
+        }
+    );
+}
+
+#[test]
+fn migrate_creator() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("larry", &[]),
+            Cw721InstantiateMsg {
+                name: "collection_name".into(),
+                symbol: "collection_symbol".into(),
+                minter: Some("minter".into()),
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+        )
+        .unwrap();
+    assert_eq!(
+        CREATOR.get_ownership(deps.as_ref().storage).unwrap().owner,
+        Some(Addr::unchecked("minter"))
+    );
+
+    // creator shared minter's record before, so it is copied from minter
+    CREATOR.item.remove(deps.as_mut().storage);
+    let migrate_msg = |creator: Option<&str>| Cw721MigrateMsg::WithUpdate {
+        minter: None,
+        creator: creator.map(String::from),
+        withdraw_address: None,
+        name: None,
+        symbol: None,
+        allow_unknown: None,
+        two_phase: None,
+        import_mode: None,
+    };
+    contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            migrate_msg(None),
+            "contract_name",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap();
+    assert_eq!(
+        CREATOR.get_ownership(deps.as_ref().storage).unwrap().owner,
+        Some(Addr::unchecked("minter"))
+    );
+
+    // explicit creator leaves minter untouched
+    contract
+        .migrate(
+            deps.as_mut(),
+            env,
+            migrate_msg(Some("creator")),
+            "contract_name",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap();
+    assert_eq!(
+        CREATOR.get_ownership(deps.as_ref().storage).unwrap().owner,
+        Some(Addr::unchecked("creator"))
+    );
+    assert_eq!(
+        MINTER.get_ownership(deps.as_ref().storage).unwrap().owner,
+        Some(Addr::unchecked("minter"))
+    );
+}
+
+#[test]
+fn migrate_opens_import_mode() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("larry", &[]),
+            Cw721InstantiateMsg {
+                name: "collection_name".into(),
+                symbol: "collection_symbol".into(),
+                minter: Some("minter".into()),
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+        )
+        .unwrap();
+    let open_import = Cw721MigrateMsg::WithUpdate {
+        minter: None,
+        creator: None,
+        withdraw_address: None,
+        name: None,
+        symbol: None,
+        allow_unknown: None,
+        two_phase: None,
+        import_mode: Some(true),
+    };
+
+    contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            open_import.clone(),
+            "contract_name",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap();
+    let import_mode = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default()
+        .import_mode
+        .load(deps.as_ref().storage)
+        .unwrap();
+    assert!(import_mode);
+
+    // can't be reopened once closed
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("minter", &[]),
+            Cw721ExecuteMsg::CloseImport {},
+        )
+        .unwrap();
+    let err = contract
+        .migrate(
+            deps.as_mut(),
+            env,
+            open_import,
+            "contract_name",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::ImportClosed {});
+}
+
+#[test]
+fn migrate_guards_contract_name() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("larry", &[]),
+            Cw721InstantiateMsg {
+                name: "collection_name".into(),
+                symbol: "collection_symbol".into(),
+                minter: Some("minter".into()),
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
+            },
+            "crates.io:cw721-base",
+            "1.0.0",
+        )
+        .unwrap();
+    // some unrelated contract
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "1.0.0").unwrap();
+
+    let err = contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            crate::msg::Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+                withdraw_address: None,
+                name: None,
+                symbol: None,
+                allow_unknown: None,
+                two_phase: None,
+                import_mode: None,
+            },
+            "crates.io:cw721-base",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::IncompatibleContract {
+            stored: "crates.io:cw20-base".to_string(),
+            contract_name: "crates.io:cw721-base".to_string(),
+        }
+    );
+
+    // derived contracts aren't compatible with base, unless target accepts them
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw721-expiration", "1.0.0")
+        .unwrap();
+    let msg = crate::msg::Cw721MigrateMsg::WithUpdate {
+        minter: None,
+        creator: None,
+        withdraw_address: None,
+        name: None,
+        symbol: None,
+        allow_unknown: None,
+        two_phase: None,
+        import_mode: None,
+    };
+    let err = contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            msg.clone(),
+            "crates.io:cw721-base",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::IncompatibleContract {
+            stored: "crates.io:cw721-expiration".to_string(),
+            contract_name: "crates.io:cw721-base".to_string(),
+        }
+    );
+    contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            msg,
+            "crates.io:cw721-derived",
+            "1.0.0",
+            &["crates.io:cw721-expiration"],
+        )
+        .unwrap();
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "1.0.0").unwrap();
+
+    // escape hatch
+    contract
+        .migrate(
+            deps.as_mut(),
+            env,
+            crate::msg::Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+                withdraw_address: None,
+                name: None,
+                symbol: None,
+                allow_unknown: Some(true),
+                two_phase: None,
+                import_mode: None,
+            },
+            "crates.io:cw721-base",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap();
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.contract, "crates.io:cw721-base");
+}
+
+/// Owner lookup must be maintained on save and remove, for both IndexedMap and `plain-storage` mode.
+#[test]
+fn two_phase_migration() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    use cw721_base_016 as v16;
+    v16::entry::instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        v16::InstantiateMsg {
+            name: "legacy_name".into(),
+            symbol: "legacy_symbol".into(),
+            minter: "legacy_minter".into(),
+        },
+    )
+    .unwrap();
+    for i in 0..3 {
+        let msg = v16::ExecuteMsg::Mint(v16::msg::MintMsg {
+            token_id: i.to_string(),
+            owner: "owner".into(),
+            token_uri: None,
+            extension: None,
+        });
+        v16::entry::execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("legacy_minter", &[]),
+            msg,
+        )
+        .unwrap();
+    }
+
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    contract
+        .migrate(
+            deps.as_mut(),
+            env.clone(),
+            crate::msg::Cw721MigrateMsg::WithUpdate {
+                minter: None,
+                creator: None,
+                withdraw_address: None,
+                name: None,
+                symbol: None,
+                allow_unknown: None,
+                two_phase: Some(true),
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+            BASE_COMPATIBLE_CONTRACT_NAMES,
+        )
+        .unwrap();
+
+    // prepared: new structures written, legacy storage and version kept
+    let legacy_minter_store: Item<Addr> = Item::new("minter");
+    assert!(legacy_minter_store.exists(deps.as_ref().storage));
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, "0.16.0");
+    let validation = validate_migration(deps.as_ref().storage, &contract.config).unwrap();
+    assert_eq!(validation.token_count, 3);
+    assert_eq!(validation.sampled, 3);
+    assert_eq!(validation.errors, Vec::<String>::new());
+    assert_eq!(
+        validation.pending.map(|pending| pending.contract_version),
+        Some("1.0.0".to_string())
+    );
+
+    // contract is locked until committed
+    let minter = mock_info("legacy_minter", &[]);
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::Burn {
+                token_id: "0".into(),
+            },
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::MigrationPending {});
+
+    // mismatches are rejected on commit
+    let config = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    config.token_count.save(&mut deps.storage, &4).unwrap();
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::CommitMigration {},
+        )
+        .unwrap_err();
+    assert_eq!(
+        err,
+        Cw721ContractError::InvalidMigration {
+            errors: "Token count changed from 3 to 4; Found 3 tokens, but token count is 4"
+                .to_string()
+        }
+    );
+    config.token_count.save(&mut deps.storage, &3).unwrap();
+
+    contract
+        .execute(
+            deps.as_mut(),
+            env.clone(),
+            minter.clone(),
+            Cw721ExecuteMsg::CommitMigration {},
+        )
+        .unwrap();
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(version.version, "1.0.0");
+    assert!(!legacy_minter_store.exists(deps.as_ref().storage));
+    assert!(!config.pending_migration.exists(deps.as_ref().storage));
+    let err = contract
+        .execute(
+            deps.as_mut(),
+            env,
+            minter,
+            Cw721ExecuteMsg::CommitMigration {},
+        )
+        .unwrap_err();
+    assert_eq!(err, Cw721ContractError::NoPendingMigration {});
+}
+
+#[test]
+fn save_nft_updates_owner_lookup() {
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let config = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+
+    let token = NftInfo {
+        owner: Addr::unchecked("alice"),
+        approvals: vec![],
+        token_uri: None,
+        extension: None,
+        last_transferred_at: None,
+        metadata_hash: None,
+    };
+    config
+        .save_nft(deps.as_mut().storage, "1", &token, None)
+        .unwrap();
+    let tokens = contract
+        .query_tokens(deps.as_ref(), mock_env(), "alice".into(), None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["1".to_string()]);
+
+    let mut transferred = token.clone();
+    transferred.owner = Addr::unchecked("bob");
+    config
+        .save_nft(deps.as_mut().storage, "1", &transferred, Some(&token))
+        .unwrap();
+    let tokens = contract
+        .query_tokens(deps.as_ref(), mock_env(), "alice".into(), None, None)
+        .unwrap();
+    assert!(tokens.tokens.is_empty());
+    let tokens = contract
+        .query_tokens(deps.as_ref(), mock_env(), "bob".into(), None, None)
+        .unwrap();
+    assert_eq!(tokens.tokens, vec!["1".to_string()]);
+
+    config
+        .remove_nft(deps.as_mut().storage, "1", &transferred)
+        .unwrap();
+    let tokens = contract
+        .query_tokens(deps.as_ref(), mock_env(), "bob".into(), None, None)
+        .unwrap();
+    assert!(tokens.tokens.is_empty());
+    assert!(config
+        .nft_info
+        .may_load(deps.as_ref().storage, "1")
+        .unwrap()
+        .is_none());
+}
+
+/// Owner lookup writes (sets and removes) per token save, as documented for `plain-storage` in README:
+/// the owner index is rewritten on every save, the plain owner map only if the owner changed.
+#[test]
+fn owner_lookup_storage_ops() {
+    use crate::state::{Approval, APPROVAL_PERMISSION_ALL};
+    use cosmwasm_std::{testing::MockStorage, Order, Record, Storage};
+    use cw_utils::Expiration;
+
+    /// Records keys written to storage
+    #[derive(Default)]
+    struct WriteLog {
+        storage: MockStorage,
+        writes: Vec<Vec<u8>>,
+    }
+    impl Storage for WriteLog {
+        fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+            self.storage.get(key)
+        }
+        fn range<'a>(
+            &'a self,
+            start: Option<&[u8]>,
+            end: Option<&[u8]>,
+            order: Order,
+        ) -> Box<dyn Iterator<Item = Record> + 'a> {
+            self.storage.range(start, end, order)
+        }
+        fn set(&mut self, key: &[u8], value: &[u8]) {
+            self.writes.push(key.to_vec());
+            self.storage.set(key, value)
+        }
+        fn remove(&mut self, key: &[u8]) {
+            self.writes.push(key.to_vec());
+            self.storage.remove(key)
+        }
+    }
+
+    let mut storage = WriteLog::default();
+    let config = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let owner_keys = [
+        config.owner_index_raw_key(&alice, "1"),
+        config.owner_index_raw_key(&bob, "1"),
+    ];
+    let owner_lookup_writes = |storage: &mut WriteLog| {
+        let count = storage
+            .writes
+            .iter()
+            .filter(|key| owner_keys.contains(key))
+            .count();
+        storage.writes.clear();
+        count
+    };
+
+    let minted = NftInfo {
+        owner: alice.clone(),
+        approvals: vec![],
+        token_uri: None,
+        extension: None,
+        last_transferred_at: None,
+        metadata_hash: None,
+    };
+    config.save_nft(&mut storage, "1", &minted, None).unwrap();
+    let mint = owner_lookup_writes(&mut storage);
+    let mut approved = minted.clone();
+    approved.approvals.push(Approval {
+        spender: bob.clone(),
+        expires: Expiration::Never {},
+        permissions: APPROVAL_PERMISSION_ALL,
+    });
+    config
+        .save_nft(&mut storage, "1", &approved, Some(&minted))
+        .unwrap();
+    let approve = owner_lookup_writes(&mut storage);
+    let mut transferred = approved.clone();
+    transferred.owner = bob;
+    transferred.approvals = vec![];
+    config
+        .save_nft(&mut storage, "1", &transferred, Some(&approved))
+        .unwrap();
+    let transfer = owner_lookup_writes(&mut storage);
+    config.remove_nft(&mut storage, "1", &transferred).unwrap();
+    let burn = owner_lookup_writes(&mut storage);
+
+    #[cfg(all(feature = "enumerable", not(feature = "plain-storage")))]
+    assert_eq!([mint, approve, transfer, burn], [1, 2, 2, 1]);
+    #[cfg(all(feature = "enumerable", feature = "plain-storage"))]
+    assert_eq!([mint, approve, transfer, burn], [1, 0, 2, 1]);
+    #[cfg(not(feature = "enumerable"))]
+    assert_eq!([mint, approve, transfer, burn], [0, 0, 0, 0]);
+}
+
+#[test]
+fn config_builder_custom_keys() {
+    let mut deps = mock_dependencies();
+    let default = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let custom: Cw721Config<DefaultOptionMetadataExtension, Empty, Empty> = Cw721Config::builder()
+        .tokens_key("my_tokens")
+        .tokens_owner_key("my_tokens__owner")
+        .build();
+
+    let token = NftInfo {
+        owner: Addr::unchecked("alice"),
+        approvals: vec![],
+        token_uri: None,
+        extension: None,
+        last_transferred_at: None,
+        metadata_hash: None,
+    };
+    custom
+        .save_nft(deps.as_mut().storage, "1", &token, None)
+        .unwrap();
+    assert_eq!(
+        custom.nft_info.load(deps.as_ref().storage, "1").unwrap(),
+        token
+    );
+    // stored in its own namespace, other keys unchanged
+    assert!(!default.nft_info.has(deps.as_ref().storage, "1"));
+    default.token_count.save(deps.as_mut().storage, &1).unwrap();
+    assert_eq!(custom.token_count(deps.as_ref().storage).unwrap(), 1);
+}
+
+#[test]
+fn default_methods_use_overridden_config() {
+    use crate::execute::Cw721CustomExecute;
+    use crate::query::Cw721CustomQuery;
+    use cosmwasm_std::CustomMsg;
+
+    fn namespaced<C, M: CustomMsg>() -> Cw721Config<'static, DefaultOptionMetadataExtension, C, M> {
+        Cw721Config::builder()
+            .collection_info_key("my_collection_info")
+            .token_count_key("my_num_tokens")
+            .tokens_key("my_tokens")
+            .tokens_owner_key("my_tokens__owner")
+            .build()
+    }
+
+    struct NamespacedContract;
+    impl Cw721CustomExecute<DefaultOptionMetadataExtension, Empty, Empty> for NamespacedContract {}
+    impl Cw721Execute<DefaultOptionMetadataExtension, Empty, Empty> for NamespacedContract {
+        fn config(&self) -> Cw721Config<'static, DefaultOptionMetadataExtension, Empty, Empty> {
+            namespaced()
+        }
+    }
+    impl Cw721CustomQuery<DefaultOptionMetadataExtension> for NamespacedContract {}
+    impl Cw721Query<DefaultOptionMetadataExtension> for NamespacedContract {
+        fn config(&self) -> Cw721Config<'static, DefaultOptionMetadataExtension, Empty, Empty> {
+            namespaced()
+        }
+    }
+
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    let contract = NamespacedContract;
+    Cw721Execute::instantiate(
+        &contract,
+        deps.as_mut(),
+        env.clone(),
+        mock_info(CREATOR_ADDR, &[]),
+        Cw721InstantiateMsg {
+            name: "collection_name".into(),
+            symbol: "collection_symbol".into(),
+            minter: None,
+            withdraw_address: None,
+            initial_tokens: None,
+            import_mode: None,
+        },
+        "contract_name",
+        "1.0.0",
+    )
+    .unwrap();
+    contract
+        .mint(
+            deps.as_mut(),
+            &env,
+            mock_info(CREATOR_ADDR, &[]),
+            "1".to_string(),
+            "alice".to_string(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+
+    // written and read under custom keys only
+    let owner = contract
+        .query_owner_of(deps.as_ref(), env.clone(), "1".to_string(), false)
+        .unwrap();
+    assert_eq!(owner.owner, "alice");
+    let collection_info = contract
+        .query_collection_info(deps.as_ref(), env.clone())
+        .unwrap();
+    assert_eq!(collection_info.name, "collection_name");
+    let num_tokens = contract.query_num_tokens(deps.as_ref(), env).unwrap();
+    assert_eq!(num_tokens.count, 1);
+    let default = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    assert!(!default.nft_info.has(deps.as_ref().storage, "1"));
+    assert!(!default.collection_info.exists(deps.as_ref().storage));
+    assert_eq!(default.token_count(deps.as_ref().storage).unwrap(), 0);
+}
+
+#[test]
+fn cooldown_ends_overflow() {
+    use cosmwasm_std::Timestamp;
+    use cw_utils::{Duration, Expiration};
+
+    let transferred_at = TransferredAt {
+        height: 100,
+        time: Timestamp::from_seconds(1_000),
+    };
+    assert_eq!(
+        transferred_at.cooldown_ends(Duration::Height(5)).unwrap(),
+        Expiration::AtHeight(105)
+    );
+    assert_eq!(
+        transferred_at.cooldown_ends(Duration::Time(60)).unwrap(),
+        Expiration::AtTime(Timestamp::from_seconds(1_060))
+    );
+    // huge cooldowns error instead of panicking
+    transferred_at
+        .cooldown_ends(Duration::Height(u64::MAX))
+        .unwrap_err();
+    transferred_at
+        .cooldown_ends(Duration::Time(u64::MAX / 1_000_000_000))
+        .unwrap_err();
+    transferred_at
+        .cooldown_ends(Duration::Time(u64::MAX))
+        .unwrap_err();
+}
+
+#[test]
+fn raw_storage_keys() {
+    use cosmwasm_std::{from_json, Storage};
+    use cw_utils::Expiration;
+
+    let mut deps = mock_dependencies();
+    let config = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let token = NftInfo {
+        owner: alice.clone(),
+        approvals: vec![],
+        token_uri: None,
+        extension: None,
+        last_transferred_at: None,
+        metadata_hash: None,
+    };
+    config
+        .save_nft(deps.as_mut().storage, "1", &token, None)
+        .unwrap();
+    config
+        .save_operator(
+            deps.as_mut().storage,
+            &alice,
+            &bob,
+            &Expiration::AtHeight(100),
+            None,
+        )
+        .unwrap();
+
+    assert_eq!(
+        config.collection_info_raw_key(),
+        b"collection_info".to_vec()
+    );
+    assert!(deps.storage.get(&config.nft_info_raw_key("1")).is_some());
+    #[cfg(all(feature = "enumerable", not(feature = "plain-storage")))]
+    {
+        let mut owner_key = vec![0, 13];
+        owner_key.extend_from_slice(b"tokens__owner");
+        owner_key.extend_from_slice(&[0, 5]);
+        owner_key.extend_from_slice(b"alice1");
+        assert_eq!(config.owner_index_raw_key(&alice, "1"), owner_key);
+        assert!(deps.storage.get(&owner_key).is_some());
+    }
+    let expires: Expiration = from_json(
+        deps.storage
+            .get(&config.operator_raw_key(&alice, &bob))
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(expires, Expiration::AtHeight(100));
+}
+
+#[test]
+fn token_storage_entries() {
+    use crate::msg::{Cw721QueryMsg, TokenStorageEntriesResponse};
+    use crate::state::VersionedNftInfo;
+    use cosmwasm_std::{from_json, Storage};
+
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let config = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let token = NftInfo {
+        owner: Addr::unchecked("alice"),
+        approvals: vec![],
+        token_uri: Some("ipfs://1".to_string()),
+        extension: None,
+        last_transferred_at: None,
+        metadata_hash: None,
+    };
+    config
+        .save_nft(deps.as_mut().storage, "1", &token, None)
+        .unwrap();
+
+    let res: TokenStorageEntriesResponse = from_json(
+        contract
+            .query(
+                deps.as_ref(),
+                mock_env(),
+                Cw721QueryMsg::TokenStorageEntries {
+                    token_id: "1".to_string(),
+                },
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    // entries are exactly what is in storage
+    assert_eq!(res.nft_info.key.to_vec(), config.nft_info_raw_key("1"));
+    assert_eq!(
+        deps.storage.get(&res.nft_info.key).unwrap(),
+        res.nft_info.value.to_vec()
+    );
+    let stored: VersionedNftInfo<DefaultOptionMetadataExtension> =
+        from_json(&res.nft_info.value).unwrap();
+    assert_eq!(NftInfo::from(stored), token);
+    #[cfg(feature = "enumerable")]
+    {
+        let owner_index = res.owner_index.unwrap();
+        assert_eq!(
+            deps.storage.get(&owner_index.key).unwrap(),
+            owner_index.value.to_vec()
+        );
+    }
+
+    // unknown token
+    contract
+        .query(
+            deps.as_ref(),
+            mock_env(),
+            Cw721QueryMsg::TokenStorageEntries {
+                token_id: "2".to_string(),
+            },
+        )
+        .unwrap_err();
+}
+
+/// Tokens stored before versioning load transparently and are saved with version envelope.
+#[test]
+fn nft_info_versioned_storage() {
+    use crate::state::{NftInfoVersion, VersionedNftInfo};
+    use cosmwasm_std::{from_json, Storage};
+    use cw_storage_plus::Map;
+
+    let mut deps = mock_dependencies();
+    let config = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    let key = Map::<&str, Empty>::new("tokens").key("1").to_vec();
+    deps.storage.set(
+        &key,
+        br#"{"owner":"alice","approvals":[],"token_uri":"ipfs://1","extension":null}"#,
+    );
+
+    let token = config.nft_info.load(deps.as_ref().storage, "1").unwrap();
+    assert_eq!(
+        token,
+        NftInfo {
+            owner: Addr::unchecked("alice"),
+            approvals: vec![],
+            token_uri: Some("ipfs://1".to_string()),
+            extension: None,
+            last_transferred_at: None,
+            metadata_hash: None,
+        }
+    );
+
+    config
+        .save_nft(deps.as_mut().storage, "1", &token, Some(&token))
+        .unwrap();
+    let raw = deps.storage.get(&key).unwrap();
+    assert!(raw.starts_with(br#"{"version":"v1","data":{"#));
+    let stored: VersionedNftInfo<DefaultOptionMetadataExtension> = from_json(&raw).unwrap();
+    assert!(matches!(
+        stored,
+        VersionedNftInfo::Versioned(NftInfoVersion::V1(_))
+    ));
+    assert_eq!(
+        config.nft_info.load(deps.as_ref().storage, "1").unwrap(),
+        token
+    );
+}
+
+/// `Extension` query is routed to `Cw721CustomQuery`, so custom queries don't need their own dispatcher.
+#[test]
+fn custom_query_hook() {
+    use crate::msg::Cw721QueryMsg;
+    use crate::query::Cw721CustomQuery;
+    use cosmwasm_std::{to_json_binary, Binary, Deps, Env, StdResult};
+
+    struct CustomContract;
+    impl Cw721CustomQuery<DefaultOptionMetadataExtension> for CustomContract {
+        fn query_custom(
+            &self,
+            _deps: Deps,
+            _env: Env,
+            msg: DefaultOptionMetadataExtension,
+        ) -> StdResult<Binary> {
+            to_json_binary(&msg.and_then(|metadata| metadata.name))
+        }
+    }
+    impl Cw721Query<DefaultOptionMetadataExtension> for CustomContract {}
+
+    let deps = mock_dependencies();
+    let res = CustomContract
+        .query(
+            deps.as_ref(),
+            mock_env(),
+            Cw721QueryMsg::Extension {
+                msg: Some(Metadata {
+                    name: Some("custom".to_string()),
+                    ..Metadata::default()
+                }),
+            },
+        )
+        .unwrap();
+    assert_eq!(res, to_json_binary(&Some("custom".to_string())).unwrap());
+
+    // default is a no-op
+    let res = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default()
+        .query(
+            deps.as_ref(),
+            mock_env(),
+            Cw721QueryMsg::Extension { msg: None },
+        )
+        .unwrap();
+    assert_eq!(res, Binary::default());
+}
+
+/// `Extension` execute msg is routed to `Cw721CustomExecute`, with access to the config.
+#[test]
+fn custom_execute_hook() {
+    use crate::error::Cw721ContractError;
+    use crate::execute::Cw721CustomExecute;
+    use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+
+    struct CustomContract;
+    impl Cw721CustomExecute<DefaultOptionMetadataExtension, Empty, Empty> for CustomContract {
+        fn execute_custom(
+            &self,
+            deps: DepsMut,
+            _env: Env,
+            _info: MessageInfo,
+            config: &Cw721Config<DefaultOptionMetadataExtension, Empty, Empty>,
+            _msg: Empty,
+        ) -> Result<Response, Cw721ContractError> {
+            config.token_count.save(deps.storage, &42)?;
+            Ok(Response::new().add_attribute("action", "custom"))
+        }
+    }
+    impl Cw721Execute<DefaultOptionMetadataExtension, Empty, Empty> for CustomContract {}
+
+    let mut deps = mock_dependencies();
+    let res = CustomContract
+        .execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            Cw721ExecuteMsg::Extension { msg: Empty {} },
+        )
+        .unwrap();
+    assert_eq!(res.attributes[0].value, "custom");
+    let count = Cw721Config::<DefaultOptionMetadataExtension, Empty, Empty>::default()
+        .token_count(deps.as_ref().storage)
+        .unwrap();
+    assert_eq!(count, 42);
+}
+
+/// Archived payloads of older versions are accepted and translated to current messages.
+#[cfg(feature = "legacy-msg")]
+#[test]
+fn legacy_execute_msgs() {
+    use crate::legacy::Cw721CompatExecuteMsg;
+    use cosmwasm_std::{from_json, Binary};
+    use cw_ownable::Action;
+
+    type CompatMsg = Cw721CompatExecuteMsg<DefaultOptionMetadataExtension, Empty>;
+    let parse = |payload: &str| {
+        from_json::<CompatMsg>(payload.as_bytes())
+            .unwrap()
+            .into_current()
+            .unwrap()
+    };
+
+    // pre-v0.9 mint with metadata in msg
+    let msg = parse(
+        r#"{"mint":{"token_id":"1","owner":"owner","name":"Cosmos","description":"first","image":"ipfs://image"}}"#,
+    );
+    assert_eq!(
+        msg,
+        Cw721ExecuteMsg::Mint {
+            token_id: "1".to_string(),
+            owner: "owner".to_string(),
+            token_uri: None,
+            extension: Some(Metadata {
+                name: Some("Cosmos".to_string()),
+                description: Some("first".to_string()),
+                image: Some("ipfs://image".to_string()),
+                ..Metadata::default()
+            }),
+            approval: None,
+            metadata_hash: None,
+        }
+    );
+    // pre-v0.9 send without msg
+    let msg = parse(r#"{"send_nft":{"contract":"market","token_id":"1"}}"#);
+    assert_eq!(
+        msg,
+        Cw721ExecuteMsg::SendNft {
+            contract: "market".to_string(),
+            token_id: "1".to_string(),
+            msg: Binary::default(),
+        }
+    );
+    // v0.16 `Mint(MintMsg{..})` is the same shape as current mint
+    let msg = parse(
+        r#"{"mint":{"token_id":"2","owner":"owner","token_uri":"ipfs://2","extension":null}}"#,
+    );
+    assert_eq!(
+        msg,
+        Cw721ExecuteMsg::Mint {
+            token_id: "2".to_string(),
+            owner: "owner".to_string(),
+            token_uri: Some("ipfs://2".to_string()),
+            extension: None,
+            approval: None,
+            metadata_hash: None,
+        }
+    );
+    // v0.18 `UpdateOwnership`
+    let msg = parse(r#"{"update_ownership":"accept_ownership"}"#);
+    assert_eq!(
+        msg,
+        Cw721ExecuteMsg::UpdateOwnership(Action::AcceptOwnership)
+    );
+
+    // legacy mint executes with metadata stored in extension
+    let mut deps = mock_dependencies();
+    let contract = Cw721Contract::<DefaultOptionMetadataExtension, Empty, Empty>::default();
+    contract
+        .instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(CREATOR_ADDR, &[]),
+            Cw721InstantiateMsg {
+                name: "collection_name".into(),
+                symbol: "collection_symbol".into(),
+                minter: Some("minter".into()),
+                withdraw_address: None,
+                initial_tokens: None,
+                import_mode: None,
+            },
+            "contract_name",
+            "1.0.0",
+        )
+        .unwrap();
+    let msg = parse(r#"{"mint":{"token_id":"1","owner":"owner","name":"Cosmos"}}"#);
+    contract
+        .execute(deps.as_mut(), mock_env(), mock_info("minter", &[]), msg)
+        .unwrap();
+    let nft_info = contract
+        .query_nft_info(deps.as_ref(), mock_env(), "1".to_string())
+        .unwrap();
+    assert_eq!(
+        nft_info.extension.and_then(|metadata| metadata.name),
+        Some("Cosmos".to_string())
+    );
+}